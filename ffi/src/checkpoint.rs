@@ -0,0 +1,393 @@
+//! Checkpoint writing related ffi code. See the [`delta_kernel::checkpoint`] module documentation
+//! for the complete workflow.
+
+use std::sync::Arc;
+
+use delta_kernel::checkpoint::{CheckpointDataIterator, CheckpointWriter};
+use delta_kernel::DeltaResult;
+use delta_kernel_ffi_macros::handle_descriptor;
+
+use crate::engine_funcs::{kernel_file_meta, FileMeta};
+use crate::{
+    kernel_string_slice, AllocateStringFn, ExclusiveEngineData, ExternEngine, ExternResult,
+    IntoExternResult, KernelBoolSlice, NullableCvoid, SharedExternEngine, SharedSnapshot,
+};
+
+use super::handle::Handle;
+
+/// A handle to a [`CheckpointWriter`], which is consumed by [`checkpoint_finalize`].
+#[handle_descriptor(target=CheckpointWriter, mutable=true, sized=true)]
+pub struct ExclusiveCheckpointWriter;
+
+// Intentionally opaque to the engine.
+pub struct CheckpointData {
+    // The data iterator is handed back to the kernel by `checkpoint_finalize`, which reads the
+    // action counts it accumulated while being consumed.
+    data: CheckpointDataIterator,
+
+    // Also keep a reference to the external engine for its error allocator.
+    engine: Arc<dyn ExternEngine>,
+}
+
+#[handle_descriptor(target=CheckpointData, mutable=true, sized=true)]
+pub struct ExclusiveCheckpointDataIterator;
+
+/// Create a [`CheckpointWriter`] to write a checkpoint of the table at the version of the given
+/// snapshot. It is the responsibility of the _engine_ to either finalize the checkpoint with
+/// [`checkpoint_finalize`] or free the writer with [`free_checkpoint_writer`].
+///
+/// # Safety
+///
+/// Caller is responsible for passing a valid snapshot handle and engine handle.
+#[no_mangle]
+pub unsafe extern "C" fn checkpoint_writer(
+    snapshot: Handle<SharedSnapshot>,
+    engine: Handle<SharedExternEngine>,
+) -> ExternResult<Handle<ExclusiveCheckpointWriter>> {
+    let snapshot = unsafe { snapshot.clone_as_arc() };
+    let engine = unsafe { engine.as_ref() };
+    snapshot
+        .checkpoint()
+        .map(|writer| Box::new(writer).into())
+        .into_extern_result(&engine)
+}
+
+/// Free a checkpoint writer without finalizing the checkpoint.
+///
+/// # Safety
+///
+/// Caller is responsible for passing a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn free_checkpoint_writer(writer: Handle<ExclusiveCheckpointWriter>) {
+    writer.drop_handle();
+}
+
+/// Get the path the checkpoint file should be written to.
+///
+/// # Safety
+///
+/// Caller is responsible for passing a valid writer handle, engine handle and allocate_fn (for
+/// allocating the string)
+#[no_mangle]
+pub unsafe extern "C" fn checkpoint_path(
+    writer: Handle<ExclusiveCheckpointWriter>,
+    engine: Handle<SharedExternEngine>,
+    allocate_fn: AllocateStringFn,
+) -> ExternResult<NullableCvoid> {
+    let writer = unsafe { writer.as_ref() };
+    let engine = unsafe { engine.as_ref() };
+    checkpoint_path_impl(writer, allocate_fn).into_extern_result(&engine)
+}
+
+fn checkpoint_path_impl(
+    writer: &CheckpointWriter,
+    allocate_fn: AllocateStringFn,
+) -> DeltaResult<NullableCvoid> {
+    let path = writer.checkpoint_path()?.to_string();
+    Ok(allocate_fn(kernel_string_slice!(path)))
+}
+
+/// Get an iterator over the data to write to the checkpoint file. The iterator must be fully
+/// consumed with [`checkpoint_data_next`], and all of its data written to the checkpoint path,
+/// before passing it to [`checkpoint_finalize`].
+///
+/// # Safety
+///
+/// Caller is responsible for passing a valid writer handle and engine handle.
+#[no_mangle]
+pub unsafe extern "C" fn checkpoint_data(
+    writer: Handle<ExclusiveCheckpointWriter>,
+    engine: Handle<SharedExternEngine>,
+) -> ExternResult<Handle<ExclusiveCheckpointDataIterator>> {
+    let writer = unsafe { writer.as_ref() };
+    let engine = unsafe { engine.clone_as_arc() };
+    checkpoint_data_impl(writer, engine.clone()).into_extern_result(&engine.as_ref())
+}
+
+fn checkpoint_data_impl(
+    writer: &CheckpointWriter,
+    engine: Arc<dyn ExternEngine>,
+) -> DeltaResult<Handle<ExclusiveCheckpointDataIterator>> {
+    let data = writer.checkpoint_data(engine.engine().as_ref())?;
+    Ok(Box::new(CheckpointData { data, engine }).into())
+}
+
+/// Call the engine back with the next batch of checkpoint data. The visitor is provided with the
+/// data and a selection vector of the same length, where `true` marks the rows to write to the
+/// checkpoint file. The _engine_ owns both, and must free them with [`free_engine_data`] and
+/// [`free_bool_slice`] respectively.
+///
+/// Returns `false` once the iterator is exhausted.
+///
+/// # Safety
+///
+/// The iterator must be valid (returned by [`checkpoint_data`]) and not yet consumed by
+/// [`checkpoint_finalize`] or freed by [`free_checkpoint_data_iter`]. The visitor function pointer
+/// must be non-null.
+///
+/// [`free_engine_data`]: crate::free_engine_data
+/// [`free_bool_slice`]: crate::free_bool_slice
+#[no_mangle]
+pub unsafe extern "C" fn checkpoint_data_next(
+    mut data: Handle<ExclusiveCheckpointDataIterator>,
+    engine_context: NullableCvoid,
+    engine_visitor: extern "C" fn(
+        engine_context: NullableCvoid,
+        engine_data: Handle<ExclusiveEngineData>,
+        selection_vector: KernelBoolSlice,
+    ),
+) -> ExternResult<bool> {
+    let iter = unsafe { data.as_mut() };
+    checkpoint_data_next_impl(&mut iter.data, engine_context, engine_visitor)
+        .into_extern_result(iter.engine.error_allocator())
+}
+
+fn checkpoint_data_next_impl(
+    data: &mut CheckpointDataIterator,
+    engine_context: NullableCvoid,
+    engine_visitor: extern "C" fn(
+        engine_context: NullableCvoid,
+        engine_data: Handle<ExclusiveEngineData>,
+        selection_vector: KernelBoolSlice,
+    ),
+) -> DeltaResult<bool> {
+    let Some(filtered_data) = data.next().transpose()? else {
+        return Ok(false);
+    };
+    (engine_visitor)(
+        engine_context,
+        filtered_data.data.into(),
+        filtered_data.selection_vector.into(),
+    );
+    Ok(true)
+}
+
+/// Free a checkpoint data iterator without finalizing the checkpoint.
+///
+/// # Safety
+///
+/// Caller is responsible for (at most once) passing a valid pointer returned by a call to
+/// [`checkpoint_data`].
+#[no_mangle]
+pub unsafe extern "C" fn free_checkpoint_data_iter(data: Handle<ExclusiveCheckpointDataIterator>) {
+    data.drop_handle();
+}
+
+/// Finalize the checkpoint by writing the `_last_checkpoint` file, given the metadata of the
+/// checkpoint file the engine wrote. This must only be called once the checkpoint data iterator is
+/// exhausted and all its data has been written to the checkpoint path.
+///
+/// # Safety
+///
+/// Caller is responsible for passing valid handles and file metadata. CONSUMES the writer and the
+/// data iterator, even on failure.
+#[no_mangle]
+pub unsafe extern "C" fn checkpoint_finalize(
+    writer: Handle<ExclusiveCheckpointWriter>,
+    engine: Handle<SharedExternEngine>,
+    metadata: &FileMeta,
+    data: Handle<ExclusiveCheckpointDataIterator>,
+) -> ExternResult<bool> {
+    let writer = unsafe { writer.into_inner() };
+    let data = unsafe { data.into_inner() };
+    let engine = unsafe { engine.as_ref() };
+    let metadata = unsafe { kernel_file_meta(metadata) };
+    checkpoint_finalize_impl(*writer, engine, metadata, *data).into_extern_result(&engine)
+}
+
+fn checkpoint_finalize_impl(
+    writer: CheckpointWriter,
+    extern_engine: &dyn ExternEngine,
+    metadata: DeltaResult<delta_kernel::FileMeta>,
+    data: CheckpointData,
+) -> DeltaResult<bool> {
+    writer.finalize(extern_engine.engine().as_ref(), &metadata?, data.data)?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::KernelError;
+    use crate::ffi_test_utils::{
+        allocate_err, allocate_str, assert_extern_result_error_with_message, ok_or_panic,
+        recover_string,
+    };
+    use crate::{engine_to_handle, free_engine, free_snapshot, kernel_string_slice, snapshot};
+    use delta_kernel::arrow::array::{BooleanArray, RecordBatch};
+    use delta_kernel::arrow::compute::filter_record_batch;
+    use delta_kernel::engine::arrow_data::ArrowEngineData;
+    use delta_kernel::engine::default::executor::tokio::TokioBackgroundExecutor;
+    use delta_kernel::engine::default::DefaultEngine;
+    use delta_kernel::parquet::arrow::ArrowWriter;
+    use delta_kernel::EngineData;
+    use object_store::memory::InMemory;
+    use object_store::path::Path;
+    use object_store::ObjectStore;
+    use std::ptr::NonNull;
+    use test_utils::{actions_to_string, add_commit, TestAction};
+
+    // collects the selected rows of each batch into the `Vec<RecordBatch>` behind the context
+    extern "C" fn collect_selected_rows(
+        engine_context: NullableCvoid,
+        engine_data: Handle<ExclusiveEngineData>,
+        selection_vector: KernelBoolSlice,
+    ) {
+        let batches = unsafe { engine_context.unwrap().cast::<Vec<RecordBatch>>().as_mut() };
+        let data: Box<dyn EngineData> = unsafe { engine_data.into_inner() };
+        let batch: RecordBatch = data
+            .into_any()
+            .downcast::<ArrowEngineData>()
+            .unwrap()
+            .into();
+        let selection_vector = unsafe { selection_vector.into_vec() };
+        assert_eq!(selection_vector.len(), batch.num_rows());
+        let batch = filter_record_batch(&batch, &BooleanArray::from(selection_vector)).unwrap();
+        batches.push(batch);
+    }
+
+    async fn setup() -> (Arc<InMemory>, Handle<SharedExternEngine>) {
+        let storage = Arc::new(InMemory::new());
+        add_commit(
+            storage.as_ref(),
+            0,
+            actions_to_string(vec![TestAction::Metadata]),
+        )
+        .await
+        .unwrap();
+        add_commit(
+            storage.as_ref(),
+            1,
+            actions_to_string(vec![
+                TestAction::Add("a.parquet".into()),
+                TestAction::Add("b.parquet".into()),
+            ]),
+        )
+        .await
+        .unwrap();
+        let engine = DefaultEngine::new(storage.clone(), Arc::new(TokioBackgroundExecutor::new()));
+        (storage, engine_to_handle(Arc::new(engine), allocate_err))
+    }
+
+    #[tokio::test]
+    async fn test_write_checkpoint() -> Result<(), Box<dyn std::error::Error>> {
+        let (storage, engine) = setup().await;
+        let path = "memory:///";
+        let snapshot =
+            unsafe { ok_or_panic(snapshot(kernel_string_slice!(path), engine.shallow_copy())) };
+        let writer = unsafe {
+            ok_or_panic(checkpoint_writer(
+                snapshot.shallow_copy(),
+                engine.shallow_copy(),
+            ))
+        };
+
+        let checkpoint_path = unsafe {
+            ok_or_panic(checkpoint_path(
+                writer.shallow_copy(),
+                engine.shallow_copy(),
+                allocate_str,
+            ))
+        };
+        let checkpoint_path = recover_string(checkpoint_path.unwrap());
+        assert_eq!(
+            checkpoint_path,
+            "memory:///_delta_log/00000000000000000001.checkpoint.parquet"
+        );
+
+        // consume the checkpoint data, writing the selected rows as the engine would
+        let data = unsafe {
+            ok_or_panic(checkpoint_data(
+                writer.shallow_copy(),
+                engine.shallow_copy(),
+            ))
+        };
+        let mut batches: Vec<RecordBatch> = vec![];
+        let context = NonNull::from(&mut batches).cast();
+        while unsafe {
+            ok_or_panic(checkpoint_data_next(
+                data.shallow_copy(),
+                Some(context),
+                collect_selected_rows,
+            ))
+        } {}
+        let mut buffer = vec![];
+        let mut parquet_writer = ArrowWriter::try_new(&mut buffer, batches[0].schema(), None)?;
+        for batch in &batches {
+            parquet_writer.write(batch)?;
+        }
+        parquet_writer.close()?;
+        let size = buffer.len();
+        let location = Path::from("_delta_log/00000000000000000001.checkpoint.parquet");
+        storage.put(&location, buffer.into()).await?;
+
+        let metadata = FileMeta {
+            path: kernel_string_slice!(checkpoint_path),
+            last_modified: 0,
+            size,
+        };
+        unsafe {
+            ok_or_panic(checkpoint_finalize(
+                writer,
+                engine.shallow_copy(),
+                &metadata,
+                data,
+            ))
+        };
+
+        // the `_last_checkpoint` file points to the new checkpoint
+        let last_checkpoint = storage
+            .get(&Path::from("_delta_log/_last_checkpoint"))
+            .await?
+            .bytes()
+            .await?;
+        let last_checkpoint: serde_json::Value = serde_json::from_slice(&last_checkpoint)?;
+        assert_eq!(last_checkpoint["version"], 1);
+        assert_eq!(last_checkpoint["numOfAddFiles"], 2);
+        assert_eq!(last_checkpoint["sizeInBytes"], size);
+
+        unsafe {
+            free_snapshot(snapshot);
+            free_engine(engine);
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_finalize_unconsumed_checkpoint_data() {
+        let (_storage, engine) = setup().await;
+        let path = "memory:///";
+        let snapshot =
+            unsafe { ok_or_panic(snapshot(kernel_string_slice!(path), engine.shallow_copy())) };
+        let writer = unsafe {
+            ok_or_panic(checkpoint_writer(
+                snapshot.shallow_copy(),
+                engine.shallow_copy(),
+            ))
+        };
+        let data = unsafe {
+            ok_or_panic(checkpoint_data(
+                writer.shallow_copy(),
+                engine.shallow_copy(),
+            ))
+        };
+
+        let checkpoint_path = "memory:///_delta_log/00000000000000000001.checkpoint.parquet";
+        let metadata = FileMeta {
+            path: kernel_string_slice!(checkpoint_path),
+            last_modified: 0,
+            size: 0,
+        };
+        let res = unsafe { checkpoint_finalize(writer, engine.shallow_copy(), &metadata, data) };
+        assert_extern_result_error_with_message(
+            res,
+            KernelError::CheckpointWriteError,
+            "Error writing checkpoint: The checkpoint data iterator must be fully consumed and written to storage before calling finalize",
+        );
+
+        unsafe {
+            free_snapshot(snapshot);
+            free_engine(engine);
+        }
+    }
+}
@@ -1,21 +1,21 @@
 //! EngineData related ffi code
 
-#[cfg(feature = "default-engine-base")]
+#[cfg(feature = "arrow")]
 use delta_kernel::arrow;
-#[cfg(feature = "default-engine-base")]
+#[cfg(feature = "arrow")]
 use delta_kernel::arrow::array::{
     ffi::{FFI_ArrowArray, FFI_ArrowSchema},
     ArrayData, RecordBatch, StructArray,
 };
-#[cfg(feature = "default-engine-base")]
+#[cfg(feature = "arrow")]
 use delta_kernel::engine::arrow_data::ArrowEngineData;
-#[cfg(feature = "default-engine-base")]
+#[cfg(feature = "arrow")]
 use delta_kernel::DeltaResult;
 use delta_kernel::EngineData;
 use std::ffi::c_void;
 
 use crate::ExclusiveEngineData;
-#[cfg(feature = "default-engine-base")]
+#[cfg(feature = "arrow")]
 use crate::{ExternResult, IntoExternResult, SharedExternEngine};
 
 use super::handle::Handle;
@@ -55,7 +55,7 @@ unsafe fn get_raw_engine_data_impl(data: &mut Handle<ExclusiveEngineData>) -> &m
 /// Struct to allow binding to the arrow [C Data
 /// Interface](https://arrow.apache.org/docs/format/CDataInterface.html). This includes the data and
 /// the schema.
-#[cfg(feature = "default-engine-base")]
+#[cfg(feature = "arrow")]
 #[repr(C)]
 pub struct ArrowFFIData {
     pub array: FFI_ArrowArray,
@@ -70,7 +70,7 @@ pub struct ArrowFFIData {
 /// # Safety
 /// data_handle must be a valid ExclusiveEngineData as read by the
 /// [`delta_kernel::engine::default::DefaultEngine`] obtained from `get_default_engine`.
-#[cfg(feature = "default-engine-base")]
+#[cfg(feature = "arrow")]
 #[no_mangle]
 pub unsafe extern "C" fn get_raw_arrow_data(
     data: Handle<ExclusiveEngineData>,
@@ -82,7 +82,7 @@ pub unsafe extern "C" fn get_raw_arrow_data(
 }
 
 // TODO: This method leaks the returned pointer memory. How will the engine free it?
-#[cfg(feature = "default-engine-base")]
+#[cfg(feature = "arrow")]
 fn get_raw_arrow_data_impl(data: Box<dyn EngineData>) -> DeltaResult<*mut ArrowFFIData> {
     let record_batch: delta_kernel::arrow::array::RecordBatch = data
         .into_any()
@@ -108,7 +108,7 @@ fn get_raw_arrow_data_impl(data: Box<dyn EngineData>) -> DeltaResult<*mut ArrowF
 /// - `array` must be a valid FFI_ArrowArray
 /// - `schema` must be a valid pointer to a FFI_ArrowSchema
 /// - `engine` must be a valid Handle to a SharedExternEngine
-#[cfg(feature = "default-engine-base")]
+#[cfg(feature = "arrow")]
 #[no_mangle]
 pub unsafe extern "C" fn get_engine_data(
     array: FFI_ArrowArray,
@@ -118,7 +118,7 @@ pub unsafe extern "C" fn get_engine_data(
     get_engine_data_impl(array, schema).into_extern_result(&engine.as_ref())
 }
 
-#[cfg(feature = "default-engine-base")]
+#[cfg(feature = "arrow")]
 unsafe fn get_engine_data_impl(
     array: FFI_ArrowArray,
     schema: &FFI_ArrowSchema,
@@ -24,6 +24,23 @@ pub struct FileMeta {
     pub size: usize,
 }
 
+/// Convert an engine-provided [`FileMeta`] into a kernel [`delta_kernel::FileMeta`].
+///
+/// # Safety
+///
+/// Caller is responsible for passing a `file` whose `path` is a valid string slice.
+pub(crate) unsafe fn kernel_file_meta(file: &FileMeta) -> DeltaResult<delta_kernel::FileMeta> {
+    let path: &str = unsafe { TryFromStringSlice::try_from_slice(&file.path) }?;
+    Ok(delta_kernel::FileMeta {
+        location: Url::parse(path)?,
+        last_modified: file.last_modified,
+        size: file
+            .size
+            .try_into()
+            .map_err(|_| Error::generic_err("unable to convert to FileSize"))?,
+    })
+}
+
 // Intentionally opaque to the engine.
 pub struct FileReadResultIterator {
     // Box -> Wrap its unsized content this struct is fixed-size with thin pointers.
@@ -108,30 +125,20 @@ pub unsafe extern "C" fn read_parquet_file(
 ) -> ExternResult<Handle<ExclusiveFileReadResultIterator>> {
     let engine = unsafe { engine.clone_as_arc() };
     let physical_schema = unsafe { physical_schema.clone_as_arc() };
-    let path = unsafe { TryFromStringSlice::try_from_slice(&file.path) };
-    let res = read_parquet_file_impl(engine.clone(), path, file, physical_schema);
+    let file = unsafe { kernel_file_meta(file) };
+    let res = read_parquet_file_impl(engine.clone(), file, physical_schema);
     res.into_extern_result(&engine.as_ref())
 }
 
 fn read_parquet_file_impl(
     extern_engine: Arc<dyn ExternEngine>,
-    path: DeltaResult<&str>,
-    file: &FileMeta,
+    file: DeltaResult<delta_kernel::FileMeta>,
     physical_schema: Arc<Schema>,
 ) -> DeltaResult<Handle<ExclusiveFileReadResultIterator>> {
     let engine = extern_engine.engine();
     let parquet_handler = engine.parquet_handler();
-    let location = Url::parse(path?)?;
-    let delta_fm = delta_kernel::FileMeta {
-        location,
-        last_modified: file.last_modified,
-        size: file
-            .size
-            .try_into()
-            .map_err(|_| Error::generic_err("unable to convert to FileSize"))?,
-    };
     // TODO: Plumb the predicate through the FFI?
-    let data = parquet_handler.read_parquet_files(&[delta_fm], physical_schema, None)?;
+    let data = parquet_handler.read_parquet_files(&[file?], physical_schema, None)?;
     let res = Box::new(FileReadResultIterator {
         data,
         engine: extern_engine,
@@ -0,0 +1,1205 @@
+//! Engines whose handlers are implemented by the engine itself, rather than by the default engine.
+//!
+//! An engine with its own I/O stack describes its handlers as tables of function pointers
+//! ([`EngineHandlers`]) and passes them to [`get_engine_with_handlers`]. Kernel then drives log
+//! replay through the engine's callbacks. Each callback is handed a [`HandlerResults`] through
+//! which it returns its results ([`handler_results_push_file`], [`handler_results_push_bytes`] and
+//! [`handler_results_push_engine_data`]) or reports a failure ([`handler_results_set_error`]).
+//!
+//! Engine data crosses the boundary as [`ExclusiveEngineData`] handles in both directions, and
+//! must hold Arrow data (see [`get_engine_data`] and [`get_raw_arrow_data`]). Handles passed to a
+//! callback are owned by the engine, which must free them once done; handles pushed into a
+//! [`HandlerResults`] are owned by kernel.
+//!
+//! This module only needs the `arrow` feature, so an engine can use its own handlers without
+//! building the default engine.
+//!
+//! # Limitations
+//!
+//! Of the JSON operations, only reading JSON files ([`EngineJsonHandler::read_json_file`]) can be
+//! provided by the engine. Kernel always parses JSON strings (e.g. the stats of checkpoint files)
+//! and serializes the JSON files it writes (e.g. commits) itself, with Arrow, and writes them
+//! through the engine's storage handler.
+//!
+//! [`get_engine_data`]: crate::engine_data::get_engine_data
+//! [`get_raw_arrow_data`]: crate::engine_data::get_raw_arrow_data
+
+use std::sync::Arc;
+
+use bytes::Bytes;
+use delta_kernel::arrow::array::{RecordBatch, StringArray};
+use delta_kernel::arrow::datatypes::{DataType as ArrowDataType, Field, Schema as ArrowSchema};
+use delta_kernel::engine::arrow_data::ArrowEngineData;
+use delta_kernel::engine::arrow_expression::ArrowEvaluationHandler;
+use delta_kernel::schema::{DataType, SchemaRef, StructField, StructType};
+use delta_kernel::{
+    DeltaResult, Engine, EngineData, Error, EvaluationHandler, Expression, ExpressionEvaluator,
+    FileDataReadResultIterator, FileSlice, JsonHandler, ParquetHandler, Predicate,
+    PredicateEvaluator, PredicateRef, StorageHandler,
+};
+use tracing::debug;
+use url::Url;
+
+use crate::engine_funcs::{kernel_file_meta, FileMeta};
+use crate::error::{AllocateErrorFn, ExternResult, IntoExternResult, KernelError};
+use crate::expressions::{SharedExpression, SharedPredicate};
+use crate::handle::Handle;
+use crate::{
+    engine_to_handle, kernel_string_slice, ExclusiveEngineData, KernelStringSlice, NullableCvoid,
+    SharedExternEngine, SharedSchema, TryFromStringSlice,
+};
+
+/// Collects the results of a call to one of the engine's handler callbacks. Intentionally opaque
+/// to the engine, which can only push results into it.
+#[derive(Default)]
+pub struct HandlerResults {
+    files: Vec<delta_kernel::FileMeta>,
+    bytes: Vec<u8>,
+    data: Vec<Box<dyn EngineData>>,
+    error: Option<Error>,
+}
+
+impl HandlerResults {
+    // Call an engine callback with fresh results, failing if the callback reported an error.
+    fn collect(call: impl FnOnce(&mut HandlerResults)) -> DeltaResult<HandlerResults> {
+        let mut results = HandlerResults::default();
+        call(&mut results);
+        match results.error.take() {
+            Some(error) => Err(error),
+            None => Ok(results),
+        }
+    }
+
+    fn set_error(&mut self, error: Error) {
+        // keep the first error, which is likely the root cause of any later ones
+        self.error.get_or_insert(error);
+    }
+
+    fn into_file(self) -> DeltaResult<delta_kernel::FileMeta> {
+        let mut files = self.files.into_iter();
+        match (files.next(), files.next()) {
+            (Some(file), None) => Ok(file),
+            _ => Err(Error::generic(
+                "Engine handler must return exactly one file",
+            )),
+        }
+    }
+
+    fn into_engine_data(self) -> DeltaResult<Box<dyn EngineData>> {
+        let mut data = self.data.into_iter();
+        match (data.next(), data.next()) {
+            (Some(data), None) => Ok(data),
+            _ => Err(Error::generic(
+                "Engine handler must return exactly one batch",
+            )),
+        }
+    }
+}
+
+/// Return a file from a handler callback.
+///
+/// # Safety
+///
+/// Caller is responsible for passing the results given to the callback, and valid file metadata.
+#[no_mangle]
+pub unsafe extern "C" fn handler_results_push_file(results: &mut HandlerResults, file: &FileMeta) {
+    match unsafe { kernel_file_meta(file) } {
+        Ok(file) => results.files.push(file),
+        Err(error) => results.set_error(error),
+    }
+}
+
+/// Return (a chunk of) the contents of a file from a handler callback. The bytes are copied, so
+/// the engine keeps ownership of `data`.
+///
+/// # Safety
+///
+/// Caller is responsible for passing the results given to the callback, and a pointer to at least
+/// `len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn handler_results_push_bytes(
+    results: &mut HandlerResults,
+    data: *const u8,
+    len: usize,
+) {
+    if len > 0 {
+        let data = unsafe { std::slice::from_raw_parts(data, len) };
+        results.bytes.extend_from_slice(data);
+    }
+}
+
+/// Return a batch of engine data from a handler callback. CONSUMES the engine data.
+///
+/// # Safety
+///
+/// Caller is responsible for passing the results given to the callback, and a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn handler_results_push_engine_data(
+    results: &mut HandlerResults,
+    data: Handle<ExclusiveEngineData>,
+) {
+    results.data.push(unsafe { data.into_inner() });
+}
+
+/// Report that a handler callback failed, in which case anything else it returned is ignored.
+/// Storage handlers must report a missing file as `FileNotFoundError`, and a `put` of an existing
+/// file without `overwrite` as `FileAlreadyExists`, since kernel relies on these to detect
+/// conflicts.
+///
+/// # Safety
+///
+/// Caller is responsible for passing the results given to the callback, and a valid message.
+#[no_mangle]
+pub unsafe extern "C" fn handler_results_set_error(
+    results: &mut HandlerResults,
+    etype: KernelError,
+    message: KernelStringSlice,
+) {
+    let message = unsafe { String::try_from_slice(&message) };
+    let error = message.map(|message| match etype {
+        KernelError::FileNotFoundError => Error::file_not_found(message),
+        KernelError::FileAlreadyExists => Error::FileAlreadyExists(message),
+        KernelError::UnsupportedError => Error::unsupported(message),
+        _ => Error::generic(message),
+    });
+    results.set_error(error.unwrap_or_else(|error| error));
+}
+
+/// A range of bytes to read from a file, from `start` (inclusive) to `end` (exclusive).
+#[repr(C)]
+pub struct ByteRange {
+    pub start: u64,
+    pub end: u64,
+}
+
+/// The storage operations of an engine, see [`delta_kernel::StorageHandler`]. The write operations
+/// may be null if the engine only reads tables.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct EngineStorageHandler {
+    /// Return the files in the same directory as `path` whose path is lexicographically greater
+    /// than `path`, sorted by path. If `path` ends with `/`, return all the files in that directory.
+    pub list_from: extern "C" fn(
+        context: NullableCvoid,
+        path: KernelStringSlice,
+        results: &mut HandlerResults,
+    ),
+    /// Return the contents of the file at `path`, or of the given byte range of it if `range` is
+    /// not null.
+    pub read_file: extern "C" fn(
+        context: NullableCvoid,
+        path: KernelStringSlice,
+        range: Option<&ByteRange>,
+        results: &mut HandlerResults,
+    ),
+    /// Write `len` bytes of `data` to the file at `path`. Unless `overwrite` is set, this must
+    /// atomically fail if the file already exists.
+    pub put: Option<
+        extern "C" fn(
+            context: NullableCvoid,
+            path: KernelStringSlice,
+            data: *const u8,
+            len: usize,
+            overwrite: bool,
+            results: &mut HandlerResults,
+        ),
+    >,
+    /// Return the file at `path`.
+    pub head: Option<
+        extern "C" fn(
+            context: NullableCvoid,
+            path: KernelStringSlice,
+            results: &mut HandlerResults,
+        ),
+    >,
+    /// Delete the file at `path`, if it exists.
+    pub delete: Option<
+        extern "C" fn(
+            context: NullableCvoid,
+            path: KernelStringSlice,
+            results: &mut HandlerResults,
+        ),
+    >,
+    /// Copy the file at `from` to `to`, overwriting `to` if it exists.
+    pub copy: Option<
+        extern "C" fn(
+            context: NullableCvoid,
+            from: KernelStringSlice,
+            to: KernelStringSlice,
+            results: &mut HandlerResults,
+        ),
+    >,
+}
+
+/// The JSON operations of an engine, see [`delta_kernel::JsonHandler`]. If `read_json_file` is
+/// null, kernel reads JSON files through the storage handler and parses them itself.
+///
+/// NOTE: `read_json_file` is the only JSON operation an engine can override. Kernel always parses
+/// JSON strings ([`JsonHandler::parse_json`]) and serializes JSON files
+/// ([`JsonHandler::write_json_file`]) itself; see the [module docs](self) for details.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct EngineJsonHandler {
+    /// Return the contents of the newline-delimited JSON `file`, as batches of `physical_schema`.
+    pub read_json_file: Option<
+        extern "C" fn(
+            context: NullableCvoid,
+            file: &FileMeta,
+            physical_schema: Handle<SharedSchema>,
+            results: &mut HandlerResults,
+        ),
+    >,
+}
+
+/// The Parquet operations of an engine, see [`delta_kernel::ParquetHandler`].
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct EngineParquetHandler {
+    /// Return the contents of the Parquet `file`, as batches of exactly the columns of
+    /// `physical_schema`, in schema order.
+    pub read_parquet_file: extern "C" fn(
+        context: NullableCvoid,
+        file: &FileMeta,
+        physical_schema: Handle<SharedSchema>,
+        results: &mut HandlerResults,
+    ),
+}
+
+/// An expression or predicate evaluator created by the engine. Kernel calls `free_evaluator` (if
+/// not null) with the evaluator's `context` once it no longer needs the evaluator.
+#[repr(C)]
+pub struct EngineEvaluator {
+    pub context: NullableCvoid,
+    /// Return the result of the evaluation on `batch`, as a single batch with one row per row of
+    /// `batch`.
+    pub evaluate: extern "C" fn(
+        context: NullableCvoid,
+        batch: Handle<ExclusiveEngineData>,
+        results: &mut HandlerResults,
+    ),
+    pub free_evaluator: Option<extern "C" fn(context: NullableCvoid)>,
+}
+
+/// The expression evaluation operations of an engine, see [`delta_kernel::EvaluationHandler`].
+/// Either all or none of the functions must be provided; if none are, kernel evaluates
+/// expressions itself.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct EngineEvaluationHandler {
+    /// Create an evaluator of `expression` on batches of `input_schema`, which produces batches of
+    /// `output_schema`.
+    pub new_expression_evaluator: Option<
+        extern "C" fn(
+            context: NullableCvoid,
+            input_schema: Handle<SharedSchema>,
+            expression: Handle<SharedExpression>,
+            output_schema: Handle<SharedSchema>,
+        ) -> EngineEvaluator,
+    >,
+    /// Create an evaluator of `predicate` on batches of `input_schema`, which produces batches of a
+    /// single nullable boolean column named `output`.
+    pub new_predicate_evaluator: Option<
+        extern "C" fn(
+            context: NullableCvoid,
+            input_schema: Handle<SharedSchema>,
+            predicate: Handle<SharedPredicate>,
+        ) -> EngineEvaluator,
+    >,
+    /// Return a single-row batch of `output_schema` where all values are null.
+    pub null_row: Option<
+        extern "C" fn(
+            context: NullableCvoid,
+            output_schema: Handle<SharedSchema>,
+            results: &mut HandlerResults,
+        ),
+    >,
+}
+
+/// The handlers of an engine. `context` is passed to every callback, and `free_context` (if not
+/// null) is called with it once kernel no longer uses any of the handlers.
+#[repr(C)]
+pub struct EngineHandlers {
+    pub context: NullableCvoid,
+    pub free_context: Option<extern "C" fn(context: NullableCvoid)>,
+    pub storage: EngineStorageHandler,
+    pub json: EngineJsonHandler,
+    pub parquet: EngineParquetHandler,
+    pub evaluation: EngineEvaluationHandler,
+}
+
+/// Create an engine which uses the given handlers. It is the responsibility of the _engine_ to
+/// free the returned engine with [`free_engine`]. The engine context is freed even if this fails.
+///
+/// # Safety
+///
+/// Caller is responsible for passing valid function pointers, which must remain callable (and the
+/// context valid) until the context is freed. Kernel doesn't use any threading or concurrency, but
+/// a multi-threaded engine may call into kernel (and thus its own callbacks) from several threads.
+///
+/// [`free_engine`]: crate::free_engine
+#[no_mangle]
+pub unsafe extern "C" fn get_engine_with_handlers(
+    handlers: EngineHandlers,
+    allocate_error: AllocateErrorFn,
+) -> ExternResult<Handle<SharedExternEngine>> {
+    get_engine_with_handlers_impl(handlers)
+        .map(|engine| engine_to_handle(Arc::new(engine), allocate_error))
+        .into_extern_result(&allocate_error)
+}
+
+fn get_engine_with_handlers_impl(handlers: EngineHandlers) -> DeltaResult<HandlerEngine> {
+    let context = Arc::new(HandlerContext {
+        context: handlers.context,
+        free_context: handlers.free_context,
+    });
+    let storage = Arc::new(ExternStorageHandler {
+        vtable: handlers.storage,
+        context: context.clone(),
+    });
+    let json = Arc::new(ExternJsonHandler {
+        vtable: handlers.json,
+        storage: storage.clone(),
+    });
+    let parquet = Arc::new(ExternParquetHandler {
+        vtable: handlers.parquet,
+        context: context.clone(),
+    });
+    let evaluation: Arc<dyn EvaluationHandler> = match handlers.evaluation {
+        EngineEvaluationHandler {
+            new_expression_evaluator: None,
+            new_predicate_evaluator: None,
+            null_row: None,
+        } => Arc::new(ArrowEvaluationHandler),
+        EngineEvaluationHandler {
+            new_expression_evaluator: Some(new_expression_evaluator),
+            new_predicate_evaluator: Some(new_predicate_evaluator),
+            null_row: Some(null_row),
+        } => Arc::new(ExternEvaluationHandler {
+            new_expression_evaluator,
+            new_predicate_evaluator,
+            null_row,
+            context,
+        }),
+        _ => {
+            return Err(Error::generic(
+                "Engine evaluation handler must provide either all or none of its functions",
+            ))
+        }
+    };
+    Ok(HandlerEngine {
+        storage,
+        json,
+        parquet,
+        evaluation,
+    })
+}
+
+// The engine's context, freed once the last handler (or evaluator) using it is dropped.
+struct HandlerContext {
+    context: NullableCvoid,
+    free_context: Option<extern "C" fn(context: NullableCvoid)>,
+}
+
+impl Drop for HandlerContext {
+    fn drop(&mut self) {
+        debug!("dropping engine handler context");
+        if let Some(free_context) = self.free_context {
+            free_context(self.context);
+        }
+    }
+}
+
+/// # Safety
+///
+/// The context is only ever passed back to the engine. If the engine uses threads, it is
+/// responsible for making its callbacks safe to call from any thread, as for `ExternEngineVtable`.
+unsafe impl Send for HandlerContext {}
+
+/// # Safety
+///
+/// See the `Send` impl above.
+unsafe impl Sync for HandlerContext {}
+
+struct HandlerEngine {
+    storage: Arc<ExternStorageHandler>,
+    json: Arc<ExternJsonHandler>,
+    parquet: Arc<ExternParquetHandler>,
+    evaluation: Arc<dyn EvaluationHandler>,
+}
+
+impl Engine for HandlerEngine {
+    fn evaluation_handler(&self) -> Arc<dyn EvaluationHandler> {
+        self.evaluation.clone()
+    }
+
+    fn storage_handler(&self) -> Arc<dyn StorageHandler> {
+        self.storage.clone()
+    }
+
+    fn json_handler(&self) -> Arc<dyn JsonHandler> {
+        self.json.clone()
+    }
+
+    fn parquet_handler(&self) -> Arc<dyn ParquetHandler> {
+        self.parquet.clone()
+    }
+}
+
+// Call `f` with the kernel representation of `file`, which borrows the file's path.
+fn with_file_meta<T>(file: &delta_kernel::FileMeta, f: impl FnOnce(&FileMeta) -> T) -> T {
+    let path = file.location.to_string();
+    let file = FileMeta {
+        path: kernel_string_slice!(path),
+        last_modified: file.last_modified,
+        size: file.size as usize,
+    };
+    f(&file)
+}
+
+fn unsupported(operation: &str) -> Error {
+    Error::unsupported(format!(
+        "Engine storage handler does not support {operation}"
+    ))
+}
+
+struct ExternStorageHandler {
+    vtable: EngineStorageHandler,
+    context: Arc<HandlerContext>,
+}
+
+impl ExternStorageHandler {
+    fn read_file(&self, path: &Url, range: Option<ByteRange>) -> DeltaResult<Bytes> {
+        let path = path.to_string();
+        let results = HandlerResults::collect(|results| {
+            let path = kernel_string_slice!(path);
+            (self.vtable.read_file)(self.context.context, path, range.as_ref(), results)
+        })?;
+        Ok(results.bytes.into())
+    }
+}
+
+impl StorageHandler for ExternStorageHandler {
+    fn list_from(
+        &self,
+        path: &Url,
+    ) -> DeltaResult<Box<dyn Iterator<Item = DeltaResult<delta_kernel::FileMeta>>>> {
+        let path = path.to_string();
+        let results = HandlerResults::collect(|results| {
+            (self.vtable.list_from)(self.context.context, kernel_string_slice!(path), results)
+        })?;
+        Ok(Box::new(results.files.into_iter().map(Ok)))
+    }
+
+    fn read_files(
+        &self,
+        files: Vec<FileSlice>,
+    ) -> DeltaResult<Box<dyn Iterator<Item = DeltaResult<Bytes>>>> {
+        let handler = Self {
+            vtable: self.vtable,
+            context: self.context.clone(),
+        };
+        Ok(Box::new(files.into_iter().map(move |(path, range)| {
+            let range = range.map(|range| ByteRange {
+                start: range.start,
+                end: range.end,
+            });
+            handler.read_file(&path, range)
+        })))
+    }
+
+    fn put(&self, path: &Url, data: Bytes, overwrite: bool) -> DeltaResult<()> {
+        let put = self.vtable.put.ok_or_else(|| unsupported("put"))?;
+        let path = path.to_string();
+        HandlerResults::collect(|results| {
+            let path = kernel_string_slice!(path);
+            put(
+                self.context.context,
+                path,
+                data.as_ptr(),
+                data.len(),
+                overwrite,
+                results,
+            )
+        })?;
+        Ok(())
+    }
+
+    fn head(&self, path: &Url) -> DeltaResult<delta_kernel::FileMeta> {
+        let head = self.vtable.head.ok_or_else(|| unsupported("head"))?;
+        let path = path.to_string();
+        HandlerResults::collect(|results| {
+            head(self.context.context, kernel_string_slice!(path), results)
+        })?
+        .into_file()
+    }
+
+    fn delete(&self, path: &Url) -> DeltaResult<()> {
+        let delete = self.vtable.delete.ok_or_else(|| unsupported("delete"))?;
+        let path = path.to_string();
+        HandlerResults::collect(|results| {
+            delete(self.context.context, kernel_string_slice!(path), results)
+        })?;
+        Ok(())
+    }
+
+    fn copy(&self, from: &Url, to: &Url) -> DeltaResult<()> {
+        let copy = self.vtable.copy.ok_or_else(|| unsupported("copy"))?;
+        let (from, to) = (from.to_string(), to.to_string());
+        HandlerResults::collect(|results| {
+            let (from, to) = (kernel_string_slice!(from), kernel_string_slice!(to));
+            copy(self.context.context, from, to, results)
+        })?;
+        Ok(())
+    }
+}
+
+struct ExternJsonHandler {
+    vtable: EngineJsonHandler,
+    storage: Arc<ExternStorageHandler>,
+}
+
+impl ExternJsonHandler {
+    // Read a JSON file through the storage handler, and parse it with kernel's JSON parser.
+    fn read_and_parse_json_file(
+        storage: &ExternStorageHandler,
+        file: &delta_kernel::FileMeta,
+        physical_schema: SchemaRef,
+    ) -> DeltaResult<Box<dyn EngineData>> {
+        let bytes = storage.read_file(&file.location, None)?;
+        let contents = std::str::from_utf8(&bytes).map_err(Error::generic_err)?;
+        let lines: StringArray = contents
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(Some)
+            .collect();
+        let schema = ArrowSchema::new(vec![Field::new("json", ArrowDataType::Utf8, true)]);
+        let json_strings = RecordBatch::try_new(schema.into(), vec![Arc::new(lines)])?;
+        delta_kernel::engine::parse_json(
+            Box::new(ArrowEngineData::new(json_strings)),
+            physical_schema,
+        )
+    }
+}
+
+impl JsonHandler for ExternJsonHandler {
+    fn parse_json(
+        &self,
+        json_strings: Box<dyn EngineData>,
+        output_schema: SchemaRef,
+    ) -> DeltaResult<Box<dyn EngineData>> {
+        delta_kernel::engine::parse_json(json_strings, output_schema)
+    }
+
+    fn read_json_files(
+        &self,
+        files: &[delta_kernel::FileMeta],
+        physical_schema: SchemaRef,
+        _predicate: Option<PredicateRef>,
+    ) -> DeltaResult<FileDataReadResultIterator> {
+        let files = files.to_vec();
+        let storage = self.storage.clone();
+        let Some(read_json_file) = self.vtable.read_json_file else {
+            return Ok(Box::new(files.into_iter().map(move |file| {
+                Self::read_and_parse_json_file(&storage, &file, physical_schema.clone())
+            })));
+        };
+        let data = files.into_iter().map(move |file| {
+            let results = with_file_meta(&file, |file| {
+                HandlerResults::collect(|results| {
+                    let schema = physical_schema.clone().into();
+                    read_json_file(storage.context.context, file, schema, results)
+                })
+            })?;
+            Ok(results.data)
+        });
+        Ok(Box::new(flatten_batches(data)))
+    }
+
+    fn write_json_file(
+        &self,
+        path: &Url,
+        data: Box<dyn Iterator<Item = DeltaResult<Box<dyn EngineData>>> + Send + '_>,
+        overwrite: bool,
+    ) -> DeltaResult<()> {
+        let bytes = delta_kernel::engine::to_json_bytes(data)?;
+        self.storage.put(path, bytes.into(), overwrite)
+    }
+}
+
+// Flatten the batches returned for each file, stopping at the first failed file.
+fn flatten_batches(
+    data: impl Iterator<Item = DeltaResult<Vec<Box<dyn EngineData>>>> + Send + 'static,
+) -> impl Iterator<Item = DeltaResult<Box<dyn EngineData>>> + Send + 'static {
+    data.flat_map(|batches| match batches {
+        Ok(batches) => batches.into_iter().map(Ok).collect::<Vec<_>>(),
+        Err(error) => vec![Err(error)],
+    })
+}
+
+struct ExternParquetHandler {
+    vtable: EngineParquetHandler,
+    context: Arc<HandlerContext>,
+}
+
+impl ParquetHandler for ExternParquetHandler {
+    fn read_parquet_files(
+        &self,
+        files: &[delta_kernel::FileMeta],
+        physical_schema: SchemaRef,
+        _predicate: Option<PredicateRef>,
+    ) -> DeltaResult<FileDataReadResultIterator> {
+        let read_parquet_file = self.vtable.read_parquet_file;
+        let context = self.context.clone();
+        let files = files.to_vec();
+        let data = files.into_iter().map(move |file| {
+            let results = with_file_meta(&file, |file| {
+                HandlerResults::collect(|results| {
+                    let schema = physical_schema.clone().into();
+                    read_parquet_file(context.context, file, schema, results)
+                })
+            })?;
+            Ok(results.data)
+        });
+        Ok(Box::new(flatten_batches(data)))
+    }
+}
+
+struct ExternEvaluationHandler {
+    new_expression_evaluator: extern "C" fn(
+        context: NullableCvoid,
+        input_schema: Handle<SharedSchema>,
+        expression: Handle<SharedExpression>,
+        output_schema: Handle<SharedSchema>,
+    ) -> EngineEvaluator,
+    new_predicate_evaluator: extern "C" fn(
+        context: NullableCvoid,
+        input_schema: Handle<SharedSchema>,
+        predicate: Handle<SharedPredicate>,
+    ) -> EngineEvaluator,
+    null_row: extern "C" fn(
+        context: NullableCvoid,
+        output_schema: Handle<SharedSchema>,
+        results: &mut HandlerResults,
+    ),
+    context: Arc<HandlerContext>,
+}
+
+impl EvaluationHandler for ExternEvaluationHandler {
+    fn new_expression_evaluator(
+        &self,
+        input_schema: SchemaRef,
+        expression: Expression,
+        output_type: DataType,
+    ) -> Arc<dyn ExpressionEvaluator> {
+        // Non-struct outputs are a single column named "output", see `new_expression_evaluator`
+        let output_schema = match output_type {
+            DataType::Struct(output_schema) => *output_schema,
+            output_type => StructType::new([StructField::nullable("output", output_type)]),
+        };
+        let evaluator = (self.new_expression_evaluator)(
+            self.context.context,
+            input_schema.into(),
+            Arc::new(expression).into(),
+            Arc::new(output_schema).into(),
+        );
+        Arc::new(ExternEvaluator {
+            evaluator,
+            _context: self.context.clone(),
+        })
+    }
+
+    fn new_predicate_evaluator(
+        &self,
+        input_schema: SchemaRef,
+        predicate: Predicate,
+    ) -> Arc<dyn PredicateEvaluator> {
+        let evaluator = (self.new_predicate_evaluator)(
+            self.context.context,
+            input_schema.into(),
+            Arc::new(predicate).into(),
+        );
+        Arc::new(ExternEvaluator {
+            evaluator,
+            _context: self.context.clone(),
+        })
+    }
+
+    fn null_row(&self, output_schema: SchemaRef) -> DeltaResult<Box<dyn EngineData>> {
+        HandlerResults::collect(|results| {
+            (self.null_row)(self.context.context, output_schema.into(), results)
+        })?
+        .into_engine_data()
+    }
+}
+
+struct ExternEvaluator {
+    evaluator: EngineEvaluator,
+    // evaluators may call back into the engine context, so they keep it alive
+    _context: Arc<HandlerContext>,
+}
+
+impl ExternEvaluator {
+    fn evaluate_batch(&self, batch: &dyn EngineData) -> DeltaResult<Box<dyn EngineData>> {
+        // The engine owns the batch it is given, so hand it a (cheap) copy of the Arrow data
+        let batch = batch
+            .any_ref()
+            .downcast_ref::<ArrowEngineData>()
+            .ok_or_else(|| Error::engine_data_type("ArrowEngineData"))?;
+        let batch: Box<dyn EngineData> =
+            Box::new(ArrowEngineData::new(batch.record_batch().clone()));
+        HandlerResults::collect(|results| {
+            (self.evaluator.evaluate)(self.evaluator.context, batch.into(), results)
+        })?
+        .into_engine_data()
+    }
+}
+
+impl Drop for ExternEvaluator {
+    fn drop(&mut self) {
+        if let Some(free_evaluator) = self.evaluator.free_evaluator {
+            free_evaluator(self.evaluator.context);
+        }
+    }
+}
+
+/// # Safety
+///
+/// See the `Send` impl of `HandlerContext`.
+unsafe impl Send for ExternEvaluator {}
+
+/// # Safety
+///
+/// See the `Send` impl of `HandlerContext`.
+unsafe impl Sync for ExternEvaluator {}
+
+impl ExpressionEvaluator for ExternEvaluator {
+    fn evaluate(&self, batch: &dyn EngineData) -> DeltaResult<Box<dyn EngineData>> {
+        self.evaluate_batch(batch)
+    }
+}
+
+impl PredicateEvaluator for ExternEvaluator {
+    fn evaluate(&self, batch: &dyn EngineData) -> DeltaResult<Box<dyn EngineData>> {
+        self.evaluate_batch(batch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::io::Write as _;
+    use std::path::{Path, PathBuf};
+    use std::ptr::NonNull;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::time::UNIX_EPOCH;
+
+    use delta_kernel::engine::default::executor::tokio::TokioBackgroundExecutor;
+    use delta_kernel::engine::default::DefaultEngine;
+    use test_utils::METADATA;
+
+    use super::*;
+    use crate::ffi_test_utils::{
+        allocate_err, assert_extern_result_error_with_message, ok_or_panic,
+    };
+    use crate::transaction::{
+        commit, free_transaction, get_app_id_version, transaction, with_transaction_id,
+        TransactionCommitResult,
+    };
+    use crate::{free_engine, free_snapshot, snapshot, OptionalValue};
+
+    // The engine's side of the handlers: storage on the local file system, with the default
+    // engine's Parquet reader (and expression evaluation) standing in for the engine's own.
+    struct TestHost {
+        parquet: Arc<dyn ParquetHandler>,
+        evaluations: Arc<AtomicUsize>,
+        freed: Arc<AtomicBool>,
+    }
+
+    fn host<'a>(context: NullableCvoid) -> &'a TestHost {
+        unsafe { context.unwrap().cast::<TestHost>().as_ref() }
+    }
+
+    extern "C" fn free_host(context: NullableCvoid) {
+        let host = unsafe { Box::from_raw(context.unwrap().cast::<TestHost>().as_ptr()) };
+        host.freed.store(true, Ordering::SeqCst);
+    }
+
+    fn to_url(path: &KernelStringSlice) -> Url {
+        let path: &str = unsafe { TryFromStringSlice::try_from_slice(path) }.unwrap();
+        Url::parse(path).unwrap()
+    }
+
+    fn to_path(path: &KernelStringSlice) -> PathBuf {
+        to_url(path).to_file_path().unwrap()
+    }
+
+    fn set_error(results: &mut HandlerResults, etype: KernelError, message: String) {
+        unsafe { handler_results_set_error(results, etype, kernel_string_slice!(message)) };
+    }
+
+    fn set_io_error(results: &mut HandlerResults, error: std::io::Error) {
+        let etype = match error.kind() {
+            std::io::ErrorKind::NotFound => KernelError::FileNotFoundError,
+            std::io::ErrorKind::AlreadyExists => KernelError::FileAlreadyExists,
+            _ => KernelError::GenericError,
+        };
+        set_error(results, etype, error.to_string());
+    }
+
+    fn push_data(results: &mut HandlerResults, data: DeltaResult<Vec<Box<dyn EngineData>>>) {
+        match data {
+            Ok(data) => data
+                .into_iter()
+                .for_each(|data| unsafe { handler_results_push_engine_data(results, data.into()) }),
+            Err(error) => set_error(results, KernelError::GenericError, error.to_string()),
+        }
+    }
+
+    fn push_file(results: &mut HandlerResults, path: &Path) -> std::io::Result<()> {
+        let metadata = std::fs::metadata(path)?;
+        let modified = metadata.modified()?.duration_since(UNIX_EPOCH).unwrap();
+        let url = Url::from_file_path(path).unwrap().to_string();
+        let file = FileMeta {
+            path: kernel_string_slice!(url),
+            last_modified: modified.as_millis() as i64,
+            size: metadata.len() as usize,
+        };
+        unsafe { handler_results_push_file(results, &file) };
+        Ok(())
+    }
+
+    extern "C" fn list_from(
+        _context: NullableCvoid,
+        path: KernelStringSlice,
+        results: &mut HandlerResults,
+    ) {
+        let url = to_url(&path);
+        let path = url.to_file_path().unwrap();
+        let (dir, from) = match url.path().ends_with('/') {
+            true => (path, None),
+            false => (path.parent().unwrap().to_path_buf(), Some(path)),
+        };
+        let listed = std::fs::read_dir(dir).and_then(|entries| {
+            let mut paths: Vec<_> = entries
+                .map(|entry| entry.map(|entry| entry.path()))
+                .collect::<std::io::Result<_>>()?;
+            paths.retain(|path| path.is_file() && from.as_ref().is_none_or(|from| path > from));
+            paths.sort();
+            paths.iter().try_for_each(|path| push_file(results, path))
+        });
+        if let Err(error) = listed {
+            set_io_error(results, error);
+        }
+    }
+
+    extern "C" fn read_file(
+        _context: NullableCvoid,
+        path: KernelStringSlice,
+        range: Option<&ByteRange>,
+        results: &mut HandlerResults,
+    ) {
+        match std::fs::read(to_path(&path)) {
+            Ok(data) => {
+                let data = match range {
+                    Some(range) => &data[range.start as usize..range.end as usize],
+                    None => &data[..],
+                };
+                unsafe { handler_results_push_bytes(results, data.as_ptr(), data.len()) };
+            }
+            Err(error) => set_io_error(results, error),
+        }
+    }
+
+    extern "C" fn put(
+        _context: NullableCvoid,
+        path: KernelStringSlice,
+        data: *const u8,
+        len: usize,
+        overwrite: bool,
+        results: &mut HandlerResults,
+    ) {
+        let path = to_path(&path);
+        let data = unsafe { std::slice::from_raw_parts(data, len) };
+        let written = std::fs::create_dir_all(path.parent().unwrap()).and_then(|_| {
+            std::fs::OpenOptions::new()
+                .write(true)
+                .create(overwrite)
+                .truncate(overwrite)
+                .create_new(!overwrite)
+                .open(&path)?
+                .write_all(data)
+        });
+        if let Err(error) = written {
+            set_io_error(results, error);
+        }
+    }
+
+    extern "C" fn head(
+        _context: NullableCvoid,
+        path: KernelStringSlice,
+        results: &mut HandlerResults,
+    ) {
+        if let Err(error) = push_file(results, &to_path(&path)) {
+            set_io_error(results, error);
+        }
+    }
+
+    extern "C" fn read_parquet_file(
+        context: NullableCvoid,
+        file: &FileMeta,
+        physical_schema: Handle<SharedSchema>,
+        results: &mut HandlerResults,
+    ) {
+        let physical_schema = unsafe { physical_schema.into_inner() };
+        let file = unsafe { kernel_file_meta(file) }.unwrap();
+        let data = host(context)
+            .parquet
+            .read_parquet_files(&[file], physical_schema, None)
+            .and_then(|data| data.collect());
+        push_data(results, data);
+    }
+
+    enum Evaluator {
+        Expression(Arc<dyn ExpressionEvaluator>),
+        Predicate(Arc<dyn PredicateEvaluator>),
+    }
+
+    struct EvaluatorContext {
+        evaluator: Evaluator,
+        evaluations: Arc<AtomicUsize>,
+    }
+
+    fn engine_evaluator(context: NullableCvoid, evaluator: Evaluator) -> EngineEvaluator {
+        let context = Box::new(EvaluatorContext {
+            evaluator,
+            evaluations: host(context).evaluations.clone(),
+        });
+        EngineEvaluator {
+            context: NonNull::new(Box::into_raw(context).cast()),
+            evaluate,
+            free_evaluator: Some(free_evaluator),
+        }
+    }
+
+    extern "C" fn new_expression_evaluator(
+        context: NullableCvoid,
+        input_schema: Handle<SharedSchema>,
+        expression: Handle<SharedExpression>,
+        output_schema: Handle<SharedSchema>,
+    ) -> EngineEvaluator {
+        let input_schema = unsafe { input_schema.into_inner() };
+        let expression = unsafe { expression.into_inner() };
+        let output_schema = unsafe { output_schema.into_inner() };
+        let evaluator = ArrowEvaluationHandler.new_expression_evaluator(
+            input_schema,
+            expression.as_ref().clone(),
+            DataType::Struct(Box::new(output_schema.as_ref().clone())),
+        );
+        engine_evaluator(context, Evaluator::Expression(evaluator))
+    }
+
+    extern "C" fn new_predicate_evaluator(
+        context: NullableCvoid,
+        input_schema: Handle<SharedSchema>,
+        predicate: Handle<SharedPredicate>,
+    ) -> EngineEvaluator {
+        let input_schema = unsafe { input_schema.into_inner() };
+        let predicate = unsafe { predicate.into_inner() };
+        let evaluator = ArrowEvaluationHandler
+            .new_predicate_evaluator(input_schema, predicate.as_ref().clone());
+        engine_evaluator(context, Evaluator::Predicate(evaluator))
+    }
+
+    extern "C" fn evaluate(
+        context: NullableCvoid,
+        batch: Handle<ExclusiveEngineData>,
+        results: &mut HandlerResults,
+    ) {
+        let context = unsafe { context.unwrap().cast::<EvaluatorContext>().as_ref() };
+        context.evaluations.fetch_add(1, Ordering::SeqCst);
+        let batch = unsafe { batch.into_inner() };
+        let result = match &context.evaluator {
+            Evaluator::Expression(evaluator) => evaluator.evaluate(batch.as_ref()),
+            Evaluator::Predicate(evaluator) => evaluator.evaluate(batch.as_ref()),
+        };
+        push_data(results, result.map(|result| vec![result]));
+    }
+
+    extern "C" fn free_evaluator(context: NullableCvoid) {
+        drop(unsafe { Box::from_raw(context.unwrap().cast::<EvaluatorContext>().as_ptr()) });
+    }
+
+    extern "C" fn null_row(
+        _context: NullableCvoid,
+        output_schema: Handle<SharedSchema>,
+        results: &mut HandlerResults,
+    ) {
+        let output_schema = unsafe { output_schema.into_inner() };
+        let result = ArrowEvaluationHandler.null_row(output_schema);
+        push_data(results, result.map(|result| vec![result]));
+    }
+
+    const NO_EVALUATION: EngineEvaluationHandler = EngineEvaluationHandler {
+        new_expression_evaluator: None,
+        new_predicate_evaluator: None,
+        null_row: None,
+    };
+
+    const EVALUATION: EngineEvaluationHandler = EngineEvaluationHandler {
+        new_expression_evaluator: Some(new_expression_evaluator),
+        new_predicate_evaluator: Some(new_predicate_evaluator),
+        null_row: Some(null_row),
+    };
+
+    fn handlers(
+        table_root: &Url,
+        evaluation: EngineEvaluationHandler,
+    ) -> (EngineHandlers, Arc<TestHost>) {
+        let default_engine = DefaultEngine::try_new(
+            table_root,
+            HashMap::<String, String>::new(),
+            Arc::new(TokioBackgroundExecutor::new()),
+        )
+        .unwrap();
+        let host = Box::new(TestHost {
+            parquet: default_engine.parquet_handler(),
+            evaluations: Default::default(),
+            freed: Default::default(),
+        });
+        let stats = Arc::new(TestHost {
+            parquet: host.parquet.clone(),
+            evaluations: host.evaluations.clone(),
+            freed: host.freed.clone(),
+        });
+        let handlers = EngineHandlers {
+            context: NonNull::new(Box::into_raw(host).cast()),
+            free_context: Some(free_host),
+            storage: EngineStorageHandler {
+                list_from,
+                read_file,
+                put: Some(put),
+                head: Some(head),
+                delete: None,
+                copy: None,
+            },
+            json: EngineJsonHandler {
+                read_json_file: None,
+            },
+            parquet: EngineParquetHandler { read_parquet_file },
+            evaluation,
+        };
+        (handlers, stats)
+    }
+
+    fn table_url(path: impl AsRef<Path>) -> Url {
+        Url::from_directory_path(std::fs::canonicalize(path).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_read_with_engine_handlers() {
+        // a table with a checkpoint, which is read with the engine's parquet handler
+        let table_root = table_url("../kernel/tests/data/app-txn-checkpoint");
+        let (handlers, host) = handlers(&table_root, NO_EVALUATION);
+        let engine = unsafe { ok_or_panic(get_engine_with_handlers(handlers, allocate_err)) };
+
+        let path = table_root.to_string();
+        let snapshot =
+            unsafe { ok_or_panic(snapshot(kernel_string_slice!(path), engine.shallow_copy())) };
+        assert_eq!(unsafe { crate::version(snapshot.shallow_copy()) }, 1);
+        let app_id = "my-app2";
+        let app_id_version = unsafe {
+            ok_or_panic(get_app_id_version(
+                snapshot.shallow_copy(),
+                kernel_string_slice!(app_id),
+                engine.shallow_copy(),
+            ))
+        };
+        assert_eq!(app_id_version, OptionalValue::Some(2));
+
+        unsafe { free_snapshot(snapshot) };
+        assert!(!host.freed.load(Ordering::SeqCst));
+        unsafe { free_engine(engine) };
+        assert!(host.freed.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn test_commit_with_engine_handlers() {
+        let test_dir = tempfile::tempdir().unwrap();
+        let log_dir = test_dir.path().join("_delta_log");
+        std::fs::create_dir(&log_dir).unwrap();
+        std::fs::write(log_dir.join("00000000000000000000.json"), METADATA).unwrap();
+        let table_root = table_url(test_dir.path());
+        let (handlers, host) = handlers(&table_root, EVALUATION);
+        let engine = unsafe { ok_or_panic(get_engine_with_handlers(handlers, allocate_err)) };
+
+        // two concurrent transactions on version 0 of the table
+        let path = table_root.to_string();
+        let app_id = "my-app";
+        let start_transaction = || unsafe {
+            let txn = ok_or_panic(transaction(
+                kernel_string_slice!(path),
+                engine.shallow_copy(),
+            ));
+            ok_or_panic(with_transaction_id(
+                txn,
+                kernel_string_slice!(app_id),
+                1,
+                engine.shallow_copy(),
+            ))
+        };
+        let (txn1, txn2) = (start_transaction(), start_transaction());
+
+        let result = unsafe { ok_or_panic(commit(txn1, engine.shallow_copy())) };
+        assert!(matches!(
+            result,
+            TransactionCommitResult::Committed { version: 1, .. }
+        ));
+        // the commit info action was created with the engine's expression evaluation
+        assert!(host.evaluations.load(Ordering::SeqCst) > 0);
+
+        // the engine's put reports the existing commit, which kernel turns into a conflict
+        let result = unsafe { ok_or_panic(commit(txn2, engine.shallow_copy())) };
+        let TransactionCommitResult::Conflict {
+            version: 1,
+            transaction,
+        } = result
+        else {
+            panic!("expected the second commit to conflict at version 1");
+        };
+        unsafe { free_transaction(transaction) };
+
+        let snapshot =
+            unsafe { ok_or_panic(snapshot(kernel_string_slice!(path), engine.shallow_copy())) };
+        let app_id_version = unsafe {
+            ok_or_panic(get_app_id_version(
+                snapshot.shallow_copy(),
+                kernel_string_slice!(app_id),
+                engine.shallow_copy(),
+            ))
+        };
+        assert_eq!(app_id_version, OptionalValue::Some(1));
+
+        unsafe {
+            free_snapshot(snapshot);
+            free_engine(engine);
+        }
+    }
+
+    #[test]
+    fn test_partial_evaluation_handler() {
+        let table_root = table_url("../kernel/tests/data/app-txn-checkpoint");
+        let evaluation = EngineEvaluationHandler {
+            null_row: None,
+            ..EVALUATION
+        };
+        let (handlers, host) = handlers(&table_root, evaluation);
+        let engine = unsafe { get_engine_with_handlers(handlers, allocate_err) };
+        assert_extern_result_error_with_message(
+            engine,
+            KernelError::GenericError,
+            "Generic delta kernel error: Engine evaluation handler must provide either all or none of its functions",
+        );
+        // the engine context is freed even though no engine was created
+        assert!(host.freed.load(Ordering::SeqCst));
+    }
+}
@@ -54,6 +54,9 @@ pub enum KernelError {
     LiteralExpressionTransformError,
     CheckpointWriteError,
     SchemaError,
+    AppendOnlyViolationError,
+    NonAdditiveChangeError,
+    MissingRestoredFileError,
 }
 
 impl From<Error> for KernelError {
@@ -115,6 +118,9 @@ impl From<Error> for KernelError {
                 KernelError::LiteralExpressionTransformError
             }
             Error::Schema(_) => KernelError::SchemaError,
+            Error::AppendOnlyViolation(_) => KernelError::AppendOnlyViolationError,
+            Error::NonAdditiveChange(..) => KernelError::NonAdditiveChangeError,
+            Error::MissingRestoredFile(..) => KernelError::MissingRestoredFileError,
             _ => KernelError::UnknownError,
         }
     }
@@ -32,10 +32,13 @@ use handle::Handle;
 // relies on `crate::`
 extern crate self as delta_kernel_ffi;
 
+pub mod checkpoint;
 mod domain_metadata;
 pub use domain_metadata::get_domain_metadata;
 pub mod engine_data;
 pub mod engine_funcs;
+#[cfg(feature = "arrow")]
+pub mod engine_handlers;
 pub mod error;
 use error::{AllocateError, AllocateErrorFn, ExternResult, IntoExternResult};
 pub mod expressions;
@@ -43,6 +46,7 @@ pub mod expressions;
 pub mod ffi_tracing;
 pub mod scan;
 pub mod schema;
+pub mod table_changes;
 
 #[cfg(test)]
 mod ffi_test_utils;
@@ -52,6 +56,23 @@ pub mod transaction;
 
 pub(crate) type NullableCvoid = Option<NonNull<c_void>>;
 
+/// An optional value, for returning an [`Option`] of a non-pointer type to the engine.
+#[repr(C)]
+#[derive(Debug, PartialEq)]
+pub enum OptionalValue<T> {
+    Some(T),
+    None,
+}
+
+impl<T> From<Option<T>> for OptionalValue<T> {
+    fn from(value: Option<T>) -> Self {
+        match value {
+            Some(value) => OptionalValue::Some(value),
+            None => OptionalValue::None,
+        }
+    }
+}
+
 /// Model iterators. This allows an engine to specify iteration however it likes, and we simply wrap
 /// the engine functions. The engine retains ownership of the iterator.
 #[repr(C)]
@@ -362,14 +383,14 @@ pub trait ExternEngine: Send + Sync {
 #[handle_descriptor(target=dyn ExternEngine, mutable=false)]
 pub struct SharedExternEngine;
 
-#[cfg(feature = "default-engine-base")]
+#[cfg(feature = "arrow")]
 struct ExternEngineVtable {
     // Actual engine instance to use
     engine: Arc<dyn Engine>,
     allocate_error: AllocateErrorFn,
 }
 
-#[cfg(feature = "default-engine-base")]
+#[cfg(feature = "arrow")]
 impl Drop for ExternEngineVtable {
     fn drop(&mut self) {
         debug!("dropping engine interface");
@@ -380,7 +401,7 @@ impl Drop for ExternEngineVtable {
 ///
 /// Kernel doesn't use any threading or concurrency. If engine chooses to do so, engine is
 /// responsible for handling  any races that could result.
-#[cfg(feature = "default-engine-base")]
+#[cfg(feature = "arrow")]
 unsafe impl Send for ExternEngineVtable {}
 
 /// # Safety
@@ -392,10 +413,10 @@ unsafe impl Send for ExternEngineVtable {}
 /// Basically, by failing to implement these traits, we forbid the engine from being able to declare
 /// its thread-safety (because rust assumes it is not threadsafe). By implementing them, we leave it
 /// up to the engine to enforce thread safety if engine chooses to use threads at all.
-#[cfg(feature = "default-engine-base")]
+#[cfg(feature = "arrow")]
 unsafe impl Sync for ExternEngineVtable {}
 
-#[cfg(feature = "default-engine-base")]
+#[cfg(feature = "arrow")]
 impl ExternEngine for ExternEngineVtable {
     fn engine(&self) -> Arc<dyn Engine> {
         self.engine.clone()
@@ -522,7 +543,7 @@ fn get_default_default_engine_impl(
 /// Safety
 ///
 /// Caller must free this handle to prevent memory leaks
-#[cfg(feature = "default-engine-base")]
+#[cfg(feature = "arrow")]
 fn engine_to_handle(
     engine: Arc<dyn Engine>,
     allocate_error: AllocateErrorFn,
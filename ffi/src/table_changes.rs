@@ -0,0 +1,704 @@
+//! TableChanges (change data feed) related ffi code
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use delta_kernel::scan::state::DvInfo;
+use delta_kernel::scan::ScanResult;
+use delta_kernel::table_changes::scan::{TableChangesScan, TableChangesScanMetadata};
+use delta_kernel::table_changes::TableChanges;
+use delta_kernel::{DeltaResult, Error, Version};
+use delta_kernel_ffi_macros::handle_descriptor;
+use tracing::debug;
+use url::Url;
+
+use crate::expressions::kernel_visitor::{unwrap_kernel_predicate, KernelExpressionVisitorState};
+use crate::scan::EnginePredicate;
+use crate::{
+    kernel_string_slice, unwrap_and_parse_path_as_url, AllocateStringFn, ExclusiveEngineData,
+    ExternEngine, ExternResult, IntoExternResult, KernelBoolSlice, KernelStringSlice,
+    NullableCvoid, SharedExternEngine, SharedSchema, TryFromStringSlice,
+};
+
+use super::handle::Handle;
+
+#[handle_descriptor(target=TableChanges, mutable=false, sized=true)]
+pub struct SharedTableChanges;
+
+#[handle_descriptor(target=TableChangesScan, mutable=false, sized=true)]
+pub struct SharedTableChangesScan;
+
+/// Get the changes of the specified table from `start_version` up to its latest version. It is the
+/// responsibility of the _engine_ to free the table changes when complete by calling
+/// [`free_table_changes`].
+///
+/// # Safety
+///
+/// Caller is responsible for passing valid handles and path pointer.
+#[no_mangle]
+pub unsafe extern "C" fn table_changes_from_version(
+    path: KernelStringSlice,
+    engine: Handle<SharedExternEngine>,
+    start_version: Version,
+) -> ExternResult<Handle<SharedTableChanges>> {
+    let url = unsafe { unwrap_and_parse_path_as_url(path) };
+    let engine = unsafe { engine.as_ref() };
+    table_changes_impl(url, engine, start_version, None).into_extern_result(&engine)
+}
+
+/// Get the changes of the specified table from `start_version` up to `end_version` (inclusive). It
+/// is the responsibility of the _engine_ to free the table changes when complete by calling
+/// [`free_table_changes`].
+///
+/// # Safety
+///
+/// Caller is responsible for passing valid handles and path pointer.
+#[no_mangle]
+pub unsafe extern "C" fn table_changes_between_versions(
+    path: KernelStringSlice,
+    engine: Handle<SharedExternEngine>,
+    start_version: Version,
+    end_version: Version,
+) -> ExternResult<Handle<SharedTableChanges>> {
+    let url = unsafe { unwrap_and_parse_path_as_url(path) };
+    let engine = unsafe { engine.as_ref() };
+    table_changes_impl(url, engine, start_version, end_version.into()).into_extern_result(&engine)
+}
+
+fn table_changes_impl(
+    url: DeltaResult<Url>,
+    extern_engine: &dyn ExternEngine,
+    start_version: Version,
+    end_version: Option<Version>,
+) -> DeltaResult<Handle<SharedTableChanges>> {
+    let table_changes = TableChanges::try_new(
+        url?,
+        extern_engine.engine().as_ref(),
+        start_version,
+        end_version,
+    )?;
+    Ok(Arc::new(table_changes).into())
+}
+
+/// Drops table changes.
+///
+/// # Safety
+/// Caller is responsible for passing a valid table changes handle.
+#[no_mangle]
+pub unsafe extern "C" fn free_table_changes(table_changes: Handle<SharedTableChanges>) {
+    table_changes.drop_handle();
+}
+
+/// Get the schema of the table changes, i.e. the schema of the table along with the
+/// `_change_type`, `_commit_version` and `_commit_timestamp` columns.
+///
+/// # Safety
+/// Engine is responsible for providing a valid `SharedTableChanges` handle
+#[no_mangle]
+pub unsafe extern "C" fn table_changes_schema(
+    table_changes: Handle<SharedTableChanges>,
+) -> Handle<SharedSchema> {
+    let table_changes = unsafe { table_changes.as_ref() };
+    Arc::new(table_changes.schema().clone()).into()
+}
+
+/// Get the table root of the table changes.
+///
+/// # Safety
+/// Engine is responsible for providing a valid table changes pointer and allocate_fn (for
+/// allocating the string)
+#[no_mangle]
+pub unsafe extern "C" fn table_changes_table_root(
+    table_changes: Handle<SharedTableChanges>,
+    allocate_fn: AllocateStringFn,
+) -> NullableCvoid {
+    let table_changes = unsafe { table_changes.as_ref() };
+    let table_root = table_changes.table_root().to_string();
+    allocate_fn(kernel_string_slice!(table_root))
+}
+
+/// Get the start version of the table changes.
+///
+/// # Safety
+/// Engine is responsible for providing a valid `SharedTableChanges` handle
+#[no_mangle]
+pub unsafe extern "C" fn table_changes_start_version(
+    table_changes: Handle<SharedTableChanges>,
+) -> u64 {
+    let table_changes = unsafe { table_changes.as_ref() };
+    table_changes.start_version()
+}
+
+/// Get the end version (inclusive) of the table changes.
+///
+/// # Safety
+/// Engine is responsible for providing a valid `SharedTableChanges` handle
+#[no_mangle]
+pub unsafe extern "C" fn table_changes_end_version(
+    table_changes: Handle<SharedTableChanges>,
+) -> u64 {
+    let table_changes = unsafe { table_changes.as_ref() };
+    table_changes.end_version()
+}
+
+/// Get a [`TableChangesScan`] over the specified table changes. It is the responsibility of the
+/// _engine_ to free this scan when complete by calling [`free_table_changes_scan`].
+///
+/// # Safety
+///
+/// Caller is responsible for passing a valid table changes pointer, and engine pointer
+#[no_mangle]
+pub unsafe extern "C" fn table_changes_scan(
+    table_changes: Handle<SharedTableChanges>,
+    engine: Handle<SharedExternEngine>,
+    predicate: Option<&mut EnginePredicate>,
+) -> ExternResult<Handle<SharedTableChangesScan>> {
+    let table_changes = unsafe { table_changes.clone_as_arc() };
+    table_changes_scan_impl(table_changes, predicate).into_extern_result(&engine.as_ref())
+}
+
+fn table_changes_scan_impl(
+    table_changes: Arc<TableChanges>,
+    predicate: Option<&mut EnginePredicate>,
+) -> DeltaResult<Handle<SharedTableChangesScan>> {
+    let mut scan_builder = table_changes.scan_builder();
+    if let Some(predicate) = predicate {
+        let mut visitor_state = KernelExpressionVisitorState::default();
+        let pred_id = (predicate.visitor)(predicate.predicate, &mut visitor_state);
+        let predicate = unwrap_kernel_predicate(&mut visitor_state, pred_id);
+        debug!("Got predicate: {:#?}", predicate);
+        scan_builder = scan_builder.with_predicate(predicate.map(Arc::new));
+    }
+    Ok(Arc::new(scan_builder.build()?).into())
+}
+
+/// Drops a table changes scan.
+///
+/// # Safety
+/// Caller is responsible for passing a valid table changes scan handle.
+#[no_mangle]
+pub unsafe extern "C" fn free_table_changes_scan(scan: Handle<SharedTableChangesScan>) {
+    scan.drop_handle();
+}
+
+/// Get the table root of a table changes scan.
+///
+/// # Safety
+/// Engine is responsible for providing a valid scan pointer and allocate_fn (for allocating the
+/// string)
+#[no_mangle]
+pub unsafe extern "C" fn table_changes_scan_table_root(
+    scan: Handle<SharedTableChangesScan>,
+    allocate_fn: AllocateStringFn,
+) -> NullableCvoid {
+    let scan = unsafe { scan.as_ref() };
+    let table_root = scan.table_root().to_string();
+    allocate_fn(kernel_string_slice!(table_root))
+}
+
+/// Get the logical (i.e. output) schema of a table changes scan, which includes the
+/// `_change_type`, `_commit_version` and `_commit_timestamp` columns.
+///
+/// # Safety
+/// Engine is responsible for providing a valid `SharedTableChangesScan` handle
+#[no_mangle]
+pub unsafe extern "C" fn table_changes_scan_logical_schema(
+    scan: Handle<SharedTableChangesScan>,
+) -> Handle<SharedSchema> {
+    let scan = unsafe { scan.as_ref() };
+    scan.logical_schema().clone().into()
+}
+
+/// Get the kernel view of the physical read schema that an engine should read from parquet file in
+/// a table changes scan
+///
+/// # Safety
+/// Engine is responsible for providing a valid `SharedTableChangesScan` handle
+#[no_mangle]
+pub unsafe extern "C" fn table_changes_scan_physical_schema(
+    scan: Handle<SharedTableChangesScan>,
+) -> Handle<SharedSchema> {
+    let scan = unsafe { scan.as_ref() };
+    scan.physical_schema().clone().into()
+}
+
+// Intentionally opaque to the engine. See `ScanMetadataIterator`.
+pub struct TableChangesScanMetadataIterator {
+    // Item = DeltaResult<TableChangesScanMetadata>
+    data: Mutex<Box<dyn Iterator<Item = DeltaResult<TableChangesScanMetadata>> + Send>>,
+
+    // Also keep a reference to the external engine for its error allocator.
+    engine: Arc<dyn ExternEngine>,
+}
+
+#[handle_descriptor(target=TableChangesScanMetadataIterator, mutable=false, sized=true)]
+pub struct SharedTableChangesScanMetadataIterator;
+
+/// The deletion vectors of the files removed in the commit a batch of table changes scan metadata
+/// belongs to, by path. See [`get_remove_dv`].
+pub struct CRemoveDvs {
+    dvs: Arc<HashMap<String, DvInfo>>,
+}
+
+/// Get the deletion vector of the removed file at `path`, or `NULL` if the file was not removed
+/// or had no deletion vector. The returned [`DvInfo`] is only valid for the duration of the
+/// visitor call it was obtained in, and can be passed to [`selection_vector_from_dv`] or
+/// [`row_indexes_from_dv`].
+///
+/// # Safety
+///
+/// The engine is responsible for providing a valid [`CRemoveDvs`] pointer and [`KernelStringSlice`]
+///
+/// [`selection_vector_from_dv`]: crate::scan::selection_vector_from_dv
+/// [`row_indexes_from_dv`]: crate::scan::row_indexes_from_dv
+#[no_mangle]
+pub unsafe extern "C" fn get_remove_dv(
+    remove_dvs: &CRemoveDvs,
+    path: KernelStringSlice,
+) -> Option<&DvInfo> {
+    let path: DeltaResult<&str> = unsafe { TryFromStringSlice::try_from_slice(&path) };
+    remove_dvs.dvs.get(path.ok()?)
+}
+
+/// Get an iterator over the metadata of the actions needed to read the changes of a table changes
+/// scan. This will return a [`TableChangesScanMetadataIterator`] which can be passed to
+/// [`table_changes_scan_metadata_next`] to get the actual data.
+///
+/// # Safety
+///
+/// Engine is responsible for passing a valid [`SharedExternEngine`] and [`SharedTableChangesScan`]
+#[no_mangle]
+pub unsafe extern "C" fn table_changes_scan_metadata(
+    scan: Handle<SharedTableChangesScan>,
+    engine: Handle<SharedExternEngine>,
+) -> ExternResult<Handle<SharedTableChangesScanMetadataIterator>> {
+    let engine = unsafe { engine.clone_as_arc() };
+    let scan = unsafe { scan.as_ref() };
+    table_changes_scan_metadata_impl(&engine, scan).into_extern_result(&engine.as_ref())
+}
+
+fn table_changes_scan_metadata_impl(
+    engine: &Arc<dyn ExternEngine>,
+    scan: &TableChangesScan,
+) -> DeltaResult<Handle<SharedTableChangesScanMetadataIterator>> {
+    let scan_metadata = scan.scan_metadata(engine.engine())?;
+    let data = TableChangesScanMetadataIterator {
+        data: Mutex::new(Box::new(scan_metadata)),
+        engine: engine.clone(),
+    };
+    Ok(Arc::new(data).into())
+}
+
+/// Call the provided `engine_visitor` on the next table changes scan metadata item. The visitor is
+/// provided with an [`ExclusiveEngineData`] holding the scan rows of the commit's actions, the
+/// selection vector of the rows which _must_ be processed (all other rows _must_ be ignored), and
+/// the [`CRemoveDvs`] of the commit. All batches are guaranteed to belong to a single commit, but a
+/// commit may span several batches. It is the responsibility of the _engine_ to free the engine
+/// data and selection vector after use by calling [`free_engine_data`] and [`free_bool_slice`]
+/// respectively; the [`CRemoveDvs`] is only valid for the duration of the visitor call.
+///
+/// Returns `false` once the iterator is exhausted.
+///
+/// # Safety
+///
+/// The iterator must be valid (returned by [`table_changes_scan_metadata`]) and not yet freed by
+/// [`free_table_changes_scan_metadata_iter`]. The visitor function pointer must be non-null.
+///
+/// [`free_bool_slice`]: crate::free_bool_slice
+/// [`free_engine_data`]: crate::free_engine_data
+#[no_mangle]
+pub unsafe extern "C" fn table_changes_scan_metadata_next(
+    data: Handle<SharedTableChangesScanMetadataIterator>,
+    engine_context: NullableCvoid,
+    engine_visitor: extern "C" fn(
+        engine_context: NullableCvoid,
+        engine_data: Handle<ExclusiveEngineData>,
+        selection_vector: KernelBoolSlice,
+        remove_dvs: &CRemoveDvs,
+    ),
+) -> ExternResult<bool> {
+    let data = unsafe { data.as_ref() };
+    table_changes_scan_metadata_next_impl(data, engine_context, engine_visitor)
+        .into_extern_result(&data.engine.as_ref())
+}
+
+fn table_changes_scan_metadata_next_impl(
+    data: &TableChangesScanMetadataIterator,
+    engine_context: NullableCvoid,
+    engine_visitor: extern "C" fn(
+        engine_context: NullableCvoid,
+        engine_data: Handle<ExclusiveEngineData>,
+        selection_vector: KernelBoolSlice,
+        remove_dvs: &CRemoveDvs,
+    ),
+) -> DeltaResult<bool> {
+    let mut data = data
+        .data
+        .lock()
+        .map_err(|_| Error::generic("poisoned mutex"))?;
+    let Some(scan_metadata) = data.next().transpose()? else {
+        return Ok(false);
+    };
+    let remove_dvs = CRemoveDvs {
+        dvs: scan_metadata.remove_dvs,
+    };
+    (engine_visitor)(
+        engine_context,
+        scan_metadata.scan_metadata.into(),
+        scan_metadata.selection_vector.into(),
+        &remove_dvs,
+    );
+    Ok(true)
+}
+
+/// # Safety
+///
+/// Caller is responsible for (at most once) passing a valid pointer returned by a call to
+/// [`table_changes_scan_metadata`].
+#[no_mangle]
+pub unsafe extern "C" fn free_table_changes_scan_metadata_iter(
+    data: Handle<SharedTableChangesScanMetadataIterator>,
+) {
+    data.drop_handle();
+}
+
+// Intentionally opaque to the engine. See `ScanMetadataIterator`.
+pub struct TableChangesIterator {
+    // Item = DeltaResult<ScanResult>
+    data: Mutex<Box<dyn Iterator<Item = DeltaResult<ScanResult>> + Send>>,
+
+    // Also keep a reference to the external engine for its error allocator.
+    engine: Arc<dyn ExternEngine>,
+}
+
+#[handle_descriptor(target=TableChangesIterator, mutable=false, sized=true)]
+pub struct SharedTableChangesIterator;
+
+/// Execute a table changes scan, reading all the change data with the engine. This will return a
+/// [`TableChangesIterator`] which can be passed to [`table_changes_next`] to get the actual data.
+///
+/// # Safety
+///
+/// Engine is responsible for passing a valid [`SharedExternEngine`] and [`SharedTableChangesScan`]
+#[no_mangle]
+pub unsafe extern "C" fn table_changes_scan_execute(
+    scan: Handle<SharedTableChangesScan>,
+    engine: Handle<SharedExternEngine>,
+) -> ExternResult<Handle<SharedTableChangesIterator>> {
+    let engine = unsafe { engine.clone_as_arc() };
+    let scan = unsafe { scan.as_ref() };
+    table_changes_scan_execute_impl(&engine, scan).into_extern_result(&engine.as_ref())
+}
+
+fn table_changes_scan_execute_impl(
+    engine: &Arc<dyn ExternEngine>,
+    scan: &TableChangesScan,
+) -> DeltaResult<Handle<SharedTableChangesIterator>> {
+    let results = scan.execute(engine.engine())?;
+    let data = TableChangesIterator {
+        data: Mutex::new(Box::new(results)),
+        engine: engine.clone(),
+    };
+    Ok(Arc::new(data).into())
+}
+
+/// Call the provided `engine_visitor` on the next batch of change data. The visitor is provided
+/// with an [`ExclusiveEngineData`] holding the data in the scan's logical schema (which can be
+/// exported to Arrow with [`get_raw_arrow_data`]), and a selection vector of the rows to keep. An
+/// empty selection vector selects all rows; a selection vector shorter than the data selects the
+/// rows past its end. It is the responsibility of the _engine_ to free the associated resources
+/// after use by calling [`free_engine_data`] and [`free_bool_slice`] respectively.
+///
+/// Returns `false` once the iterator is exhausted.
+///
+/// # Safety
+///
+/// The iterator must be valid (returned by [`table_changes_scan_execute`]) and not yet freed by
+/// [`free_table_changes_iter`]. The visitor function pointer must be non-null.
+///
+/// [`get_raw_arrow_data`]: crate::engine_data::get_raw_arrow_data
+/// [`free_bool_slice`]: crate::free_bool_slice
+/// [`free_engine_data`]: crate::free_engine_data
+#[no_mangle]
+pub unsafe extern "C" fn table_changes_next(
+    data: Handle<SharedTableChangesIterator>,
+    engine_context: NullableCvoid,
+    engine_visitor: extern "C" fn(
+        engine_context: NullableCvoid,
+        engine_data: Handle<ExclusiveEngineData>,
+        selection_vector: KernelBoolSlice,
+    ),
+) -> ExternResult<bool> {
+    let data = unsafe { data.as_ref() };
+    table_changes_next_impl(data, engine_context, engine_visitor)
+        .into_extern_result(&data.engine.as_ref())
+}
+
+fn table_changes_next_impl(
+    data: &TableChangesIterator,
+    engine_context: NullableCvoid,
+    engine_visitor: extern "C" fn(
+        engine_context: NullableCvoid,
+        engine_data: Handle<ExclusiveEngineData>,
+        selection_vector: KernelBoolSlice,
+    ),
+) -> DeltaResult<bool> {
+    let mut data = data
+        .data
+        .lock()
+        .map_err(|_| Error::generic("poisoned mutex"))?;
+    let Some(result) = data.next().transpose()? else {
+        return Ok(false);
+    };
+    let selection_vector = match result.full_mask() {
+        Some(mask) => mask.into(),
+        None => KernelBoolSlice::empty(),
+    };
+    (engine_visitor)(engine_context, result.raw_data?.into(), selection_vector);
+    Ok(true)
+}
+
+/// # Safety
+///
+/// Caller is responsible for (at most once) passing a valid pointer returned by a call to
+/// [`table_changes_scan_execute`].
+#[no_mangle]
+pub unsafe extern "C" fn free_table_changes_iter(data: Handle<SharedTableChangesIterator>) {
+    data.drop_handle();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::KernelError;
+    use crate::ffi_test_utils::{
+        allocate_str, assert_extern_result_error_with_message, ok_or_panic, recover_string,
+    };
+    use crate::tests::get_default_engine;
+    use crate::{free_engine, free_schema};
+    use delta_kernel::arrow::array::{Array, Int64Array, RecordBatch, StringArray, StructArray};
+    use delta_kernel::engine::arrow_data::ArrowEngineData;
+    use delta_kernel::EngineData;
+    use std::ptr::NonNull;
+    use test_utils::load_test_data;
+
+    fn table_path(path: impl AsRef<std::path::Path>) -> String {
+        let path = std::fs::canonicalize(path).unwrap();
+        Url::from_directory_path(path).unwrap().to_string()
+    }
+
+    // collects each batch into the `Vec<RecordBatch>` behind the context
+    extern "C" fn collect_batches(
+        engine_context: NullableCvoid,
+        engine_data: Handle<ExclusiveEngineData>,
+        selection_vector: KernelBoolSlice,
+    ) {
+        let batches = unsafe { engine_context.unwrap().cast::<Vec<RecordBatch>>().as_mut() };
+        let data: Box<dyn EngineData> = unsafe { engine_data.into_inner() };
+        let batch: RecordBatch = data
+            .into_any()
+            .downcast::<ArrowEngineData>()
+            .unwrap()
+            .into();
+        // this table has no deletion vectors, so all rows are selected
+        let selection_vector = unsafe { selection_vector.into_vec() };
+        assert!(selection_vector.iter().all(|&selected| selected));
+        batches.push(batch);
+    }
+
+    #[test]
+    fn test_table_changes() {
+        let test_dir = load_test_data("../kernel/tests/data", "cdf-table").unwrap();
+        let path = table_path(test_dir.path().join("cdf-table"));
+        let engine = get_default_engine(&path);
+        let table_changes = unsafe {
+            ok_or_panic(table_changes_between_versions(
+                kernel_string_slice!(path),
+                engine.shallow_copy(),
+                0,
+                1,
+            ))
+        };
+        unsafe {
+            assert_eq!(table_changes_start_version(table_changes.shallow_copy()), 0);
+            assert_eq!(table_changes_end_version(table_changes.shallow_copy()), 1);
+            let table_root =
+                table_changes_table_root(table_changes.shallow_copy(), allocate_str).unwrap();
+            assert_eq!(recover_string(table_root), path);
+
+            let schema = table_changes_schema(table_changes.shallow_copy());
+            let field_names: Vec<_> = schema.as_ref().fields().map(|f| f.name()).collect();
+            assert_eq!(
+                field_names,
+                [
+                    "id",
+                    "name",
+                    "birthday",
+                    "_change_type",
+                    "_commit_version",
+                    "_commit_timestamp"
+                ]
+            );
+            free_schema(schema);
+        }
+
+        let scan = unsafe {
+            ok_or_panic(table_changes_scan(
+                table_changes.shallow_copy(),
+                engine.shallow_copy(),
+                None,
+            ))
+        };
+        let iter = unsafe {
+            ok_or_panic(table_changes_scan_execute(
+                scan.shallow_copy(),
+                engine.shallow_copy(),
+            ))
+        };
+        let mut batches: Vec<RecordBatch> = vec![];
+        let context = NonNull::from(&mut batches).cast();
+        while unsafe {
+            ok_or_panic(table_changes_next(
+                iter.shallow_copy(),
+                Some(context),
+                collect_batches,
+            ))
+        } {}
+
+        let mut changes = vec![];
+        for batch in &batches {
+            let change_types = batch
+                .column_by_name("_change_type")
+                .unwrap()
+                .as_any()
+                .downcast_ref::<StringArray>()
+                .unwrap();
+            let versions = batch
+                .column_by_name("_commit_version")
+                .unwrap()
+                .as_any()
+                .downcast_ref::<Int64Array>()
+                .unwrap();
+            let timestamps = batch.column_by_name("_commit_timestamp").unwrap();
+            assert_eq!(timestamps.null_count(), 0);
+            changes.extend(change_types.iter().zip(versions.iter()));
+        }
+        changes.sort();
+        let expected = [(Some("insert"), Some(0)); 10]
+            .into_iter()
+            .chain([(Some("update_postimage"), Some(1)); 3])
+            .chain([(Some("update_preimage"), Some(1)); 3]);
+        assert!(changes.into_iter().eq(expected));
+
+        unsafe {
+            free_table_changes_iter(iter);
+            free_table_changes_scan(scan);
+            free_table_changes(table_changes);
+            free_engine(engine);
+        }
+    }
+
+    // collects the version and action of the selected scan rows into the `Vec<(i64, String)>`
+    // behind the context
+    extern "C" fn collect_actions(
+        engine_context: NullableCvoid,
+        engine_data: Handle<ExclusiveEngineData>,
+        selection_vector: KernelBoolSlice,
+        remove_dvs: &CRemoveDvs,
+    ) {
+        let actions = unsafe {
+            engine_context
+                .unwrap()
+                .cast::<Vec<(i64, String)>>()
+                .as_mut()
+        };
+        let data: Box<dyn EngineData> = unsafe { engine_data.into_inner() };
+        let batch: RecordBatch = data
+            .into_any()
+            .downcast::<ArrowEngineData>()
+            .unwrap()
+            .into();
+        let selection_vector = unsafe { selection_vector.into_vec() };
+        let versions = batch.column_by_name("commit_version").unwrap();
+        let versions = versions.as_any().downcast_ref::<Int64Array>().unwrap();
+        for row in 0..batch.num_rows() {
+            if !selection_vector.get(row).copied().unwrap_or(true) {
+                continue;
+            }
+            let action = ["add", "remove", "cdc"]
+                .into_iter()
+                .find(|action| {
+                    let action = batch.column_by_name(action).unwrap();
+                    let action = action.as_any().downcast_ref::<StructArray>().unwrap();
+                    action.column_by_name("path").unwrap().is_valid(row)
+                })
+                .unwrap();
+            actions.push((versions.value(row), action.to_string()));
+        }
+        // this table has no deletion vectors
+        assert!(remove_dvs.dvs.is_empty());
+    }
+
+    #[test]
+    fn test_table_changes_scan_metadata() {
+        let test_dir = load_test_data("../kernel/tests/data", "cdf-table").unwrap();
+        let path = table_path(test_dir.path().join("cdf-table"));
+        let engine = get_default_engine(&path);
+        let table_changes = unsafe {
+            ok_or_panic(table_changes_between_versions(
+                kernel_string_slice!(path),
+                engine.shallow_copy(),
+                0,
+                1,
+            ))
+        };
+        let scan = unsafe {
+            ok_or_panic(table_changes_scan(
+                table_changes.shallow_copy(),
+                engine.shallow_copy(),
+                None,
+            ))
+        };
+        let iter = unsafe {
+            ok_or_panic(table_changes_scan_metadata(
+                scan.shallow_copy(),
+                engine.shallow_copy(),
+            ))
+        };
+        let mut actions: Vec<(i64, String)> = vec![];
+        let context = NonNull::from(&mut actions).cast();
+        while unsafe {
+            ok_or_panic(table_changes_scan_metadata_next(
+                iter.shallow_copy(),
+                Some(context),
+                collect_actions,
+            ))
+        } {}
+        // version 0 adds files, and version 1 updates rows through cdc files
+        let expected = [(0, "add"); 10].into_iter().chain([(1, "cdc"); 6]);
+        assert!(actions.iter().map(|(v, a)| (*v, a.as_str())).eq(expected));
+
+        unsafe {
+            free_table_changes_scan_metadata_iter(iter);
+            free_table_changes_scan(scan);
+            free_table_changes(table_changes);
+            free_engine(engine);
+        }
+    }
+
+    #[test]
+    fn test_table_changes_cdf_disabled() {
+        let path = table_path("../kernel/tests/data/table-with-cdf");
+        let engine = get_default_engine(&path);
+        // change data feed is disabled at version 2
+        let res = unsafe {
+            table_changes_between_versions(kernel_string_slice!(path), engine.shallow_copy(), 0, 2)
+        };
+        assert_extern_result_error_with_message(
+            res,
+            KernelError::ChangeDataFeedUnsupported,
+            "Change data feed is unsupported for the table at version 2",
+        );
+        unsafe { free_engine(engine) }
+    }
+}
@@ -3,11 +3,11 @@ mod write_context;
 
 use crate::error::{ExternResult, IntoExternResult};
 use crate::handle::Handle;
-use crate::KernelStringSlice;
 use crate::{unwrap_and_parse_path_as_url, TryFromStringSlice};
 use crate::{DeltaResult, ExternEngine, Snapshot, Url};
-use crate::{ExclusiveEngineData, SharedExternEngine};
-use delta_kernel::transaction::{CommitResult, Transaction};
+use crate::{ExclusiveEngineData, SharedExternEngine, SharedSnapshot};
+use crate::{KernelStringSlice, OptionalValue};
+use delta_kernel::transaction::{CommitResult, PostCommitStats, Transaction};
 use delta_kernel_ffi_macros::handle_descriptor;
 use std::sync::Arc;
 
@@ -99,34 +99,149 @@ pub unsafe extern "C" fn add_files(
     txn.add_files(write_metadata);
 }
 
-/// Attempt to commit a transaction to the table. Returns version number if successful.
-/// Returns error if the commit fails.
+/// Sets the operation of the transaction's commit info action, e.g. `WRITE`. If not set, the
+/// operation is `UNKNOWN`.
+///
+/// # Safety
+///
+/// Caller is responsible for passing a valid handle. CONSUMES TRANSACTION
+#[no_mangle]
+pub unsafe extern "C" fn with_operation(
+    txn: Handle<ExclusiveTransaction>,
+    operation: KernelStringSlice,
+    engine: Handle<SharedExternEngine>,
+) -> ExternResult<Handle<ExclusiveTransaction>> {
+    let txn = unsafe { txn.into_inner() };
+    let engine = unsafe { engine.as_ref() };
+    let operation = unsafe { String::try_from_slice(&operation) };
+    operation
+        .map(|operation| Box::new(txn.with_operation(operation)).into())
+        .into_extern_result(&engine)
+}
+
+/// Include a `txn` (set transaction) action for the given application id and version in the
+/// transaction. Engines can use this to write idempotently: before retrying a write, compare the
+/// version the application last committed (see [`get_app_id_version`]) with the version of the
+/// write.
+///
+/// # Safety
+///
+/// Caller is responsible for passing a valid handle. CONSUMES TRANSACTION
+#[no_mangle]
+pub unsafe extern "C" fn with_transaction_id(
+    txn: Handle<ExclusiveTransaction>,
+    app_id: KernelStringSlice,
+    version: i64,
+    engine: Handle<SharedExternEngine>,
+) -> ExternResult<Handle<ExclusiveTransaction>> {
+    let txn = unsafe { txn.into_inner() };
+    let engine = unsafe { engine.as_ref() };
+    let app_id = unsafe { String::try_from_slice(&app_id) };
+    app_id
+        .map(|app_id| Box::new(txn.with_transaction_id(app_id, version)).into())
+        .into_extern_result(&engine)
+}
+
+/// Get the latest version committed to the table by the given application (see
+/// [`with_transaction_id`]) as of this snapshot, if any.
+///
+/// # Safety
+///
+/// Caller is responsible for passing valid handles.
+#[no_mangle]
+pub unsafe extern "C" fn get_app_id_version(
+    snapshot: Handle<SharedSnapshot>,
+    app_id: KernelStringSlice,
+    engine: Handle<SharedExternEngine>,
+) -> ExternResult<OptionalValue<i64>> {
+    let snapshot = unsafe { snapshot.clone_as_arc() };
+    let engine = unsafe { engine.as_ref() };
+    let app_id = unsafe { String::try_from_slice(&app_id) };
+    get_app_id_version_impl(snapshot, app_id, engine).into_extern_result(&engine)
+}
+
+fn get_app_id_version_impl(
+    snapshot: Arc<Snapshot>,
+    app_id: DeltaResult<String>,
+    extern_engine: &dyn ExternEngine,
+) -> DeltaResult<OptionalValue<i64>> {
+    let version = snapshot.get_app_id_version(&app_id?, extern_engine.engine().as_ref())?;
+    Ok(version.into())
+}
+
+/// Statistics about the table after a successful commit, which engines can use to decide whether
+/// to checkpoint the table.
+#[repr(C)]
+#[derive(Debug, PartialEq)]
+pub struct CommitStats {
+    /// The number of commits since this table has been checkpointed. Note that commit 0 is
+    /// considered a checkpoint for the purposes of this computation.
+    pub commits_since_checkpoint: u64,
+    /// The number of commits since the log has been compacted or checkpointed.
+    pub commits_since_log_compaction: u64,
+}
+
+impl From<PostCommitStats> for CommitStats {
+    fn from(stats: PostCommitStats) -> Self {
+        CommitStats {
+            commits_since_checkpoint: stats.commits_since_checkpoint,
+            commits_since_log_compaction: stats.commits_since_log_compaction,
+        }
+    }
+}
+
+/// The outcome of [`commit`]ting a transaction.
+#[repr(C)]
+pub enum TransactionCommitResult {
+    /// The transaction was committed at `version`.
+    Committed {
+        version: u64,
+        post_commit_stats: CommitStats,
+    },
+    /// The transaction conflicted with an existing commit at `version`. The transaction is handed
+    /// back to the engine, which is responsible for freeing it with [`free_transaction`].
+    Conflict {
+        version: u64,
+        transaction: Handle<ExclusiveTransaction>,
+    },
+}
+
+/// Attempt to commit a transaction to the table. Returns the committed version and post-commit
+/// stats if successful, or the conflicting version if another writer committed that version
+/// first. Returns an error if the commit fails for any other reason.
 ///
 /// # Safety
 ///
 /// Caller is responsible for passing a valid handle. And MUST NOT USE transaction after this
-/// method is called.
+/// method is called, other than the transaction returned on conflict.
 #[no_mangle]
 pub unsafe extern "C" fn commit(
     txn: Handle<ExclusiveTransaction>,
     engine: Handle<SharedExternEngine>,
-) -> ExternResult<u64> {
+) -> ExternResult<TransactionCommitResult> {
     let txn = unsafe { txn.into_inner() };
     let extern_engine = unsafe { engine.as_ref() };
-    let engine = extern_engine.engine();
-    // TODO: for now this removes the enum, which prevents doing any conflict resolution. We should fix
-    //       this by making the commit function return the enum somehow.
-    match txn.commit(engine.as_ref()) {
-        Ok(CommitResult::Committed {
-            version: v,
-            post_commit_stats: _,
-        }) => Ok(v),
-        Ok(CommitResult::Conflict(_, v)) => Err(delta_kernel::Error::Generic(format!(
-            "commit conflict at version {v}"
-        ))),
-        Err(e) => Err(e),
-    }
-    .into_extern_result(&extern_engine)
+    commit_impl(*txn, extern_engine).into_extern_result(&extern_engine)
+}
+
+fn commit_impl(
+    txn: Transaction,
+    extern_engine: &dyn ExternEngine,
+) -> DeltaResult<TransactionCommitResult> {
+    let result = match txn.commit(extern_engine.engine().as_ref())? {
+        CommitResult::Committed {
+            version,
+            post_commit_stats,
+        } => TransactionCommitResult::Committed {
+            version,
+            post_commit_stats: post_commit_stats.into(),
+        },
+        CommitResult::Conflict(txn, version) => TransactionCommitResult::Conflict {
+            version,
+            transaction: Box::new(txn).into(),
+        },
+    };
+    Ok(result)
 }
 
 #[cfg(test)]
@@ -148,10 +263,16 @@ mod tests {
     use delta_kernel_ffi::ffi_test_utils::{allocate_str, ok_or_panic, recover_string};
     use delta_kernel_ffi::tests::get_default_engine;
 
-    use crate::{free_engine, free_schema, kernel_string_slice};
+    use crate::ffi_test_utils::allocate_err;
+    use crate::{
+        engine_to_handle, free_engine, free_schema, free_snapshot, kernel_string_slice, snapshot,
+    };
+    use delta_kernel::engine::default::executor::tokio::TokioBackgroundExecutor;
+    use delta_kernel::engine::default::DefaultEngine;
+    use object_store::memory::InMemory;
     use write_context::{free_write_context, get_write_context, get_write_path, get_write_schema};
 
-    use test_utils::{set_json_value, setup_test_tables, test_read};
+    use test_utils::{add_commit, set_json_value, setup_test_tables, test_read, METADATA};
 
     use itertools::Itertools;
     use object_store::path::Path;
@@ -331,7 +452,12 @@ mod tests {
 
             unsafe { add_files(txn_with_engine_info.shallow_copy(), file_info_engine_data) };
 
-            ok_or_panic(unsafe { commit(txn_with_engine_info, engine.shallow_copy()) });
+            let result =
+                ok_or_panic(unsafe { commit(txn_with_engine_info, engine.shallow_copy()) });
+            assert!(matches!(
+                result,
+                TransactionCommitResult::Committed { version: 1, .. }
+            ));
 
             // Confirm that our commit is what we expect
             let commit1_url = table_url
@@ -384,4 +510,98 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_commit_with_transaction_id_and_conflict() -> Result<(), Box<dyn std::error::Error>>
+    {
+        let storage = Arc::new(InMemory::new());
+        add_commit(storage.as_ref(), 0, METADATA.to_string()).await?;
+        let engine = DefaultEngine::new(storage.clone(), Arc::new(TokioBackgroundExecutor::new()));
+        let engine = engine_to_handle(Arc::new(engine), allocate_err);
+        let path = "memory:///";
+        let app_id = "my-app";
+
+        let snapshot0 =
+            unsafe { ok_or_panic(snapshot(kernel_string_slice!(path), engine.shallow_copy())) };
+        let app_id_version = unsafe {
+            ok_or_panic(get_app_id_version(
+                snapshot0.shallow_copy(),
+                kernel_string_slice!(app_id),
+                engine.shallow_copy(),
+            ))
+        };
+        assert_eq!(app_id_version, OptionalValue::None);
+
+        // two concurrent transactions on version 0 of the table
+        let start_transaction = || {
+            let txn = ok_or_panic(unsafe {
+                transaction(kernel_string_slice!(path), engine.shallow_copy())
+            });
+            let operation = "WRITE";
+            let txn = ok_or_panic(unsafe {
+                with_operation(txn, kernel_string_slice!(operation), engine.shallow_copy())
+            });
+            ok_or_panic(unsafe {
+                with_transaction_id(txn, kernel_string_slice!(app_id), 1, engine.shallow_copy())
+            })
+        };
+        let txn1 = start_transaction();
+        let txn2 = start_transaction();
+
+        let result = ok_or_panic(unsafe { commit(txn1, engine.shallow_copy()) });
+        let TransactionCommitResult::Committed {
+            version,
+            post_commit_stats,
+        } = result
+        else {
+            panic!("expected the first commit to succeed");
+        };
+        assert_eq!(version, 1);
+        assert_eq!(
+            post_commit_stats,
+            CommitStats {
+                commits_since_checkpoint: 1,
+                commits_since_log_compaction: 1,
+            }
+        );
+
+        let result = ok_or_panic(unsafe { commit(txn2, engine.shallow_copy()) });
+        let TransactionCommitResult::Conflict {
+            version,
+            transaction,
+        } = result
+        else {
+            panic!("expected the second commit to conflict");
+        };
+        assert_eq!(version, 1);
+        unsafe { free_transaction(transaction) };
+
+        // the committed operation and application version are visible in the new snapshot
+        let commit1 = storage
+            .get(&Path::from("_delta_log/00000000000000000001.json"))
+            .await?;
+        let parsed_commits: Vec<serde_json::Value> =
+            Deserializer::from_slice(&commit1.bytes().await?)
+                .into_iter::<serde_json::Value>()
+                .try_collect()?;
+        assert_eq!(parsed_commits[0]["commitInfo"]["operation"], "WRITE");
+
+        let snapshot1 =
+            unsafe { ok_or_panic(snapshot(kernel_string_slice!(path), engine.shallow_copy())) };
+        let app_id_version = unsafe {
+            ok_or_panic(get_app_id_version(
+                snapshot1.shallow_copy(),
+                kernel_string_slice!(app_id),
+                engine.shallow_copy(),
+            ))
+        };
+        assert_eq!(app_id_version, OptionalValue::Some(1));
+
+        unsafe {
+            free_snapshot(snapshot0);
+            free_snapshot(snapshot1);
+            free_engine(engine);
+        }
+        Ok(())
+    }
 }
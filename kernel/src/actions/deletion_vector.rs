@@ -12,8 +12,8 @@ use delta_kernel_derive::ToSchema;
 use crate::utils::require;
 use crate::{DeltaResult, Error, StorageHandler};
 
-#[derive(Debug, Clone, PartialEq, Eq, ToSchema)]
-#[cfg_attr(test, derive(serde::Serialize), serde(rename_all = "camelCase"))]
+#[derive(Debug, Clone, PartialEq, Eq, ToSchema, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct DeletionVectorDescriptor {
     /// A single character to indicate how to access the DV. Legal options are: ['u', 'i', 'p'].
     pub storage_type: String,
@@ -108,70 +108,99 @@ impl DeletionVectorDescriptor {
         parent: &Url,
     ) -> DeltaResult<RoaringTreemap> {
         match self.absolute_path(parent)? {
-            None => {
-                let byte_slice = z85::decode(&self.path_or_inline_dv)
-                    .map_err(|_| Error::deletion_vector("Failed to decode DV"))?;
-                let magic = slice_to_u32(&byte_slice[0..4], Endian::Little)?;
-                match magic {
-                    1681511377 => RoaringTreemap::deserialize_from(&byte_slice[4..])
-                        .map_err(|err| Error::DeletionVector(err.to_string())),
-                    1681511376 => {
-                        todo!("Don't support native serialization in inline bitmaps yet");
-                    }
-                    _ => Err(Error::DeletionVector(format!("Invalid magic {magic}"))),
-                }
-            }
+            None => self.read_inline(),
             Some(path) => {
-                let offset = self.offset;
-                let size_in_bytes = self.size_in_bytes;
-
                 let dv_data = storage
                     .read_files(vec![(path, None)])?
                     .next()
                     .ok_or(Error::missing_data("No deletion vector data"))??;
+                self.parse_stored(dv_data)
+            }
+        }
+    }
 
-                let mut cursor = Cursor::new(dv_data);
-                let mut version_buf = [0; 1];
-                cursor
-                    .read(&mut version_buf)
-                    .map_err(|err| Error::DeletionVector(err.to_string()))?;
-                let version = u8::from_be_bytes(version_buf);
-                require!(
-                    version == 1,
-                    Error::DeletionVector(format!("Invalid version: {version}"))
-                );
-
-                if let Some(offset) = offset {
-                    cursor.set_position(offset as u64);
-                }
-                let dv_size = read_u32(&mut cursor, Endian::Big)?;
-                require!(
-                    dv_size == size_in_bytes as u32,
-                    Error::DeletionVector(format!(
-                        "DV size mismatch. Log indicates {size_in_bytes}, file says: {dv_size}"
-                    ))
-                );
-                let magic = read_u32(&mut cursor, Endian::Little)?;
-                require!(
-                    magic == 1681511377,
-                    Error::DeletionVector(format!("Invalid magic: {magic}"))
-                );
+    /// Like [`Self::read`], but reads a dv stored in a file through an [`AsyncStorageHandler`].
+    ///
+    /// [`AsyncStorageHandler`]: crate::async_engine::AsyncStorageHandler
+    #[cfg(feature = "async-engine")]
+    pub(crate) async fn read_async(
+        &self,
+        storage: Arc<dyn crate::async_engine::AsyncStorageHandler>,
+        parent: &Url,
+    ) -> DeltaResult<RoaringTreemap> {
+        use futures::StreamExt as _;
+
+        let Some(path) = self.absolute_path(parent)? else {
+            // inline dvs don't need any IO
+            return self.read_inline();
+        };
+        let dv_data = storage
+            .read_files(vec![(path, None)])?
+            .next()
+            .await
+            .ok_or(Error::missing_data("No deletion vector data"))??;
+        self.parse_stored(dv_data)
+    }
 
-                // get the Bytes back out and limit it to dv_size
-                let position = cursor.position();
-                let mut bytes = cursor.into_inner();
-                let truncate_pos = position + dv_size as u64;
-                assert!(
-                    truncate_pos <= usize::MAX as u64,
-                    "Can't truncate as truncate_pos is > usize::MAX"
-                );
-                bytes.truncate(truncate_pos as usize);
-                let mut cursor = Cursor::new(bytes);
-                cursor.set_position(position);
-                RoaringTreemap::deserialize_from(cursor)
-                    .map_err(|err| Error::DeletionVector(err.to_string()))
+    // Decode a dv stored inline in the log.
+    fn read_inline(&self) -> DeltaResult<RoaringTreemap> {
+        let byte_slice = z85::decode(&self.path_or_inline_dv)
+            .map_err(|_| Error::deletion_vector("Failed to decode DV"))?;
+        let magic = slice_to_u32(&byte_slice[0..4], Endian::Little)?;
+        match magic {
+            1681511377 => RoaringTreemap::deserialize_from(&byte_slice[4..])
+                .map_err(|err| Error::DeletionVector(err.to_string())),
+            1681511376 => {
+                todo!("Don't support native serialization in inline bitmaps yet");
             }
+            _ => Err(Error::DeletionVector(format!("Invalid magic {magic}"))),
+        }
+    }
+
+    // Parse the contents of the file a (non-inline) dv is stored in.
+    fn parse_stored(&self, dv_data: Bytes) -> DeltaResult<RoaringTreemap> {
+        let offset = self.offset;
+        let size_in_bytes = self.size_in_bytes;
+        let mut cursor = Cursor::new(dv_data);
+        let mut version_buf = [0; 1];
+        cursor
+            .read(&mut version_buf)
+            .map_err(|err| Error::DeletionVector(err.to_string()))?;
+        let version = u8::from_be_bytes(version_buf);
+        require!(
+            version == 1,
+            Error::DeletionVector(format!("Invalid version: {version}"))
+        );
+
+        if let Some(offset) = offset {
+            cursor.set_position(offset as u64);
         }
+        let dv_size = read_u32(&mut cursor, Endian::Big)?;
+        require!(
+            dv_size == size_in_bytes as u32,
+            Error::DeletionVector(format!(
+                "DV size mismatch. Log indicates {size_in_bytes}, file says: {dv_size}"
+            ))
+        );
+        let magic = read_u32(&mut cursor, Endian::Little)?;
+        require!(
+            magic == 1681511377,
+            Error::DeletionVector(format!("Invalid magic: {magic}"))
+        );
+
+        // get the Bytes back out and limit it to dv_size
+        let position = cursor.position();
+        let mut bytes = cursor.into_inner();
+        let truncate_pos = position + dv_size as u64;
+        assert!(
+            truncate_pos <= usize::MAX as u64,
+            "Can't truncate as truncate_pos is > usize::MAX"
+        );
+        bytes.truncate(truncate_pos as usize);
+        let mut cursor = Cursor::new(bytes);
+        cursor.set_position(position);
+        RoaringTreemap::deserialize_from(cursor)
+            .map_err(|err| Error::DeletionVector(err.to_string()))
     }
 
     /// Materialize the row indexes of the deletion vector as a `Vec<u64>` in which each element
@@ -35,6 +35,16 @@ pub(crate) fn domain_metadata_configuration(
         .map(|domain_metadata| domain_metadata.configuration))
 }
 
+/// Read the names of all domains (including 'internal' delta.* domains) which have not been
+/// removed.
+pub(crate) fn all_domain_names(
+    log_segment: &LogSegment,
+    engine: &dyn Engine,
+) -> DeltaResult<Vec<String>> {
+    let domain_metadatas = scan_domain_metadatas(log_segment, None, engine)?;
+    Ok(domain_metadatas.into_keys().collect())
+}
+
 /// Scan the entire log for all domain metadata actions but terminate early if a specific domain
 /// is provided. Note that this returns the latest domain metadata for each domain, accounting for
 /// tombstones (removed=true) - that is, removed domain metadatas will _never_ be returned.
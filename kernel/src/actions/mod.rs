@@ -89,6 +89,13 @@ static LOG_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
     ]))
 });
 
+static LOG_REMOVE_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
+    Arc::new(StructType::new([StructField::nullable(
+        REMOVE_NAME,
+        Remove::to_schema(),
+    )]))
+});
+
 static LOG_COMMIT_INFO_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
     Arc::new(StructType::new([StructField::nullable(
         COMMIT_INFO_NAME,
@@ -96,6 +103,20 @@ static LOG_COMMIT_INFO_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
     )]))
 });
 
+static LOG_METADATA_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
+    Arc::new(StructType::new([StructField::nullable(
+        METADATA_NAME,
+        Metadata::to_schema(),
+    )]))
+});
+
+static LOG_PROTOCOL_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
+    Arc::new(StructType::new([StructField::nullable(
+        PROTOCOL_NAME,
+        Protocol::to_schema(),
+    )]))
+});
+
 static LOG_TXN_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
     Arc::new(StructType::new([StructField::nullable(
         SET_TRANSACTION_NAME,
@@ -124,6 +145,18 @@ pub(crate) fn get_log_commit_info_schema() -> &'static SchemaRef {
     &LOG_COMMIT_INFO_SCHEMA
 }
 
+pub(crate) fn get_log_remove_schema() -> &'static SchemaRef {
+    &LOG_REMOVE_SCHEMA
+}
+
+pub(crate) fn get_log_metadata_schema() -> &'static SchemaRef {
+    &LOG_METADATA_SCHEMA
+}
+
+pub(crate) fn get_log_protocol_schema() -> &'static SchemaRef {
+    &LOG_PROTOCOL_SCHEMA
+}
+
 pub(crate) fn get_log_txn_schema() -> &'static SchemaRef {
     &LOG_TXN_SCHEMA
 }
@@ -132,12 +165,8 @@ pub(crate) fn get_log_domain_metadata_schema() -> &'static SchemaRef {
     &LOG_DOMAIN_METADATA_SCHEMA
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, ToSchema)]
-#[cfg_attr(
-    any(test, feature = "internal-api"),
-    derive(Serialize, Deserialize),
-    serde(rename_all = "camelCase")
-)]
+#[derive(Debug, Clone, PartialEq, Eq, ToSchema, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[internal_api]
 pub(crate) struct Format {
     /// Name of the encoding for files in this table
@@ -172,12 +201,8 @@ impl TryFrom<Format> for Scalar {
     }
 }
 
-#[derive(Debug, Default, Clone, PartialEq, Eq, ToSchema)]
-#[cfg_attr(
-    any(test, feature = "internal-api"),
-    derive(Serialize, Deserialize),
-    serde(rename_all = "camelCase")
-)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, ToSchema, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 #[internal_api]
 pub(crate) struct Metadata {
     /// Unique identifier for this table
@@ -588,9 +613,9 @@ where
     )))
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, ToSchema)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, ToSchema)]
 #[internal_api]
-#[cfg_attr(test, derive(Serialize, Default), serde(rename_all = "camelCase"))]
+#[cfg_attr(test, derive(Serialize), serde(rename_all = "camelCase"))]
 pub(crate) struct CommitInfo {
     /// The time this logical file was created, as milliseconds since the epoch.
     /// Read: optional, write: required (that is, kernel always writes).
@@ -672,6 +697,52 @@ impl IntoEngineData for CommitInfo {
     }
 }
 
+// Used to re-emit existing add actions, e.g. when restoring files (see `Transaction::restore`).
+impl IntoEngineData for Add {
+    fn into_engine_data(
+        self,
+        schema: SchemaRef,
+        engine: &dyn Engine,
+    ) -> DeltaResult<Box<dyn EngineData>> {
+        let string_map =
+            |nullable_values| MapType::new(DataType::STRING, DataType::STRING, nullable_values);
+        let partition_values =
+            Scalar::Map(MapData::try_new(string_map(true), self.partition_values)?);
+        let tags = match self.tags {
+            Some(tags) => Scalar::Map(MapData::try_new(string_map(false), tags)?),
+            None => Scalar::Null(string_map(false).into()),
+        };
+        let dv = self.deletion_vector;
+        let dv_values = [
+            Scalar::from(dv.as_ref().map(|dv| dv.storage_type.clone())),
+            Scalar::from(dv.as_ref().map(|dv| dv.path_or_inline_dv.clone())),
+            Scalar::from(dv.as_ref().and_then(|dv| dv.offset)),
+            Scalar::from(dv.as_ref().map(|dv| dv.size_in_bytes)),
+            Scalar::from(dv.as_ref().map(|dv| dv.cardinality)),
+        ];
+        let values: Vec<_> = [
+            Scalar::from(self.path),
+            partition_values,
+            Scalar::from(self.size),
+            Scalar::from(self.modification_time),
+            Scalar::from(self.data_change),
+            Scalar::from(self.stats),
+            tags,
+        ]
+        .into_iter()
+        .chain(dv_values)
+        .chain([
+            Scalar::from(self.base_row_id),
+            Scalar::from(self.default_row_commit_version),
+            Scalar::from(self.clustering_provider),
+        ])
+        .collect();
+
+        let evaluator = engine.evaluation_handler();
+        evaluator.create_one(schema, &values)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, ToSchema)]
 #[cfg_attr(test, derive(Serialize, Default), serde(rename_all = "camelCase"))]
 #[internal_api]
@@ -918,7 +989,7 @@ pub(crate) struct CheckpointMetadata {
 /// Note that the `delta.*` domain is reserved for internal use.
 ///
 /// [DomainMetadata]: https://github.com/delta-io/delta/blob/master/PROTOCOL.md#domain-metadata
-#[derive(Debug, Clone, PartialEq, Eq, ToSchema)]
+#[derive(Debug, Clone, PartialEq, Eq, ToSchema, IntoEngineData)]
 #[internal_api]
 pub(crate) struct DomainMetadata {
     domain: String,
@@ -927,6 +998,14 @@ pub(crate) struct DomainMetadata {
 }
 
 impl DomainMetadata {
+    pub(crate) fn new(domain: String, configuration: String) -> Self {
+        Self {
+            domain,
+            configuration,
+            removed: false,
+        }
+    }
+
     // returns true if the domain metadata is an system-controlled domain (all domains that start
     // with "delta.")
     #[allow(unused)]
@@ -1339,7 +1418,7 @@ mod tests {
         .unwrap();
         assert_result_error_with_message(
             protocol.ensure_write_supported(),
-            r#"Unsupported: Unknown WriterFeatures: "rowTracking". Supported WriterFeatures: "appendOnly", "deletionVectors", "invariants", "timestampNtz", "variantType", "variantType-preview", "variantShredding-preview""#,
+            r#"Unsupported: Unknown WriterFeatures: "rowTracking". Supported WriterFeatures: "appendOnly", "clustering", "deletionVectors", "domainMetadata", "generatedColumns", "identityColumns", "invariants", "timestampNtz", "vacuumProtocolCheck", "variantType", "variantType-preview", "variantShredding-preview""#,
         );
     }
 
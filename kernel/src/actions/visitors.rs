@@ -90,6 +90,63 @@ impl RowVisitor for ProtocolVisitor {
     }
 }
 
+/// Extracts the [`CommitInfo`] of a commit. Rows without a timestamp, in-commit timestamp or
+/// operation are not considered commit info actions.
+#[derive(Default)]
+pub(crate) struct CommitInfoVisitor {
+    pub(crate) commit_info: Option<CommitInfo>,
+}
+
+impl CommitInfoVisitor {
+    /// Extracts the commit info of row `i` from `getters`, which are the getters of the leaves of
+    /// [`CommitInfo::to_schema`], if the row is a commit info action.
+    pub(crate) fn visit_commit_info<'a>(
+        i: usize,
+        getters: &[&'a dyn GetData<'a>],
+    ) -> DeltaResult<Option<CommitInfo>> {
+        let timestamp: Option<i64> = getters[0].get_opt(i, "commitInfo.timestamp")?;
+        let in_commit_timestamp: Option<i64> =
+            getters[1].get_opt(i, "commitInfo.inCommitTimestamp")?;
+        let operation: Option<String> = getters[2].get_opt(i, "commitInfo.operation")?;
+        if timestamp.is_none() && in_commit_timestamp.is_none() && operation.is_none() {
+            return Ok(None);
+        }
+        Ok(Some(CommitInfo {
+            timestamp,
+            in_commit_timestamp,
+            operation,
+            operation_parameters: getters[3].get_opt(i, "commitInfo.operationParameters")?,
+            kernel_version: getters[4].get_opt(i, "commitInfo.kernelVersion")?,
+            engine_info: getters[5].get_opt(i, "commitInfo.engineInfo")?,
+            txn_id: getters[6].get_opt(i, "commitInfo.txnId")?,
+        }))
+    }
+}
+
+impl RowVisitor for CommitInfoVisitor {
+    fn selected_column_names_and_types(&self) -> (&'static [ColumnName], &'static [DataType]) {
+        static NAMES_AND_TYPES: LazyLock<ColumnNamesAndTypes> =
+            LazyLock::new(|| CommitInfo::to_schema().leaves(COMMIT_INFO_NAME));
+        NAMES_AND_TYPES.as_ref()
+    }
+    fn visit<'a>(&mut self, row_count: usize, getters: &[&'a dyn GetData<'a>]) -> DeltaResult<()> {
+        require!(
+            getters.len() == 7,
+            Error::InternalError(format!(
+                "Wrong number of CommitInfoVisitor getters: {}",
+                getters.len()
+            ))
+        );
+        for i in 0..row_count {
+            if let Some(commit_info) = Self::visit_commit_info(i, getters)? {
+                self.commit_info = Some(commit_info);
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
 #[allow(unused)]
 #[derive(Default)]
 #[internal_api]
@@ -116,9 +173,7 @@ impl AddVisitor {
         let modification_time: i64 = getters[3].get(row_index, "add.modificationTime")?;
         let data_change: bool = getters[4].get(row_index, "add.dataChange")?;
         let stats: Option<String> = getters[5].get_opt(row_index, "add.stats")?;
-
-        // TODO(nick) extract tags if we ever need them at getters[6]
-
+        let tags: Option<HashMap<String, String>> = getters[6].get_opt(row_index, "add.tags")?;
         let deletion_vector = visit_deletion_vector_at(row_index, &getters[7..])?;
 
         let base_row_id: Option<i64> = getters[12].get_opt(row_index, "add.base_row_id")?;
@@ -134,7 +189,7 @@ impl AddVisitor {
             modification_time,
             data_change,
             stats,
-            tags: None,
+            tags,
             deletion_vector,
             base_row_id,
             default_row_commit_version,
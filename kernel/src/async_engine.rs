@@ -0,0 +1,134 @@
+//! Async counterparts of the [`Engine`] handler traits.
+//!
+//! The regular [`Engine`] APIs are synchronous, which forces engines built on async IO to bridge
+//! the two worlds, typically by running their IO on a background executor. Engines implementing
+//! [`AsyncEngine`] instead expose their IO as futures and [`Stream`]s, which lets kernel drive it
+//! directly through the async APIs:
+//!
+//! - [`Snapshot::try_new_async`]
+//! - [`Scan::scan_metadata_async`]
+//! - [`Scan::execute_async`]
+//!
+//! Only IO is async. CPU-bound work (expression evaluation, JSON parsing) still goes through the
+//! synchronous handlers of the [`Engine`] supertrait.
+//!
+//! [`Snapshot::try_new_async`]: crate::Snapshot::try_new_async
+//! [`Scan::scan_metadata_async`]: crate::scan::Scan::scan_metadata_async
+//! [`Scan::execute_async`]: crate::scan::Scan::execute_async
+use std::sync::Arc;
+
+use bytes::Bytes;
+use futures::future::{self, BoxFuture, FutureExt};
+use futures::stream::BoxStream;
+use url::Url;
+
+use crate::schema::SchemaRef;
+use crate::{AsAny, DeltaResult, Engine, EngineData, Error, FileMeta, FileSlice, PredicateRef};
+
+pub use futures::Stream;
+
+/// A stream of data read from specified files
+pub type FileDataReadResultStream = BoxStream<'static, DeltaResult<Box<dyn EngineData>>>;
+
+/// Async counterpart of [`StorageHandler`]. See there for the contract of each method.
+///
+/// [`StorageHandler`]: crate::StorageHandler
+pub trait AsyncStorageHandler: AsAny {
+    /// List the paths in the same directory that are lexicographically greater than
+    /// (UTF-8 sorting) the given `path`. The result should also be sorted by the file name.
+    fn list_from(&self, path: &Url) -> DeltaResult<BoxStream<'static, DeltaResult<FileMeta>>>;
+
+    /// Read data specified by the start and end offset from the file. The data must be returned
+    /// in the same order as the provided file slices.
+    fn read_files(
+        &self,
+        files: Vec<FileSlice>,
+    ) -> DeltaResult<BoxStream<'static, DeltaResult<Bytes>>>;
+
+    /// Write `data` to the file at `path`. See [`StorageHandler::put`]. Like the synchronous
+    /// methods, the default implementations of `put`, `head`, `delete` and `copy` return
+    /// [`Error::Unsupported`].
+    ///
+    /// [`StorageHandler::put`]: crate::StorageHandler::put
+    fn put(&self, path: &Url, data: Bytes, overwrite: bool) -> BoxFuture<'static, DeltaResult<()>> {
+        let _ = (data, overwrite);
+        let err = Error::unsupported(format!(
+            "put is not supported by this storage handler: {path}"
+        ));
+        future::ready(Err(err)).boxed()
+    }
+
+    /// Return the metadata of the file at `path`. See [`StorageHandler::head`].
+    ///
+    /// [`StorageHandler::head`]: crate::StorageHandler::head
+    fn head(&self, path: &Url) -> BoxFuture<'static, DeltaResult<FileMeta>> {
+        let err = Error::unsupported(format!(
+            "head is not supported by this storage handler: {path}"
+        ));
+        future::ready(Err(err)).boxed()
+    }
+
+    /// Delete the file at `path`. See [`StorageHandler::delete`].
+    ///
+    /// [`StorageHandler::delete`]: crate::StorageHandler::delete
+    fn delete(&self, path: &Url) -> BoxFuture<'static, DeltaResult<()>> {
+        let err = Error::unsupported(format!(
+            "delete is not supported by this storage handler: {path}"
+        ));
+        future::ready(Err(err)).boxed()
+    }
+
+    /// Copy the file at `from` to `to`. See [`StorageHandler::copy`].
+    ///
+    /// [`StorageHandler::copy`]: crate::StorageHandler::copy
+    fn copy(&self, from: &Url, to: &Url) -> BoxFuture<'static, DeltaResult<()>> {
+        let err = Error::unsupported(format!(
+            "copy is not supported by this storage handler: {from} -> {to}"
+        ));
+        future::ready(Err(err)).boxed()
+    }
+}
+
+/// Async counterpart of the file reading half of [`JsonHandler`].
+///
+/// [`JsonHandler`]: crate::JsonHandler
+pub trait AsyncJsonHandler: AsAny {
+    /// Read and parse the JSON format files at given locations. The same ordering requirements as
+    /// [`JsonHandler::read_json_files`] apply to the returned stream.
+    ///
+    /// [`JsonHandler::read_json_files`]: crate::JsonHandler::read_json_files
+    fn read_json_files(
+        &self,
+        files: &[FileMeta],
+        physical_schema: SchemaRef,
+        predicate: Option<PredicateRef>,
+    ) -> DeltaResult<FileDataReadResultStream>;
+}
+
+/// Async counterpart of [`ParquetHandler`].
+///
+/// [`ParquetHandler`]: crate::ParquetHandler
+pub trait AsyncParquetHandler: AsAny {
+    /// Read and parse the Parquet files at given locations. See
+    /// [`ParquetHandler::read_parquet_files`].
+    ///
+    /// [`ParquetHandler::read_parquet_files`]: crate::ParquetHandler::read_parquet_files
+    fn read_parquet_files(
+        &self,
+        files: &[FileMeta],
+        physical_schema: SchemaRef,
+        predicate: Option<PredicateRef>,
+    ) -> DeltaResult<FileDataReadResultStream>;
+}
+
+/// An [`Engine`] which can also perform its IO asynchronously.
+pub trait AsyncEngine: Engine {
+    /// Get the connector provided [`AsyncStorageHandler`].
+    fn async_storage_handler(&self) -> Arc<dyn AsyncStorageHandler>;
+
+    /// Get the connector provided [`AsyncJsonHandler`].
+    fn async_json_handler(&self) -> Arc<dyn AsyncJsonHandler>;
+
+    /// Get the connector provided [`AsyncParquetHandler`].
+    fn async_parquet_handler(&self) -> Arc<dyn AsyncParquetHandler>;
+}
@@ -6,11 +6,14 @@ use crate::arrow::array::{
 use crate::arrow::compute::kernels::cmp::{distinct, eq, gt, gt_eq, lt, lt_eq, neq, not_distinct};
 use crate::arrow::compute::kernels::comparison::in_list_utf8;
 use crate::arrow::compute::kernels::numeric::{add, div, mul, sub};
-use crate::arrow::compute::{and_kleene, is_not_null, is_null, not, or_kleene};
+use crate::arrow::compute::{
+    and_kleene, cast_with_options, is_not_null, is_null, not, or_kleene, CastOptions,
+};
 use crate::arrow::datatypes::{
     DataType as ArrowDataType, Field as ArrowField, IntervalUnit, TimeUnit,
 };
 use crate::arrow::error::ArrowError;
+use crate::engine::arrow_conversion::TryFromKernel as _;
 use crate::engine::arrow_expression::opaque::{
     ArrowOpaqueExpressionOpAdaptor, ArrowOpaquePredicateOpAdaptor,
 };
@@ -18,8 +21,8 @@ use crate::engine::arrow_utils::prim_array_cmp;
 use crate::error::{DeltaResult, Error};
 use crate::expressions::{
     BinaryExpression, BinaryExpressionOp, BinaryPredicate, BinaryPredicateOp, Expression,
-    JunctionPredicate, JunctionPredicateOp, OpaqueExpression, OpaquePredicate, Predicate, Scalar,
-    UnaryPredicate, UnaryPredicateOp,
+    JunctionPredicate, JunctionPredicateOp, OpaqueExpression, OpaqueExpressionOp as _,
+    OpaquePredicate, Predicate, Scalar, SqlFunction, UnaryPredicate, UnaryPredicateOp,
 };
 use crate::schema::DataType;
 use itertools::Itertools;
@@ -133,6 +136,9 @@ pub fn evaluate_expression(
             Ok(eval(&left_arr, &right_arr)?)
         }
         (Opaque(OpaqueExpression { op, exprs }), _) => {
+            if let Some(function) = op.any_ref().downcast_ref::<SqlFunction>() {
+                return evaluate_sql_function(function, exprs, batch);
+            }
             match op
                 .any_ref()
                 .downcast_ref::<ArrowOpaqueExpressionOpAdaptor>()
@@ -147,6 +153,64 @@ pub fn evaluate_expression(
     }
 }
 
+// Evaluates one of kernel's built-in SQL functions, whose semantics are defined (one value at a
+// time) by [`crate::expressions::DateTimeField`] and the arrow `cast` kernel. Like `cast_scalar`,
+// casts fail on overflow and invalid input instead of producing NULL.
+fn evaluate_sql_function(
+    function: &SqlFunction,
+    exprs: &[Expression],
+    batch: &RecordBatch,
+) -> DeltaResult<ArrayRef> {
+    let [arg] = exprs else {
+        return Err(Error::generic(format!(
+            "{} expects exactly one argument",
+            function.name()
+        )));
+    };
+    let arr = evaluate_expression(arg, batch, None)?;
+    let unsupported_input = || {
+        Error::unsupported(format!(
+            "Cannot apply {} to {}",
+            function.name(),
+            arr.data_type()
+        ))
+    };
+    let result: ArrayRef = match (function, arr.data_type()) {
+        (SqlFunction::Cast(data_type), _) => {
+            let options = CastOptions {
+                safe: false,
+                ..Default::default()
+            };
+            cast_with_options(&arr, &ArrowDataType::try_from_kernel(data_type)?, &options)?
+        }
+        (SqlFunction::Extract(field), ArrowDataType::Date32) => Arc::new(
+            arr.as_primitive::<Date32Type>()
+                .unary_opt::<_, Int32Type>(|days| field.extract_from_date(days)),
+        ),
+        (SqlFunction::Extract(field), ArrowDataType::Timestamp(TimeUnit::Microsecond, _)) => {
+            Arc::new(
+                arr.as_primitive::<TimestampMicrosecondType>()
+                    .unary_opt::<_, Int32Type>(|micros| field.extract_from_timestamp(micros)),
+            )
+        }
+        (SqlFunction::Truncate(field), ArrowDataType::Date32) => Arc::new(
+            arr.as_primitive::<Date32Type>()
+                .unary_opt::<_, Date32Type>(|days| field.truncate_date(days)),
+        ),
+        (SqlFunction::Truncate(field), ArrowDataType::Timestamp(TimeUnit::Microsecond, tz)) => {
+            Arc::new(
+                arr.as_primitive::<TimestampMicrosecondType>()
+                    .unary_opt::<_, TimestampMicrosecondType>(|micros| {
+                        field.truncate_timestamp(micros)
+                    })
+                    .with_timezone_opt(tz.clone()),
+            )
+        }
+        _ => return Err(unsupported_input()),
+    };
+    Ok(result)
+}
+
 /// Evaluates a (possibly inverted) kernel predicate over a record batch
 pub fn evaluate_predicate(
     predicate: &Predicate,
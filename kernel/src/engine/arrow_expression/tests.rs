@@ -1,12 +1,12 @@
 use std::ops::{Add, Div, Mul, Sub};
 
 use crate::arrow::array::{
-    create_array, Array, ArrayRef, BooleanArray, GenericStringArray, Int32Array, Int32Builder,
-    ListArray, MapArray, MapBuilder, MapFieldNames, StringBuilder, StructArray,
+    create_array, Array, ArrayRef, BooleanArray, Date32Array, GenericStringArray, Int32Array,
+    Int32Builder, ListArray, MapArray, MapBuilder, MapFieldNames, StringBuilder, StructArray,
 };
 use crate::arrow::buffer::{OffsetBuffer, ScalarBuffer};
 use crate::arrow::compute::kernels::cmp::{gt_eq, lt};
-use crate::arrow::datatypes::{DataType, Field, Fields, Schema};
+use crate::arrow::datatypes::{DataType, Field, Fields, Schema, TimeUnit};
 
 use super::*;
 use crate::engine::arrow_expression::opaque::{
@@ -900,3 +900,51 @@ fn test_null_scalar_map() -> DeltaResult<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_sql_functions() {
+    // 2024-05-17 and 1969-12-31
+    let days = Date32Array::from(vec![Some(19_860), Some(-1), None]);
+    let schema = Schema::new(vec![Field::new("d", DataType::Date32, true)]);
+    let batch = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(days)]).unwrap();
+
+    let year = Expr::opaque(
+        SqlFunction::Extract(DateTimeField::Year),
+        [column_expr!("d")],
+    );
+    let results = evaluate_expression(&year, &batch, None).unwrap();
+    let expected: ArrayRef = Arc::new(Int32Array::from(vec![Some(2024), Some(1969), None]));
+    assert_eq!(&results, &expected);
+
+    let month = Expr::opaque(
+        SqlFunction::Truncate(DateTimeField::Month),
+        [column_expr!("d")],
+    );
+    let results = evaluate_expression(&month, &batch, None).unwrap();
+    let expected: ArrayRef = Arc::new(Date32Array::from(vec![Some(19_844), Some(-31), None]));
+    assert_eq!(&results, &expected);
+
+    let cast = Expr::opaque(
+        SqlFunction::Cast(KernelDataType::TIMESTAMP),
+        [column_expr!("d")],
+    );
+    let results = evaluate_expression(&cast, &batch, None).unwrap();
+    assert_eq!(
+        results.data_type(),
+        &DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into()))
+    );
+
+    // casts fail on overflow rather than producing NULL
+    let cast = Expr::opaque(
+        SqlFunction::Cast(KernelDataType::INTEGER),
+        [Expr::literal(i64::MAX)],
+    );
+    assert!(evaluate_expression(&cast, &batch, None).is_err());
+
+    // extracting from a non-temporal column is an error
+    let hour = Expr::opaque(
+        SqlFunction::Extract(DateTimeField::Hour),
+        [Expr::literal(1)],
+    );
+    assert!(evaluate_expression(&hour, &batch, None).is_err());
+}
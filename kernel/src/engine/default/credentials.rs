@@ -0,0 +1,529 @@
+//! Expiring storage credentials for the [`DefaultEngine`]. A [`CredentialProvider`] vends
+//! credentials as object store options; the [`RefreshingObjectStore`] (re)builds its underlying
+//! [`ObjectStore`] with fresh credentials whenever the current ones are about to expire.
+//!
+//! [`DefaultEngine`]: super::DefaultEngine
+
+use std::collections::HashMap;
+use std::fmt::{self, Debug};
+use std::ops::Range;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use bytes::Bytes;
+use futures::future::BoxFuture;
+use futures::lock::Mutex;
+use futures::stream::{self, BoxStream, StreamExt as _, TryStreamExt as _};
+use object_store::path::Path;
+use object_store::{
+    DynObjectStore, GetOptions, GetResult, ListResult, MultipartUpload, ObjectMeta, ObjectStore,
+    PutMultipartOptions, PutOptions, PutPayload, PutResult,
+};
+use tracing::warn;
+
+use crate::DeltaResult;
+
+/// Storage credentials vended by a [`CredentialProvider`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StorageCredentials {
+    /// The credentials, as options for the object store, e.g. `aws_session_token`. See
+    /// [`parse_url_opts`](super::storage::parse_url_opts).
+    pub options: HashMap<String, String>,
+    /// When the credentials expire, in milliseconds since the epoch, if they do.
+    pub expires_at: Option<i64>,
+}
+
+/// Vends (temporary) credentials to access a table's storage, e.g. from a catalog.
+pub trait CredentialProvider: Debug + Send + Sync {
+    /// Get fresh credentials.
+    fn get_credentials(&self) -> BoxFuture<'_, DeltaResult<StorageCredentials>>;
+}
+
+type StoreBuilder =
+    dyn Fn(&HashMap<String, String>) -> DeltaResult<Arc<DynObjectStore>> + Send + Sync;
+
+/// The default time before credentials expire at which they are refreshed.
+pub const DEFAULT_REFRESH_MARGIN: Duration = Duration::from_secs(5 * 60);
+
+/// An [`ObjectStore`] whose credentials are vended by a [`CredentialProvider`]. The credentials are
+/// fetched on the first request, and refreshed (rebuilding the underlying store) on the first
+/// request within the refresh margin of their expiry, so that long-running reads and writes keep
+/// working across credential expiry. Requests already in flight keep using the store they started
+/// with. If refreshing fails, requests keep using the current credentials until they expire.
+pub struct RefreshingObjectStore {
+    inner: Arc<Inner>,
+    refresh_margin: Duration,
+}
+
+struct Inner {
+    provider: Arc<dyn CredentialProvider>,
+    build_store: Box<StoreBuilder>,
+    current: Mutex<Option<CurrentStore>>,
+}
+
+// The current store, along with when its credentials were fetched and when they expire (in
+// milliseconds since the epoch).
+struct CurrentStore {
+    store: Arc<DynObjectStore>,
+    fetched_at: i64,
+    expires_at: Option<i64>,
+}
+
+impl CurrentStore {
+    // Whether the credentials expire within `refresh_margin`. The margin is at most half the
+    // lifetime of the credentials, so that short-lived credentials are not refreshed on every
+    // request.
+    fn needs_refresh(&self, now: i64, refresh_margin: Duration) -> bool {
+        let Some(expires_at) = self.expires_at else {
+            return false;
+        };
+        let refresh_margin = i64::try_from(refresh_margin.as_millis()).unwrap_or(i64::MAX);
+        let refresh_margin = refresh_margin.min((expires_at - self.fetched_at) / 2);
+        now >= expires_at.saturating_sub(refresh_margin)
+    }
+
+    fn is_expired(&self, now: i64) -> bool {
+        self.expires_at.is_some_and(|expires_at| now >= expires_at)
+    }
+}
+
+impl RefreshingObjectStore {
+    /// Create a store building its underlying store with `build_store` from the object store
+    /// options vended by `provider`.
+    pub fn new(
+        provider: Arc<dyn CredentialProvider>,
+        build_store: impl Fn(&HashMap<String, String>) -> DeltaResult<Arc<DynObjectStore>>
+            + Send
+            + Sync
+            + 'static,
+    ) -> Self {
+        let inner = Inner {
+            provider,
+            build_store: Box::new(build_store),
+            current: Mutex::new(None),
+        };
+        Self {
+            inner: Arc::new(inner),
+            refresh_margin: DEFAULT_REFRESH_MARGIN,
+        }
+    }
+
+    /// Set how long before their expiry the credentials are refreshed. Defaults to
+    /// [`DEFAULT_REFRESH_MARGIN`]. Credentials are refreshed after half their lifetime at the
+    /// latest, however short-lived they are.
+    pub fn with_refresh_margin(mut self, refresh_margin: Duration) -> Self {
+        self.refresh_margin = refresh_margin;
+        self
+    }
+
+    async fn store(&self) -> object_store::Result<Arc<DynObjectStore>> {
+        self.inner.store(self.refresh_margin).await
+    }
+
+    // A stream listing with the current store. Listing is lazy, so the store is only resolved once
+    // the stream is polled.
+    fn list_stream(
+        &self,
+        list: impl FnOnce(&DynObjectStore) -> BoxStream<'static, object_store::Result<ObjectMeta>>
+            + Send
+            + 'static,
+    ) -> BoxStream<'static, object_store::Result<ObjectMeta>> {
+        let (inner, refresh_margin) = (self.inner.clone(), self.refresh_margin);
+        let list = async move {
+            let store = inner.store(refresh_margin).await?;
+            Ok::<_, object_store::Error>(list(store.as_ref()))
+        };
+        stream::once(list).try_flatten().boxed()
+    }
+}
+
+impl Inner {
+    // The store to issue the next request with, refreshing the credentials if they expire within
+    // `refresh_margin`.
+    async fn store(&self, refresh_margin: Duration) -> object_store::Result<Arc<DynObjectStore>> {
+        let mut current = self.current.lock().await;
+        let now = now_ms();
+        if let Some(current) = current.as_ref() {
+            if !current.needs_refresh(now, refresh_margin) {
+                return Ok(current.store.clone());
+            }
+        }
+        match self.fetch_store(now).await {
+            Ok(fetched) => {
+                let store = fetched.store.clone();
+                *current = Some(fetched);
+                Ok(store)
+            }
+            // the current credentials still work until they expire, so only refreshing failed
+            Err(e) => match current.as_ref() {
+                Some(current) if !current.is_expired(now) => {
+                    warn!("Failed to refresh storage credentials, using the current ones: {e}");
+                    Ok(current.store.clone())
+                }
+                _ => Err(generic_err(e)),
+            },
+        }
+    }
+
+    async fn fetch_store(&self, now: i64) -> DeltaResult<CurrentStore> {
+        let credentials = self.provider.get_credentials().await?;
+        Ok(CurrentStore {
+            store: (self.build_store)(&credentials.options)?,
+            fetched_at: now,
+            expires_at: credentials.expires_at,
+        })
+    }
+}
+
+fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis().try_into().unwrap_or(i64::MAX))
+}
+
+impl Debug for RefreshingObjectStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RefreshingObjectStore")
+            .field("provider", &self.inner.provider)
+            .field("refresh_margin", &self.refresh_margin)
+            .finish_non_exhaustive()
+    }
+}
+
+impl fmt::Display for RefreshingObjectStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "RefreshingObjectStore({:?})", self.inner.provider)
+    }
+}
+
+fn generic_err(e: crate::Error) -> object_store::Error {
+    object_store::Error::Generic {
+        store: "RefreshingObjectStore",
+        source: Box::new(e),
+    }
+}
+
+// NOTE: `ObjectStore` is declared with `async_trait`, so its async methods are implemented here
+// with the boxed futures (and lifetimes) that `async_trait` expands them to.
+impl ObjectStore for RefreshingObjectStore {
+    fn put_opts<'s, 'a, 'fut>(
+        &'s self,
+        location: &'a Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> BoxFuture<'fut, object_store::Result<PutResult>>
+    where
+        's: 'fut,
+        'a: 'fut,
+        Self: 'fut,
+    {
+        Box::pin(async move { self.store().await?.put_opts(location, payload, opts).await })
+    }
+
+    fn put_multipart_opts<'s, 'a, 'fut>(
+        &'s self,
+        location: &'a Path,
+        opts: PutMultipartOptions,
+    ) -> BoxFuture<'fut, object_store::Result<Box<dyn MultipartUpload>>>
+    where
+        's: 'fut,
+        'a: 'fut,
+        Self: 'fut,
+    {
+        Box::pin(async move { self.store().await?.put_multipart_opts(location, opts).await })
+    }
+
+    fn get_opts<'s, 'a, 'fut>(
+        &'s self,
+        location: &'a Path,
+        options: GetOptions,
+    ) -> BoxFuture<'fut, object_store::Result<GetResult>>
+    where
+        's: 'fut,
+        'a: 'fut,
+        Self: 'fut,
+    {
+        Box::pin(async move { self.store().await?.get_opts(location, options).await })
+    }
+
+    fn get_range<'s, 'a, 'fut>(
+        &'s self,
+        location: &'a Path,
+        range: Range<u64>,
+    ) -> BoxFuture<'fut, object_store::Result<Bytes>>
+    where
+        's: 'fut,
+        'a: 'fut,
+        Self: 'fut,
+    {
+        Box::pin(async move { self.store().await?.get_range(location, range).await })
+    }
+
+    fn get_ranges<'s, 'a, 'b, 'fut>(
+        &'s self,
+        location: &'a Path,
+        ranges: &'b [Range<u64>],
+    ) -> BoxFuture<'fut, object_store::Result<Vec<Bytes>>>
+    where
+        's: 'fut,
+        'a: 'fut,
+        'b: 'fut,
+        Self: 'fut,
+    {
+        Box::pin(async move { self.store().await?.get_ranges(location, ranges).await })
+    }
+
+    fn head<'s, 'a, 'fut>(
+        &'s self,
+        location: &'a Path,
+    ) -> BoxFuture<'fut, object_store::Result<ObjectMeta>>
+    where
+        's: 'fut,
+        'a: 'fut,
+        Self: 'fut,
+    {
+        Box::pin(async move { self.store().await?.head(location).await })
+    }
+
+    fn delete<'s, 'a, 'fut>(
+        &'s self,
+        location: &'a Path,
+    ) -> BoxFuture<'fut, object_store::Result<()>>
+    where
+        's: 'fut,
+        'a: 'fut,
+        Self: 'fut,
+    {
+        Box::pin(async move { self.store().await?.delete(location).await })
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, object_store::Result<ObjectMeta>> {
+        let prefix = prefix.cloned();
+        self.list_stream(move |store| store.list(prefix.as_ref()))
+    }
+
+    fn list_with_offset(
+        &self,
+        prefix: Option<&Path>,
+        offset: &Path,
+    ) -> BoxStream<'static, object_store::Result<ObjectMeta>> {
+        let (prefix, offset) = (prefix.cloned(), offset.clone());
+        self.list_stream(move |store| store.list_with_offset(prefix.as_ref(), &offset))
+    }
+
+    fn list_with_delimiter<'s, 'a, 'fut>(
+        &'s self,
+        prefix: Option<&'a Path>,
+    ) -> BoxFuture<'fut, object_store::Result<ListResult>>
+    where
+        's: 'fut,
+        'a: 'fut,
+        Self: 'fut,
+    {
+        Box::pin(async move { self.store().await?.list_with_delimiter(prefix).await })
+    }
+
+    fn copy<'s, 'a, 'b, 'fut>(
+        &'s self,
+        from: &'a Path,
+        to: &'b Path,
+    ) -> BoxFuture<'fut, object_store::Result<()>>
+    where
+        's: 'fut,
+        'a: 'fut,
+        'b: 'fut,
+        Self: 'fut,
+    {
+        Box::pin(async move { self.store().await?.copy(from, to).await })
+    }
+
+    fn rename<'s, 'a, 'b, 'fut>(
+        &'s self,
+        from: &'a Path,
+        to: &'b Path,
+    ) -> BoxFuture<'fut, object_store::Result<()>>
+    where
+        's: 'fut,
+        'a: 'fut,
+        'b: 'fut,
+        Self: 'fut,
+    {
+        Box::pin(async move { self.store().await?.rename(from, to).await })
+    }
+
+    fn copy_if_not_exists<'s, 'a, 'b, 'fut>(
+        &'s self,
+        from: &'a Path,
+        to: &'b Path,
+    ) -> BoxFuture<'fut, object_store::Result<()>>
+    where
+        's: 'fut,
+        'a: 'fut,
+        'b: 'fut,
+        Self: 'fut,
+    {
+        Box::pin(async move { self.store().await?.copy_if_not_exists(from, to).await })
+    }
+
+    fn rename_if_not_exists<'s, 'a, 'b, 'fut>(
+        &'s self,
+        from: &'a Path,
+        to: &'b Path,
+    ) -> BoxFuture<'fut, object_store::Result<()>>
+    where
+        's: 'fut,
+        'a: 'fut,
+        'b: 'fut,
+        Self: 'fut,
+    {
+        Box::pin(async move { self.store().await?.rename_if_not_exists(from, to).await })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicI64, AtomicUsize, Ordering};
+
+    use object_store::memory::InMemory;
+
+    use super::*;
+    use crate::engine::default::executor::tokio::TokioBackgroundExecutor;
+    use crate::engine::default::DefaultEngine;
+    use crate::{Engine as _, Error};
+
+    // Vends credentials with a token counting the fetches, expiring at `expires_at` (if positive).
+    #[derive(Debug, Default)]
+    struct TestProvider {
+        fetches: AtomicUsize,
+        expires_at: AtomicI64,
+        fail: std::sync::atomic::AtomicBool,
+    }
+
+    impl CredentialProvider for TestProvider {
+        fn get_credentials(&self) -> BoxFuture<'_, DeltaResult<StorageCredentials>> {
+            Box::pin(async move {
+                if self.fail.load(Ordering::SeqCst) {
+                    return Err(Error::generic("credentials unavailable"));
+                }
+                let fetch = self.fetches.fetch_add(1, Ordering::SeqCst) + 1;
+                let expires_at = self.expires_at.load(Ordering::SeqCst);
+                Ok(StorageCredentials {
+                    options: HashMap::from([("token".to_string(), fetch.to_string())]),
+                    expires_at: (expires_at > 0).then_some(expires_at),
+                })
+            })
+        }
+    }
+
+    // A store over a shared in-memory store, recording the tokens it was built with.
+    fn test_store(
+        provider: Arc<TestProvider>,
+    ) -> (RefreshingObjectStore, Arc<std::sync::Mutex<Vec<String>>>) {
+        let data = Arc::new(InMemory::new());
+        let tokens = Arc::new(std::sync::Mutex::new(vec![]));
+        let built_tokens = tokens.clone();
+        let store = RefreshingObjectStore::new(provider, move |options| {
+            built_tokens.lock().unwrap().push(options["token"].clone());
+            Ok(data.clone() as Arc<DynObjectStore>)
+        });
+        (store, tokens)
+    }
+
+    #[tokio::test]
+    async fn test_refresh_before_expiry() {
+        let provider = Arc::new(TestProvider::default());
+        provider
+            .expires_at
+            .store(now_ms() + 60 * 60 * 1000, Ordering::SeqCst);
+        let (store, tokens) = test_store(provider.clone());
+
+        // credentials are fetched lazily, and reused while they are valid
+        assert!(tokens.lock().unwrap().is_empty());
+        let path = Path::from("a/b.json");
+        store.put(&path, "data".into()).await.unwrap();
+        let listed: Vec<_> = store
+            .list(Some(&Path::from("a")))
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(*tokens.lock().unwrap(), ["1"]);
+
+        // the refresh margin is at most half the lifetime of the credentials, so that they are
+        // not refreshed on every request
+        let store = store.with_refresh_margin(Duration::from_secs(2 * 60 * 60));
+        store.head(&path).await.unwrap();
+        assert_eq!(*tokens.lock().unwrap(), ["1"]);
+
+        // credentials expiring within the refresh margin are refreshed before the next request
+        provider.expires_at.store(now_ms() + 1000, Ordering::SeqCst);
+        let (store, tokens) = test_store(provider.clone());
+        store.put(&path, "data".into()).await.unwrap();
+        store.head(&path).await.unwrap();
+        assert_eq!(*tokens.lock().unwrap(), ["2"]);
+        tokio::time::sleep(Duration::from_millis(600)).await;
+
+        // credentials without expiry are never refreshed
+        provider.expires_at.store(0, Ordering::SeqCst);
+        store.head(&path).await.unwrap();
+        store.head(&path).await.unwrap();
+        assert_eq!(*tokens.lock().unwrap(), ["2", "3"]);
+        assert_eq!(provider.fetches.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_refresh_error() {
+        let provider = Arc::new(TestProvider::default());
+        provider.expires_at.store(now_ms() + 1000, Ordering::SeqCst);
+        let (store, tokens) = test_store(provider.clone());
+        let path = Path::from("a/b.json");
+        store.put(&path, "data".into()).await.unwrap();
+
+        // failing to refresh credentials that are still valid keeps using them
+        tokio::time::sleep(Duration::from_millis(600)).await;
+        provider.fail.store(true, Ordering::SeqCst);
+        store.head(&path).await.unwrap();
+        assert_eq!(*tokens.lock().unwrap(), ["1"]);
+
+        // once they expire, requests fail
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        let err = store.head(&path).await.unwrap_err();
+        assert!(err.to_string().contains("credentials unavailable"));
+    }
+
+    #[tokio::test]
+    async fn test_provider_error() {
+        let provider = Arc::new(TestProvider::default());
+        provider.fail.store(true, Ordering::SeqCst);
+        let (store, tokens) = test_store(provider);
+        let err = store.head(&Path::from("a")).await.unwrap_err();
+        assert!(err.to_string().contains("credentials unavailable"));
+        let listed: Vec<_> = store.list(None).collect().await;
+        assert!(matches!(
+            listed[..],
+            [Err(object_store::Error::Generic { .. })]
+        ));
+        assert!(tokens.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_engine_with_credentials() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.json"), "data").unwrap();
+        let provider = Arc::new(TestProvider::default());
+        let table_root = url::Url::from_directory_path(dir.path()).unwrap();
+        let engine = DefaultEngine::try_new_with_credentials(
+            &table_root,
+            HashMap::<String, String>::new(),
+            provider.clone(),
+            Arc::new(TokioBackgroundExecutor::new()),
+        )
+        .unwrap();
+        let file = engine
+            .storage_handler()
+            .head(&table_root.join("a.json").unwrap())
+            .unwrap();
+        assert_eq!(file.size, 4);
+        assert_eq!(provider.fetches.load(Ordering::SeqCst), 1);
+    }
+}
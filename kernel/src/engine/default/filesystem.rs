@@ -2,10 +2,11 @@ use std::sync::Arc;
 
 use bytes::Bytes;
 use delta_kernel_derive::internal_api;
-use futures::stream::StreamExt;
+use futures::future::{BoxFuture, FutureExt as _};
+use futures::stream::{BoxStream, Stream, StreamExt, TryStreamExt as _};
 use itertools::Itertools;
 use object_store::path::Path;
-use object_store::{DynObjectStore, ObjectStore};
+use object_store::{DynObjectStore, ObjectStore, PutMode};
 use url::Url;
 
 use super::UrlExt;
@@ -41,68 +42,13 @@ impl<E: TaskExecutor> StorageHandler for ObjectStoreStorageHandler<E> {
         &self,
         path: &Url,
     ) -> DeltaResult<Box<dyn Iterator<Item = DeltaResult<FileMeta>>>> {
-        // The offset is used for list-after; the prefix is used to restrict the listing to a specific directory.
-        // Unfortunately, `Path` provides no easy way to check whether a name is directory-like,
-        // because it strips trailing /, so we're reduced to manually checking the original URL.
-        let offset = Path::from_url_path(path.path())?;
-        let prefix = if path.path().ends_with('/') {
-            offset.clone()
-        } else {
-            let mut parts = offset.parts().collect_vec();
-            if parts.pop().is_none() {
-                return Err(Error::Generic(format!(
-                    "Offset path must not be a root directory. Got: '{}'",
-                    path.as_str()
-                )));
-            }
-            Path::from_iter(parts)
-        };
-
-        let store = self.inner.clone();
-
-        // HACK to check if we're using a LocalFileSystem from ObjectStore. We need this because
-        // local filesystem doesn't return a sorted list by default. Although the `object_store`
-        // crate explicitly says it _does not_ return a sorted listing, in practice all the cloud
-        // implementations actually do:
-        // - AWS:
-        //   [`ListObjectsV2`](https://docs.aws.amazon.com/AmazonS3/latest/API/API_ListObjectsV2.html)
-        //   states: "For general purpose buckets, ListObjectsV2 returns objects in lexicographical
-        //   order based on their key names." (Directory buckets are out of scope for now)
-        // - Azure: Docs state
-        //   [here](https://learn.microsoft.com/en-us/rest/api/storageservices/enumerating-blob-resources):
-        //   "A listing operation returns an XML response that contains all or part of the requested
-        //   list. The operation returns entities in alphabetical order."
-        // - GCP: The [main](https://cloud.google.com/storage/docs/xml-api/get-bucket-list) doc
-        //   doesn't indicate order, but [this
-        //   page](https://cloud.google.com/storage/docs/xml-api/get-bucket-list) does say: "This page
-        //   shows you how to list the [objects](https://cloud.google.com/storage/docs/objects) stored
-        //   in your Cloud Storage buckets, which are ordered in the list lexicographically by name."
-        // So we just need to know if we're local and then if so, we sort the returned file list
-        let has_ordered_listing = path.scheme() != "file";
+        let (mut stream, has_ordered_listing) = list_stream(self.inner.clone(), path)?;
 
         // This channel will become the iterator
         let (sender, receiver) = std::sync::mpsc::sync_channel(4_000);
-        let url = path.clone();
         self.task_executor.spawn(async move {
-            let mut stream = store.list_with_offset(Some(&prefix), &offset);
-
             while let Some(meta) = stream.next().await {
-                match meta {
-                    Ok(meta) => {
-                        let mut location = url.clone();
-                        location.set_path(&format!("/{}", meta.location.as_ref()));
-                        sender
-                            .send(Ok(FileMeta {
-                                location,
-                                last_modified: meta.last_modified.timestamp_millis(),
-                                size: meta.size,
-                            }))
-                            .ok();
-                    }
-                    Err(e) => {
-                        sender.send(Err(e.into())).ok();
-                    }
-                }
+                sender.send(meta).ok();
             }
         });
 
@@ -126,55 +72,239 @@ impl<E: TaskExecutor> StorageHandler for ObjectStoreStorageHandler<E> {
         &self,
         files: Vec<FileSlice>,
     ) -> DeltaResult<Box<dyn Iterator<Item = DeltaResult<Bytes>>>> {
-        let store = self.inner.clone();
-
         // This channel will become the output iterator.
         // Because there will already be buffering in the stream, we set the
         // buffer size to 0.
         let (sender, receiver) = std::sync::mpsc::sync_channel(0);
 
         self.task_executor.spawn(
-            futures::stream::iter(files)
-                .map(move |(url, range)| {
-                    let store = store.clone();
-                    async move {
-                        // Wasn't checking the scheme before calling to_file_path causing the url path to
-                        // be eaten in a strange way. Now, if not a file scheme, just blindly convert to a path.
-                        // https://docs.rs/url/latest/url/struct.Url.html#method.to_file_path has more
-                        // details about why this check is necessary
-                        let path = if url.scheme() == "file" {
-                            let file_path = url.to_file_path().map_err(|_| {
-                                Error::InvalidTableLocation(format!("Invalid file URL: {url}"))
-                            })?;
-                            Path::from_absolute_path(file_path).map_err(|e| {
-                                Error::InvalidTableLocation(format!("Invalid file path: {e}"))
-                            })?
-                        } else {
-                            Path::from(url.path())
-                        };
-                        if url.is_presigned() {
-                            // have to annotate type here or rustc can't figure it out
-                            Ok::<bytes::Bytes, Error>(reqwest::get(url).await?.bytes().await?)
-                        } else if let Some(rng) = range {
-                            Ok(store.get_range(&path, rng).await?)
-                        } else {
-                            let result = store.get(&path).await?;
-                            Ok(result.bytes().await?)
-                        }
-                    }
-                })
-                // We allow executing up to `readahead` futures concurrently and
-                // buffer the results. This allows us to achieve async concurrency
-                // within a synchronous method.
-                .buffered(self.readahead)
-                .for_each(move |res| {
-                    sender.send(res).ok();
-                    futures::future::ready(())
-                }),
+            read_files_stream(self.inner.clone(), files, self.readahead).for_each(move |res| {
+                sender.send(res).ok();
+                futures::future::ready(())
+            }),
         );
 
         Ok(Box::new(receiver.into_iter()))
     }
+
+    fn put(&self, path: &Url, data: Bytes, overwrite: bool) -> DeltaResult<()> {
+        self.task_executor
+            .block_on(put(self.inner.clone(), path.clone(), data, overwrite))
+    }
+
+    fn head(&self, path: &Url) -> DeltaResult<FileMeta> {
+        self.task_executor
+            .block_on(head(self.inner.clone(), path.clone()))
+    }
+
+    fn delete(&self, path: &Url) -> DeltaResult<()> {
+        self.task_executor
+            .block_on(delete(self.inner.clone(), path.clone()))
+    }
+
+    fn copy(&self, from: &Url, to: &Url) -> DeltaResult<()> {
+        self.task_executor
+            .block_on(copy(self.inner.clone(), from.clone(), to.clone()))
+    }
+}
+
+/// The async handler reads directly from the object store, without going through the
+/// [`TaskExecutor`].
+impl<E: TaskExecutor> crate::async_engine::AsyncStorageHandler for ObjectStoreStorageHandler<E> {
+    fn list_from(&self, path: &Url) -> DeltaResult<BoxStream<'static, DeltaResult<FileMeta>>> {
+        let (stream, has_ordered_listing) = list_stream(self.inner.clone(), path)?;
+        if has_ordered_listing {
+            return Ok(stream);
+        }
+        // This FS doesn't return things in the order we require, so we have to buffer the
+        // whole listing to sort it
+        let sorted = async move {
+            let mut fms: Vec<FileMeta> = stream.try_collect().await?;
+            fms.sort_unstable();
+            Ok::<_, Error>(futures::stream::iter(fms.into_iter().map(Ok)))
+        };
+        Ok(futures::stream::once(sorted).try_flatten().boxed())
+    }
+
+    fn read_files(
+        &self,
+        files: Vec<FileSlice>,
+    ) -> DeltaResult<BoxStream<'static, DeltaResult<Bytes>>> {
+        Ok(read_files_stream(self.inner.clone(), files, self.readahead).boxed())
+    }
+
+    fn put(&self, path: &Url, data: Bytes, overwrite: bool) -> BoxFuture<'static, DeltaResult<()>> {
+        put(self.inner.clone(), path.clone(), data, overwrite).boxed()
+    }
+
+    fn head(&self, path: &Url) -> BoxFuture<'static, DeltaResult<FileMeta>> {
+        head(self.inner.clone(), path.clone()).boxed()
+    }
+
+    fn delete(&self, path: &Url) -> BoxFuture<'static, DeltaResult<()>> {
+        delete(self.inner.clone(), path.clone()).boxed()
+    }
+
+    fn copy(&self, from: &Url, to: &Url) -> BoxFuture<'static, DeltaResult<()>> {
+        copy(self.inner.clone(), from.clone(), to.clone()).boxed()
+    }
+}
+
+/// Converts a URL into the object store [`Path`] it refers to. All storage operations go through
+/// this, so that a URL produced by listing resolves to the same object when it is read, written or
+/// deleted.
+fn object_store_path(url: &Url) -> DeltaResult<Path> {
+    // Wasn't checking the scheme before calling to_file_path causing the url path to be eaten in
+    // a strange way. https://docs.rs/url/latest/url/struct.Url.html#method.to_file_path has more
+    // details about why this check is necessary
+    if url.scheme() == "file" {
+        let file_path = url
+            .to_file_path()
+            .map_err(|_| Error::InvalidTableLocation(format!("Invalid file URL: {url}")))?;
+        Path::from_absolute_path(file_path)
+            .map_err(|e| Error::InvalidTableLocation(format!("Invalid file path: {e}")))
+    } else {
+        // URL paths are percent-encoded, which `from_url_path` undoes
+        Ok(Path::from_url_path(url.path())?)
+    }
+}
+
+/// Lists the files in the same directory as `path` that sort after it. Also returns whether the
+/// listing is returned in the (sorted) order kernel requires.
+fn list_stream(
+    store: Arc<DynObjectStore>,
+    path: &Url,
+) -> DeltaResult<(BoxStream<'static, DeltaResult<FileMeta>>, bool)> {
+    // The offset is used for list-after; the prefix is used to restrict the listing to a specific directory.
+    // Unfortunately, `Path` provides no easy way to check whether a name is directory-like,
+    // because it strips trailing /, so we're reduced to manually checking the original URL.
+    let offset = object_store_path(path)?;
+    let prefix = if path.path().ends_with('/') {
+        offset.clone()
+    } else {
+        let mut parts = offset.parts().collect_vec();
+        if parts.pop().is_none() {
+            return Err(Error::Generic(format!(
+                "Offset path must not be a root directory. Got: '{}'",
+                path.as_str()
+            )));
+        }
+        Path::from_iter(parts)
+    };
+
+    // HACK to check if we're using a LocalFileSystem from ObjectStore. We need this because
+    // local filesystem doesn't return a sorted list by default. Although the `object_store`
+    // crate explicitly says it _does not_ return a sorted listing, in practice all the cloud
+    // implementations actually do:
+    // - AWS:
+    //   [`ListObjectsV2`](https://docs.aws.amazon.com/AmazonS3/latest/API/API_ListObjectsV2.html)
+    //   states: "For general purpose buckets, ListObjectsV2 returns objects in lexicographical
+    //   order based on their key names." (Directory buckets are out of scope for now)
+    // - Azure: Docs state
+    //   [here](https://learn.microsoft.com/en-us/rest/api/storageservices/enumerating-blob-resources):
+    //   "A listing operation returns an XML response that contains all or part of the requested
+    //   list. The operation returns entities in alphabetical order."
+    // - GCP: The [main](https://cloud.google.com/storage/docs/xml-api/get-bucket-list) doc
+    //   doesn't indicate order, but [this
+    //   page](https://cloud.google.com/storage/docs/xml-api/get-bucket-list) does say: "This page
+    //   shows you how to list the [objects](https://cloud.google.com/storage/docs/objects) stored
+    //   in your Cloud Storage buckets, which are ordered in the list lexicographically by name."
+    // So we just need to know if we're local and then if so, we sort the returned file list
+    let has_ordered_listing = path.scheme() != "file";
+
+    let url = path.clone();
+    let stream = futures::stream::once(async move {
+        store
+            .list_with_offset(Some(&prefix), &offset)
+            .map(move |meta| {
+                let meta = meta?;
+                let mut location = url.clone();
+                location.set_path(&format!("/{}", meta.location.as_ref()));
+                Ok(FileMeta {
+                    location,
+                    last_modified: meta.last_modified.timestamp_millis(),
+                    size: meta.size,
+                })
+            })
+    })
+    .flatten()
+    .boxed();
+    Ok((stream, has_ordered_listing))
+}
+
+/// Reads the given file slices, in order, with up to `readahead` reads in flight.
+fn read_files_stream(
+    store: Arc<DynObjectStore>,
+    files: Vec<FileSlice>,
+    readahead: usize,
+) -> impl Stream<Item = DeltaResult<Bytes>> + Send + 'static {
+    futures::stream::iter(files)
+        .map(move |(url, range)| {
+            let store = store.clone();
+            async move {
+                let path = object_store_path(&url)?;
+                if url.is_presigned() {
+                    // have to annotate type here or rustc can't figure it out
+                    Ok::<bytes::Bytes, Error>(reqwest::get(url).await?.bytes().await?)
+                } else if let Some(rng) = range {
+                    Ok(store.get_range(&path, rng).await?)
+                } else {
+                    let result = store.get(&path).await?;
+                    Ok(result.bytes().await?)
+                }
+            }
+        })
+        // We allow executing up to `readahead` futures concurrently and
+        // buffer the results.
+        .buffered(readahead)
+}
+
+async fn put(
+    store: Arc<DynObjectStore>,
+    path: Url,
+    data: Bytes,
+    overwrite: bool,
+) -> DeltaResult<()> {
+    let put_mode = if overwrite {
+        PutMode::Overwrite
+    } else {
+        PutMode::Create
+    };
+    let path = object_store_path(&path)?;
+    store
+        .put_opts(&path, data.into(), put_mode.into())
+        .await
+        .map_err(|e| match e {
+            object_store::Error::AlreadyExists { .. } => Error::FileAlreadyExists(path.to_string()),
+            e => e.into(),
+        })?;
+    Ok(())
+}
+
+async fn head(store: Arc<DynObjectStore>, path: Url) -> DeltaResult<FileMeta> {
+    let object_path = object_store_path(&path)?;
+    let meta = store.head(&object_path).await?;
+    Ok(FileMeta {
+        location: path,
+        last_modified: meta.last_modified.timestamp_millis(),
+        size: meta.size,
+    })
+}
+
+async fn delete(store: Arc<DynObjectStore>, path: Url) -> DeltaResult<()> {
+    let path = object_store_path(&path)?;
+    match store.delete(&path).await {
+        // some stores (e.g. the local file system) fail to delete missing files
+        Ok(()) | Err(object_store::Error::NotFound { .. }) => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+async fn copy(store: Arc<DynObjectStore>, from: Url, to: Url) -> DeltaResult<()> {
+    let from = object_store_path(&from)?;
+    let to = object_store_path(&to)?;
+    store.copy(&from, &to).await?;
+    Ok(())
 }
 
 #[cfg(test)]
@@ -299,4 +429,156 @@ mod tests {
         }
         assert_eq!(len, 10, "list_from should have returned 10 files");
     }
+
+    #[tokio::test]
+    async fn test_put_head_delete_copy() {
+        let tmp = tempfile::tempdir().unwrap();
+        let store = Arc::new(LocalFileSystem::new());
+        let executor = Arc::new(TokioBackgroundExecutor::new());
+        let storage = ObjectStoreStorageHandler::new(store, executor);
+        let root = Url::from_directory_path(tmp.path()).unwrap();
+        let a = root.join("dir/a").unwrap();
+        let b = root.join("b").unwrap();
+
+        assert!(matches!(storage.head(&a), Err(Error::FileNotFound(_))));
+        storage.put(&a, Bytes::from("kernel"), false).unwrap();
+        assert_eq!(storage.head(&a).unwrap().size, 6);
+
+        // put-if-absent leaves the existing file untouched
+        let err = storage.put(&a, Bytes::from("data"), false).unwrap_err();
+        assert!(matches!(err, Error::FileAlreadyExists(_)));
+        let data: Vec<Bytes> = storage
+            .read_files(vec![(a.clone(), None)])
+            .unwrap()
+            .try_collect()
+            .unwrap();
+        assert_eq!(data, vec![Bytes::from("kernel")]);
+        storage.put(&a, Bytes::from("data"), true).unwrap();
+        assert_eq!(storage.head(&a).unwrap().size, 4);
+
+        storage.copy(&a, &b).unwrap();
+        assert_eq!(storage.head(&b).unwrap().size, 4);
+
+        storage.delete(&a).unwrap();
+        assert!(matches!(storage.head(&a), Err(Error::FileNotFound(_))));
+        // deleting a missing file is fine
+        storage.delete(&a).unwrap();
+        assert!(matches!(storage.copy(&a, &b), Err(Error::FileNotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_listed_paths_round_trip() {
+        // listing percent-encodes special characters in the returned URLs; every other operation
+        // must decode them back to the same object
+        let store = Arc::new(InMemory::new());
+        let executor = Arc::new(TokioBackgroundExecutor::new());
+        let storage = ObjectStoreStorageHandler::new(store, executor);
+        let root = Url::parse("memory:///dir/").unwrap();
+        storage
+            .put(&root.join("a%20b").unwrap(), Bytes::from("kernel"), false)
+            .unwrap();
+
+        let listed: Vec<FileMeta> = storage
+            .list_from(&root.join("a").unwrap())
+            .unwrap()
+            .try_collect()
+            .unwrap();
+        assert_eq!(listed.len(), 1);
+        let location = &listed[0].location;
+        assert_eq!(location.path(), "/dir/a%20b");
+
+        let data: Vec<Bytes> = storage
+            .read_files(vec![(location.clone(), None)])
+            .unwrap()
+            .try_collect()
+            .unwrap();
+        assert_eq!(data, vec![Bytes::from("kernel")]);
+        assert_eq!(storage.head(location).unwrap().size, 6);
+        storage.delete(location).unwrap();
+        assert!(matches!(
+            storage.head(location),
+            Err(Error::FileNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_storage_handler_defaults_unsupported() {
+        struct ReadOnlyStorage;
+        impl StorageHandler for ReadOnlyStorage {
+            fn list_from(
+                &self,
+                _path: &Url,
+            ) -> DeltaResult<Box<dyn Iterator<Item = DeltaResult<FileMeta>>>> {
+                Ok(Box::new(std::iter::empty()))
+            }
+            fn read_files(
+                &self,
+                _files: Vec<FileSlice>,
+            ) -> DeltaResult<Box<dyn Iterator<Item = DeltaResult<Bytes>>>> {
+                Ok(Box::new(std::iter::empty()))
+            }
+        }
+
+        let storage = ReadOnlyStorage;
+        let path = Url::parse("memory:///a").unwrap();
+        let unsupported = |res: DeltaResult<()>| matches!(res, Err(Error::Unsupported(_)));
+        assert!(unsupported(storage.put(&path, Bytes::new(), false)));
+        assert!(unsupported(storage.head(&path).map(|_| ())));
+        assert!(unsupported(storage.delete(&path)));
+        assert!(unsupported(storage.copy(&path, &path)));
+    }
+
+    #[tokio::test]
+    async fn test_async_storage_handler() {
+        use futures::TryStreamExt as _;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let store = Arc::new(LocalFileSystem::new());
+        let executor = Arc::new(TokioBackgroundExecutor::new());
+        let storage = ObjectStoreStorageHandler::new(store, executor);
+        let storage: &dyn crate::async_engine::AsyncStorageHandler = &storage;
+        let root = Url::from_directory_path(tmp.path()).unwrap();
+
+        // put them in in reverse order; the listing must come back sorted
+        let names = ["c", "b", "a"];
+        for name in names {
+            let path = root.join(name).unwrap();
+            storage.put(&path, Bytes::from(name), false).await.unwrap();
+        }
+        let err = storage
+            .put(&root.join("a").unwrap(), Bytes::new(), false)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::FileAlreadyExists(_)));
+
+        let listed: Vec<FileMeta> = storage
+            .list_from(&root.join("a").unwrap())
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+        let listed = listed.iter().map(|f| f.location.clone()).collect_vec();
+        assert_eq!(
+            listed,
+            vec![root.join("b").unwrap(), root.join("c").unwrap()]
+        );
+
+        let slices = listed.into_iter().map(|url| (url, None)).collect();
+        let data: Vec<Bytes> = storage
+            .read_files(slices)
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(data, vec![Bytes::from("b"), Bytes::from("c")]);
+
+        let (a, d) = (root.join("a").unwrap(), root.join("d").unwrap());
+        storage.copy(&a, &d).await.unwrap();
+        assert_eq!(storage.head(&d).await.unwrap().size, 1);
+        storage.delete(&d).await.unwrap();
+        assert!(matches!(
+            storage.head(&d).await,
+            Err(Error::FileNotFound(_))
+        ));
+    }
 }
@@ -17,6 +17,7 @@ use tracing::warn;
 use url::Url;
 
 use super::executor::TaskExecutor;
+use crate::async_engine::FileDataReadResultStream;
 use crate::engine::arrow_conversion::TryFromKernel as _;
 use crate::engine::arrow_data::ArrowEngineData;
 use crate::engine::arrow_utils::parse_json as arrow_parse_json;
@@ -83,6 +84,37 @@ impl<E: TaskExecutor> DefaultJsonHandler<E> {
     }
 }
 
+impl<E: TaskExecutor> DefaultJsonHandler<E> {
+    fn read_json_files_stream(
+        &self,
+        files: &[FileMeta],
+        physical_schema: SchemaRef,
+    ) -> DeltaResult<FileDataReadResultStream> {
+        let schema = Arc::new(ArrowSchema::try_from_kernel(physical_schema.as_ref())?);
+        let file_opener = Arc::new(JsonOpener::new(
+            self.batch_size,
+            schema.clone(),
+            self.store.clone(),
+        ));
+
+        // an iterator of futures that open each file
+        let files = files.to_vec();
+        let file_futures = files.into_iter().map(move |file| {
+            let file_opener = file_opener.clone();
+            async move { file_opener.open(file, None).await }
+        });
+
+        // create a stream from that iterator which buffers up to `buffer_size` futures at a time
+        Ok(stream::iter(file_futures)
+            .buffered(self.buffer_size)
+            .try_flatten()
+            .map_ok(|record_batch| -> Box<dyn EngineData> {
+                Box::new(ArrowEngineData::new(record_batch))
+            })
+            .boxed())
+    }
+}
+
 impl<E: TaskExecutor> JsonHandler for DefaultJsonHandler<E> {
     fn parse_json(
         &self,
@@ -102,25 +134,10 @@ impl<E: TaskExecutor> JsonHandler for DefaultJsonHandler<E> {
             return Ok(Box::new(std::iter::empty()));
         }
 
-        let schema = Arc::new(ArrowSchema::try_from_kernel(physical_schema.as_ref())?);
-        let file_opener = JsonOpener::new(self.batch_size, schema.clone(), self.store.clone());
-
+        let mut stream = self.read_json_files_stream(files, physical_schema)?;
         let (tx, rx) = mpsc::sync_channel(self.buffer_size);
-        let files = files.to_vec();
-        let buffer_size = self.buffer_size;
 
         self.task_executor.spawn(async move {
-            // an iterator of futures that open each file
-            let file_futures = files.into_iter().map(|file| file_opener.open(file, None));
-
-            // create a stream from that iterator which buffers up to `buffer_size` futures at a time
-            let mut stream = stream::iter(file_futures)
-                .buffered(buffer_size)
-                .try_flatten()
-                .map_ok(|record_batch| -> Box<dyn EngineData> {
-                    Box::new(ArrowEngineData::new(record_batch))
-                });
-
             // send each record batch over the channel
             while let Some(item) = stream.next().await {
                 if tx.send(item).is_err() {
@@ -159,6 +176,17 @@ impl<E: TaskExecutor> JsonHandler for DefaultJsonHandler<E> {
     }
 }
 
+impl<E: TaskExecutor> crate::async_engine::AsyncJsonHandler for DefaultJsonHandler<E> {
+    fn read_json_files(
+        &self,
+        files: &[FileMeta],
+        physical_schema: SchemaRef,
+        _predicate: Option<PredicateRef>,
+    ) -> DeltaResult<FileDataReadResultStream> {
+        self.read_json_files_stream(files, physical_schema)
+    }
+}
+
 /// Opens JSON files and returns a stream of record batches
 #[allow(missing_debug_implementations)]
 pub struct JsonOpener {
@@ -2,9 +2,10 @@
 //!
 //! The default implementation of [`Engine`] is [`DefaultEngine`].
 //!
-//! The underlying implementations use asynchronous IO. Async tasks are run on
-//! a separate thread pool, provided by the [`TaskExecutor`] trait. Read more in
-//! the [executor] module.
+//! The underlying implementations use asynchronous IO. When driven through the synchronous
+//! [`Engine`] APIs, async tasks are run on a separate thread pool, provided by the
+//! [`TaskExecutor`] trait. Read more in the [executor] module. The [`AsyncEngine`] APIs instead
+//! hand the IO to the caller's runtime directly.
 
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -13,6 +14,7 @@ use self::storage::parse_url_opts;
 use object_store::DynObjectStore;
 use url::Url;
 
+use self::credentials::{CredentialProvider, RefreshingObjectStore};
 use self::executor::TaskExecutor;
 use self::filesystem::ObjectStoreStorageHandler;
 use self::json::DefaultJsonHandler;
@@ -20,12 +22,20 @@ use self::parquet::DefaultParquetHandler;
 use super::arrow_conversion::TryFromArrow as _;
 use super::arrow_data::ArrowEngineData;
 use super::arrow_expression::ArrowEvaluationHandler;
-use crate::schema::Schema;
+use crate::arrow::array::AsArray as _;
+use crate::arrow::datatypes::Int64Type;
+use crate::async_engine::{
+    AsyncEngine, AsyncJsonHandler, AsyncParquetHandler, AsyncStorageHandler,
+};
+use crate::schema::{Schema, SchemaRef};
+use crate::table_features::{ColumnInvariant, GeneratedColumn, IdentityColumn};
 use crate::transaction::WriteContext;
 use crate::{
-    DeltaResult, Engine, EngineData, EvaluationHandler, JsonHandler, ParquetHandler, StorageHandler,
+    DeltaResult, Engine, EngineData, Error, EvaluationHandler, Expression, JsonHandler,
+    ParquetHandler, Predicate, StorageHandler,
 };
 
+pub mod credentials;
 pub mod executor;
 pub mod file_stream;
 pub mod filesystem;
@@ -64,6 +74,39 @@ impl<E: TaskExecutor> DefaultEngine<E> {
         Ok(Self::new(Arc::new(object_store), task_executor))
     }
 
+    /// Create a new [`DefaultEngine`] instance accessing storage with expiring credentials, which
+    /// are refreshed before they expire. See [`RefreshingObjectStore`].
+    ///
+    /// # Parameters
+    ///
+    /// - `table_root`: The URL of the table within storage.
+    /// - `options`: key/value pairs of options to pass to the object store, in addition to the
+    ///   credentials.
+    /// - `credential_provider`: Vends the credentials, as object store options.
+    /// - `task_executor`: Used to spawn async IO tasks. See [executor::TaskExecutor].
+    pub fn try_new_with_credentials<K, V>(
+        table_root: &Url,
+        options: impl IntoIterator<Item = (K, V)>,
+        credential_provider: Arc<dyn CredentialProvider>,
+        task_executor: Arc<E>,
+    ) -> DeltaResult<Self>
+    where
+        K: AsRef<str>,
+        V: Into<String>,
+    {
+        let options: HashMap<String, String> = options
+            .into_iter()
+            .map(|(k, v)| (k.as_ref().to_string(), v.into()))
+            .collect();
+        let table_root = table_root.clone();
+        let object_store = RefreshingObjectStore::new(credential_provider, move |credentials| {
+            let options = options.iter().chain(credentials);
+            let (object_store, _table_root) = parse_url_opts(&table_root, options)?;
+            Ok(object_store.into())
+        });
+        Ok(Self::new(Arc::new(object_store), task_executor))
+    }
+
     /// Create a new [`DefaultEngine`] instance
     ///
     /// # Parameters
@@ -101,10 +144,13 @@ impl<E: TaskExecutor> DefaultEngine<E> {
         data_change: bool,
     ) -> DeltaResult<Box<dyn EngineData>> {
         let transform = write_context.logical_to_physical();
-        let input_schema = Schema::try_from_arrow(data.record_batch().schema())?;
+        let input_schema: SchemaRef = Schema::try_from_arrow(data.record_batch().schema())?.into();
+        self.check_invariants(data, &input_schema, write_context.invariants())?;
+        self.check_generated_columns(data, &input_schema, write_context.generated_columns())?;
+        check_identity_columns(data, write_context.identity_columns())?;
         let output_schema = write_context.schema();
         let logical_to_physical_expr = self.evaluation_handler().new_expression_evaluator(
-            input_schema.into(),
+            input_schema,
             transform.clone(),
             output_schema.clone().into(),
         );
@@ -118,6 +164,105 @@ impl<E: TaskExecutor> DefaultEngine<E> {
             )
             .await
     }
+
+    // Every row of `data` must evaluate each invariant to `true` (NULL counts as a violation).
+    fn check_invariants(
+        &self,
+        data: &ArrowEngineData,
+        input_schema: &SchemaRef,
+        invariants: &[ColumnInvariant],
+    ) -> DeltaResult<()> {
+        for invariant in invariants {
+            let violations = self.count_violations(data, input_schema, invariant.predicate())?;
+            if violations > 0 {
+                return Err(Error::invariant_violation(format!(
+                    "{violations} of {} rows violate invariant '{}' on column {}",
+                    data.record_batch().num_rows(),
+                    invariant.sql(),
+                    invariant.column()
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    // Values supplied for a generated column must match its generation expression (the transform
+    // would otherwise silently replace them).
+    fn check_generated_columns(
+        &self,
+        data: &ArrowEngineData,
+        input_schema: &SchemaRef,
+        generated_columns: &[GeneratedColumn],
+    ) -> DeltaResult<()> {
+        for generated_column in generated_columns {
+            let name = generated_column.name();
+            if input_schema.field(name).is_none() {
+                continue;
+            }
+            let predicate = Predicate::not(Predicate::distinct(
+                Expression::column([name]),
+                generated_column.expression().clone(),
+            ));
+            let violations = self.count_violations(data, input_schema, &predicate)?;
+            if violations > 0 {
+                return Err(Error::invariant_violation(format!(
+                    "{violations} of {} rows do not match the generation expression '{}' of \
+                     column {name}",
+                    data.record_batch().num_rows(),
+                    generated_column.sql(),
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    // The number of rows of `data` for which `predicate` does not evaluate to `true`.
+    fn count_violations(
+        &self,
+        data: &ArrowEngineData,
+        input_schema: &SchemaRef,
+        predicate: &Predicate,
+    ) -> DeltaResult<usize> {
+        let evaluator = self
+            .evaluation
+            .new_predicate_evaluator(input_schema.clone(), predicate.clone());
+        let result = ArrowEngineData::try_from_engine_data(evaluator.evaluate(data)?)?;
+        let result = result.record_batch().column(0).as_boolean();
+        Ok(result.len() - result.true_count())
+    }
+}
+
+// Identity columns that do not allow explicit inserts may only contain values reserved by the
+// transaction.
+fn check_identity_columns(
+    data: &ArrowEngineData,
+    identity_columns: &[IdentityColumn],
+) -> DeltaResult<()> {
+    for identity_column in identity_columns {
+        if identity_column.allow_explicit_insert() {
+            continue;
+        }
+        let name = identity_column.name();
+        let column = data
+            .record_batch()
+            .column_by_name(name)
+            .ok_or_else(|| Error::missing_column(format!("Missing identity column {name}")))?;
+        let values = column.as_primitive_opt::<Int64Type>().ok_or_else(|| {
+            Error::unexpected_column_type(format!("LONG for identity column {name}"))
+        })?;
+        let explicit = values
+            .iter()
+            .filter(|value| !value.is_some_and(|value| identity_column.is_reserved(value)))
+            .count();
+        if explicit > 0 {
+            return Err(Error::invariant_violation(format!(
+                "{explicit} of {} rows insert values into identity column {name}, which does not \
+                 allow explicit inserts",
+                values.len()
+            )));
+        }
+    }
+    Ok(())
 }
 
 impl<E: TaskExecutor> Engine for DefaultEngine<E> {
@@ -138,6 +283,20 @@ impl<E: TaskExecutor> Engine for DefaultEngine<E> {
     }
 }
 
+impl<E: TaskExecutor> AsyncEngine for DefaultEngine<E> {
+    fn async_storage_handler(&self) -> Arc<dyn AsyncStorageHandler> {
+        self.storage.clone()
+    }
+
+    fn async_json_handler(&self) -> Arc<dyn AsyncJsonHandler> {
+        self.json.clone()
+    }
+
+    fn async_parquet_handler(&self) -> Arc<dyn AsyncParquetHandler> {
+        self.parquet.clone()
+    }
+}
+
 trait UrlExt {
     // Check if a given url is a presigned url and can be used
     // to access the object store via simple http requests
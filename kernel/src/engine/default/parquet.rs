@@ -11,13 +11,14 @@ use crate::parquet::arrow::arrow_reader::{
 };
 use crate::parquet::arrow::arrow_writer::ArrowWriter;
 use crate::parquet::arrow::async_reader::{ParquetObjectReader, ParquetRecordBatchStreamBuilder};
-use futures::StreamExt;
+use futures::{StreamExt, TryStreamExt as _};
 use object_store::path::Path;
 use object_store::DynObjectStore;
 use uuid::Uuid;
 
 use super::file_stream::{FileOpenFuture, FileOpener, FileStream};
 use super::UrlExt;
+use crate::async_engine::FileDataReadResultStream;
 use crate::engine::arrow_conversion::TryIntoArrow as _;
 use crate::engine::arrow_data::ArrowEngineData;
 use crate::engine::arrow_utils::{fixup_parquet_read, generate_mask, get_requested_indices};
@@ -187,6 +188,45 @@ impl<E: TaskExecutor> ParquetHandler for DefaultParquetHandler<E> {
             return Ok(Box::new(std::iter::empty()));
         }
 
+        FileStream::new_async_read_iterator(
+            self.task_executor.clone(),
+            Arc::new(physical_schema.as_ref().try_into_arrow()?),
+            self.file_opener(files, physical_schema, predicate),
+            files,
+            self.readahead,
+        )
+    }
+}
+
+impl<E: TaskExecutor> crate::async_engine::AsyncParquetHandler for DefaultParquetHandler<E> {
+    fn read_parquet_files(
+        &self,
+        files: &[FileMeta],
+        physical_schema: SchemaRef,
+        predicate: Option<PredicateRef>,
+    ) -> DeltaResult<FileDataReadResultStream> {
+        if files.is_empty() {
+            return Ok(futures::stream::empty().boxed());
+        }
+
+        let stream = FileStream::new(
+            files.to_vec(),
+            Arc::new(physical_schema.as_ref().try_into_arrow()?),
+            self.file_opener(files, physical_schema, predicate),
+        )?;
+        Ok(stream
+            .map_ok(|rb| -> Box<dyn EngineData> { Box::new(ArrowEngineData::new(rb)) })
+            .boxed())
+    }
+}
+
+impl<E: TaskExecutor> DefaultParquetHandler<E> {
+    fn file_opener(
+        &self,
+        files: &[FileMeta],
+        physical_schema: SchemaRef,
+        predicate: Option<PredicateRef>,
+    ) -> Box<dyn FileOpener> {
         // get the first FileMeta to decide how to fetch the file.
         // NB: This means that every file in `FileMeta` _must_ have the same scheme or things will break
         // s3://    -> aws   (ParquetOpener)
@@ -194,28 +234,17 @@ impl<E: TaskExecutor> ParquetHandler for DefaultParquetHandler<E> {
         // https:// -> assume presigned URL (and fetch without object_store)
         //   -> reqwest to get data
         //   -> parse to parquet
-        // SAFETY: we did is_empty check above, this is ok.
-        let file_opener: Box<dyn FileOpener> = if files[0].location.is_presigned() {
-            Box::new(PresignedUrlOpener::new(
-                1024,
-                physical_schema.clone(),
-                predicate,
-            ))
+        // SAFETY: callers check that `files` is not empty.
+        if files[0].location.is_presigned() {
+            Box::new(PresignedUrlOpener::new(1024, physical_schema, predicate))
         } else {
             Box::new(ParquetOpener::new(
                 1024,
-                physical_schema.clone(),
+                physical_schema,
                 predicate,
                 self.store.clone(),
             ))
-        };
-        FileStream::new_async_read_iterator(
-            self.task_executor.clone(),
-            Arc::new(physical_schema.as_ref().try_into_arrow()?),
-            file_opener,
-            files,
-            self.readahead,
-        )
+        }
     }
 }
 
@@ -5,9 +5,11 @@
 #[cfg(feature = "arrow-conversion")]
 pub mod arrow_conversion;
 
-#[cfg(all(feature = "arrow-expression", feature = "default-engine-base"))]
+#[cfg(feature = "arrow-expression")]
 pub mod arrow_expression;
+// the parquet read utilities are only used by the default engine
 #[cfg(feature = "arrow-expression")]
+#[cfg_attr(not(feature = "default-engine-base"), allow(dead_code))]
 pub(crate) mod arrow_utils;
 #[cfg(feature = "internal-api")]
 pub use self::arrow_utils::{parse_json, to_json_bytes};
@@ -18,11 +20,11 @@ pub mod default;
 #[cfg(test)]
 pub(crate) mod sync;
 
-#[cfg(feature = "default-engine-base")]
+#[cfg(feature = "arrow-expression")]
 pub mod arrow_data;
-#[cfg(feature = "default-engine-base")]
+#[cfg(feature = "arrow-expression")]
 pub(crate) mod arrow_get_data;
-#[cfg(feature = "default-engine-base")]
+#[cfg(feature = "arrow-expression")]
 pub(crate) mod ensure_data_types;
 #[cfg(feature = "default-engine-base")]
 pub mod parquet_row_group_skipping;
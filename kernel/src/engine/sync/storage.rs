@@ -1,5 +1,10 @@
+use std::io::Write as _;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
 use bytes::Bytes;
 use itertools::Itertools;
+use tempfile::NamedTempFile;
 use url::Url;
 
 use crate::{DeltaResult, Error, FileMeta, FileSlice, StorageHandler};
@@ -70,6 +75,81 @@ impl StorageHandler for SyncStorageHandler {
         });
         Ok(Box::new(iter))
     }
+
+    /// Write the data to a temporary file in the same directory, and atomically move it to `path`
+    /// (failing if `path` already exists, unless `overwrite` is true).
+    fn put(&self, path: &Url, data: Bytes, overwrite: bool) -> DeltaResult<()> {
+        let path = to_file_path(path)?;
+        let Some(parent) = path.parent() else {
+            return Err(Error::generic(format!("no parent found for {path:?}")));
+        };
+        std::fs::create_dir_all(parent)?;
+
+        let mut tmp_file = NamedTempFile::new_in(parent)?;
+        tmp_file.write_all(&data)?;
+        tmp_file.flush()?;
+        let persist_result = if overwrite {
+            tmp_file.persist(&path)
+        } else {
+            tmp_file.persist_noclobber(&path)
+        };
+        persist_result.map_err(|e| {
+            if !overwrite && e.error.kind() == std::io::ErrorKind::AlreadyExists {
+                Error::FileAlreadyExists(path.to_string_lossy().to_string())
+            } else {
+                Error::IOError(e.into())
+            }
+        })?;
+        Ok(())
+    }
+
+    fn head(&self, path: &Url) -> DeltaResult<FileMeta> {
+        let metadata = std::fs::metadata(to_file_path(path)?).map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => Error::file_not_found(path.path()),
+            _ => e.into(),
+        })?;
+        let last_modified = metadata
+            .modified()?
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_err(|_| Error::generic("Failed to convert file timestamp to milliseconds"))?;
+        Ok(FileMeta {
+            location: path.clone(),
+            last_modified: last_modified.as_millis().try_into().map_err(|_| {
+                Error::generic(format!(
+                    "Failed to convert file modification time {:?} into i64",
+                    last_modified.as_millis()
+                ))
+            })?,
+            size: metadata.len(),
+        })
+    }
+
+    fn delete(&self, path: &Url) -> DeltaResult<()> {
+        match std::fs::remove_file(to_file_path(path)?) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    fn copy(&self, from: &Url, to: &Url) -> DeltaResult<()> {
+        let to = to_file_path(to)?;
+        if let Some(parent) = to.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::copy(to_file_path(from)?, to).map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => Error::file_not_found(from.path()),
+            _ => e.into(),
+        })?;
+        Ok(())
+    }
+}
+
+fn to_file_path(url: &Url) -> DeltaResult<PathBuf> {
+    if url.scheme() != "file" {
+        return Err(Error::generic("Can only access local filesystem"));
+    }
+    url.to_file_path()
+        .map_err(|_| Error::generic(format!("Invalid path: {url}")))
 }
 
 #[cfg(test)]
@@ -78,12 +158,12 @@ mod tests {
     use std::time::{Duration, SystemTime};
     use std::{fs::File, time::UNIX_EPOCH};
 
-    use bytes::{BufMut, BytesMut};
+    use bytes::{BufMut, Bytes, BytesMut};
     use itertools::Itertools;
     use url::Url;
 
     use super::SyncStorageHandler;
-    use crate::StorageHandler;
+    use crate::{Error, StorageHandler};
 
     /// generate json filenames that follow the spec (numbered padded to 20 chars)
     fn get_json_filename(index: usize) -> String {
@@ -175,4 +255,34 @@ mod tests {
         assert_eq!(file_count, 1);
         Ok(())
     }
+
+    #[test]
+    fn test_put_head_delete_copy() -> Result<(), Box<dyn std::error::Error>> {
+        let storage = SyncStorageHandler;
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let root = Url::from_directory_path(tmp_dir.path()).unwrap();
+        let a = root.join("dir/a")?;
+        let b = root.join("b")?;
+
+        assert!(matches!(storage.head(&a), Err(Error::FileNotFound(_))));
+        storage.put(&a, Bytes::from("kernel"), false)?;
+        assert_eq!(storage.head(&a)?.size, 6);
+
+        // put-if-absent leaves the existing file untouched
+        let err = storage.put(&a, Bytes::from("data"), false).unwrap_err();
+        assert!(matches!(err, Error::FileAlreadyExists(_)));
+        assert_eq!(std::fs::read(tmp_dir.path().join("dir/a"))?, b"kernel");
+        storage.put(&a, Bytes::from("data"), true)?;
+        assert_eq!(storage.head(&a)?.size, 4);
+
+        storage.copy(&a, &b)?;
+        assert_eq!(storage.head(&b)?.size, 4);
+
+        storage.delete(&a)?;
+        assert!(matches!(storage.head(&a), Err(Error::FileNotFound(_))));
+        // deleting a missing file is fine
+        storage.delete(&a)?;
+        assert!(matches!(storage.copy(&a, &b), Err(Error::FileNotFound(_))));
+        Ok(())
+    }
 }
@@ -10,7 +10,7 @@ use crate::schema::{DataType, StructType};
 use crate::table_properties::ParseIntervalError;
 use crate::Version;
 
-#[cfg(feature = "default-engine-base")]
+#[cfg(feature = "need-arrow")]
 use crate::arrow::error::ArrowError;
 #[cfg(feature = "default-engine-base")]
 use object_store;
@@ -33,7 +33,7 @@ pub enum Error {
     },
 
     /// An error performing operations on arrow data
-    #[cfg(feature = "default-engine-base")]
+    #[cfg(feature = "need-arrow")]
     #[error(transparent)]
     Arrow(ArrowError),
 
@@ -206,6 +206,24 @@ pub enum Error {
     /// Schema mismatch has occurred or invalid schema used somewhere
     #[error("Schema error: {0}")]
     Schema(String),
+
+    /// Data to be written violates a column invariant of the table
+    #[error("Invariant violation: {0}")]
+    InvariantViolation(String),
+
+    /// A transaction attempted to change existing data of an append-only table
+    #[error("Append-only table violation: {0}")]
+    AppendOnlyViolation(String),
+
+    /// A streaming source encountered a change it cannot process in the given table version, e.g.
+    /// deleted data or a schema change. The stream must be restarted to make progress.
+    #[error("Non-additive change in table version {0}: {1}")]
+    NonAdditiveChange(Version, String),
+
+    /// A data file that a RESTORE would bring back to the table no longer exists, e.g. because the
+    /// table was vacuumed since the restored version.
+    #[error("Cannot restore table to version {0}: data file {1} no longer exists")]
+    MissingRestoredFile(Version, String),
 }
 
 // Convenience constructors for Error types that take a String argument
@@ -291,6 +309,22 @@ impl Error {
         Self::Schema(msg.to_string())
     }
 
+    pub fn invariant_violation(msg: impl ToString) -> Self {
+        Self::InvariantViolation(msg.to_string())
+    }
+
+    pub fn append_only_violation(msg: impl ToString) -> Self {
+        Self::AppendOnlyViolation(msg.to_string())
+    }
+
+    pub fn non_additive_change(version: impl Into<Version>, msg: impl ToString) -> Self {
+        Self::NonAdditiveChange(version.into(), msg.to_string())
+    }
+
+    pub fn missing_restored_file(version: impl Into<Version>, path: impl ToString) -> Self {
+        Self::MissingRestoredFile(version.into(), path.to_string())
+    }
+
     // Capture a backtrace when the error is constructed.
     #[must_use]
     pub fn with_backtrace(self) -> Self {
@@ -322,7 +356,7 @@ from_with_backtrace!(
     (std::io::Error, IOError)
 );
 
-#[cfg(feature = "default-engine-base")]
+#[cfg(feature = "need-arrow")]
 impl From<ArrowError> for Error {
     fn from(value: ArrowError) -> Self {
         Self::Arrow(value).with_backtrace()
@@ -6,8 +6,11 @@ use std::hash::{Hash, Hasher};
 use std::iter::Peekable;
 use std::ops::Deref;
 
+use serde::{Deserialize, Serialize};
+
 /// A (possibly nested) column name.
-#[derive(Debug, Clone, PartialEq, PartialOrd, Eq, Ord)]
+#[derive(Debug, Clone, PartialEq, PartialOrd, Eq, Ord, Serialize, Deserialize)]
+#[serde(transparent)]
 pub struct ColumnName {
     path: Vec<String>,
 }
@@ -0,0 +1,392 @@
+//! Built-in SQL functions that kernel understands, e.g. to evaluate generated column expressions.
+//!
+//! Kernel's expression language has no function calls, so these are surfaced as
+//! [`Expression::Opaque`] expressions whose op is a [`SqlFunction`]. Engines can recognize them by
+//! downcasting the op (see [`crate::AsAny`]); the default engine evaluates them natively.
+//!
+//! Date/time functions operate on microsecond-precision timestamps (interpreted in UTC) and dates
+//! (days since the unix epoch).
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, Timelike};
+
+use crate::expressions::{Expression, OpaqueExpressionOp, Scalar, ScalarExpressionEvaluator};
+use crate::schema::{DataType, PrimitiveType};
+use crate::{DeltaResult, Error};
+
+const MICROS_PER_DAY: i64 = 86_400_000_000;
+
+/// A SQL function over a single argument.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SqlFunction {
+    /// `CAST(x AS <type>)`, also produced by `TO_DATE(x)`
+    Cast(DataType),
+    /// Extracts a field of a date or timestamp as an INTEGER, e.g. `YEAR(x)` or `HOUR(x)`
+    Extract(DateTimeField),
+    /// Truncates a date or timestamp to the given unit, e.g. `DATE_TRUNC('MONTH', x)`
+    Truncate(DateTimeField),
+}
+
+/// A date/time field, used both to extract values from and to truncate dates and timestamps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateTimeField {
+    Year,
+    Quarter,
+    Month,
+    /// The ISO week (extraction) or the Monday that starts the week (truncation)
+    Week,
+    Day,
+    Hour,
+    Minute,
+    Second,
+}
+
+impl SqlFunction {
+    /// The type this function produces for an argument of type `input_type`, if known.
+    pub fn result_type(&self, input_type: Option<&DataType>) -> Option<DataType> {
+        match self {
+            Self::Cast(data_type) => Some(data_type.clone()),
+            Self::Extract(_) => Some(DataType::INTEGER),
+            Self::Truncate(_) => input_type.cloned(),
+        }
+    }
+
+    /// Applies this function to a single value.
+    pub(crate) fn eval_scalar(&self, value: Scalar) -> DeltaResult<Scalar> {
+        let result = match (self, value) {
+            (Self::Cast(data_type), value) => return cast_scalar(value, data_type),
+            (Self::Extract(_), Scalar::Null(_)) => Some(Scalar::Null(DataType::INTEGER)),
+            (Self::Truncate(_), Scalar::Null(data_type)) => Some(Scalar::Null(data_type)),
+            (Self::Extract(field), Scalar::Date(days)) => {
+                field.extract_from_date(days).map(Scalar::Integer)
+            }
+            (Self::Extract(field), Scalar::Timestamp(micros) | Scalar::TimestampNtz(micros)) => {
+                field.extract_from_timestamp(micros).map(Scalar::Integer)
+            }
+            (Self::Truncate(field), Scalar::Date(days)) => {
+                field.truncate_date(days).map(Scalar::Date)
+            }
+            (Self::Truncate(field), Scalar::Timestamp(micros)) => {
+                field.truncate_timestamp(micros).map(Scalar::Timestamp)
+            }
+            (Self::Truncate(field), Scalar::TimestampNtz(micros)) => {
+                field.truncate_timestamp(micros).map(Scalar::TimestampNtz)
+            }
+            (_, value) => {
+                return Err(Error::generic(format!(
+                    "Cannot apply {} to {value:?}",
+                    self.name()
+                )))
+            }
+        };
+        result.ok_or_else(|| Error::generic(format!("{} is out of range", self.name())))
+    }
+}
+
+impl OpaqueExpressionOp for SqlFunction {
+    fn name(&self) -> &str {
+        match self {
+            Self::Cast(_) => "CAST",
+            Self::Extract(field) => field.as_str(),
+            Self::Truncate(_) => "DATE_TRUNC",
+        }
+    }
+
+    fn eval_expr_scalar(
+        &self,
+        eval_expr: &ScalarExpressionEvaluator<'_>,
+        exprs: &[Expression],
+    ) -> DeltaResult<Scalar> {
+        let [arg] = exprs else {
+            return Err(Error::generic(format!(
+                "{} expects exactly one argument",
+                self.name()
+            )));
+        };
+        let value = eval_expr(arg).ok_or_else(|| {
+            Error::generic(format!("Cannot evaluate argument of {}", self.name()))
+        })?;
+        self.eval_scalar(value)
+    }
+}
+
+impl DateTimeField {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Year => "YEAR",
+            Self::Quarter => "QUARTER",
+            Self::Month => "MONTH",
+            Self::Week => "WEEK",
+            Self::Day => "DAY",
+            Self::Hour => "HOUR",
+            Self::Minute => "MINUTE",
+            Self::Second => "SECOND",
+        }
+    }
+
+    /// Parses the name of the SQL function that extracts a field, e.g. `YEAR` or `DAYOFMONTH`.
+    pub(crate) fn from_extract_function(name: &str) -> Option<Self> {
+        let field = match name.to_ascii_uppercase().as_str() {
+            "YEAR" => Self::Year,
+            "QUARTER" => Self::Quarter,
+            "MONTH" => Self::Month,
+            "WEEKOFYEAR" => Self::Week,
+            "DAY" | "DAYOFMONTH" => Self::Day,
+            "HOUR" => Self::Hour,
+            "MINUTE" => Self::Minute,
+            "SECOND" => Self::Second,
+            _ => return None,
+        };
+        Some(field)
+    }
+
+    /// Parses a truncation unit as accepted by `DATE_TRUNC` and `TRUNC`, e.g. `'MONTH'` or `'MM'`.
+    pub(crate) fn from_unit(unit: &str) -> Option<Self> {
+        let field = match unit.to_ascii_uppercase().as_str() {
+            "YEAR" | "YYYY" | "YY" => Self::Year,
+            "QUARTER" => Self::Quarter,
+            "MONTH" | "MM" | "MON" => Self::Month,
+            "WEEK" => Self::Week,
+            "DAY" | "DD" => Self::Day,
+            "HOUR" => Self::Hour,
+            "MINUTE" => Self::Minute,
+            "SECOND" => Self::Second,
+            _ => return None,
+        };
+        Some(field)
+    }
+
+    /// Extracts this field from a timestamp (microseconds since the epoch).
+    pub(crate) fn extract_from_timestamp(&self, micros: i64) -> Option<i32> {
+        let datetime = to_datetime(micros)?;
+        let value = match self {
+            Self::Hour => datetime.hour(),
+            Self::Minute => datetime.minute(),
+            Self::Second => datetime.second(),
+            _ => return self.extract(datetime.date()),
+        };
+        i32::try_from(value).ok()
+    }
+
+    /// Extracts this field from a date (days since the epoch). Time fields of a date are zero.
+    pub(crate) fn extract_from_date(&self, days: i32) -> Option<i32> {
+        self.extract(to_date(days)?)
+    }
+
+    fn extract(&self, date: NaiveDate) -> Option<i32> {
+        let value = match self {
+            Self::Year => return Some(date.year()),
+            Self::Quarter => (date.month() - 1) / 3 + 1,
+            Self::Month => date.month(),
+            Self::Week => date.iso_week().week(),
+            Self::Day => date.day(),
+            Self::Hour | Self::Minute | Self::Second => 0,
+        };
+        i32::try_from(value).ok()
+    }
+
+    /// Truncates a timestamp (microseconds since the epoch) to this unit.
+    pub(crate) fn truncate_timestamp(&self, micros: i64) -> Option<i64> {
+        let datetime = to_datetime(micros)?;
+        let truncated = match self {
+            Self::Hour => datetime.date().and_hms_opt(datetime.hour(), 0, 0)?,
+            Self::Minute => datetime
+                .date()
+                .and_hms_opt(datetime.hour(), datetime.minute(), 0)?,
+            Self::Second => datetime.with_nanosecond(0)?,
+            _ => self.truncate(datetime.date())?.and_hms_opt(0, 0, 0)?,
+        };
+        Some(truncated.and_utc().timestamp_micros())
+    }
+
+    /// Truncates a date (days since the epoch) to this unit. Time units leave the date unchanged.
+    pub(crate) fn truncate_date(&self, days: i32) -> Option<i32> {
+        let truncated = self.truncate(to_date(days)?)?;
+        i32::try_from((truncated - NaiveDate::default()).num_days()).ok()
+    }
+
+    fn truncate(&self, date: NaiveDate) -> Option<NaiveDate> {
+        match self {
+            Self::Year => NaiveDate::from_ymd_opt(date.year(), 1, 1),
+            Self::Quarter => {
+                NaiveDate::from_ymd_opt(date.year(), (date.month() - 1) / 3 * 3 + 1, 1)
+            }
+            Self::Month => date.with_day(1),
+            Self::Week => date
+                .checked_sub_signed(Duration::days(date.weekday().num_days_from_monday().into())),
+            Self::Day | Self::Hour | Self::Minute | Self::Second => Some(date),
+        }
+    }
+}
+
+// NOTE: `NaiveDate::default()` is the unix epoch (1970-01-01)
+fn to_date(days: i32) -> Option<NaiveDate> {
+    NaiveDate::default().checked_add_signed(Duration::days(days.into()))
+}
+
+fn to_datetime(micros: i64) -> Option<NaiveDateTime> {
+    DateTime::from_timestamp_micros(micros).map(|datetime| datetime.naive_utc())
+}
+
+/// Casts a single value. Only the lossless or well-defined casts that commonly appear in generation
+/// expressions are supported: between integer types (failing on overflow), from integers to
+/// floating point, between dates and timestamps, and from integers, booleans and strings to string.
+fn cast_scalar(value: Scalar, data_type: &DataType) -> DeltaResult<Scalar> {
+    if value.is_null() {
+        return Ok(Scalar::Null(data_type.clone()));
+    }
+    if value.data_type() == *data_type {
+        return Ok(value);
+    }
+    let integer = match value {
+        Scalar::Byte(v) => Some(i64::from(v)),
+        Scalar::Short(v) => Some(i64::from(v)),
+        Scalar::Integer(v) => Some(i64::from(v)),
+        Scalar::Long(v) => Some(v),
+        _ => None,
+    };
+    let overflow = || Error::generic(format!("Cannot cast {value:?} to {data_type}: overflow"));
+    let DataType::Primitive(target) = data_type else {
+        return Err(Error::generic(format!(
+            "Cannot cast {value:?} to {data_type}"
+        )));
+    };
+    let result = match (target, &value, integer) {
+        (PrimitiveType::Byte, _, Some(v)) => Scalar::Byte(v.try_into().map_err(|_| overflow())?),
+        (PrimitiveType::Short, _, Some(v)) => Scalar::Short(v.try_into().map_err(|_| overflow())?),
+        (PrimitiveType::Integer, _, Some(v)) => {
+            Scalar::Integer(v.try_into().map_err(|_| overflow())?)
+        }
+        (PrimitiveType::Long, _, Some(v)) => Scalar::Long(v),
+        (PrimitiveType::Float, _, Some(v)) => Scalar::Float(v as f32),
+        (PrimitiveType::Double, _, Some(v)) => Scalar::Double(v as f64),
+        (PrimitiveType::String, _, Some(v)) => Scalar::String(v.to_string()),
+        (PrimitiveType::String, Scalar::Boolean(v), _) => Scalar::String(v.to_string()),
+        (PrimitiveType::String, Scalar::String(v), _) => Scalar::String(v.clone()),
+        (PrimitiveType::Date, Scalar::Timestamp(v) | Scalar::TimestampNtz(v), _) => Scalar::Date(
+            v.div_euclid(MICROS_PER_DAY)
+                .try_into()
+                .map_err(|_| overflow())?,
+        ),
+        (PrimitiveType::Timestamp, Scalar::Date(v), _) => Scalar::Timestamp(
+            i64::from(*v)
+                .checked_mul(MICROS_PER_DAY)
+                .ok_or_else(overflow)?,
+        ),
+        (PrimitiveType::TimestampNtz, Scalar::Date(v), _) => Scalar::TimestampNtz(
+            i64::from(*v)
+                .checked_mul(MICROS_PER_DAY)
+                .ok_or_else(overflow)?,
+        ),
+        (PrimitiveType::Timestamp, Scalar::TimestampNtz(v), _) => Scalar::Timestamp(*v),
+        (PrimitiveType::TimestampNtz, Scalar::Timestamp(v), _) => Scalar::TimestampNtz(*v),
+        _ => {
+            return Err(Error::generic(format!(
+                "Cannot cast {value:?} to {data_type}"
+            )))
+        }
+    };
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2024-05-17 13:45:30.5 UTC
+    const MICROS: i64 = 1_715_953_530_500_000;
+    // 2024-05-17
+    const DAYS: i32 = 19_860;
+
+    #[test]
+    fn test_extract() {
+        let cases = [
+            (DateTimeField::Year, 2024),
+            (DateTimeField::Quarter, 2),
+            (DateTimeField::Month, 5),
+            (DateTimeField::Week, 20),
+            (DateTimeField::Day, 17),
+            (DateTimeField::Hour, 13),
+            (DateTimeField::Minute, 45),
+            (DateTimeField::Second, 30),
+        ];
+        for (field, expected) in cases {
+            assert_eq!(
+                field.extract_from_timestamp(MICROS),
+                Some(expected),
+                "{field:?}"
+            );
+        }
+        assert_eq!(DateTimeField::Month.extract_from_date(DAYS), Some(5));
+        assert_eq!(DateTimeField::Hour.extract_from_date(DAYS), Some(0));
+        // before the epoch
+        assert_eq!(DateTimeField::Year.extract_from_date(-1), Some(1969));
+        assert_eq!(DateTimeField::Hour.extract_from_timestamp(-1), Some(23));
+    }
+
+    #[test]
+    fn test_truncate() {
+        let day = i64::from(DAYS) * MICROS_PER_DAY;
+        let hour = 3_600_000_000;
+        let cases = [
+            (DateTimeField::Year, 19_723),    // 2024-01-01
+            (DateTimeField::Quarter, 19_814), // 2024-04-01
+            (DateTimeField::Month, 19_844),   // 2024-05-01
+            (DateTimeField::Week, 19_856),    // Monday 2024-05-13
+            (DateTimeField::Day, DAYS),
+        ];
+        for (field, expected_days) in cases {
+            assert_eq!(field.truncate_date(DAYS), Some(expected_days), "{field:?}");
+            assert_eq!(
+                field.truncate_timestamp(MICROS),
+                Some(i64::from(expected_days) * MICROS_PER_DAY),
+                "{field:?}"
+            );
+        }
+        assert_eq!(
+            DateTimeField::Hour.truncate_timestamp(MICROS),
+            Some(day + 13 * hour)
+        );
+        assert_eq!(
+            DateTimeField::Second.truncate_timestamp(MICROS),
+            Some(MICROS - 500_000)
+        );
+        assert_eq!(DateTimeField::Hour.truncate_date(DAYS), Some(DAYS));
+    }
+
+    #[test]
+    fn test_eval_scalar() {
+        let year = SqlFunction::Extract(DateTimeField::Year);
+        assert_eq!(
+            year.eval_scalar(Scalar::Timestamp(MICROS)).unwrap(),
+            Scalar::Integer(2024)
+        );
+        let null = year.eval_scalar(Scalar::Null(DataType::TIMESTAMP)).unwrap();
+        assert!(matches!(null, Scalar::Null(DataType::INTEGER)));
+        assert!(year.eval_scalar(Scalar::Integer(1)).is_err());
+
+        let trunc = SqlFunction::Truncate(DateTimeField::Day);
+        assert_eq!(
+            trunc.eval_scalar(Scalar::TimestampNtz(MICROS)).unwrap(),
+            Scalar::TimestampNtz(i64::from(DAYS) * MICROS_PER_DAY)
+        );
+
+        let to_date = SqlFunction::Cast(DataType::DATE);
+        assert_eq!(
+            to_date.eval_scalar(Scalar::Timestamp(MICROS)).unwrap(),
+            Scalar::Date(DAYS)
+        );
+        let to_long = SqlFunction::Cast(DataType::LONG);
+        assert_eq!(
+            to_long.eval_scalar(Scalar::Integer(7)).unwrap(),
+            Scalar::Long(7)
+        );
+        let to_byte = SqlFunction::Cast(DataType::BYTE);
+        assert!(to_byte.eval_scalar(Scalar::Integer(1000)).is_err());
+        let to_string = SqlFunction::Cast(DataType::STRING);
+        assert_eq!(
+            to_string.eval_scalar(Scalar::Long(42)).unwrap(),
+            Scalar::String("42".into())
+        );
+        assert!(to_long.eval_scalar(Scalar::String("42".into())).is_err());
+    }
+}
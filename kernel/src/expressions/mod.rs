@@ -5,10 +5,12 @@ use std::fmt::{Display, Formatter};
 use std::sync::Arc;
 
 use itertools::Itertools;
+use serde::{Deserialize, Serialize};
 
 pub use self::column_names::{
     column_expr, column_name, column_pred, joined_column_expr, joined_column_name, ColumnName,
 };
+pub use self::functions::{DateTimeField, SqlFunction};
 pub use self::scalars::{ArrayData, DecimalData, MapData, Scalar, StructData};
 use self::transforms::{ExpressionTransform as _, GetColumnReferences};
 use crate::kernel_predicates::{
@@ -18,8 +20,10 @@ use crate::kernel_predicates::{
 use crate::{DataType, DeltaResult, DynPartialEq};
 
 mod column_names;
+mod functions;
 pub(crate) mod literal_expression_transform;
 mod scalars;
+pub(crate) mod sql;
 pub mod transforms;
 
 pub type ExpressionRef = std::sync::Arc<Expression>;
@@ -30,14 +34,14 @@ pub type PredicateRef = std::sync::Arc<Predicate>;
 ////////////////////////////////////////////////////////////////////////
 
 /// A unary predicate operator.
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum UnaryPredicateOp {
     /// Unary Is Null
     IsNull,
 }
 
 /// A binary predicate operator.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum BinaryPredicateOp {
     /// Comparison Less Than
     LessThan,
@@ -52,7 +56,7 @@ pub enum BinaryPredicateOp {
 }
 
 /// A binary expression operator.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum BinaryExpressionOp {
     /// Arithmetic Plus
     Plus,
@@ -65,7 +69,7 @@ pub enum BinaryExpressionOp {
 }
 
 /// A junction (AND/OR) predicate operator.
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum JunctionPredicateOp {
     /// Conjunction
     And,
@@ -175,7 +179,7 @@ pub type OpaquePredicateOpRef = Arc<dyn OpaquePredicateOp>;
 // Expressions and predicates
 ////////////////////////////////////////////////////////////////////////
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct UnaryPredicate {
     /// The operator.
     pub op: UnaryPredicateOp,
@@ -183,7 +187,7 @@ pub struct UnaryPredicate {
     pub expr: Box<Expression>,
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct BinaryPredicate {
     /// The operator.
     pub op: BinaryPredicateOp,
@@ -193,7 +197,7 @@ pub struct BinaryPredicate {
     pub right: Box<Expression>,
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct BinaryExpression {
     /// The operator.
     pub op: BinaryExpressionOp,
@@ -203,7 +207,7 @@ pub struct BinaryExpression {
     pub right: Box<Expression>,
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct JunctionPredicate {
     /// The operator.
     pub op: JunctionPredicateOp,
@@ -248,7 +252,7 @@ impl OpaqueExpression {
 /// These expressions do not track or validate data types, other than the type
 /// of literals. It is up to the expression evaluator to validate the
 /// expression against a schema and add appropriate casts as required.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Expression {
     /// A literal value.
     Literal(Scalar),
@@ -262,6 +266,9 @@ pub enum Expression {
     Binary(BinaryExpression),
     /// An expression that the engine defines and implements. Kernel interacts with the expression
     /// only through methods provided by the [`OpaqueExpressionOp`] trait.
+    ///
+    /// Opaque expressions cannot be serialized, because their operation is only known to the engine.
+    #[serde(skip)]
     Opaque(OpaqueExpression),
     /// An unknown expression (i.e. one that neither kernel nor engine attempts to evaluate). For
     /// data skipping purposes, kernel treats unknown expressions as if they were literal NULL
@@ -279,7 +286,7 @@ pub enum Expression {
 /// These predicates do not track or validate data types, other than the type
 /// of literals. It is up to the predicate evaluator to validate the
 /// predicate against a schema and add appropriate casts as required.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Predicate {
     /// A boolean-valued expression, useful for e.g. `AND(<boolean_col1>, <boolean_col2>)`.
     BooleanExpression(Expression),
@@ -298,6 +305,9 @@ pub enum Predicate {
     Junction(JunctionPredicate),
     /// A predicate that the engine defines and implements. Kernel interacts with the predicate
     /// only through methods provided by the [`OpaquePredicateOp`] trait.
+    ///
+    /// Opaque predicates cannot be serialized, because their operation is only known to the engine.
+    #[serde(skip)]
     Opaque(OpaquePredicate),
     /// An unknown predicate (i.e. one that neither kernel nor engine attempts to evaluate). For
     /// data skipping purposes, kernel treats unknown predicates as if they were literal NULL values
@@ -841,4 +851,22 @@ mod tests {
             assert_eq!(result, expected);
         }
     }
+
+    #[test]
+    fn test_serde_roundtrip() {
+        let pred = Pred::and_from([
+            column_expr!("a.b").ge(Expr::literal(2)),
+            Pred::not(Pred::is_null(column_expr!("x") * Expr::literal(3.5))),
+            Pred::or(
+                column_pred!("flag"),
+                Pred::from_expr(Expr::struct_from([Expr::unknown("udf")])),
+            ),
+        ]);
+        let json = serde_json::to_string(&pred).unwrap();
+        assert_eq!(serde_json::from_str::<Pred>(&json).unwrap(), pred);
+
+        let expr = Expr::from(pred) + column_expr!("y");
+        let json = serde_json::to_string(&expr).unwrap();
+        assert_eq!(serde_json::from_str::<Expr>(&json).unwrap(), expr);
+    }
 }
@@ -3,13 +3,16 @@ use std::fmt::{Display, Formatter};
 
 use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
 use itertools::Itertools;
+use serde::{Deserialize, Serialize};
 
 use crate::schema::derive_macro_utils::ToDataType;
 use crate::schema::{ArrayType, DataType, DecimalType, MapType, PrimitiveType, StructField};
 use crate::utils::require;
 use crate::{DeltaResult, Error};
 
-#[derive(Debug, Clone, PartialEq)]
+// Serialized as a `(bits, precision, scale)` tuple, so that deserialization can validate it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(into = "(i128, u8, u8)", try_from = "(i128, u8, u8)")]
 pub struct DecimalData {
     bits: i128,
     ty: DecimalType,
@@ -53,7 +56,22 @@ fn get_decimal_precision(value: i128) -> u8 {
     value.unsigned_abs().checked_ilog10().map_or(0, |p| p + 1) as _
 }
 
-#[derive(Clone, Debug, PartialEq)]
+impl From<DecimalData> for (i128, u8, u8) {
+    fn from(value: DecimalData) -> Self {
+        (value.bits, value.precision(), value.scale())
+    }
+}
+
+impl TryFrom<(i128, u8, u8)> for DecimalData {
+    type Error = Error;
+
+    fn try_from((bits, precision, scale): (i128, u8, u8)) -> DeltaResult<Self> {
+        Self::try_new(bits, DecimalType::try_new(precision, scale)?)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "ArrayDataParts")]
 pub struct ArrayData {
     tpe: ArrayType,
     /// This exists currently for literal list comparisons, but should not be depended on see below
@@ -101,7 +119,23 @@ impl ArrayData {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+// Deserialization goes through `try_new`, so that it enforces the same invariants.
+#[derive(Deserialize)]
+struct ArrayDataParts {
+    tpe: ArrayType,
+    elements: Vec<Scalar>,
+}
+
+impl TryFrom<ArrayDataParts> for ArrayData {
+    type Error = Error;
+
+    fn try_from(parts: ArrayDataParts) -> DeltaResult<Self> {
+        Self::try_new(parts.tpe, parts.elements)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "MapDataParts")]
 pub struct MapData {
     data_type: MapType,
     pairs: Vec<(Scalar, Scalar)>,
@@ -159,7 +193,22 @@ impl MapData {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Deserialize)]
+struct MapDataParts {
+    data_type: MapType,
+    pairs: Vec<(Scalar, Scalar)>,
+}
+
+impl TryFrom<MapDataParts> for MapData {
+    type Error = Error;
+
+    fn try_from(parts: MapDataParts) -> DeltaResult<Self> {
+        Self::try_new(parts.data_type, parts.pairs)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "StructDataParts")]
 pub struct StructData {
     fields: Vec<StructField>,
     values: Vec<Scalar>,
@@ -215,9 +264,23 @@ impl StructData {
     }
 }
 
+#[derive(Deserialize)]
+struct StructDataParts {
+    fields: Vec<StructField>,
+    values: Vec<Scalar>,
+}
+
+impl TryFrom<StructDataParts> for StructData {
+    type Error = Error;
+
+    fn try_from(parts: StructDataParts) -> DeltaResult<Self> {
+        Self::try_new(parts.fields, parts.values)
+    }
+}
+
 /// A single value, which can be null. Used for representing literal values
 /// in [Expressions][crate::expressions::Expression].
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Scalar {
     /// 32bit integer
     Integer(i32),
@@ -999,4 +1062,53 @@ mod tests {
         let null = Scalar::Null(DataType::INTEGER);
         assert!(!null.eq(&null));
     }
+
+    #[test]
+    fn test_serde_roundtrip() {
+        let array_type = ArrayType::new(DataType::INTEGER, true);
+        let map_type = MapType::new(DataType::STRING, DataType::LONG, false);
+        let fields = vec![
+            StructField::nullable("a", DataType::decimal(10, 2).unwrap()),
+            StructField::not_null("b", DataType::BINARY),
+        ];
+        let scalars = [
+            Scalar::Integer(7),
+            Scalar::String("foo".to_string()),
+            Scalar::Timestamp(1_234_567),
+            Scalar::Null(DataType::DATE),
+            Scalar::decimal(i128::MAX / 100, 38, 10).unwrap(),
+            Scalar::Array(ArrayData::try_new(array_type, [Some(1), None]).unwrap()),
+            Scalar::Map(MapData::try_new(map_type, [("k", 1i64)]).unwrap()),
+            Scalar::Struct(
+                StructData::try_new(
+                    fields,
+                    vec![
+                        Scalar::Null(DataType::decimal(10, 2).unwrap()),
+                        Scalar::Binary(vec![1]),
+                    ],
+                )
+                .unwrap(),
+            ),
+        ];
+        for scalar in scalars {
+            let json = serde_json::to_string(&scalar).unwrap();
+            let result: Scalar = serde_json::from_str(&json).unwrap();
+            // NULL values are incomparable, so compare the serialized forms instead
+            assert_eq!(serde_json::to_string(&result).unwrap(), json);
+        }
+    }
+
+    #[test]
+    fn test_deserialize_invalid() {
+        // decimal value exceeds its precision
+        serde_json::from_str::<Scalar>(r#"{"Decimal":[123456,3,0]}"#).expect_err("invalid decimal");
+        // array element doesn't match the element type
+        let array = Scalar::Array(
+            ArrayData::try_new(ArrayType::new(DataType::INTEGER, false), [1]).unwrap(),
+        );
+        let json = serde_json::to_string(&array)
+            .unwrap()
+            .replace(r#"{"Integer":1}"#, r#"{"String":"s"}"#);
+        serde_json::from_str::<Scalar>(&json).expect_err("invalid array");
+    }
 }
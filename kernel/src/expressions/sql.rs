@@ -0,0 +1,763 @@
+//! A parser for the small subset of Spark SQL that Delta stores in table metadata (e.g. column
+//! invariants). The parser produces kernel [`Expression`]s and [`Predicate`]s, and optionally
+//! resolves column references against a schema so that column names match the schema's casing
+//! and numeric/string literals are coerced to the type of the column they are compared with.
+//!
+//! Supported syntax:
+//! - literals: integers (with optional `Y`, `S`, `L` suffix), decimals/doubles, `'strings'`,
+//!   `TRUE`, `FALSE` and `NULL`
+//! - column references: `a`, `a.b`, `` `weird name`.c ``
+//! - arithmetic: `+`, `-`, `*`, `/` and unary minus
+//! - comparisons: `=`, `==`, `!=`, `<>`, `<`, `<=`, `>`, `>=`, `<=>`
+//! - `IS [NOT] NULL`, `[NOT] IN (...)`, `[NOT] BETWEEN x AND y`
+//! - boolean logic: `AND`, `OR`, `NOT`, `!` and parentheses
+//! - the functions `CAST(x AS <type>)`, `TO_DATE(x)`, `YEAR(x)`, `QUARTER(x)`, `MONTH(x)`,
+//!   `WEEKOFYEAR(x)`, `DAY(x)`/`DAYOFMONTH(x)`, `HOUR(x)`, `MINUTE(x)`, `SECOND(x)`,
+//!   `DATE_TRUNC('<unit>', x)` and `TRUNC(x, '<unit>')`, which become [`SqlFunction`]s
+//!
+//! Anything else (notably other function calls) is reported as [`Error::Unsupported`].
+
+use std::iter::Peekable;
+use std::str::Chars;
+
+use crate::expressions::{
+    ColumnName, DateTimeField, Expression, OpaqueExpression, Predicate, Scalar, SqlFunction,
+};
+use crate::schema::{DataType, PrimitiveType, StructField, StructType};
+use crate::{DeltaResult, Error};
+
+/// Parses a boolean-valued SQL string into a [`Predicate`]. If `schema` is provided, column
+/// references are resolved against it (see module docs).
+pub(crate) fn parse_predicate(sql: &str, schema: Option<&StructType>) -> DeltaResult<Predicate> {
+    Ok(Predicate::from_expr(parse_expression(sql, schema)?))
+}
+
+/// Parses a SQL string into an [`Expression`]. If `schema` is provided, column references are
+/// resolved against it (see module docs).
+pub(crate) fn parse_expression(sql: &str, schema: Option<&StructType>) -> DeltaResult<Expression> {
+    let parse_error = |msg: String| Error::unsupported(format!("Cannot parse SQL '{sql}': {msg}"));
+    let tokens = tokenize(sql).map_err(parse_error)?;
+    let mut parser = Parser {
+        tokens,
+        pos: 0,
+        schema,
+    };
+    let expr = parser.parse_or().map_err(parse_error)?;
+    match parser.peek() {
+        None => Ok(expr),
+        Some(token) => Err(parse_error(format!("unexpected trailing token {token:?}"))),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    /// An unquoted identifier or keyword
+    Ident(String),
+    /// A backtick-quoted identifier
+    QuotedIdent(String),
+    /// A numeric literal, as written
+    Number(String),
+    /// A string literal (quotes removed, escapes processed)
+    Str(String),
+    /// An operator or punctuation
+    Op(&'static str),
+}
+
+type ParseResult<T> = Result<T, String>;
+
+const OPERATORS: &[&str] = &[
+    "<=>", "==", "!=", "<>", "<=", ">=", "<", ">", "=", "+", "-", "*", "/", "(", ")", ",", ".", "!",
+];
+
+fn tokenize(sql: &str) -> ParseResult<Vec<Token>> {
+    let mut tokens = vec![];
+    let mut chars = sql.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c.is_ascii_digit() {
+            tokens.push(Token::Number(take_number(&mut chars)));
+        } else if c.is_alphabetic() || c == '_' {
+            let mut ident = String::new();
+            while let Some(&c) = chars.peek().filter(|c| c.is_alphanumeric() || **c == '_') {
+                ident.push(c);
+                chars.next();
+            }
+            tokens.push(Token::Ident(ident));
+        } else if c == '`' {
+            tokens.push(Token::QuotedIdent(take_quoted(&mut chars, '`')?));
+        } else if c == '\'' || c == '"' {
+            tokens.push(Token::Str(take_quoted(&mut chars, c)?));
+        } else {
+            let rest = &sql[sql.len() - chars.clone().map(char::len_utf8).sum::<usize>()..];
+            let op = OPERATORS
+                .iter()
+                .find(|op| rest.starts_with(**op))
+                .ok_or_else(|| format!("unexpected character '{c}'"))?;
+            for _ in 0..op.len() {
+                chars.next();
+            }
+            tokens.push(Token::Op(op));
+        }
+    }
+    Ok(tokens)
+}
+
+fn take_number(chars: &mut Peekable<Chars<'_>>) -> String {
+    let mut number = String::new();
+    while let Some(&c) = chars.peek() {
+        let is_exponent_sign = (c == '-' || c == '+') && number.ends_with(['e', 'E']);
+        if c.is_ascii_alphanumeric() || c == '.' || is_exponent_sign {
+            number.push(c);
+            chars.next();
+        } else {
+            break;
+        }
+    }
+    number
+}
+
+/// Consumes a quoted token. A doubled quote character inside the token is an escaped quote, and
+/// (for string literals) a backslash escapes the following character.
+fn take_quoted(chars: &mut Peekable<Chars<'_>>, quote: char) -> ParseResult<String> {
+    chars.next(); // opening quote
+    let mut value = String::new();
+    loop {
+        match chars.next() {
+            Some(c) if c == quote => {
+                if chars.peek() == Some(&quote) {
+                    value.push(quote);
+                    chars.next();
+                } else {
+                    return Ok(value);
+                }
+            }
+            Some('\\') if quote != '`' => match chars.next() {
+                Some('n') => value.push('\n'),
+                Some('t') => value.push('\t'),
+                Some(c) => value.push(c),
+                None => break,
+            },
+            Some(c) => value.push(c),
+            None => break,
+        }
+    }
+    Err(format!("unterminated quote {quote}"))
+}
+
+struct Parser<'s> {
+    tokens: Vec<Token>,
+    pos: usize,
+    schema: Option<&'s StructType>,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Ident(ident)) if ident.eq_ignore_ascii_case(keyword))
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let found = self.peek_keyword(keyword);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn eat_op(&mut self, op: &str) -> bool {
+        let found = matches!(self.peek(), Some(Token::Op(o)) if *o == op);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn expect_op(&mut self, op: &str) -> ParseResult<()> {
+        if self.eat_op(op) {
+            Ok(())
+        } else {
+            Err(format!("expected '{op}' but found {:?}", self.peek()))
+        }
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> ParseResult<()> {
+        if self.eat_keyword(keyword) {
+            Ok(())
+        } else {
+            Err(format!("expected {keyword} but found {:?}", self.peek()))
+        }
+    }
+
+    fn parse_or(&mut self) -> ParseResult<Expression> {
+        let mut preds = vec![self.parse_and()?];
+        while self.eat_keyword("OR") {
+            preds.push(self.parse_and()?);
+        }
+        Ok(junction(preds, Predicate::or_from))
+    }
+
+    fn parse_and(&mut self) -> ParseResult<Expression> {
+        let mut preds = vec![self.parse_not()?];
+        while self.eat_keyword("AND") {
+            preds.push(self.parse_not()?);
+        }
+        Ok(junction(preds, Predicate::and_from))
+    }
+
+    fn parse_not(&mut self) -> ParseResult<Expression> {
+        if self.eat_keyword("NOT") || self.eat_op("!") {
+            let pred = Predicate::from_expr(self.parse_not()?);
+            return Ok(Predicate::not(pred).into());
+        }
+        self.parse_comparison()
+    }
+
+    fn parse_comparison(&mut self) -> ParseResult<Expression> {
+        let left = self.parse_additive()?;
+        if self.eat_keyword("IS") {
+            let negated = self.eat_keyword("NOT");
+            self.expect_keyword("NULL")?;
+            let pred = Predicate::is_null(left);
+            return Ok(negate_if(pred, negated).into());
+        }
+        let negated = self.eat_keyword("NOT");
+        if self.eat_keyword("IN") {
+            self.expect_op("(")?;
+            let mut values = vec![];
+            loop {
+                let value = self.parse_additive()?;
+                let (left, value) = self.coerce(left.clone(), value);
+                values.push(Predicate::eq(left, value));
+                if !self.eat_op(",") {
+                    break;
+                }
+            }
+            self.expect_op(")")?;
+            return Ok(negate_if(Predicate::or_from(values), negated).into());
+        }
+        if self.eat_keyword("BETWEEN") {
+            let low = self.parse_additive()?;
+            self.expect_keyword("AND")?;
+            let high = self.parse_additive()?;
+            let (l1, low) = self.coerce(left.clone(), low);
+            let (l2, high) = self.coerce(left, high);
+            let pred = Predicate::and(Predicate::ge(l1, low), Predicate::le(l2, high));
+            return Ok(negate_if(pred, negated).into());
+        }
+        if negated {
+            return Err(format!(
+                "expected IN or BETWEEN after NOT, found {:?}",
+                self.peek()
+            ));
+        }
+
+        type Comparison = fn(Expression, Expression) -> Predicate;
+        let comparisons: [(&str, Comparison); 9] = [
+            ("<=>", |a, b| Predicate::not(Predicate::distinct(a, b))),
+            ("==", Predicate::eq),
+            ("=", Predicate::eq),
+            ("!=", Predicate::ne),
+            ("<>", Predicate::ne),
+            ("<=", Predicate::le),
+            (">=", Predicate::ge),
+            ("<", Predicate::lt),
+            (">", Predicate::gt),
+        ];
+        for (op, make_pred) in comparisons {
+            if self.eat_op(op) {
+                let right = self.parse_additive()?;
+                let (left, right) = self.coerce(left, right);
+                return Ok(make_pred(left, right).into());
+            }
+        }
+        Ok(left)
+    }
+
+    fn parse_additive(&mut self) -> ParseResult<Expression> {
+        let mut left = self.parse_multiplicative()?;
+        loop {
+            if self.eat_op("+") {
+                let right = self.parse_multiplicative()?;
+                let (l, r) = self.coerce(left, right);
+                left = l + r;
+            } else if self.eat_op("-") {
+                let right = self.parse_multiplicative()?;
+                let (l, r) = self.coerce(left, right);
+                left = l - r;
+            } else {
+                return Ok(left);
+            }
+        }
+    }
+
+    fn parse_multiplicative(&mut self) -> ParseResult<Expression> {
+        let mut left = self.parse_unary()?;
+        loop {
+            if self.eat_op("*") {
+                let right = self.parse_unary()?;
+                let (l, r) = self.coerce(left, right);
+                left = l * r;
+            } else if self.eat_op("/") {
+                let right = self.parse_unary()?;
+                let (l, r) = self.coerce(left, right);
+                left = l / r;
+            } else {
+                return Ok(left);
+            }
+        }
+    }
+
+    fn parse_unary(&mut self) -> ParseResult<Expression> {
+        if self.eat_op("-") {
+            return match self.parse_unary()? {
+                Expression::Literal(value) => negate_literal(value).map(Expression::Literal),
+                expr => {
+                    let (zero, expr) = self.coerce(Expression::literal(0), expr);
+                    Ok(zero - expr)
+                }
+            };
+        }
+        if self.eat_op("+") {
+            return self.parse_unary();
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> ParseResult<Expression> {
+        match self.next() {
+            Some(Token::Op("(")) => {
+                let expr = self.parse_or()?;
+                self.expect_op(")")?;
+                Ok(expr)
+            }
+            Some(Token::Number(number)) => parse_number(&number).map(Expression::Literal),
+            Some(Token::Str(value)) => Ok(Expression::literal(value)),
+            Some(Token::Ident(ident)) if ident.eq_ignore_ascii_case("TRUE") => {
+                Ok(Expression::literal(true))
+            }
+            Some(Token::Ident(ident)) if ident.eq_ignore_ascii_case("FALSE") => {
+                Ok(Expression::literal(false))
+            }
+            Some(Token::Ident(ident)) if ident.eq_ignore_ascii_case("NULL") => {
+                Ok(Expression::null_literal(DataType::BOOLEAN))
+            }
+            Some(Token::Ident(ident)) if self.peek() == Some(&Token::Op("(")) => {
+                self.parse_function(ident)
+            }
+            Some(Token::Ident(field) | Token::QuotedIdent(field)) => self.parse_column(field),
+            Some(token) => Err(format!("unexpected token {token:?}")),
+            None => Err("unexpected end of input".to_string()),
+        }
+    }
+
+    fn parse_function(&mut self, name: String) -> ParseResult<Expression> {
+        self.expect_op("(")?;
+        let (function, arg) = match name.to_ascii_uppercase().as_str() {
+            "CAST" => {
+                let arg = self.parse_or()?;
+                self.expect_keyword("AS")?;
+                (SqlFunction::Cast(self.parse_data_type()?), arg)
+            }
+            "TO_DATE" => (SqlFunction::Cast(DataType::DATE), self.parse_or()?),
+            "DATE_TRUNC" => {
+                let unit = self.parse_unit()?;
+                self.expect_op(",")?;
+                (SqlFunction::Truncate(unit), self.parse_or()?)
+            }
+            "TRUNC" => {
+                let arg = self.parse_or()?;
+                self.expect_op(",")?;
+                (SqlFunction::Truncate(self.parse_unit()?), arg)
+            }
+            _ => match DateTimeField::from_extract_function(&name) {
+                Some(field) => (SqlFunction::Extract(field), self.parse_or()?),
+                None => return Err(format!("unsupported function '{name}'")),
+            },
+        };
+        self.expect_op(")")?;
+        Ok(Expression::opaque(function, [arg]))
+    }
+
+    fn parse_unit(&mut self) -> ParseResult<DateTimeField> {
+        match self.next() {
+            Some(Token::Str(unit)) => DateTimeField::from_unit(&unit)
+                .ok_or_else(|| format!("unsupported truncation unit '{unit}'")),
+            token => Err(format!("expected a truncation unit but found {token:?}")),
+        }
+    }
+
+    fn parse_data_type(&mut self) -> ParseResult<DataType> {
+        let Some(Token::Ident(name)) = self.next() else {
+            return Err("expected a type name".to_string());
+        };
+        let data_type = match name.to_ascii_uppercase().as_str() {
+            "BOOLEAN" => DataType::BOOLEAN,
+            "TINYINT" | "BYTE" => DataType::BYTE,
+            "SMALLINT" | "SHORT" => DataType::SHORT,
+            "INT" | "INTEGER" => DataType::INTEGER,
+            "BIGINT" | "LONG" => DataType::LONG,
+            "FLOAT" | "REAL" => DataType::FLOAT,
+            "DOUBLE" => DataType::DOUBLE,
+            "STRING" => DataType::STRING,
+            "BINARY" => DataType::BINARY,
+            "DATE" => DataType::DATE,
+            "TIMESTAMP" => DataType::TIMESTAMP,
+            "TIMESTAMP_NTZ" => DataType::TIMESTAMP_NTZ,
+            "DECIMAL" | "DEC" | "NUMERIC" => {
+                let (mut precision, mut scale) = (10, 0);
+                if self.eat_op("(") {
+                    precision = self.parse_type_parameter()?;
+                    if self.eat_op(",") {
+                        scale = self.parse_type_parameter()?;
+                    }
+                    self.expect_op(")")?;
+                }
+                DataType::decimal(precision, scale).map_err(|err| err.to_string())?
+            }
+            _ => return Err(format!("unsupported type '{name}'")),
+        };
+        Ok(data_type)
+    }
+
+    fn parse_type_parameter(&mut self) -> ParseResult<u8> {
+        match self.next() {
+            Some(Token::Number(number)) => number
+                .parse()
+                .map_err(|_| format!("invalid type parameter {number}")),
+            token => Err(format!("expected a type parameter but found {token:?}")),
+        }
+    }
+
+    fn parse_column(&mut self, first: String) -> ParseResult<Expression> {
+        let mut path = vec![first];
+        while self.eat_op(".") {
+            match self.next() {
+                Some(Token::Ident(field) | Token::QuotedIdent(field)) => path.push(field),
+                token => return Err(format!("expected a field name but found {token:?}")),
+            }
+        }
+        match self.schema {
+            Some(schema) => resolve_column(schema, &path)
+                .map(|(name, _)| Expression::Column(name))
+                .ok_or_else(|| format!("column {} not found in schema", ColumnName::new(path))),
+            None => Ok(Expression::Column(ColumnName::new(path))),
+        }
+    }
+
+    /// If one side of a binary operation is a column of known primitive type and the other is a
+    /// literal, converts the literal to the column's type. Spark performs the same implicit cast,
+    /// and without it engines would be asked to compare e.g. an INT literal with a LONG column.
+    fn coerce(&self, left: Expression, right: Expression) -> (Expression, Expression) {
+        let Some(schema) = self.schema else {
+            return (left, right);
+        };
+        let column_type = |expr: &Expression| match expr {
+            Expression::Column(_) | Expression::Opaque(_) => match expression_type(schema, expr)? {
+                DataType::Primitive(ptype) => Some(ptype),
+                _ => None,
+            },
+            _ => None,
+        };
+        match (left, right) {
+            (Expression::Literal(value), right) => match column_type(&right) {
+                Some(ptype) => (Expression::Literal(coerce_literal(value, &ptype)), right),
+                None => (Expression::Literal(value), right),
+            },
+            (left, Expression::Literal(value)) => match column_type(&left) {
+                Some(ptype) => (left, Expression::Literal(coerce_literal(value, &ptype))),
+                None => (left, Expression::Literal(value)),
+            },
+            sides => sides,
+        }
+    }
+}
+
+fn junction(mut preds: Vec<Expression>, make: fn(Vec<Predicate>) -> Predicate) -> Expression {
+    if preds.len() == 1 {
+        return preds.remove(0);
+    }
+    make(preds.into_iter().map(Predicate::from_expr).collect()).into()
+}
+
+fn negate_if(pred: Predicate, negated: bool) -> Predicate {
+    if negated {
+        Predicate::not(pred)
+    } else {
+        pred
+    }
+}
+
+fn parse_number(number: &str) -> ParseResult<Scalar> {
+    let invalid = || format!("invalid numeric literal '{number}'");
+    let (digits, suffix) = match number.char_indices().last() {
+        Some((i, c)) if c.is_ascii_alphabetic() && !number.contains(['e', 'E']) => {
+            (&number[..i], Some(c.to_ascii_uppercase()))
+        }
+        _ => (number, None),
+    };
+    let scalar = match suffix {
+        Some('Y') => Scalar::Byte(digits.parse().map_err(|_| invalid())?),
+        Some('S') => Scalar::Short(digits.parse().map_err(|_| invalid())?),
+        Some('L') => Scalar::Long(digits.parse().map_err(|_| invalid())?),
+        Some('F') => Scalar::Float(digits.parse().map_err(|_| invalid())?),
+        Some('D') => Scalar::Double(digits.parse().map_err(|_| invalid())?),
+        Some(_) => return Err(invalid()),
+        None if digits.contains(['.', 'e', 'E']) => {
+            Scalar::Double(digits.parse().map_err(|_| invalid())?)
+        }
+        // like Spark, integral literals are INT when they fit and BIGINT otherwise
+        None => match digits.parse::<i32>() {
+            Ok(value) => Scalar::Integer(value),
+            Err(_) => Scalar::Long(digits.parse().map_err(|_| invalid())?),
+        },
+    };
+    Ok(scalar)
+}
+
+fn negate_literal(value: Scalar) -> ParseResult<Scalar> {
+    let overflow = || "numeric literal out of range".to_string();
+    let negated = match value {
+        Scalar::Byte(v) => Scalar::Byte(v.checked_neg().ok_or_else(overflow)?),
+        Scalar::Short(v) => Scalar::Short(v.checked_neg().ok_or_else(overflow)?),
+        Scalar::Integer(v) => Scalar::Integer(v.checked_neg().ok_or_else(overflow)?),
+        Scalar::Long(v) => Scalar::Long(v.checked_neg().ok_or_else(overflow)?),
+        Scalar::Float(v) => Scalar::Float(-v),
+        Scalar::Double(v) => Scalar::Double(-v),
+        other => return Err(format!("cannot negate literal {other}")),
+    };
+    Ok(negated)
+}
+
+/// Converts a parsed literal to the given type, if the conversion is lossless. Otherwise the
+/// literal is returned unchanged (and the engine will report any type mismatch).
+fn coerce_literal(value: Scalar, target: &PrimitiveType) -> Scalar {
+    use PrimitiveType::*;
+    let raw = match &value {
+        Scalar::Byte(v) => v.to_string(),
+        Scalar::Short(v) => v.to_string(),
+        Scalar::Integer(v) => v.to_string(),
+        Scalar::Long(v) => v.to_string(),
+        Scalar::Float(v) => v.to_string(),
+        Scalar::Double(v) => v.to_string(),
+        Scalar::String(v) if matches!(target, Date | Timestamp | TimestampNtz) => v.clone(),
+        _ => return value,
+    };
+    match target {
+        Byte | Short | Integer | Long | Float | Double | Decimal(_) | Date | Timestamp
+        | TimestampNtz => target.parse_scalar(&raw).unwrap_or(value),
+        _ => value,
+    }
+}
+
+/// Infers the type of a parsed expression, as far as kernel can tell: the types of columns,
+/// literals and [`SqlFunction`]s are known, and arithmetic takes the type of its operands.
+pub(crate) fn expression_type(schema: &StructType, expr: &Expression) -> Option<DataType> {
+    match expr {
+        Expression::Literal(value) => Some(value.data_type()),
+        Expression::Column(name) => resolve_column(schema, name.path()).map(|(_, t)| t.clone()),
+        Expression::Binary(binary) => {
+            expression_type(schema, &binary.left).or_else(|| expression_type(schema, &binary.right))
+        }
+        Expression::Opaque(OpaqueExpression { op, exprs }) => {
+            let function = op.any_ref().downcast_ref::<SqlFunction>()?;
+            let input_type = exprs.first().and_then(|arg| expression_type(schema, arg));
+            function.result_type(input_type.as_ref())
+        }
+        _ => None,
+    }
+}
+
+/// Resolves a column path against the schema, matching field names case-insensitively (as Spark
+/// does) and returning the column name as spelled in the schema along with its type.
+pub(crate) fn resolve_column<'a>(
+    schema: &'a StructType,
+    path: &[impl AsRef<str>],
+) -> Option<(ColumnName, &'a DataType)> {
+    let (first, rest) = path.split_first()?;
+    let mut field = find_field(schema, first.as_ref())?;
+    let mut fields = vec![field.name().clone()];
+    for name in rest {
+        let DataType::Struct(inner) = field.data_type() else {
+            return None;
+        };
+        field = find_field(inner, name.as_ref())?;
+        fields.push(field.name().clone());
+    }
+    Some((ColumnName::new(fields), field.data_type()))
+}
+
+fn find_field<'a>(schema: &'a StructType, name: &str) -> Option<&'a StructField> {
+    schema.field(name).or_else(|| {
+        schema
+            .fields()
+            .find(|f| f.name().eq_ignore_ascii_case(name))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expressions::column_expr;
+
+    fn test_schema() -> StructType {
+        StructType::new([
+            StructField::nullable("id", DataType::LONG),
+            StructField::nullable("name", DataType::STRING),
+            StructField::nullable("day", DataType::DATE),
+            StructField::nullable(
+                "nested",
+                StructType::new([StructField::nullable("Value", DataType::INTEGER)]),
+            ),
+        ])
+    }
+
+    #[test]
+    fn test_parse_predicates() {
+        let cases = [
+            ("x > 3", column_expr!("x").gt(Expression::literal(3))),
+            ("x <= 3", column_expr!("x").le(Expression::literal(3))),
+            ("x <> 'a'", column_expr!("x").ne(Expression::literal("a"))),
+            ("x IS NOT NULL", column_expr!("x").is_not_null()),
+            (
+                "a.b = 10L AND NOT c",
+                Predicate::and(
+                    column_expr!("a.b").eq(Expression::literal(10i64)),
+                    Predicate::not(Predicate::column(["c"])),
+                ),
+            ),
+            (
+                "`a.b` < -1.5 OR x IN (1, 2)",
+                Predicate::or(
+                    Expression::column(["a.b"]).lt(Expression::literal(-1.5)),
+                    Predicate::or(
+                        column_expr!("x").eq(Expression::literal(1)),
+                        column_expr!("x").eq(Expression::literal(2)),
+                    ),
+                ),
+            ),
+            (
+                "(x + 1) * 2 BETWEEN 0 AND 10",
+                Predicate::and(
+                    ((column_expr!("x") + Expression::literal(1)) * Expression::literal(2))
+                        .ge(Expression::literal(0)),
+                    ((column_expr!("x") + Expression::literal(1)) * Expression::literal(2))
+                        .le(Expression::literal(10)),
+                ),
+            ),
+        ];
+        for (sql, expected) in cases {
+            assert_eq!(parse_predicate(sql, None).unwrap(), expected, "{sql}");
+        }
+    }
+
+    #[test]
+    fn test_parse_with_schema() {
+        let schema = test_schema();
+        let pred = parse_predicate("ID > 3 and nested.value < 10", Some(&schema)).unwrap();
+        let expected = Predicate::and(
+            column_expr!("id").gt(Expression::literal(3i64)),
+            Expression::column(["nested", "Value"]).lt(Expression::literal(10)),
+        );
+        assert_eq!(pred, expected);
+
+        let pred = parse_predicate("day >= '2024-01-01'", Some(&schema)).unwrap();
+        assert_eq!(
+            pred,
+            column_expr!("day").ge(Expression::literal(Scalar::Date(19723)))
+        );
+
+        let err = parse_predicate("missing > 3", Some(&schema)).unwrap_err();
+        assert!(err.to_string().contains("column missing not found"));
+    }
+
+    #[test]
+    fn test_parse_functions() {
+        let schema = test_schema();
+        let cases = [
+            (
+                "CAST(id AS int)",
+                Expression::opaque(SqlFunction::Cast(DataType::INTEGER), [column_expr!("id")]),
+            ),
+            (
+                "cast(name as decimal(5, 2))",
+                Expression::opaque(
+                    SqlFunction::Cast(DataType::decimal(5, 2).unwrap()),
+                    [column_expr!("name")],
+                ),
+            ),
+            (
+                "to_date(name)",
+                Expression::opaque(SqlFunction::Cast(DataType::DATE), [column_expr!("name")]),
+            ),
+            (
+                "year(day)",
+                Expression::opaque(
+                    SqlFunction::Extract(DateTimeField::Year),
+                    [column_expr!("day")],
+                ),
+            ),
+            (
+                "date_trunc('MM', day)",
+                Expression::opaque(
+                    SqlFunction::Truncate(DateTimeField::Month),
+                    [column_expr!("day")],
+                ),
+            ),
+            (
+                "trunc(day, 'year')",
+                Expression::opaque(
+                    SqlFunction::Truncate(DateTimeField::Year),
+                    [column_expr!("day")],
+                ),
+            ),
+        ];
+        for (sql, expected) in cases {
+            let expr = parse_expression(sql, Some(&schema)).unwrap();
+            assert_eq!(expr, expected, "{sql}");
+        }
+
+        // literals compared with a function result are coerced to its type
+        let pred = parse_predicate("DATE_TRUNC('DAY', day) = '2024-01-01'", Some(&schema)).unwrap();
+        let expected = Expression::opaque(
+            SqlFunction::Truncate(DateTimeField::Day),
+            [column_expr!("day")],
+        )
+        .eq(Expression::literal(Scalar::Date(19723)));
+        assert_eq!(pred, expected);
+
+        let expr = parse_expression("MONTH(day) + 1", Some(&schema)).unwrap();
+        assert_eq!(expression_type(&schema, &expr), Some(DataType::INTEGER));
+        let expr = parse_expression("trunc(day, 'week')", Some(&schema)).unwrap();
+        assert_eq!(expression_type(&schema, &expr), Some(DataType::DATE));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for sql in [
+            "x >",
+            "upper(x) = 'A'",
+            "x = 'unterminated",
+            "x NOT LIKE 'a'",
+            "(x > 1",
+            "x > 1 y",
+            "x # 1",
+            "cast(x as varchar2)",
+            "cast(x)",
+            "date_trunc('fortnight', x)",
+            "year(x, y)",
+        ] {
+            let err = parse_predicate(sql, None).unwrap_err();
+            assert!(matches!(err, Error::Unsupported(_)), "{sql}: {err}");
+        }
+    }
+}
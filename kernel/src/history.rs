@@ -0,0 +1,373 @@
+//! Reading the history of a table, i.e. the [`HistoryEntry`] of each of its commits. See
+//! [`Snapshot::history`].
+//!
+//! [`Snapshot::history`]: crate::Snapshot::history
+
+use std::collections::HashMap;
+use std::num::NonZero;
+use std::slice;
+use std::sync::{Arc, LazyLock};
+
+use crate::actions::visitors::CommitInfoVisitor;
+use crate::actions::{CommitInfo, COMMIT_INFO_NAME};
+use crate::engine_data::{GetData, RowVisitor, TypedGetData as _};
+use crate::log_segment::LogSegment;
+use crate::path::{LogPathFileType, ParsedLogPath};
+use crate::schema::{
+    ColumnName, ColumnNamesAndTypes, DataType, MapType, SchemaRef, StructField, StructType,
+    ToSchema as _,
+};
+use crate::snapshot::Snapshot;
+use crate::utils::require;
+use crate::{DeltaResult, Engine, Error, Version};
+
+// The commit info fields read for the history. Besides the fields of [`CommitInfo`], this includes
+// fields other writers (e.g. Spark) record, which kernel itself never writes.
+static HISTORY_READ_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
+    let commit_info_schema = CommitInfo::to_schema();
+    let fields = commit_info_schema.fields().cloned().chain([
+        StructField::nullable("isolationLevel", DataType::STRING),
+        StructField::nullable(
+            "operationMetrics",
+            MapType::new(DataType::STRING, DataType::STRING, false),
+        ),
+    ]);
+    Arc::new(StructType::new([StructField::nullable(
+        COMMIT_INFO_NAME,
+        StructType::new(fields),
+    )]))
+});
+
+/// Information about a single commit of a table, as recorded in its `commitInfo` action. All
+/// fields but the version and timestamp are optional, since writers are free to omit them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoryEntry {
+    /// The table version of the commit.
+    pub version: Version,
+    /// The timestamp of the commit, in milliseconds since the epoch. This is the in-commit
+    /// timestamp if in-commit timestamps are enabled at the version of the commit, and the
+    /// modification time of the commit file otherwise.
+    pub timestamp: i64,
+    /// The operation of the commit, e.g. `WRITE` or `MERGE`.
+    pub operation: Option<String>,
+    /// Parameters of the operation, e.g. the predicate of a `DELETE`.
+    pub operation_parameters: HashMap<String, String>,
+    /// The engine which made the commit.
+    pub engine_info: Option<String>,
+    /// The isolation level the commit was made with, e.g. `Serializable`.
+    pub isolation_level: Option<String>,
+    /// Metrics of the operation, e.g. the number of files added.
+    pub operation_metrics: HashMap<String, String>,
+}
+
+/// Read the history of `snapshot`, newest first. See [`Snapshot::history`].
+pub(crate) fn read_history(
+    snapshot: &Snapshot,
+    engine: &dyn Engine,
+    limit: Option<usize>,
+) -> DeltaResult<Vec<HistoryEntry>> {
+    history_entries(snapshot, engine, limit)?.collect()
+}
+
+/// Like [`read_history`], but each commit is only read once its entry is consumed, so callers can
+/// stop early, e.g. upon reaching a timestamp.
+pub(crate) fn history_entries<'a>(
+    snapshot: &Snapshot,
+    engine: &'a dyn Engine,
+    limit: Option<usize>,
+) -> DeltaResult<impl Iterator<Item = DeltaResult<HistoryEntry>> + 'a> {
+    let limit = match limit.map(NonZero::new) {
+        Some(None) => return Ok(None.into_iter().flatten()),
+        Some(limit) => limit,
+        None => None,
+    };
+    // The staged commits of catalog-managed tables cannot be listed, so they are taken from the
+    // snapshot's log segment.
+    let log_tail = snapshot
+        .log_segment()
+        .ascending_commit_files
+        .iter()
+        .skip_while(|commit| commit.file_type != LogPathFileType::StagedCommit)
+        .cloned()
+        .collect();
+    // Only lists (and reads) the commits within the limit, and stops at the first missing commit.
+    let log_segment = LogSegment::for_timestamp_conversion(
+        engine.storage_handler().as_ref(),
+        snapshot.log_segment().log_root.clone(),
+        snapshot.version(),
+        limit,
+        log_tail,
+    )?;
+    let ict_enablement_version = snapshot
+        .table_configuration()
+        .in_commit_timestamp_enablement_version();
+    let entries = log_segment
+        .ascending_commit_files
+        .into_iter()
+        .rev()
+        .map(move |commit_file| read_history_entry(engine, &commit_file, ict_enablement_version));
+    Ok(Some(entries).into_iter().flatten())
+}
+
+fn read_history_entry(
+    engine: &dyn Engine,
+    commit_file: &ParsedLogPath,
+    ict_enablement_version: Option<Version>,
+) -> DeltaResult<HistoryEntry> {
+    let actions = engine.json_handler().read_json_files(
+        slice::from_ref(&commit_file.location),
+        HISTORY_READ_SCHEMA.clone(),
+        None,
+    )?;
+    let mut visitor = HistoryVisitor::default();
+    for actions in actions {
+        visitor.visit_rows_of(actions?.as_ref())?;
+        if visitor.entry.is_some() {
+            break;
+        }
+    }
+    let (commit_info, isolation_level, operation_metrics) = visitor.entry.unwrap_or_default();
+    // In-commit timestamps are only meaningful at the versions they are enabled at
+    let in_commit_timestamp = commit_info
+        .in_commit_timestamp
+        .filter(|_| ict_enablement_version.is_some_and(|v| v <= commit_file.version));
+    Ok(HistoryEntry {
+        version: commit_file.version,
+        timestamp: in_commit_timestamp.unwrap_or(commit_file.location.last_modified),
+        operation: commit_info.operation,
+        operation_parameters: commit_info.operation_parameters.unwrap_or_default(),
+        engine_info: commit_info.engine_info,
+        isolation_level,
+        operation_metrics: operation_metrics.unwrap_or_default(),
+    })
+}
+
+/// Extracts the commit info of a commit, along with its isolation level and operation metrics.
+#[derive(Default)]
+struct HistoryVisitor {
+    #[allow(clippy::type_complexity)]
+    entry: Option<(CommitInfo, Option<String>, Option<HashMap<String, String>>)>,
+}
+
+impl RowVisitor for HistoryVisitor {
+    fn selected_column_names_and_types(&self) -> (&'static [ColumnName], &'static [DataType]) {
+        static NAMES_AND_TYPES: LazyLock<ColumnNamesAndTypes> =
+            LazyLock::new(|| HISTORY_READ_SCHEMA.leaves(None));
+        NAMES_AND_TYPES.as_ref()
+    }
+    fn visit<'a>(&mut self, row_count: usize, getters: &[&'a dyn GetData<'a>]) -> DeltaResult<()> {
+        require!(
+            getters.len() == 9,
+            Error::InternalError(format!(
+                "Wrong number of HistoryVisitor getters: {}",
+                getters.len()
+            ))
+        );
+        for i in 0..row_count {
+            if let Some(commit_info) = CommitInfoVisitor::visit_commit_info(i, &getters[..7])? {
+                let isolation_level = getters[7].get_opt(i, "commitInfo.isolationLevel")?;
+                let operation_metrics = getters[8].get_opt(i, "commitInfo.operationMetrics")?;
+                self.entry = Some((commit_info, isolation_level, operation_metrics));
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::sync::Arc;
+
+    use object_store::memory::InMemory;
+    use serde_json::json;
+    use test_utils::add_commit;
+    use url::Url;
+
+    use super::*;
+    use crate::engine::default::executor::tokio::TokioBackgroundExecutor;
+    use crate::engine::default::DefaultEngine;
+    use crate::engine::sync::SyncEngine;
+
+    #[tokio::test]
+    async fn test_history() {
+        let store = Arc::new(InMemory::new());
+        let commits = [
+            vec![
+                json!({
+                    "commitInfo": {
+                        "timestamp": 1,
+                        "operation": "CREATE TABLE",
+                        "operationParameters": {"partitionBy": "[]"},
+                        "isolationLevel": "Serializable",
+                        "engineInfo": "test"
+                    }
+                }),
+                json!({"protocol": {"minReaderVersion": 1, "minWriterVersion": 2}}),
+                json!({
+                    "metaData": {
+                        "id": "test_id",
+                        "format": {"provider": "parquet", "options": {}},
+                        "schemaString": r#"{"type":"struct","fields":[{"name":"id","type":"integer","nullable":true,"metadata":{}}]}"#,
+                        "partitionColumns": [],
+                        "configuration": {},
+                        "createdTime": 1677811175819u64
+                    }
+                }),
+            ],
+            // a commit without commit info
+            vec![json!({
+                "add": {
+                    "path": "a.parquet",
+                    "partitionValues": {},
+                    "size": 1,
+                    "modificationTime": 0,
+                    "dataChange": true
+                }
+            })],
+            vec![json!({
+                "commitInfo": {
+                    "timestamp": 2,
+                    "inCommitTimestamp": 1234,
+                    "operation": "WRITE",
+                    "operationMetrics": {"numFiles": "0"}
+                }
+            })],
+        ];
+        for (version, commit) in commits.iter().enumerate() {
+            let commit = commit.iter().map(ToString::to_string).collect::<Vec<_>>();
+            add_commit(store.as_ref(), version as u64, commit.join("\n"))
+                .await
+                .unwrap();
+        }
+        let engine = DefaultEngine::new(store, Arc::new(TokioBackgroundExecutor::new()));
+        let table_root = Url::parse("memory:///").unwrap();
+        let snapshot = Snapshot::try_new(table_root.clone(), &engine, None).unwrap();
+
+        let history = snapshot.history(&engine, None).unwrap();
+        let versions: Vec<_> = history.iter().map(|entry| entry.version).collect();
+        assert_eq!(versions, [2, 1, 0]);
+
+        // in-commit timestamps are not enabled, so the commit's is ignored
+        let write = &history[0];
+        let commit = table_root
+            .join("_delta_log/00000000000000000002.json")
+            .unwrap();
+        let commit = engine.storage_handler().head(&commit).unwrap();
+        assert_eq!(write.timestamp, commit.last_modified);
+        assert_eq!(write.operation.as_deref(), Some("WRITE"));
+        assert_eq!(
+            write.operation_metrics,
+            HashMap::from([("numFiles".to_string(), "0".to_string())])
+        );
+
+        let add = &history[1];
+        assert_eq!(add.operation, None);
+        assert!(add.operation_parameters.is_empty());
+
+        let create = &history[2];
+        assert_eq!(create.operation.as_deref(), Some("CREATE TABLE"));
+        assert_eq!(create.engine_info.as_deref(), Some("test"));
+        assert_eq!(create.isolation_level.as_deref(), Some("Serializable"));
+        assert_eq!(
+            create.operation_parameters,
+            HashMap::from([("partitionBy".to_string(), "[]".to_string())])
+        );
+
+        // only the newest commits within the limit are returned
+        let history = snapshot.history(&engine, Some(2)).unwrap();
+        let versions: Vec<_> = history.iter().map(|entry| entry.version).collect();
+        assert_eq!(versions, [2, 1]);
+        assert!(snapshot.history(&engine, Some(0)).unwrap().is_empty());
+
+        // history of an older snapshot
+        let snapshot = Snapshot::try_new(Url::parse("memory:///").unwrap(), &engine, Some(1));
+        let history = snapshot.unwrap().history(&engine, None).unwrap();
+        let versions: Vec<_> = history.iter().map(|entry| entry.version).collect();
+        assert_eq!(versions, [1, 0]);
+    }
+
+    #[tokio::test]
+    async fn test_history_in_commit_timestamps() {
+        let store = Arc::new(InMemory::new());
+        let metadata = |configuration: serde_json::Value| {
+            json!({
+                "metaData": {
+                    "id": "test_id",
+                    "format": {"provider": "parquet", "options": {}},
+                    "schemaString": r#"{"type":"struct","fields":[{"name":"id","type":"integer","nullable":true,"metadata":{}}]}"#,
+                    "partitionColumns": [],
+                    "configuration": configuration,
+                    "createdTime": 1677811175819u64
+                }
+            })
+        };
+        let commit_info = |ict: i64| json!({"commitInfo": {"inCommitTimestamp": ict}});
+        let commits = [
+            vec![
+                commit_info(100),
+                json!({
+                    "protocol": {
+                        "minReaderVersion": 1,
+                        "minWriterVersion": 7,
+                        "writerFeatures": ["inCommitTimestamp"]
+                    }
+                }),
+                metadata(json!({})),
+            ],
+            // in-commit timestamps are enabled at version 1
+            vec![
+                commit_info(1000),
+                metadata(json!({
+                    "delta.enableInCommitTimestamps": "true",
+                    "delta.inCommitTimestampEnablementVersion": "1",
+                    "delta.inCommitTimestampEnablementTimestamp": "1000"
+                })),
+            ],
+            vec![commit_info(2000)],
+        ];
+        for (version, commit) in commits.iter().enumerate() {
+            let commit = commit.iter().map(ToString::to_string).collect::<Vec<_>>();
+            add_commit(store.as_ref(), version as u64, commit.join("\n"))
+                .await
+                .unwrap();
+        }
+        let engine = DefaultEngine::new(store, Arc::new(TokioBackgroundExecutor::new()));
+        let table_root = Url::parse("memory:///").unwrap();
+        let snapshot = Snapshot::try_new(table_root.clone(), &engine, None).unwrap();
+
+        let timestamps: Vec<_> = snapshot
+            .history(&engine, None)
+            .unwrap()
+            .iter()
+            .map(|entry| entry.timestamp)
+            .collect();
+        let commit = table_root
+            .join("_delta_log/00000000000000000000.json")
+            .unwrap();
+        let commit = engine.storage_handler().head(&commit).unwrap();
+        assert_eq!(timestamps, [2000, 1000, commit.last_modified]);
+    }
+
+    #[test]
+    fn test_history_with_cleaned_up_commits() {
+        // copy a table with a checkpoint at version 1, and clean up the commit of version 0
+        let source = PathBuf::from("./tests/data/app-txn-checkpoint/_delta_log/");
+        let dir = tempfile::tempdir().unwrap();
+        let log_dir = dir.path().join("_delta_log");
+        std::fs::create_dir(&log_dir).unwrap();
+        for file in std::fs::read_dir(&source).unwrap() {
+            let file = file.unwrap();
+            std::fs::copy(file.path(), log_dir.join(file.file_name())).unwrap();
+        }
+        std::fs::remove_file(log_dir.join("00000000000000000000.json")).unwrap();
+
+        let engine = SyncEngine::new();
+        let table_root = Url::from_directory_path(dir.path()).unwrap();
+        let snapshot = Snapshot::try_new(table_root, &engine, None).unwrap();
+        let history = snapshot.history(&engine, None).unwrap();
+        let versions: Vec<_> = history.iter().map(|entry| entry.version).collect();
+        assert_eq!(versions, [1]);
+    }
+}
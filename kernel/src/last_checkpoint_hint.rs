@@ -5,6 +5,7 @@ use crate::schema::Schema;
 use crate::{DeltaResult, Error, StorageHandler, Version};
 use delta_kernel_derive::internal_api;
 
+use bytes::Bytes;
 use serde::{Deserialize, Serialize};
 use tracing::warn;
 use url::Url;
@@ -50,7 +51,27 @@ impl LastCheckpointHint {
         log_root: &Url,
     ) -> DeltaResult<Option<LastCheckpointHint>> {
         let file_path = log_root.join(LAST_CHECKPOINT_FILE_NAME)?;
-        match storage.read_files(vec![(file_path, None)])?.next() {
+        Self::from_read_result(storage.read_files(vec![(file_path, None)])?.next())
+    }
+
+    /// Like [`Self::try_read`], but reads the file through an [`AsyncStorageHandler`].
+    ///
+    /// [`AsyncStorageHandler`]: crate::async_engine::AsyncStorageHandler
+    #[cfg(feature = "async-engine")]
+    pub(crate) async fn try_read_async(
+        storage: &dyn crate::async_engine::AsyncStorageHandler,
+        log_root: &Url,
+    ) -> DeltaResult<Option<LastCheckpointHint>> {
+        use futures::StreamExt as _;
+
+        let file_path = log_root.join(LAST_CHECKPOINT_FILE_NAME)?;
+        Self::from_read_result(storage.read_files(vec![(file_path, None)])?.next().await)
+    }
+
+    fn from_read_result(
+        result: Option<DeltaResult<Bytes>>,
+    ) -> DeltaResult<Option<LastCheckpointHint>> {
+        match result {
             Some(Ok(data)) => Ok(serde_json::from_slice(&data)
                 .inspect_err(|e| warn!("invalid _last_checkpoint JSON: {e}"))
                 .ok()),
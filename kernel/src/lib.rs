@@ -88,18 +88,24 @@ use url::Url;
 use self::schema::{DataType, SchemaRef};
 
 pub mod actions;
+#[cfg(feature = "async-engine")]
+pub mod async_engine;
 pub mod checkpoint;
 pub mod engine_data;
 pub mod error;
 pub mod expressions;
+pub mod history;
+pub mod log_cleanup;
 pub mod scan;
 pub mod schema;
 pub mod snapshot;
+pub mod streaming;
 pub mod table_changes;
 pub mod table_configuration;
 pub mod table_features;
 pub mod table_properties;
 pub mod transaction;
+pub mod vacuum;
 
 mod arrow_compat;
 #[cfg(any(feature = "arrow-55", feature = "arrow-56"))]
@@ -146,7 +152,9 @@ pub use delta_kernel_derive;
 pub use engine_data::{EngineData, RowVisitor};
 pub use error::{DeltaResult, Error};
 pub use expressions::{Expression, ExpressionRef, Predicate, PredicateRef};
-pub use snapshot::Snapshot;
+#[cfg(feature = "catalog-managed")]
+pub use path::LogPath;
+pub use snapshot::{Snapshot, SnapshotBuilder};
 
 use expressions::literal_expression_transform::LiteralExpressionTransform;
 use expressions::Scalar;
@@ -525,6 +533,51 @@ pub trait StorageHandler: AsAny {
         &self,
         files: Vec<FileSlice>,
     ) -> DeltaResult<Box<dyn Iterator<Item = DeltaResult<Bytes>>>>;
+
+    /// Write `data` to the file at `path`, creating any missing parent directories.
+    ///
+    /// If `overwrite` is false and the file already exists, the write must fail with
+    /// [`Error::FileAlreadyExists`], and the existing file must be left untouched. This check must
+    /// be atomic (put-if-absent), since kernel relies on it to detect conflicting writes.
+    ///
+    /// The default implementations of `put`, `head`, `delete` and `copy` return
+    /// [`Error::Unsupported`]. They are only needed by the APIs that manage files directly, such as
+    /// vacuum, log cleanup, restore and staged commits; reads and regular commits never call them.
+    fn put(&self, path: &Url, data: Bytes, overwrite: bool) -> DeltaResult<()> {
+        let _ = (data, overwrite);
+        Err(Error::unsupported(format!(
+            "put is not supported by this storage handler: {path}"
+        )))
+    }
+
+    /// Return the metadata of the file at `path`, or fail with [`Error::FileNotFound`] if it does
+    /// not exist.
+    ///
+    /// The default implementation returns [`Error::Unsupported`].
+    fn head(&self, path: &Url) -> DeltaResult<FileMeta> {
+        Err(Error::unsupported(format!(
+            "head is not supported by this storage handler: {path}"
+        )))
+    }
+
+    /// Delete the file at `path`. Deleting a file that does not exist is not an error.
+    ///
+    /// The default implementation returns [`Error::Unsupported`].
+    fn delete(&self, path: &Url) -> DeltaResult<()> {
+        Err(Error::unsupported(format!(
+            "delete is not supported by this storage handler: {path}"
+        )))
+    }
+
+    /// Copy the file at `from` to `to`, overwriting `to` if it already exists. Fails with
+    /// [`Error::FileNotFound`] if `from` does not exist.
+    ///
+    /// The default implementation returns [`Error::Unsupported`].
+    fn copy(&self, from: &Url, to: &Url) -> DeltaResult<()> {
+        Err(Error::unsupported(format!(
+            "copy is not supported by this storage handler: {from} -> {to}"
+        )))
+    }
 }
 
 /// Provides JSON handling functionality to Delta Kernel.
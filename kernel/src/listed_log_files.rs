@@ -17,10 +17,13 @@ use std::convert::identity;
 
 use crate::last_checkpoint_hint::LastCheckpointHint;
 use crate::path::{LogPathFileType, ParsedLogPath};
-use crate::{DeltaResult, Error, StorageHandler, Version};
+use crate::{DeltaResult, Error, FileMeta, StorageHandler, Version};
 
 use delta_kernel_derive::internal_api;
 
+#[cfg(feature = "async-engine")]
+use crate::async_engine::AsyncStorageHandler;
+
 use itertools::Itertools;
 use tracing::{info, warn};
 use url::Url;
@@ -52,20 +55,34 @@ fn list_log_files(
     start_version: impl Into<Option<Version>>,
     end_version: impl Into<Option<Version>>,
 ) -> DeltaResult<impl Iterator<Item = DeltaResult<ParsedLogPath>>> {
+    let start_from = listing_start(log_root, start_version)?;
+    Ok(parse_log_files(
+        storage.list_from(&start_from)?,
+        end_version,
+    ))
+}
+
+/// The path to start listing from to find log files with versions at or above `start_version`.
+fn listing_start(log_root: &Url, start_version: impl Into<Option<Version>>) -> DeltaResult<Url> {
     let start_version = start_version.into().unwrap_or(0);
-    let end_version = end_version.into();
     let version_prefix = format!("{start_version:020}");
-    let start_from = log_root.join(&version_prefix)?;
+    Ok(log_root.join(&version_prefix)?)
+}
 
-    Ok(storage
-        .list_from(&start_from)?
+/// Parses a (sorted) listing of the log into [`ParsedLogPath`]s, stopping after `end_version`.
+fn parse_log_files(
+    listing: impl Iterator<Item = DeltaResult<FileMeta>>,
+    end_version: impl Into<Option<Version>>,
+) -> impl Iterator<Item = DeltaResult<ParsedLogPath>> {
+    let end_version = end_version.into();
+    listing
         .map(|meta| ParsedLogPath::try_from(meta?))
         // TODO this filters out .crc files etc which start with "." - how do we want to use these kind of files?
         .filter_map_ok(identity)
         .take_while(move |path_res| match path_res {
             Ok(path) => end_version.is_none_or(|end_version| end_version >= path.version),
             Err(_) => true,
-        }))
+        })
 }
 
 /// Groups all checkpoint parts according to the checkpoint they belong to.
@@ -108,7 +125,7 @@ fn group_checkpoint_parts(parts: Vec<ParsedLogPath>) -> HashMap<u32, Vec<ParsedL
                     }
                 }
             }
-            Commit | CompactedCommit { .. } | Crc | Unknown => {}
+            Commit | StagedCommit | CompactedCommit { .. } | Crc | Unknown => {}
         }
     }
     checkpoints
@@ -196,7 +213,33 @@ impl ListedLogFiles {
         // on config at some point
 
         let log_files = list_log_files(storage, log_root, start_version, end_version)?;
+        Self::from_log_files(log_files, end_version)
+    }
 
+    /// Like [`Self::list`], but lists the log through an [`AsyncStorageHandler`].
+    ///
+    /// [`AsyncStorageHandler`]: crate::async_engine::AsyncStorageHandler
+    #[cfg(feature = "async-engine")]
+    pub(crate) async fn list_async(
+        storage: &dyn AsyncStorageHandler,
+        log_root: &Url,
+        start_version: Option<Version>,
+        end_version: Option<Version>,
+    ) -> DeltaResult<Self> {
+        use futures::StreamExt as _;
+
+        let start_from = listing_start(log_root, start_version)?;
+        let listing: Vec<_> = storage.list_from(&start_from)?.collect().await;
+        let log_files = parse_log_files(listing.into_iter(), end_version);
+        Self::from_log_files(log_files, end_version)
+    }
+
+    /// Builds a [`ListedLogFiles`] from a sorted iterator of log files, keeping only the most
+    /// recent complete checkpoint and the commits (and compactions) after it.
+    pub(crate) fn from_log_files(
+        log_files: impl Iterator<Item = DeltaResult<ParsedLogPath>>,
+        end_version: Option<Version>,
+    ) -> DeltaResult<Self> {
         log_files.process_results(|iter| {
             let mut ascending_commit_files = Vec::with_capacity(10);
             let mut ascending_compaction_files = Vec::with_capacity(2);
@@ -212,6 +255,8 @@ impl ListedLogFiles {
                     use LogPathFileType::*;
                     match file.file_type {
                         Commit => ascending_commit_files.push(file),
+                        // staged commits are only used when the catalog provides them in a log tail
+                        StagedCommit => (),
                         CompactedCommit { hi } if end_version.is_none_or(|end| hi <= end) => {
                             ascending_compaction_files.push(file);
                         }
@@ -256,6 +301,31 @@ impl ListedLogFiles {
         })?
     }
 
+    /// Merge a log tail of commits (e.g. the ratified commits of a catalog-managed table, which may
+    /// not have been published yet) into the listed files. The log tail takes precedence: listed
+    /// commits are only kept below the first version of the log tail, and so are listed compaction
+    /// files, which must not cover any commit of the log tail. Commits after `end_version` are
+    /// ignored.
+    pub(crate) fn with_log_tail(
+        mut self,
+        log_tail: Vec<ParsedLogPath>,
+        end_version: Option<Version>,
+    ) -> Self {
+        if let Some(first) = log_tail.first() {
+            self.ascending_commit_files
+                .retain(|commit| commit.version < first.version);
+            self.ascending_compaction_files.retain(|compaction| {
+                !matches!(compaction.file_type, LogPathFileType::CompactedCommit { hi } if hi >= first.version)
+            });
+        }
+        self.ascending_commit_files.extend(
+            log_tail
+                .into_iter()
+                .filter(|commit| end_version.is_none_or(|end| commit.version <= end)),
+        );
+        self
+    }
+
     /// List all commit and checkpoint files after the provided checkpoint. It is guaranteed that all
     /// the returned [`ParsedLogPath`]s will have a version less than or equal to the `end_version`.
     /// See [`list_log_files_with_version`] for details on the return type.
@@ -271,8 +341,36 @@ impl ListedLogFiles {
             Some(checkpoint_metadata.version),
             end_version,
         )?;
+        listed_files.validate_checkpoint_hint(checkpoint_metadata)
+    }
+
+    /// Like [`Self::list_with_checkpoint_hint`], but lists the log through an
+    /// [`AsyncStorageHandler`].
+    ///
+    /// [`AsyncStorageHandler`]: crate::async_engine::AsyncStorageHandler
+    #[cfg(feature = "async-engine")]
+    pub(crate) async fn list_with_checkpoint_hint_async(
+        checkpoint_metadata: &LastCheckpointHint,
+        storage: &dyn AsyncStorageHandler,
+        log_root: &Url,
+        end_version: Option<Version>,
+    ) -> DeltaResult<Self> {
+        let listed_files = Self::list_async(
+            storage,
+            log_root,
+            Some(checkpoint_metadata.version),
+            end_version,
+        )
+        .await?;
+        listed_files.validate_checkpoint_hint(checkpoint_metadata)
+    }
 
-        let Some(latest_checkpoint) = listed_files.checkpoint_parts.last() else {
+    // Checks the listed checkpoint against the `_last_checkpoint` hint the listing started from.
+    fn validate_checkpoint_hint(
+        self,
+        checkpoint_metadata: &LastCheckpointHint,
+    ) -> DeltaResult<Self> {
+        let Some(latest_checkpoint) = self.checkpoint_parts.last() else {
             // TODO: We could potentially recover here
             return Err(Error::invalid_checkpoint(
                 "Had a _last_checkpoint hint but didn't find any checkpoints",
@@ -284,13 +382,13 @@ impl ListedLogFiles {
             checkpoint_metadata.version,
             latest_checkpoint.version
         );
-        } else if listed_files.checkpoint_parts.len() != checkpoint_metadata.parts.unwrap_or(1) {
+        } else if self.checkpoint_parts.len() != checkpoint_metadata.parts.unwrap_or(1) {
             return Err(Error::InvalidCheckpoint(format!(
                 "_last_checkpoint indicated that checkpoint should have {} parts, but it has {}",
                 checkpoint_metadata.parts.unwrap_or(1),
-                listed_files.checkpoint_parts.len()
+                self.checkpoint_parts.len()
             )));
         }
-        Ok(listed_files)
+        Ok(self)
     }
 }
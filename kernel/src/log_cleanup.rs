@@ -0,0 +1,380 @@
+//! This module implements the API for cleaning up expired files in the `_delta_log` directory.
+//!
+//! The entry point for this API is [`Snapshot::log_cleanup`].
+//!
+//! Commits, checkpoints, checksum (CRC) files, log compaction files and V2 checkpoint sidecars are
+//! expired once they are older than the table's `delta.logRetentionDuration` (30 days if not set).
+//! Expired files are only deleted if they are not needed to reconstruct any version of the table at
+//! or after the newest complete checkpoint preceding that cutoff, so that time travel keeps
+//! working for all retained versions. Nothing is deleted if the table sets
+//! `delta.enableExpiredLogCleanup` to `false`.
+//!
+//! ## Usage
+//!
+//! ```no_run
+//! # use std::sync::Arc;
+//! # use delta_kernel::{Engine, Error, Snapshot};
+//! let engine: &dyn Engine = todo!(); /* create engine instance */
+//! let snapshot = Arc::new(Snapshot::try_from_uri("./tests/data/app-txn-checkpoint", engine, None)?);
+//!
+//! let plan = snapshot.log_cleanup()?.plan(engine)?;
+//! let num_deleted = plan.execute(engine)?;
+//! # Ok::<_, Error>(())
+//! ```
+use std::collections::{BTreeMap, HashSet};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use itertools::Itertools;
+use tracing::debug;
+use url::Url;
+
+use crate::actions::visitors::SidecarVisitor;
+use crate::actions::{get_log_schema, SIDECAR_NAME};
+use crate::engine_data::RowVisitor as _;
+use crate::path::{LogPathFileType, ParsedLogPath};
+use crate::snapshot::Snapshot;
+use crate::table_features::ReaderFeature;
+use crate::utils::retention_cutoff_timestamp;
+use crate::{DeltaResult, Engine, Error, FileMeta, Version};
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+/// The default retention period for log files in seconds.
+/// This is set to 30 days, which is the default in delta-spark.
+const DEFAULT_LOG_RETENTION_SECS: u64 = 30 * SECONDS_PER_DAY;
+
+/// Plans the deletion of expired files from the `_delta_log` directory of a table. Create one with
+/// [`Snapshot::log_cleanup`].
+#[derive(Debug)]
+pub struct LogCleanupPlanner {
+    snapshot: Arc<Snapshot>,
+}
+
+impl LogCleanupPlanner {
+    pub(crate) fn try_new(snapshot: Arc<Snapshot>) -> DeltaResult<Self> {
+        // Deleting log files is a write, so it is subject to the same protocol checks.
+        snapshot.table_configuration().ensure_write_supported()?;
+        Ok(Self { snapshot })
+    }
+
+    /// Compute the expired log files to delete. The plan is empty if the table disables expired
+    /// log cleanup.
+    ///
+    /// Note that this method lists the `_delta_log` directory, and reads the checkpoints that are
+    /// retained to find the sidecar files they reference.
+    pub fn plan(&self, engine: &dyn Engine) -> DeltaResult<LogCleanupPlan> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| Error::generic(format!("Failed to calculate system time: {e}")))?;
+        self.plan_with_time(engine, now)
+    }
+
+    // Factored out of `plan` to allow testing with an injectable time.
+    fn plan_with_time(&self, engine: &dyn Engine, now: Duration) -> DeltaResult<LogCleanupPlan> {
+        let table_properties = self.snapshot.table_properties();
+        if table_properties.enable_expired_log_cleanup == Some(false) {
+            return Ok(LogCleanupPlan { files: vec![] });
+        }
+        let retention = table_properties
+            .log_retention_duration
+            .unwrap_or_else(|| Duration::from_secs(DEFAULT_LOG_RETENTION_SECS));
+        let cutoff_timestamp = retention_cutoff_timestamp(now, retention)?;
+
+        let log_root = &self.snapshot.log_segment().log_root;
+        let log_files: Vec<ParsedLogPath> = engine
+            .storage_handler()
+            .list_from(log_root)?
+            .filter_map(|file| file.and_then(ParsedLogPath::try_from).transpose())
+            .try_collect()?;
+
+        // Versions before the newest complete checkpoint preceding the cutoff are only needed to
+        // reconstruct versions that are themselves older than the cutoff.
+        let Some(checkpoint_version) =
+            expired_checkpoint_version(&log_files, self.snapshot.version(), cutoff_timestamp)
+        else {
+            debug!("No checkpoint precedes the log retention cutoff, nothing to clean up");
+            return Ok(LogCleanupPlan { files: vec![] });
+        };
+
+        let mut files = vec![];
+        let mut retained_checkpoints = vec![];
+        for log_file in log_files {
+            let last_version = match log_file.file_type {
+                LogPathFileType::CompactedCommit { hi } => hi,
+                // never delete files we don't know about, nor staged commits (owned by the catalog)
+                LogPathFileType::Unknown | LogPathFileType::StagedCommit => continue,
+                _ => log_file.version,
+            };
+            if last_version >= checkpoint_version {
+                if log_file.is_checkpoint() {
+                    retained_checkpoints.push(log_file);
+                }
+            } else if log_file.location.last_modified < cutoff_timestamp {
+                debug!("Log cleanup will delete {}", log_file.location.location);
+                files.push(log_file.location);
+            }
+        }
+
+        // only V2 checkpoints can have sidecars
+        if self
+            .snapshot
+            .table_configuration()
+            .protocol()
+            .has_reader_feature(&ReaderFeature::V2Checkpoint)
+        {
+            let referenced = referenced_sidecars(engine, log_root, &retained_checkpoints)?;
+            let sidecars = engine
+                .storage_handler()
+                .list_from(&log_root.join("_sidecars/")?)?;
+            for sidecar in sidecars {
+                let sidecar = sidecar?;
+                if !referenced.contains(&sidecar.location)
+                    && sidecar.last_modified < cutoff_timestamp
+                {
+                    debug!("Log cleanup will delete {}", sidecar.location);
+                    files.push(sidecar);
+                }
+            }
+        }
+        Ok(LogCleanupPlan { files })
+    }
+}
+
+/// The expired log files to delete, as computed by [`LogCleanupPlanner::plan`].
+#[derive(Debug)]
+pub struct LogCleanupPlan {
+    files: Vec<FileMeta>,
+}
+
+impl LogCleanupPlan {
+    /// The files to delete.
+    pub fn files(&self) -> &[FileMeta] {
+        &self.files
+    }
+
+    /// Delete the files of this plan with the engine's [`StorageHandler`], in the order of
+    /// [`LogCleanupPlan::files`]. Returns the number of deleted files.
+    ///
+    /// [`StorageHandler`]: crate::StorageHandler
+    pub fn execute(self, engine: &dyn Engine) -> DeltaResult<usize> {
+        let storage = engine.storage_handler();
+        self.files
+            .iter()
+            .try_for_each(|file| storage.delete(&file.location))?;
+        Ok(self.files.len())
+    }
+}
+
+// Returns the version of the newest complete checkpoint at or before `max_version` whose files were
+// all written before `cutoff_timestamp`.
+fn expired_checkpoint_version(
+    log_files: &[ParsedLogPath],
+    max_version: Version,
+    cutoff_timestamp: i64,
+) -> Option<Version> {
+    // (version, num_parts) -> part numbers, where single-file checkpoints have a single part
+    let mut checkpoints: BTreeMap<(Version, u32), HashSet<u32>> = BTreeMap::new();
+    for log_file in log_files {
+        if log_file.version > max_version
+            || !log_file.is_checkpoint()
+            || log_file.location.last_modified >= cutoff_timestamp
+        {
+            continue;
+        }
+        let (part_num, num_parts) = match log_file.file_type {
+            LogPathFileType::MultiPartCheckpoint {
+                part_num,
+                num_parts,
+            } => (part_num, num_parts),
+            _ => (1, 1),
+        };
+        checkpoints
+            .entry((log_file.version, num_parts))
+            .or_default()
+            .insert(part_num);
+    }
+    checkpoints
+        .into_iter()
+        .rev()
+        .find(|((_, num_parts), parts)| parts.len() == *num_parts as usize)
+        .map(|((version, _), _)| version)
+}
+
+// Collects the locations of the sidecar files referenced by the given checkpoints. Only single-file
+// checkpoints can reference sidecars.
+fn referenced_sidecars(
+    engine: &dyn Engine,
+    log_root: &Url,
+    checkpoints: &[ParsedLogPath],
+) -> DeltaResult<HashSet<Url>> {
+    let schema = get_log_schema().project(&[SIDECAR_NAME])?;
+    let mut visitor = SidecarVisitor::default();
+    for checkpoint in checkpoints {
+        if matches!(
+            checkpoint.file_type,
+            LogPathFileType::MultiPartCheckpoint { .. }
+        ) {
+            continue;
+        }
+        let files = [checkpoint.location.clone()];
+        let batches = match checkpoint.extension.as_str() {
+            "json" => engine
+                .json_handler()
+                .read_json_files(&files, schema.clone(), None)?,
+            _ => engine
+                .parquet_handler()
+                .read_parquet_files(&files, schema.clone(), None)?,
+        };
+        for batch in batches {
+            visitor.visit_rows_of(batch?.as_ref())?;
+        }
+    }
+    visitor
+        .sidecars
+        .iter()
+        .map(|sidecar| Ok(sidecar.to_filemeta(log_root)?.location))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use object_store::memory::InMemory;
+    use object_store::path::Path;
+    use object_store::ObjectStore;
+    use serde_json::json;
+    use test_utils::{add_commit, delta_path_for_version};
+
+    use crate::engine::default::executor::tokio::TokioBackgroundExecutor;
+    use crate::engine::default::DefaultEngine;
+
+    fn now() -> Duration {
+        SystemTime::now().duration_since(UNIX_EPOCH).unwrap()
+    }
+
+    // A table with commits 0 to 4, a checkpoint at version 2, an incomplete multi-part checkpoint
+    // at version 3 and CRC files for every version.
+    async fn setup_table(
+        configuration: serde_json::Value,
+    ) -> (Arc<Snapshot>, DefaultEngine<TokioBackgroundExecutor>) {
+        let store = Arc::new(InMemory::new());
+        let commit0 = [
+            json!({"protocol": {"minReaderVersion": 1, "minWriterVersion": 2}}),
+            json!({
+                "metaData": {
+                    "id": "test_id",
+                    "format": {"provider": "parquet", "options": {}},
+                    "schemaString": r#"{"type":"struct","fields":[{"name":"id","type":"integer","nullable":true,"metadata":{}}]}"#,
+                    "partitionColumns": [],
+                    "configuration": configuration,
+                    "createdTime": 1677811175819u64
+                }
+            }),
+        ];
+        add_commit(store.as_ref(), 0, commit0.map(|a| a.to_string()).join("\n"))
+            .await
+            .unwrap();
+        for version in 1..5 {
+            let commit = json!({"commitInfo": {"timestamp": 0}});
+            add_commit(store.as_ref(), version, commit.to_string())
+                .await
+                .unwrap();
+        }
+
+        let engine = DefaultEngine::new(store.clone(), Arc::new(TokioBackgroundExecutor::new()));
+        let table_root = Url::parse("memory:///").unwrap();
+        let snapshot = Arc::new(Snapshot::try_new(table_root, &engine, None).unwrap());
+
+        // the planner only looks at file names, so the checkpoints can be empty
+        let mut paths: Vec<_> = (0..5)
+            .map(|version| delta_path_for_version(version, "crc"))
+            .collect();
+        paths.push(delta_path_for_version(2, "checkpoint.parquet"));
+        paths.push(delta_path_for_version(
+            3,
+            "checkpoint.0000000001.0000000002.parquet",
+        ));
+        paths.push(Path::from("_delta_log/_last_checkpoint"));
+        for path in paths {
+            store.put(&path, vec![].into()).await.unwrap();
+        }
+        (snapshot, engine)
+    }
+
+    fn planned_files(plan: &LogCleanupPlan) -> Vec<&str> {
+        let mut files: Vec<_> = plan
+            .files()
+            .iter()
+            .map(|f| f.location.path().trim_start_matches("/_delta_log/"))
+            .collect();
+        files.sort();
+        files
+    }
+
+    #[tokio::test]
+    async fn test_log_cleanup() {
+        let (snapshot, engine) = setup_table(json!({})).await;
+        let planner = snapshot.log_cleanup().unwrap();
+
+        // nothing is older than the default retention (30 days)
+        assert!(planner.plan(&engine).unwrap().files().is_empty());
+
+        // 31 days from now, everything before the checkpoint at version 2 can be deleted (the
+        // checkpoint at version 3 is incomplete)
+        let plan = planner
+            .plan_with_time(&engine, now() + Duration::from_secs(31 * SECONDS_PER_DAY))
+            .unwrap();
+        assert_eq!(
+            planned_files(&plan),
+            vec![
+                "00000000000000000000.crc",
+                "00000000000000000000.json",
+                "00000000000000000001.crc",
+                "00000000000000000001.json",
+            ]
+        );
+        assert_eq!(plan.execute(&engine).unwrap(), 4);
+
+        // the retained versions are still there
+        let plan = planner
+            .plan_with_time(&engine, now() + Duration::from_secs(31 * SECONDS_PER_DAY))
+            .unwrap();
+        assert!(plan.files().is_empty());
+        let log_root = Url::parse("memory:///_delta_log/").unwrap();
+        let storage = engine.storage_handler();
+        assert!(storage
+            .head(&log_root.join("00000000000000000001.json").unwrap())
+            .is_err());
+        assert!(storage
+            .head(&log_root.join("00000000000000000002.json").unwrap())
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_log_cleanup_retention() {
+        let (snapshot, engine) =
+            setup_table(json!({"delta.logRetentionDuration": "interval 2 days"})).await;
+        let planner = snapshot.log_cleanup().unwrap();
+        let plan = planner
+            .plan_with_time(&engine, now() + Duration::from_secs(SECONDS_PER_DAY))
+            .unwrap();
+        assert!(plan.files().is_empty());
+        let plan = planner
+            .plan_with_time(&engine, now() + Duration::from_secs(3 * SECONDS_PER_DAY))
+            .unwrap();
+        assert_eq!(plan.files().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_log_cleanup_disabled() {
+        let (snapshot, engine) =
+            setup_table(json!({"delta.enableExpiredLogCleanup": "false"})).await;
+        let plan = snapshot
+            .log_cleanup()
+            .unwrap()
+            .plan_with_time(&engine, now() + Duration::from_secs(365 * SECONDS_PER_DAY))
+            .unwrap();
+        assert!(plan.files().is_empty());
+    }
+}
@@ -321,6 +321,27 @@ pub(crate) trait LogReplayProcessor: Sized {
             })
     }
 
+    /// Like [`Self::process_actions_iter`], but applies the processor to a [`Stream`] of action
+    /// batches.
+    ///
+    /// [`Stream`]: futures::Stream
+    #[cfg(feature = "async-engine")]
+    fn process_actions_stream(
+        mut self,
+        action_stream: impl futures::Stream<Item = DeltaResult<ActionsBatch>>,
+    ) -> impl futures::Stream<Item = DeltaResult<Self::Output>> {
+        use futures::StreamExt as _;
+
+        action_stream
+            .map(move |actions_batch| self.process_actions_batch(actions_batch?))
+            .filter(|res| {
+                let keep = res
+                    .as_ref()
+                    .map_or(true, |result| result.has_selected_rows());
+                futures::future::ready(keep)
+            })
+    }
+
     /// Builds the initial selection vector for the action batch, used to filter out rows that
     /// are not relevant to the current processor's purpose (e.g., checkpointing, scanning).
     /// This method performs a first pass of filtering using an optional [`DataSkippingFilter`].
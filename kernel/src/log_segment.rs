@@ -21,6 +21,11 @@ use delta_kernel_derive::internal_api;
 
 use crate::listed_log_files::*;
 
+#[cfg(feature = "async-engine")]
+use crate::async_engine::{AsyncEngine, AsyncStorageHandler};
+#[cfg(feature = "async-engine")]
+use futures::{Stream, StreamExt as _, TryStreamExt as _};
+
 use itertools::Itertools;
 use tracing::{debug, warn};
 use url::Url;
@@ -135,6 +140,10 @@ impl LogSegment {
     /// The options for constructing a LogSegment for Snapshot are as follows:
     /// - `checkpoint_hint`: a `LastCheckpointHint` to start the log segment from (e.g. from reading the `last_checkpoint` file).
     /// - `time_travel_version`: The version of the log that the Snapshot will be at.
+    /// - `log_tail`: commits to merge into the listed log files, see
+    ///   `ListedLogFiles::with_log_tail`. This is how the ratified (but possibly not yet
+    ///   published) commits of catalog-managed tables become part of a snapshot. The log tail must
+    ///   consist of contiguous commits in ascending order.
     ///
     /// [`Snapshot`]: crate::snapshot::Snapshot
     #[internal_api]
@@ -143,8 +152,10 @@ impl LogSegment {
         log_root: Url,
         checkpoint_hint: impl Into<Option<LastCheckpointHint>>,
         time_travel_version: impl Into<Option<Version>>,
+        log_tail: Vec<ParsedLogPath>,
     ) -> DeltaResult<Self> {
         let time_travel_version = time_travel_version.into();
+        validate_log_tail(&log_tail)?;
 
         let listed_files = match (checkpoint_hint.into(), time_travel_version) {
             (Some(cp), None) => {
@@ -160,6 +171,38 @@ impl LogSegment {
             }
             _ => ListedLogFiles::list(storage, &log_root, None, time_travel_version)?,
         };
+        let listed_files = listed_files.with_log_tail(log_tail, time_travel_version);
+
+        LogSegment::try_new(listed_files, log_root, time_travel_version)
+    }
+
+    /// Like [`Self::for_snapshot`], but lists the log through an [`AsyncStorageHandler`].
+    #[cfg(feature = "async-engine")]
+    pub(crate) async fn for_snapshot_async(
+        storage: &dyn AsyncStorageHandler,
+        log_root: Url,
+        checkpoint_hint: Option<LastCheckpointHint>,
+        time_travel_version: Option<Version>,
+        log_tail: Vec<ParsedLogPath>,
+    ) -> DeltaResult<Self> {
+        validate_log_tail(&log_tail)?;
+        let listed_files = match (checkpoint_hint, time_travel_version) {
+            (Some(cp), None) => {
+                ListedLogFiles::list_with_checkpoint_hint_async(&cp, storage, &log_root, None)
+                    .await?
+            }
+            (Some(cp), Some(end_version)) if cp.version <= end_version => {
+                ListedLogFiles::list_with_checkpoint_hint_async(
+                    &cp,
+                    storage,
+                    &log_root,
+                    Some(end_version),
+                )
+                .await?
+            }
+            _ => ListedLogFiles::list_async(storage, &log_root, None, time_travel_version).await?,
+        };
+        let listed_files = listed_files.with_log_tail(log_tail, time_travel_version);
 
         LogSegment::try_new(listed_files, log_root, time_travel_version)
     }
@@ -203,11 +246,11 @@ impl LogSegment {
         LogSegment::try_new(listed_files, log_root, end_version)
     }
 
-    #[allow(unused)]
     /// Constructs a [`LogSegment`] to be used for timestamp conversion. This [`LogSegment`] will
     /// consist only of contiguous commit files up to `end_version` (inclusive). If present,
     /// `limit` specifies the maximum length of the returned log segment. The log segment may be
-    /// shorter than `limit` if there are missing commits.
+    /// shorter than `limit` if there are missing commits. The `log_tail` of (e.g. staged) commits is
+    /// merged into the listed commits, see [`ListedLogFiles::with_log_tail`].
     ///
     // This lists all files starting from `end-limit` if `limit` is defined. For large tables,
     // listing with a `limit` can be a significant speedup over listing _all_ the files in the log.
@@ -216,6 +259,7 @@ impl LogSegment {
         log_root: Url,
         end_version: Version,
         limit: Option<NonZero<usize>>,
+        log_tail: Vec<ParsedLogPath>,
     ) -> DeltaResult<Self> {
         // Compute the version to start listing from.
         let start_from = limit
@@ -229,8 +273,13 @@ impl LogSegment {
 
         // this is a list of commits with possible gaps, we want to take the latest contiguous
         // chunk of commits
+        let log_tail = log_tail
+            .into_iter()
+            .filter(|commit| start_from.is_none_or(|start| start <= commit.version))
+            .collect();
         let mut listed_commits =
-            ListedLogFiles::list_commits(storage, &log_root, start_from, Some(end_version))?;
+            ListedLogFiles::list_commits(storage, &log_root, start_from, Some(end_version))?
+                .with_log_tail(log_tail, Some(end_version));
 
         // remove gaps - return latest contiguous chunk of commits
         let commits = &mut listed_commits.ascending_commit_files;
@@ -283,6 +332,32 @@ impl LogSegment {
         Ok(commit_stream.chain(checkpoint_stream))
     }
 
+    /// Like [`Self::read_actions`], but reads the log files through the async handlers of an
+    /// [`AsyncEngine`], returning a [`Stream`] of [`ActionsBatch`]es.
+    #[cfg(feature = "async-engine")]
+    pub(crate) fn read_actions_async(
+        &self,
+        engine: &dyn AsyncEngine,
+        commit_read_schema: SchemaRef,
+        checkpoint_read_schema: SchemaRef,
+        meta_predicate: Option<PredicateRef>,
+    ) -> DeltaResult<impl Stream<Item = DeltaResult<ActionsBatch>> + Send> {
+        let commits_and_compactions = self.find_commit_cover();
+        let commit_stream = engine
+            .async_json_handler()
+            .read_json_files(
+                &commits_and_compactions,
+                commit_read_schema,
+                meta_predicate.clone(),
+            )?
+            .map_ok(|batch| ActionsBatch::new(batch, true));
+
+        let checkpoint_stream =
+            self.create_checkpoint_stream_async(engine, checkpoint_read_schema, meta_predicate)?;
+
+        Ok(commit_stream.chain(checkpoint_stream))
+    }
+
     /// find a minimal set to cover the range of commits we want. This is greedy so not always
     /// optimal, but we assume there are rarely overlapping compactions so this is okay. NB: This
     /// returns files is DESCENDING ORDER, as that's what `replay` expects. This function assumes
@@ -439,21 +514,13 @@ impl LogSegment {
         checkpoint_read_schema: SchemaRef,
         meta_predicate: Option<PredicateRef>,
     ) -> DeltaResult<Option<impl Iterator<Item = DeltaResult<Box<dyn EngineData>>> + Send>> {
-        // Visit the rows of the checkpoint batch to extract sidecar file references
-        let mut visitor = SidecarVisitor::default();
-        visitor.visit_rows_of(batch)?;
+        let sidecar_files = Self::sidecar_files(&log_root, batch)?;
 
         // If there are no sidecar files, return early
-        if visitor.sidecars.is_empty() {
+        if sidecar_files.is_empty() {
             return Ok(None);
         }
 
-        let sidecar_files: Vec<_> = visitor
-            .sidecars
-            .iter()
-            .map(|sidecar| sidecar.to_filemeta(&log_root))
-            .try_collect()?;
-
         // Read the sidecar files and return an iterator of sidecar file batches
         Ok(Some(parquet_handler.read_parquet_files(
             &sidecar_files,
@@ -462,6 +529,98 @@ impl LogSegment {
         )?))
     }
 
+    /// Extracts the sidecar files referenced by the given checkpoint batch.
+    fn sidecar_files(log_root: &Url, batch: &dyn EngineData) -> DeltaResult<Vec<FileMeta>> {
+        // Visit the rows of the checkpoint batch to extract sidecar file references
+        let mut visitor = SidecarVisitor::default();
+        visitor.visit_rows_of(batch)?;
+        visitor
+            .sidecars
+            .iter()
+            .map(|sidecar| sidecar.to_filemeta(log_root))
+            .try_collect()
+    }
+
+    /// Like [`Self::create_checkpoint_stream`], but reads the checkpoint (and its sidecars)
+    /// through the async handlers of an [`AsyncEngine`].
+    #[cfg(feature = "async-engine")]
+    fn create_checkpoint_stream_async(
+        &self,
+        engine: &dyn AsyncEngine,
+        checkpoint_read_schema: SchemaRef,
+        meta_predicate: Option<PredicateRef>,
+    ) -> DeltaResult<impl Stream<Item = DeltaResult<ActionsBatch>> + Send> {
+        let need_file_actions = checkpoint_read_schema.contains(ADD_NAME)
+            || checkpoint_read_schema.contains(REMOVE_NAME);
+        require!(
+            !need_file_actions || checkpoint_read_schema.contains(SIDECAR_NAME),
+            Error::invalid_checkpoint(
+                "If the checkpoint read schema contains file actions, it must contain the sidecar column"
+            )
+        );
+
+        let checkpoint_file_meta: Vec<_> = self
+            .checkpoint_parts
+            .iter()
+            .map(|f| f.location.clone())
+            .collect();
+
+        let parquet_handler = engine.async_parquet_handler();
+        let actions = match self.checkpoint_parts.first() {
+            Some(parsed_log_path) if parsed_log_path.extension == "json" => {
+                engine.async_json_handler().read_json_files(
+                    &checkpoint_file_meta,
+                    checkpoint_read_schema.clone(),
+                    meta_predicate.clone(),
+                )?
+            }
+            Some(parsed_log_path) if parsed_log_path.extension == "parquet" => parquet_handler
+                .read_parquet_files(
+                    &checkpoint_file_meta,
+                    checkpoint_read_schema.clone(),
+                    meta_predicate.clone(),
+                )?,
+            Some(parsed_log_path) => {
+                return Err(Error::generic(format!(
+                    "Unsupported checkpoint file type: {}",
+                    parsed_log_path.extension,
+                )));
+            }
+            None => futures::stream::empty().boxed(),
+        };
+
+        // Same as the sync version: single-part checkpoints may reference sidecar files, whose
+        // batches follow the checkpoint batch that referenced them.
+        let read_sidecars = need_file_actions && checkpoint_file_meta.len() == 1;
+        let log_root = self.log_root.clone();
+        let actions_stream = actions
+            .map(move |checkpoint_batch_result| -> DeltaResult<_> {
+                let checkpoint_batch = checkpoint_batch_result?;
+                let sidecar_files = if read_sidecars {
+                    Self::sidecar_files(&log_root, checkpoint_batch.as_ref())?
+                } else {
+                    vec![]
+                };
+                let sidecar_content = if sidecar_files.is_empty() {
+                    futures::stream::empty().boxed()
+                } else {
+                    parquet_handler.read_parquet_files(
+                        &sidecar_files,
+                        checkpoint_read_schema.clone(),
+                        meta_predicate.clone(),
+                    )?
+                };
+                Ok(
+                    futures::stream::once(futures::future::ready(Ok(checkpoint_batch)))
+                        .chain(sidecar_content)
+                        .map_ok(|batch| ActionsBatch::new(batch, false)),
+                )
+            })
+            .try_flatten();
+
+        Ok(actions_stream)
+    }
+
     // Do a lightweight protocol+metadata log replay to find the latest Protocol and Metadata in
     // the LogSegment
     pub(crate) fn protocol_and_metadata(
@@ -471,14 +630,7 @@ impl LogSegment {
         let actions_batches = self.replay_for_metadata(engine)?;
         let (mut metadata_opt, mut protocol_opt) = (None, None);
         for actions_batch in actions_batches {
-            let actions = actions_batch?.actions;
-            if metadata_opt.is_none() {
-                metadata_opt = Metadata::try_new_from_data(actions.as_ref())?;
-            }
-            if protocol_opt.is_none() {
-                protocol_opt = Protocol::try_new_from_data(actions.as_ref())?;
-            }
-            if metadata_opt.is_some() && protocol_opt.is_some() {
+            if visit_protocol_and_metadata(actions_batch?, &mut metadata_opt, &mut protocol_opt)? {
                 // we've found both, we can stop
                 break;
             }
@@ -488,12 +640,31 @@ impl LogSegment {
 
     // Get the most up-to-date Protocol and Metadata actions
     pub(crate) fn read_metadata(&self, engine: &dyn Engine) -> DeltaResult<(Metadata, Protocol)> {
-        match self.protocol_and_metadata(engine)? {
-            (Some(m), Some(p)) => Ok((m, p)),
-            (None, Some(_)) => Err(Error::MissingMetadata),
-            (Some(_), None) => Err(Error::MissingProtocol),
-            (None, None) => Err(Error::MissingMetadataAndProtocol),
+        require_protocol_and_metadata(self.protocol_and_metadata(engine)?)
+    }
+
+    /// Like [`Self::read_metadata`], but replays the log through the async handlers of an
+    /// [`AsyncEngine`].
+    #[cfg(feature = "async-engine")]
+    pub(crate) async fn read_metadata_async(
+        &self,
+        engine: &dyn AsyncEngine,
+    ) -> DeltaResult<(Metadata, Protocol)> {
+        let schema = get_log_schema().project(&[PROTOCOL_NAME, METADATA_NAME])?;
+        let mut actions_batches = std::pin::pin!(self.read_actions_async(
+            engine,
+            schema.clone(),
+            schema,
+            META_PREDICATE.clone()
+        )?);
+        let (mut metadata_opt, mut protocol_opt) = (None, None);
+        while let Some(actions_batch) = actions_batches.next().await {
+            if visit_protocol_and_metadata(actions_batch?, &mut metadata_opt, &mut protocol_opt)? {
+                // we've found both, we can stop
+                break;
+            }
         }
+        require_protocol_and_metadata((metadata_opt, protocol_opt))
     }
 
     // Replay the commit log, projecting rows to only contain Protocol and Metadata action columns.
@@ -502,13 +673,6 @@ impl LogSegment {
         engine: &dyn Engine,
     ) -> DeltaResult<impl Iterator<Item = DeltaResult<ActionsBatch>> + Send> {
         let schema = get_log_schema().project(&[PROTOCOL_NAME, METADATA_NAME])?;
-        // filter out log files that do not contain metadata or protocol information
-        static META_PREDICATE: LazyLock<Option<PredicateRef>> = LazyLock::new(|| {
-            Some(Arc::new(Predicate::or(
-                Expression::column([METADATA_NAME, "id"]).is_not_null(),
-                Expression::column([PROTOCOL_NAME, "minReaderVersion"]).is_not_null(),
-            )))
-        });
         // read the same protocol and metadata schema for both commits and checkpoints
         self.read_actions(engine, schema.clone(), schema, META_PREDICATE.clone())
     }
@@ -546,3 +710,53 @@ impl LogSegment {
         self.end_version - to_sub
     }
 }
+
+// filter out log files that do not contain metadata or protocol information
+static META_PREDICATE: LazyLock<Option<PredicateRef>> = LazyLock::new(|| {
+    Some(Arc::new(Predicate::or(
+        Expression::column([METADATA_NAME, "id"]).is_not_null(),
+        Expression::column([PROTOCOL_NAME, "minReaderVersion"]).is_not_null(),
+    )))
+});
+
+// Fills in whichever of Protocol and Metadata is still missing from the given batch. Returns true
+// once both have been found.
+fn visit_protocol_and_metadata(
+    actions_batch: ActionsBatch,
+    metadata_opt: &mut Option<Metadata>,
+    protocol_opt: &mut Option<Protocol>,
+) -> DeltaResult<bool> {
+    let actions = actions_batch.actions;
+    if metadata_opt.is_none() {
+        *metadata_opt = Metadata::try_new_from_data(actions.as_ref())?;
+    }
+    if protocol_opt.is_none() {
+        *protocol_opt = Protocol::try_new_from_data(actions.as_ref())?;
+    }
+    Ok(metadata_opt.is_some() && protocol_opt.is_some())
+}
+
+fn require_protocol_and_metadata(
+    protocol_and_metadata: (Option<Metadata>, Option<Protocol>),
+) -> DeltaResult<(Metadata, Protocol)> {
+    match protocol_and_metadata {
+        (Some(m), Some(p)) => Ok((m, p)),
+        (None, Some(_)) => Err(Error::MissingMetadata),
+        (Some(_), None) => Err(Error::MissingProtocol),
+        (None, None) => Err(Error::MissingMetadataAndProtocol),
+    }
+}
+
+// A log tail (see `LogSegment::for_snapshot`) must consist of contiguous commits in ascending order.
+fn validate_log_tail(log_tail: &[ParsedLogPath]) -> DeltaResult<()> {
+    require!(
+        log_tail.iter().all(|commit| matches!(
+            commit.file_type,
+            LogPathFileType::Commit | LogPathFileType::StagedCommit
+        )) && log_tail
+            .windows(2)
+            .all(|commits| commits[0].version + 1 == commits[1].version),
+        Error::generic("The log tail must consist of contiguous commits in ascending order")
+    );
+    Ok(())
+}
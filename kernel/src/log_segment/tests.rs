@@ -225,7 +225,8 @@ fn build_snapshot_with_uuid_checkpoint_parquet() {
         None,
     );
 
-    let log_segment = LogSegment::for_snapshot(storage.as_ref(), log_root, None, None).unwrap();
+    let log_segment =
+        LogSegment::for_snapshot(storage.as_ref(), log_root, None, None, vec![]).unwrap();
     let commit_files = log_segment.ascending_commit_files;
     let checkpoint_parts = log_segment.checkpoint_parts;
 
@@ -254,7 +255,8 @@ fn build_snapshot_with_uuid_checkpoint_json() {
         None,
     );
 
-    let log_segment = LogSegment::for_snapshot(storage.as_ref(), log_root, None, None).unwrap();
+    let log_segment =
+        LogSegment::for_snapshot(storage.as_ref(), log_root, None, None, vec![]).unwrap();
     let commit_files = log_segment.ascending_commit_files;
     let checkpoint_parts = log_segment.checkpoint_parts;
 
@@ -295,8 +297,14 @@ fn build_snapshot_with_correct_last_uuid_checkpoint() {
         Some(&checkpoint_metadata),
     );
 
-    let log_segment =
-        LogSegment::for_snapshot(storage.as_ref(), log_root, checkpoint_metadata, None).unwrap();
+    let log_segment = LogSegment::for_snapshot(
+        storage.as_ref(),
+        log_root,
+        checkpoint_metadata,
+        None,
+        vec![],
+    )
+    .unwrap();
     let commit_files = log_segment.ascending_commit_files;
     let checkpoint_parts = log_segment.checkpoint_parts;
 
@@ -332,7 +340,8 @@ fn build_snapshot_with_multiple_incomplete_multipart_checkpoints() {
         None,
     );
 
-    let log_segment = LogSegment::for_snapshot(storage.as_ref(), log_root, None, None).unwrap();
+    let log_segment =
+        LogSegment::for_snapshot(storage.as_ref(), log_root, None, None, vec![]).unwrap();
     let commit_files = log_segment.ascending_commit_files;
     let checkpoint_parts = log_segment.checkpoint_parts;
 
@@ -370,8 +379,14 @@ fn build_snapshot_with_out_of_date_last_checkpoint() {
         Some(&checkpoint_metadata),
     );
 
-    let log_segment =
-        LogSegment::for_snapshot(storage.as_ref(), log_root, checkpoint_metadata, None).unwrap();
+    let log_segment = LogSegment::for_snapshot(
+        storage.as_ref(),
+        log_root,
+        checkpoint_metadata,
+        None,
+        vec![],
+    )
+    .unwrap();
     let commit_files = log_segment.ascending_commit_files;
     let checkpoint_parts = log_segment.checkpoint_parts;
 
@@ -412,8 +427,14 @@ fn build_snapshot_with_correct_last_multipart_checkpoint() {
         Some(&checkpoint_metadata),
     );
 
-    let log_segment =
-        LogSegment::for_snapshot(storage.as_ref(), log_root, checkpoint_metadata, None).unwrap();
+    let log_segment = LogSegment::for_snapshot(
+        storage.as_ref(),
+        log_root,
+        checkpoint_metadata,
+        None,
+        vec![],
+    )
+    .unwrap();
     let commit_files = log_segment.ascending_commit_files;
     let checkpoint_parts = log_segment.checkpoint_parts;
 
@@ -455,8 +476,13 @@ fn build_snapshot_with_missing_checkpoint_part_from_hint_fails() {
         Some(&checkpoint_metadata),
     );
 
-    let log_segment =
-        LogSegment::for_snapshot(storage.as_ref(), log_root, checkpoint_metadata, None);
+    let log_segment = LogSegment::for_snapshot(
+        storage.as_ref(),
+        log_root,
+        checkpoint_metadata,
+        None,
+        vec![],
+    );
     assert_result_error_with_message(
         log_segment,
         "Invalid Checkpoint: Had a _last_checkpoint hint but didn't find any checkpoints",
@@ -492,8 +518,13 @@ fn build_snapshot_with_bad_checkpoint_hint_fails() {
         Some(&checkpoint_metadata),
     );
 
-    let log_segment =
-        LogSegment::for_snapshot(storage.as_ref(), log_root, checkpoint_metadata, None);
+    let log_segment = LogSegment::for_snapshot(
+        storage.as_ref(),
+        log_root,
+        checkpoint_metadata,
+        None,
+        vec![],
+    );
     assert_result_error_with_message(
         log_segment,
         "Invalid Checkpoint: _last_checkpoint indicated that checkpoint should have 1 parts, but \
@@ -524,7 +555,8 @@ fn build_snapshot_with_missing_checkpoint_part_no_hint() {
         None,
     );
 
-    let log_segment = LogSegment::for_snapshot(storage.as_ref(), log_root, None, None).unwrap();
+    let log_segment =
+        LogSegment::for_snapshot(storage.as_ref(), log_root, None, None, vec![]).unwrap();
 
     let commit_files = log_segment.ascending_commit_files;
     let checkpoint_parts = log_segment.checkpoint_parts;
@@ -569,8 +601,14 @@ fn build_snapshot_with_out_of_date_last_checkpoint_and_incomplete_recent_checkpo
         Some(&checkpoint_metadata),
     );
 
-    let log_segment =
-        LogSegment::for_snapshot(storage.as_ref(), log_root, checkpoint_metadata, None).unwrap();
+    let log_segment = LogSegment::for_snapshot(
+        storage.as_ref(),
+        log_root,
+        checkpoint_metadata,
+        None,
+        vec![],
+    )
+    .unwrap();
     let commit_files = log_segment.ascending_commit_files;
     let checkpoint_parts = log_segment.checkpoint_parts;
 
@@ -603,7 +641,7 @@ fn build_snapshot_without_checkpoints() {
 
     ///////// Specify no checkpoint or end version /////////
     let log_segment =
-        LogSegment::for_snapshot(storage.as_ref(), log_root.clone(), None, None).unwrap();
+        LogSegment::for_snapshot(storage.as_ref(), log_root.clone(), None, None, vec![]).unwrap();
     let commit_files = log_segment.ascending_commit_files;
     let checkpoint_parts = log_segment.checkpoint_parts;
 
@@ -616,7 +654,8 @@ fn build_snapshot_without_checkpoints() {
     assert_eq!(versions, expected_versions);
 
     ///////// Specify  only end version /////////
-    let log_segment = LogSegment::for_snapshot(storage.as_ref(), log_root, None, Some(2)).unwrap();
+    let log_segment =
+        LogSegment::for_snapshot(storage.as_ref(), log_root, None, Some(2), vec![]).unwrap();
     let commit_files = log_segment.ascending_commit_files;
     let checkpoint_parts = log_segment.checkpoint_parts;
 
@@ -657,8 +696,14 @@ fn build_snapshot_with_checkpoint_greater_than_time_travel_version() {
         None,
     );
 
-    let log_segment =
-        LogSegment::for_snapshot(storage.as_ref(), log_root, checkpoint_metadata, Some(4)).unwrap();
+    let log_segment = LogSegment::for_snapshot(
+        storage.as_ref(),
+        log_root,
+        checkpoint_metadata,
+        Some(4),
+        vec![],
+    )
+    .unwrap();
     let commit_files = log_segment.ascending_commit_files;
     let checkpoint_parts = log_segment.checkpoint_parts;
 
@@ -695,8 +740,14 @@ fn build_snapshot_with_start_checkpoint_and_time_travel_version() {
         Some(&checkpoint_metadata),
     );
 
-    let log_segment =
-        LogSegment::for_snapshot(storage.as_ref(), log_root, checkpoint_metadata, Some(4)).unwrap();
+    let log_segment = LogSegment::for_snapshot(
+        storage.as_ref(),
+        log_root,
+        checkpoint_metadata,
+        Some(4),
+        vec![],
+    )
+    .unwrap();
 
     assert_eq!(log_segment.checkpoint_parts[0].version, 3);
     assert_eq!(log_segment.ascending_commit_files.len(), 1);
@@ -1352,7 +1403,14 @@ fn create_segment_for(
         ));
     }
     let (storage, log_root) = build_log_with_paths_and_checkpoint(&paths, None);
-    LogSegment::for_snapshot(storage.as_ref(), log_root.clone(), None, version_to_load).unwrap()
+    LogSegment::for_snapshot(
+        storage.as_ref(),
+        log_root.clone(),
+        None,
+        version_to_load,
+        vec![],
+    )
+    .unwrap()
 }
 
 #[test]
@@ -1764,6 +1822,41 @@ fn test_debug_assert_listed_log_file_invalid_multipart_checkpoint() {
     );
 }
 
+#[test]
+fn test_log_tail_drops_overlapping_compactions() {
+    let listed_files = ListedLogFiles::try_new(
+        (0..=3)
+            .map(|version| create_log_path(&format!("file:///{version:020}.json")))
+            .collect(),
+        vec![
+            create_log_path("file:///00000000000000000000.00000000000000000001.compacted.json"),
+            create_log_path("file:///00000000000000000001.00000000000000000002.compacted.json"),
+        ],
+        vec![],
+        None,
+    )
+    .unwrap();
+    let log_tail = vec![
+        create_log_path("file:///_staged_commits/00000000000000000002.3a0d65cd-4056-49b8-937b-95f9e3ee90e5.json"),
+        create_log_path("file:///_staged_commits/00000000000000000003.5c1d6b8e-0d2a-4a5e-8b8e-7f3c2a1b9e4d.json"),
+    ];
+    let listed_files = listed_files.with_log_tail(log_tail, None);
+
+    // the compaction of versions 1 to 2 covers a commit of the log tail
+    let compactions = &listed_files.ascending_compaction_files;
+    assert_eq!(compactions.len(), 1);
+    assert_eq!(
+        compactions[0].file_type,
+        LogPathFileType::CompactedCommit { hi: 1 }
+    );
+    let commits = &listed_files.ascending_commit_files;
+    assert_eq!(
+        commits.iter().map(|c| c.version).collect_vec(),
+        [0, 1, 2, 3]
+    );
+    assert_eq!(commits[2].file_type, LogPathFileType::StagedCommit);
+}
+
 #[test]
 fn commits_since() {
     // simple
@@ -1857,7 +1950,8 @@ fn for_timestamp_conversion_gets_commit_range() {
     );
 
     let log_segment =
-        LogSegment::for_timestamp_conversion(storage.as_ref(), log_root.clone(), 7, None).unwrap();
+        LogSegment::for_timestamp_conversion(storage.as_ref(), log_root.clone(), 7, None, vec![])
+            .unwrap();
     let commit_files = log_segment.ascending_commit_files;
     let checkpoint_parts = log_segment.checkpoint_parts;
 
@@ -1887,7 +1981,8 @@ fn for_timestamp_conversion_with_old_end_version() {
     );
 
     let log_segment =
-        LogSegment::for_timestamp_conversion(storage.as_ref(), log_root.clone(), 5, None).unwrap();
+        LogSegment::for_timestamp_conversion(storage.as_ref(), log_root.clone(), 5, None, vec![])
+            .unwrap();
     let commit_files = log_segment.ascending_commit_files;
     let checkpoint_parts = log_segment.checkpoint_parts;
 
@@ -1917,7 +2012,8 @@ fn for_timestamp_conversion_only_contiguous_ranges() {
     );
 
     let log_segment =
-        LogSegment::for_timestamp_conversion(storage.as_ref(), log_root.clone(), 7, None).unwrap();
+        LogSegment::for_timestamp_conversion(storage.as_ref(), log_root.clone(), 7, None, vec![])
+            .unwrap();
     let commit_files = log_segment.ascending_commit_files;
     let checkpoint_parts = log_segment.checkpoint_parts;
 
@@ -1951,6 +2047,7 @@ fn for_timestamp_conversion_with_limit() {
         log_root.clone(),
         7,
         Some(NonZero::new(3).unwrap()),
+        vec![],
     )
     .unwrap();
     let commit_files = log_segment.ascending_commit_files;
@@ -1985,6 +2082,7 @@ fn for_timestamp_conversion_with_large_limit() {
         log_root.clone(),
         7,
         Some(NonZero::new(20).unwrap()),
+        vec![],
     )
     .unwrap();
     let commit_files = log_segment.ascending_commit_files;
@@ -2003,7 +2101,8 @@ fn for_timestamp_conversion_no_commit_files() {
         None,
     );
 
-    let res = LogSegment::for_timestamp_conversion(storage.as_ref(), log_root.clone(), 0, None);
+    let res =
+        LogSegment::for_timestamp_conversion(storage.as_ref(), log_root.clone(), 0, None, vec![]);
     assert_result_error_with_message(res, "Generic delta kernel error: No files in log segment");
 }
 
@@ -17,10 +17,16 @@ const MULTIPART_PART_LEN: usize = 10;
 /// The number of characters in the uuid part of a uuid checkpoint
 const UUID_PART_LEN: usize = 36;
 
+/// The directory (within `_delta_log`) holding the staged commits of catalog-managed tables
+const STAGED_COMMITS_DIR: &str = "_staged_commits";
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[internal_api]
 pub(crate) enum LogPathFileType {
     Commit,
+    /// A ratified commit of a catalog-managed table which may not have been published (i.e. copied
+    /// to `<version>.json`) yet, named `_staged_commits/<version>.<uuid>.json`
+    StagedCommit,
     SinglePartCheckpoint,
     #[allow(unused)]
     UuidCheckpoint(String),
@@ -85,13 +91,15 @@ impl<Location: AsUrl> ParsedLogPath<Location> {
     #[internal_api]
     pub(crate) fn try_from(location: Location) -> DeltaResult<Option<ParsedLogPath<Location>>> {
         let url = location.as_url();
-        #[allow(clippy::unwrap_used)]
-        let filename = url
+        let mut path_segments = url
             .path_segments()
-            .ok_or_else(|| Error::invalid_log_path(url))?
+            .ok_or_else(|| Error::invalid_log_path(url))?;
+        #[allow(clippy::unwrap_used)]
+        let filename = path_segments
             .next_back()
             .unwrap() // "the iterator always contains at least one string (which may be empty)"
             .to_string();
+        let is_staged = path_segments.next_back() == Some(STAGED_COMMITS_DIR);
         if filename.is_empty() {
             return Err(Error::invalid_log_path(url));
         }
@@ -123,6 +131,10 @@ impl<Location: AsUrl> ParsedLogPath<Location> {
             ["json"] => LogPathFileType::Commit,
             ["crc"] => LogPathFileType::Crc,
             ["checkpoint", "parquet"] => LogPathFileType::SinglePartCheckpoint,
+            [uuid, "json"] if is_staged => {
+                parse_path_part::<String>(uuid, UUID_PART_LEN, url)?;
+                LogPathFileType::StagedCommit
+            }
             ["checkpoint", uuid, "json" | "parquet"] => {
                 let uuid = parse_path_part(uuid, UUID_PART_LEN, url)?;
                 LogPathFileType::UuidCheckpoint(uuid)
@@ -202,6 +214,19 @@ impl ParsedLogPath<Url> {
         Ok(path)
     }
 
+    /// Create a new ParsedCommitPath<Url> for a new staged commit of a catalog-managed table, named
+    /// `_staged_commits/<version>.<uuid>.json`
+    pub(crate) fn new_staged_commit(table_root: &Url, version: Version) -> DeltaResult<Self> {
+        let filename = format!("{STAGED_COMMITS_DIR}/{version:020}.{}.json", Uuid::new_v4());
+        let path = Self::create_path(table_root, filename)?;
+        if path.file_type != LogPathFileType::StagedCommit {
+            return Err(Error::internal_error(
+                "ParsedLogPath::new_staged_commit created a non-staged-commit path",
+            ));
+        }
+        Ok(path)
+    }
+
     /// Create a new ParsedCheckpointPath<Url> for a classic parquet checkpoint file
     #[allow(dead_code)] // TODO: Remove this once we have a use case for it
     pub(crate) fn new_classic_parquet_checkpoint(
@@ -249,6 +274,43 @@ impl ParsedLogPath<Url> {
     }
 }
 
+/// A commit of a catalog-managed table, as provided by the catalog in the log tail of a snapshot
+/// (see [`SnapshotBuilder::with_log_tail`]). This is either a published commit
+/// (`_delta_log/<version>.json`) or a staged commit (`_delta_log/_staged_commits/<version>.<uuid>.json`).
+///
+/// [`SnapshotBuilder::with_log_tail`]: crate::snapshot::SnapshotBuilder::with_log_tail
+#[cfg(feature = "catalog-managed")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogPath(pub(crate) ParsedLogPath);
+
+#[cfg(feature = "catalog-managed")]
+impl LogPath {
+    /// Create a [`LogPath`] for the commit file at `file_meta`. Fails if the file is neither a
+    /// published nor a staged commit.
+    pub fn try_new(file_meta: FileMeta) -> DeltaResult<Self> {
+        let location = file_meta.location.clone();
+        match ParsedLogPath::try_from(file_meta)? {
+            Some(
+                path @ ParsedLogPath {
+                    file_type: LogPathFileType::Commit | LogPathFileType::StagedCommit,
+                    ..
+                },
+            ) => Ok(Self(path)),
+            _ => Err(Error::invalid_log_path(location)),
+        }
+    }
+
+    /// The version of the commit.
+    pub fn version(&self) -> Version {
+        self.0.version
+    }
+
+    /// The location of the commit file.
+    pub fn location(&self) -> &FileMeta {
+        &self.0.location
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::path::PathBuf;
@@ -335,6 +397,53 @@ mod tests {
         assert!(log_path.is_unknown());
     }
 
+    #[test]
+    fn test_staged_commit_patterns() {
+        let table_log_dir = table_log_dir_url();
+        let uuid = "3a0d65cd-4056-49b8-937b-95f9e3ee90e5";
+
+        let log_path = table_log_dir
+            .join(&format!("_staged_commits/00000000000000000010.{uuid}.json"))
+            .unwrap();
+        let log_path = ParsedLogPath::try_from(log_path).unwrap().unwrap();
+        assert_eq!(log_path.version, 10);
+        assert_eq!(log_path.file_type, LogPathFileType::StagedCommit);
+        assert!(!log_path.is_commit());
+
+        // staged commits only live in the _staged_commits directory
+        let log_path = table_log_dir
+            .join(&format!("00000000000000000010.{uuid}.json"))
+            .unwrap();
+        let log_path = ParsedLogPath::try_from(log_path).unwrap().unwrap();
+        assert!(log_path.is_unknown());
+
+        // invalid - malformed uuid
+        let log_path = table_log_dir
+            .join("_staged_commits/00000000000000000010.abc.json")
+            .unwrap();
+        ParsedLogPath::try_from(log_path).expect_err("malformed uuid");
+    }
+
+    #[cfg(feature = "catalog-managed")]
+    #[test]
+    fn test_log_path() {
+        let file_meta = |path: &str| FileMeta {
+            location: table_log_dir_url().join(path).unwrap(),
+            last_modified: 0,
+            size: 0,
+        };
+        let staged_commit =
+            "_staged_commits/00000000000000000007.3a0d65cd-4056-49b8-937b-95f9e3ee90e5.json";
+        let log_path = LogPath::try_new(file_meta(staged_commit)).unwrap();
+        assert_eq!(log_path.version(), 7);
+        let log_path = LogPath::try_new(file_meta("00000000000000000007.json")).unwrap();
+        assert_eq!(log_path.version(), 7);
+
+        LogPath::try_new(file_meta("00000000000000000007.checkpoint.parquet"))
+            .expect_err("checkpoints are not commits");
+        LogPath::try_new(file_meta("_last_checkpoint")).expect_err("not a log file");
+    }
+
     #[test]
     fn test_commit_patterns() {
         let table_log_dir = table_log_dir_url();
@@ -64,7 +64,7 @@ impl DataSkippingFilter {
     /// NOTE: None is equivalent to a trivial filter that always returns TRUE (= keeps all files),
     /// but using an Option lets the engine easily avoid the overhead of applying trivial filters.
     pub(crate) fn new(
-        engine: &dyn Engine,
+        engine: &(impl Engine + ?Sized),
         physical_predicate: Option<(PredicateRef, SchemaRef)>,
     ) -> Option<Self> {
         static STATS_EXPR: LazyLock<Expr> = LazyLock::new(|| column_expr!("add.stats"));
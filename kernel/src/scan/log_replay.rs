@@ -20,6 +20,9 @@ use crate::schema::{ColumnNamesAndTypes, DataType, MapType, SchemaRef, StructFie
 use crate::utils::require;
 use crate::{DeltaResult, Engine, Error, ExpressionEvaluator};
 
+#[cfg(feature = "async-engine")]
+use futures::Stream;
+
 /// [`ScanLogReplayProcessor`] performs log replay (processes actions) specifically for doing a table scan.
 ///
 /// During a table scan, the processor reads batches of log actions (in reverse chronological order)
@@ -57,7 +60,7 @@ pub(crate) struct ScanLogReplayProcessor {
 impl ScanLogReplayProcessor {
     /// Create a new [`ScanLogReplayProcessor`] instance
     fn new(
-        engine: &dyn Engine,
+        engine: &(impl Engine + ?Sized),
         physical_predicate: Option<(PredicateRef, SchemaRef)>,
         logical_schema: SchemaRef,
         transform: Option<Arc<Transform>>,
@@ -406,6 +409,19 @@ pub(crate) fn scan_action_iter(
         .process_actions_iter(action_iter)
 }
 
+/// Like [`scan_action_iter`], but processes a [`Stream`] of action batches.
+#[cfg(feature = "async-engine")]
+pub(crate) fn scan_action_stream(
+    engine: &(impl Engine + ?Sized),
+    action_stream: impl Stream<Item = DeltaResult<ActionsBatch>>,
+    logical_schema: SchemaRef,
+    transform: Option<Arc<Transform>>,
+    physical_predicate: Option<(PredicateRef, SchemaRef)>,
+) -> impl Stream<Item = DeltaResult<ScanMetadata>> {
+    ScanLogReplayProcessor::new(engine, physical_predicate, logical_schema, transform)
+        .process_actions_stream(action_stream)
+}
+
 #[cfg(test)]
 mod tests {
     use std::{collections::HashMap, sync::Arc};
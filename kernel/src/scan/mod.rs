@@ -21,7 +21,7 @@ use crate::kernel_predicates::{DefaultKernelPredicateEvaluator, EmptyColumnResol
 use crate::listed_log_files::ListedLogFiles;
 use crate::log_replay::{ActionsBatch, HasSelectionVector};
 use crate::log_segment::LogSegment;
-use crate::scan::state::{DvInfo, Stats};
+use crate::scan::state::{ScanFile, ScanState};
 use crate::schema::ToSchema as _;
 use crate::schema::{
     ArrayType, DataType, MapType, PrimitiveType, Schema, SchemaRef, SchemaTransform, StructField,
@@ -29,9 +29,15 @@ use crate::schema::{
 };
 use crate::snapshot::Snapshot;
 use crate::table_features::ColumnMappingMode;
-use crate::{DeltaResult, Engine, EngineData, Error, FileMeta, Version};
+use crate::{DeltaResult, Engine, EngineData, Error, Version};
 
 use self::log_replay::scan_action_iter;
+#[cfg(feature = "async-engine")]
+use self::log_replay::scan_action_stream;
+#[cfg(feature = "async-engine")]
+use crate::async_engine::AsyncEngine;
+#[cfg(feature = "async-engine")]
+use futures::{Stream, StreamExt as _};
 
 pub(crate) mod data_skipping;
 pub mod log_replay;
@@ -444,6 +450,18 @@ impl Scan {
         }
     }
 
+    /// Get the [`ScanState`] of this scan, which can be serialized and used to read the scan's
+    /// files without access to its [`Snapshot`]. See [`ScanState`] for details.
+    pub fn scan_state(&self) -> ScanState {
+        ScanState::new(
+            self.table_root().clone(),
+            self.logical_schema.clone(),
+            self.physical_schema.clone(),
+            self.physical_predicate(),
+            self.snapshot.table_configuration().column_mapping_mode(),
+        )
+    }
+
     /// Convert the parts of the transform that can be computed statically into `Expression`s. For
     /// parts that cannot be computed statically, include enough metadata so lower levels of
     /// processing can create and fill in an expression.
@@ -622,6 +640,25 @@ impl Scan {
         engine: &dyn Engine,
         action_batch_iter: impl Iterator<Item = DeltaResult<ActionsBatch>>,
     ) -> DeltaResult<impl Iterator<Item = DeltaResult<ScanMetadata>>> {
+        let Some((static_transform, physical_predicate)) = self.replay_parameters() else {
+            return Ok(None.into_iter().flatten());
+        };
+        let it = scan_action_iter(
+            engine,
+            action_batch_iter,
+            self.logical_schema.clone(),
+            static_transform,
+            physical_predicate,
+        );
+        Ok(Some(it).into_iter().flatten())
+    }
+
+    /// The static transform and physical predicate to replay the log with, or `None` if the
+    /// predicate statically skips all files.
+    #[allow(clippy::type_complexity)]
+    fn replay_parameters(
+        &self,
+    ) -> Option<(Option<Arc<Transform>>, Option<(PredicateRef, SchemaRef)>)> {
         // Compute the static part of the transformation. This is `None` if no transformation is
         // needed (currently just means no partition cols AND no column mapping but will be extended
         // for other transforms as we support them)
@@ -629,18 +666,65 @@ impl Scan {
             || self.snapshot.column_mapping_mode() != ColumnMappingMode::None)
             .then(|| Arc::new(Scan::get_static_transform(&self.all_fields)));
         let physical_predicate = match self.physical_predicate.clone() {
-            PhysicalPredicate::StaticSkipAll => return Ok(None.into_iter().flatten()),
+            PhysicalPredicate::StaticSkipAll => return None,
             PhysicalPredicate::Some(predicate, schema) => Some((predicate, schema)),
             PhysicalPredicate::None => None,
         };
-        let it = scan_action_iter(
+        Some((static_transform, physical_predicate))
+    }
+
+    /// The transform to apply to the data read from a file with the given partition values, or
+    /// `None` if the data is already in the logical schema of the scan.
+    pub(crate) fn file_transform(
+        &self,
+        partition_values: &HashMap<String, String>,
+    ) -> DeltaResult<Option<ExpressionRef>> {
+        let Some((Some(transform), _)) = self.replay_parameters() else {
+            return Ok(None);
+        };
+        let transforms = transform
+            .iter()
+            .map(|transform_expr| match transform_expr {
+                TransformExpr::Partition(field_idx) => {
+                    let Some((_, field)) = self.logical_schema.fields.get_index(*field_idx) else {
+                        return Err(Error::InternalError(format!(
+                            "out of bounds partition column field index {field_idx}"
+                        )));
+                    };
+                    let raw = partition_values.get(field.physical_name());
+                    Ok(parse_partition_value(raw, field.data_type())?.into())
+                }
+                TransformExpr::Static(field_expr) => Ok(field_expr.clone()),
+            })
+            .try_collect()?;
+        Ok(Some(Arc::new(Expression::Struct(transforms))))
+    }
+
+    /// Like [`Scan::scan_metadata`], but replays the log through the async handlers of an
+    /// [`AsyncEngine`], returning a [`Stream`] of [`ScanMetadata`]s.
+    #[cfg(feature = "async-engine")]
+    pub fn scan_metadata_async(
+        &self,
+        engine: &dyn AsyncEngine,
+    ) -> DeltaResult<impl Stream<Item = DeltaResult<ScanMetadata>> + Send> {
+        let Some((static_transform, physical_predicate)) = self.replay_parameters() else {
+            return Ok(futures::stream::empty().left_stream());
+        };
+        // NOTE: as in the sync version, we don't pass any meta-predicate.
+        let action_stream = self.snapshot.log_segment().read_actions_async(
             engine,
-            action_batch_iter,
+            COMMIT_READ_SCHEMA.clone(),
+            CHECKPOINT_READ_SCHEMA.clone(),
+            None,
+        )?;
+        let stream = scan_action_stream(
+            engine,
+            action_stream,
             self.logical_schema.clone(),
             static_transform,
             physical_predicate,
         );
-        Ok(Some(it).into_iter().flatten())
+        Ok(stream.right_stream())
     }
 
     // Factored out to facilitate testing
@@ -670,108 +754,170 @@ impl Scan {
         &self,
         engine: Arc<dyn Engine>,
     ) -> DeltaResult<impl Iterator<Item = DeltaResult<ScanResult>> + use<'_>> {
-        struct ScanFile {
-            path: String,
-            size: i64,
-            dv_info: DvInfo,
-            transform: Option<ExpressionRef>,
-        }
-        fn scan_metadata_callback(
-            batches: &mut Vec<ScanFile>,
-            path: &str,
-            size: i64,
-            _: Option<Stats>,
-            dv_info: DvInfo,
-            transform: Option<ExpressionRef>,
-            _: HashMap<String, String>,
-        ) {
-            batches.push(ScanFile {
-                path: path.to_string(),
-                size,
-                dv_info,
-                transform,
-            });
-        }
-
         debug!(
             "Executing scan with logical schema {:#?} and physical schema {:#?}",
             self.logical_schema, self.physical_schema
         );
 
-        let table_root = self.snapshot.table_root().clone();
-
-        let scan_metadata_iter = self.scan_metadata(engine.as_ref())?;
-        let scan_files_iter = scan_metadata_iter
-            .map(|res| {
-                let scan_metadata = res?;
-                let scan_files = vec![];
-                scan_metadata.visit_scan_files(scan_files, scan_metadata_callback)
-            })
+        let scan_files_iter = self
+            .scan_metadata(engine.as_ref())?
+            .map(|res| res?.scan_files())
             // Iterator<DeltaResult<Vec<ScanFile>>> to Iterator<DeltaResult<ScanFile>>
             .flatten_ok();
 
-        let result = scan_files_iter
-            .map(move |scan_file| -> DeltaResult<_> {
-                let scan_file = scan_file?;
-                let file_path = table_root.join(&scan_file.path)?;
-                let mut selection_vector = scan_file
-                    .dv_info
-                    .get_selection_vector(engine.as_ref(), &table_root)?;
-                let meta = FileMeta {
-                    last_modified: 0,
-                    size: scan_file.size.try_into().map_err(|_| {
-                        Error::generic("Unable to convert scan file size into FileSize")
-                    })?,
-                    location: file_path,
-                };
-
-                // WARNING: We validated the physical predicate against a schema that includes
-                // partition columns, but the read schema we use here does _NOT_ include partition
-                // columns. So we cannot safely assume that all column references are valid. See
-                // https://github.com/delta-io/delta-kernel-rs/issues/434 for more details.
-                //
-                // TODO(#860): we disable predicate pushdown until we support row indexes.
-                let read_result_iter = engine.parquet_handler().read_parquet_files(
-                    &[meta],
-                    self.physical_schema().clone(),
-                    None,
-                )?;
-
-                // Arc clones
-                let engine = engine.clone();
-                Ok(read_result_iter.map(move |read_result| -> DeltaResult<_> {
-                    let read_result = read_result?;
-                    // transform the physical data into the correct logical form
-                    let logical = state::transform_to_logical(
-                        engine.as_ref(),
-                        read_result,
-                        self.physical_schema(),
-                        self.logical_schema(),
-                        &scan_file.transform,
-                    );
-                    let len = logical.as_ref().map_or(0, |res| res.len());
-                    // need to split the dv_mask. what's left in dv_mask covers this result, and rest
-                    // will cover the following results. we `take()` out of `selection_vector` to avoid
-                    // trying to return a captured variable. We're going to reassign `selection_vector`
-                    // to `rest` in a moment anyway
-                    let mut sv = selection_vector.take();
-                    let rest = split_vector(sv.as_mut(), len, None);
-                    let result = ScanResult {
-                        raw_data: logical,
-                        raw_mask: sv,
-                    };
-                    selection_vector = rest;
-                    Ok(result)
-                }))
+        Ok(read_scan_files(
+            engine,
+            self.table_root().clone(),
+            self.physical_schema.clone(),
+            self.logical_schema.clone(),
+            scan_files_iter,
+        ))
+    }
+
+    /// Like [`Scan::execute`], but performs all IO through the async handlers of an
+    /// [`AsyncEngine`], returning a [`Stream`] of [`ScanResult`]s.
+    #[cfg(feature = "async-engine")]
+    pub fn execute_async(
+        &self,
+        engine: Arc<dyn AsyncEngine>,
+    ) -> DeltaResult<impl Stream<Item = DeltaResult<ScanResult>> + Send + use<'_>> {
+        use futures::{StreamExt as _, TryStreamExt as _};
+
+        debug!(
+            "Executing async scan with logical schema {:#?} and physical schema {:#?}",
+            self.logical_schema, self.physical_schema
+        );
+
+        let table_root = self.snapshot.table_root().clone();
+
+        let scan_files_stream = self
+            .scan_metadata_async(engine.as_ref())?
+            .map(|res| -> DeltaResult<_> {
+                let scan_files = res?.scan_files()?;
+                Ok(futures::stream::iter(
+                    scan_files.into_iter().map(Ok::<_, Error>),
+                ))
             })
-            // Iterator<DeltaResult<Iterator<DeltaResult<ScanResult>>>> to Iterator<DeltaResult<DeltaResult<ScanResult>>>
-            .flatten_ok()
-            // Iterator<DeltaResult<DeltaResult<ScanResult>>> to Iterator<DeltaResult<ScanResult>>
-            .map(|x| x?);
+            // Stream<DeltaResult<Stream<DeltaResult<ScanFile>>>> to Stream<DeltaResult<ScanFile>>
+            .try_flatten();
+
+        let result =
+            scan_files_stream
+                .and_then(move |scan_file| {
+                    let engine = engine.clone();
+                    let table_root = table_root.clone();
+                    async move {
+                        let mut selection_vector = scan_file
+                            .dv_info
+                            .get_selection_vector_async(engine.as_ref(), &table_root)
+                            .await?;
+                        let meta = scan_file.file_meta(&table_root)?;
+
+                        // See `execute` for why we don't push down the predicate here.
+                        let read_result_stream = engine
+                            .async_parquet_handler()
+                            .read_parquet_files(&[meta], self.physical_schema().clone(), None)?;
+
+                        Ok(read_result_stream.map(move |read_result| {
+                            to_scan_result(
+                                engine.as_ref(),
+                                read_result?,
+                                self.physical_schema(),
+                                self.logical_schema(),
+                                &scan_file.transform,
+                                &mut selection_vector,
+                            )
+                        }))
+                    }
+                })
+                // Stream<DeltaResult<Stream<DeltaResult<ScanResult>>>> to Stream<DeltaResult<ScanResult>>
+                .try_flatten();
         Ok(result)
     }
 }
 
+/// Read the given `scan_files`, turning the data read from each into [`ScanResult`]s. Shared by
+/// [`Scan::execute`] and [`ScanState::execute`].
+fn read_scan_files(
+    engine: Arc<dyn Engine>,
+    table_root: Url,
+    physical_schema: SchemaRef,
+    logical_schema: SchemaRef,
+    scan_files: impl Iterator<Item = DeltaResult<ScanFile>>,
+) -> impl Iterator<Item = DeltaResult<ScanResult>> {
+    scan_files
+        .map(move |scan_file| -> DeltaResult<_> {
+            let scan_file = scan_file?;
+            let mut selection_vector = scan_file
+                .dv_info
+                .get_selection_vector(engine.as_ref(), &table_root)?;
+            let meta = scan_file.file_meta(&table_root)?;
+
+            // WARNING: We validated the physical predicate against a schema that includes
+            // partition columns, but the read schema we use here does _NOT_ include partition
+            // columns. So we cannot safely assume that all column references are valid. See
+            // https://github.com/delta-io/delta-kernel-rs/issues/434 for more details.
+            //
+            // TODO(#860): we disable predicate pushdown until we support row indexes.
+            let read_result_iter = engine.parquet_handler().read_parquet_files(
+                &[meta],
+                physical_schema.clone(),
+                None,
+            )?;
+
+            // Arc clones
+            let engine = engine.clone();
+            let physical_schema = physical_schema.clone();
+            let logical_schema = logical_schema.clone();
+            Ok(read_result_iter.map(move |read_result| {
+                to_scan_result(
+                    engine.as_ref(),
+                    read_result?,
+                    &physical_schema,
+                    &logical_schema,
+                    &scan_file.transform,
+                    &mut selection_vector,
+                )
+            }))
+        })
+        // Iterator<DeltaResult<Iterator<DeltaResult<ScanResult>>>> to Iterator<DeltaResult<DeltaResult<ScanResult>>>
+        .flatten_ok()
+        // Iterator<DeltaResult<DeltaResult<ScanResult>>> to Iterator<DeltaResult<ScanResult>>
+        .map(|x| x?)
+}
+
+// Transforms physical data read from a scan file into a [`ScanResult`], consuming the part of
+// the file's `selection_vector` which covers it.
+fn to_scan_result(
+    engine: &(impl Engine + ?Sized),
+    read_result: Box<dyn EngineData>,
+    physical_schema: &SchemaRef,
+    logical_schema: &Schema,
+    transform: &Option<ExpressionRef>,
+    selection_vector: &mut Option<Vec<bool>>,
+) -> DeltaResult<ScanResult> {
+    // transform the physical data into the correct logical form
+    let logical = state::transform_to_logical(
+        engine,
+        read_result,
+        physical_schema,
+        logical_schema,
+        transform,
+    );
+    let len = logical.as_ref().map_or(0, |res| res.len());
+    // need to split the dv_mask. what's left in dv_mask covers this result, and rest
+    // will cover the following results. we `take()` out of `selection_vector` to avoid
+    // trying to return a captured variable. We're going to reassign `selection_vector`
+    // to `rest` in a moment anyway
+    let mut sv = selection_vector.take();
+    let rest = split_vector(sv.as_mut(), len, None);
+    *selection_vector = rest;
+    Ok(ScanResult {
+        raw_data: logical,
+        raw_mask: sv,
+    })
+}
+
 /// Get the schema that scan rows (from [`Scan::scan_metadata`]) will be returned with.
 ///
 /// It is:
@@ -1017,6 +1163,7 @@ mod tests {
     use crate::engine::arrow_data::ArrowEngineData;
     use crate::engine::sync::SyncEngine;
     use crate::expressions::{column_expr, column_pred, Expression as Expr, Predicate as Pred};
+    use crate::scan::state::{DvInfo, Stats, SCAN_STATE_VERSION};
     use crate::schema::{ColumnMetadataKey, PrimitiveType};
     use crate::Snapshot;
 
@@ -1489,4 +1636,80 @@ mod tests {
         );
         Ok(())
     }
+
+    // Plans a scan, then reads its files from a deserialized `ScanState` and `ScanFile`s, like a
+    // worker would, and checks the result matches executing the scan directly.
+    fn assert_scan_state_roundtrip(table: &str, predicate: Option<Pred>) {
+        let path = std::fs::canonicalize(PathBuf::from(table)).unwrap();
+        let url = url::Url::from_directory_path(path).unwrap();
+        let engine = Arc::new(SyncEngine::new());
+
+        let snapshot = Snapshot::try_new(url, engine.as_ref(), None).unwrap();
+        let scan = snapshot
+            .into_scan_builder()
+            .with_predicate(predicate.map(Arc::new))
+            .build()
+            .unwrap();
+
+        let scan_state = serde_json::to_string(&scan.scan_state()).unwrap();
+        let scan_files: Vec<_> = scan
+            .scan_metadata(engine.as_ref())
+            .unwrap()
+            .map(|res| res?.scan_files())
+            .flatten_ok()
+            .try_collect()
+            .unwrap();
+        assert!(!scan_files.is_empty());
+        let scan_files = serde_json::to_string(&scan_files).unwrap();
+
+        let scan_state: ScanState = serde_json::from_str(&scan_state).unwrap();
+        assert_eq!(scan_state, scan.scan_state());
+        let scan_files: Vec<ScanFile> = serde_json::from_str(&scan_files).unwrap();
+
+        let to_batches = |results: Vec<ScanResult>| -> Vec<(RecordBatch, Option<Vec<bool>>)> {
+            results
+                .into_iter()
+                .map(|result| {
+                    let mask = result.full_mask();
+                    let data = ArrowEngineData::try_from_engine_data(result.raw_data.unwrap());
+                    (data.unwrap().record_batch().clone(), mask)
+                })
+                .collect()
+        };
+        let expected: Vec<_> = scan.execute(engine.clone()).unwrap().try_collect().unwrap();
+        let actual: Vec<_> = scan_state
+            .execute(engine, scan_files)
+            .try_collect()
+            .unwrap();
+        assert_eq!(to_batches(actual), to_batches(expected));
+    }
+
+    #[test]
+    fn test_scan_state_roundtrip() {
+        assert_scan_state_roundtrip("./tests/data/table-with-dv-small/", None);
+        assert_scan_state_roundtrip(
+            "./tests/data/basic_partitioned/",
+            Some(column_expr!("number").gt(Expr::literal(3i64))),
+        );
+        assert_scan_state_roundtrip("./tests/data/basic-decimal-table/", None);
+    }
+
+    #[test]
+    fn test_scan_state_version() {
+        let path =
+            std::fs::canonicalize(PathBuf::from("./tests/data/table-without-dv-small/")).unwrap();
+        let url = url::Url::from_directory_path(path).unwrap();
+        let engine = SyncEngine::new();
+
+        let snapshot = Snapshot::try_new(url, &engine, None).unwrap();
+        let scan = snapshot.into_scan_builder().build().unwrap();
+        let mut scan_state = serde_json::to_value(scan.scan_state()).unwrap();
+        assert_eq!(scan_state["version"], SCAN_STATE_VERSION);
+
+        scan_state["version"] = (SCAN_STATE_VERSION + 1).into();
+        let err = serde_json::from_value::<ScanState>(scan_state).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Unsupported scan state version 2, expected 1"));
+    }
 }
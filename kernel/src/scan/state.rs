@@ -3,26 +3,30 @@
 use std::collections::HashMap;
 use std::sync::LazyLock;
 
+use std::sync::Arc;
+
 use crate::actions::deletion_vector::deletion_treemap_to_bools;
 use crate::scan::get_transform_for_row;
 use crate::schema::Schema;
-use crate::utils::require;
-use crate::ExpressionRef;
+use crate::table_features::ColumnMappingMode;
+use crate::utils::{deserialize_state_version, require, VersionedState};
 use crate::{
     actions::{deletion_vector::DeletionVectorDescriptor, visitors::visit_deletion_vector_at},
     engine_data::{GetData, RowVisitor, TypedGetData as _},
     schema::{ColumnName, ColumnNamesAndTypes, DataType, SchemaRef},
     DeltaResult, Engine, EngineData, Error,
 };
+use crate::{ExpressionRef, FileMeta, PredicateRef};
 use roaring::RoaringTreemap;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use tracing::warn;
+use url::Url;
 
 use super::log_replay::SCAN_ROW_SCHEMA;
-use super::ScanMetadata;
+use super::{read_scan_files, ScanMetadata, ScanResult};
 
 /// this struct can be used by an engine to materialize a selection vector
-#[derive(Default, Debug, Clone, PartialEq, Eq)]
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct DvInfo {
     pub(crate) deletion_vector: Option<DeletionVectorDescriptor>,
 }
@@ -75,6 +79,26 @@ impl DvInfo {
         Ok(dv_treemap.map(deletion_treemap_to_bools))
     }
 
+    /// Like [`Self::get_selection_vector`], but reads the deletion vector through the
+    /// [`AsyncStorageHandler`] of the given [`AsyncEngine`].
+    ///
+    /// [`AsyncStorageHandler`]: crate::async_engine::AsyncStorageHandler
+    /// [`AsyncEngine`]: crate::async_engine::AsyncEngine
+    #[cfg(feature = "async-engine")]
+    pub async fn get_selection_vector_async(
+        &self,
+        engine: &dyn crate::async_engine::AsyncEngine,
+        table_root: &url::Url,
+    ) -> DeltaResult<Option<Vec<bool>>> {
+        let Some(dv_descriptor) = self.deletion_vector.as_ref() else {
+            return Ok(None);
+        };
+        let dv_treemap = dv_descriptor
+            .read_async(engine.async_storage_handler(), table_root)
+            .await?;
+        Ok(Some(deletion_treemap_to_bools(dv_treemap)))
+    }
+
     /// Returns a vector of row indexes that should be *removed* from the result set
     pub fn get_row_indexes(
         &self,
@@ -94,7 +118,7 @@ impl DvInfo {
 /// utility function for applying a transform expression to convert data from physical to logical
 /// format
 pub fn transform_to_logical(
-    engine: &dyn Engine,
+    engine: &(impl Engine + ?Sized),
     physical_data: Box<dyn EngineData>,
     physical_schema: &SchemaRef,
     logical_schema: &Schema,
@@ -113,6 +137,162 @@ pub fn transform_to_logical(
     }
 }
 
+/// The version of the [`ScanState`] serialization format written by this version of kernel.
+pub const SCAN_STATE_VERSION: u32 = 1;
+
+/// The state needed to read the files of a [`Scan`], without access to its [`Snapshot`].
+///
+/// This allows planning a scan in one place (e.g. a coordinator) and executing it somewhere else
+/// (e.g. on workers): the planner sends the serialized [`ScanState`] along with the [`ScanFile`]s
+/// assigned to each worker, and the worker calls [`ScanState::execute`] to read them.
+///
+/// The serialized form carries a format version, and deserialization fails if the version is not
+/// supported by this version of kernel.
+///
+/// NOTE: A [`Scan`] whose predicate includes opaque expressions or predicates cannot be
+/// serialized, because their operations are only known to the engine.
+///
+/// [`Scan`]: super::Scan
+/// [`Snapshot`]: crate::Snapshot
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanState {
+    #[serde(deserialize_with = "deserialize_state_version::<ScanState, _>")]
+    version: u32,
+    table_root: Url,
+    logical_schema: SchemaRef,
+    physical_schema: SchemaRef,
+    physical_predicate: Option<PredicateRef>,
+    column_mapping_mode: ColumnMappingMode,
+}
+
+impl VersionedState for ScanState {
+    const NAME: &'static str = "scan";
+    const VERSION: u32 = SCAN_STATE_VERSION;
+}
+
+impl ScanState {
+    pub(crate) fn new(
+        table_root: Url,
+        logical_schema: SchemaRef,
+        physical_schema: SchemaRef,
+        physical_predicate: Option<PredicateRef>,
+        column_mapping_mode: ColumnMappingMode,
+    ) -> Self {
+        Self {
+            version: SCAN_STATE_VERSION,
+            table_root,
+            logical_schema,
+            physical_schema,
+            physical_predicate,
+            column_mapping_mode,
+        }
+    }
+
+    /// The version of the serialization format this state was created with.
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// The table's root URL, against which the paths of [`ScanFile`]s are resolved.
+    pub fn table_root(&self) -> &Url {
+        &self.table_root
+    }
+
+    /// The logical schema of the scan. See [`Scan::logical_schema`].
+    ///
+    /// [`Scan::logical_schema`]: super::Scan::logical_schema
+    pub fn logical_schema(&self) -> &SchemaRef {
+        &self.logical_schema
+    }
+
+    /// The physical schema of the scan. See [`Scan::physical_schema`].
+    ///
+    /// [`Scan::physical_schema`]: super::Scan::physical_schema
+    pub fn physical_schema(&self) -> &SchemaRef {
+        &self.physical_schema
+    }
+
+    /// The physical predicate of the scan, if any. See [`Scan::physical_predicate`].
+    ///
+    /// [`Scan::physical_predicate`]: super::Scan::physical_predicate
+    pub fn physical_predicate(&self) -> Option<PredicateRef> {
+        self.physical_predicate.clone()
+    }
+
+    /// The column mapping mode of the table.
+    pub fn column_mapping_mode(&self) -> ColumnMappingMode {
+        self.column_mapping_mode
+    }
+
+    /// Read the given `scan_files` using the provided `engine`, the same way [`Scan::execute`]
+    /// reads the files of a scan.
+    ///
+    /// [`Scan::execute`]: super::Scan::execute
+    pub fn execute(
+        &self,
+        engine: Arc<dyn Engine>,
+        scan_files: impl IntoIterator<Item = ScanFile>,
+    ) -> impl Iterator<Item = DeltaResult<ScanResult>> {
+        read_scan_files(
+            engine,
+            self.table_root.clone(),
+            self.physical_schema.clone(),
+            self.logical_schema.clone(),
+            scan_files.into_iter().map(Ok),
+        )
+    }
+}
+
+/// A file to read as part of a scan, as collected by [`ScanMetadata::scan_files`]. Its `path` is
+/// relative to the table root.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanFile {
+    /// The path of the file, relative to the table root.
+    pub path: String,
+    /// The size of the file in bytes.
+    pub size: i64,
+    /// The deletion vector of the file, if any.
+    pub dv_info: DvInfo,
+    /// The partition values of the file.
+    pub partition_values: HashMap<String, String>,
+    /// An optional expression that, if present, _must_ be applied to the physical data read from
+    /// the file to convert it to the logical schema of the scan.
+    pub transform: Option<ExpressionRef>,
+}
+
+impl ScanFile {
+    pub(crate) fn file_meta(&self, table_root: &Url) -> DeltaResult<FileMeta> {
+        Ok(FileMeta {
+            last_modified: 0,
+            size: self
+                .size
+                .try_into()
+                .map_err(|_| Error::generic("Unable to convert scan file size into FileSize"))?,
+            location: table_root.join(&self.path)?,
+        })
+    }
+}
+
+fn scan_file_callback(
+    scan_files: &mut Vec<ScanFile>,
+    path: &str,
+    size: i64,
+    _: Option<Stats>,
+    dv_info: DvInfo,
+    transform: Option<ExpressionRef>,
+    partition_values: HashMap<String, String>,
+) {
+    scan_files.push(ScanFile {
+        path: path.to_string(),
+        size,
+        dv_info,
+        partition_values,
+        transform,
+    });
+}
+
 pub type ScanCallback<T> = fn(
     context: &mut T,
     path: &str,
@@ -163,6 +343,13 @@ impl ScanMetadata {
         visitor.visit_rows_of(self.scan_files.data.as_ref())?;
         Ok(visitor.context)
     }
+
+    /// Collect the [`ScanFile`]s to read from this [`ScanMetadata`]. Unlike the scan metadata
+    /// itself, scan files can be serialized, e.g. to assign them to workers which read them with
+    /// [`ScanState::execute`].
+    pub fn scan_files(&self) -> DeltaResult<Vec<ScanFile>> {
+        self.visit_scan_files(vec![], scan_file_callback)
+    }
 }
 // add some visitor magic for engines
 struct ScanFileVisitor<'a, T> {
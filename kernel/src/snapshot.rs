@@ -6,28 +6,47 @@ use std::sync::Arc;
 use crate::actions::domain_metadata::domain_metadata_configuration;
 use crate::actions::set_transaction::SetTransactionScanner;
 use crate::actions::{Metadata, Protocol, INTERNAL_DOMAIN_PREFIX};
+#[cfg(feature = "async-engine")]
+use crate::async_engine::AsyncEngine;
 use crate::checkpoint::CheckpointWriter;
-use crate::last_checkpoint_hint::LastCheckpointHint;
+use crate::history::{read_history, HistoryEntry};
 use crate::listed_log_files::ListedLogFiles;
+use crate::log_cleanup::LogCleanupPlanner;
 use crate::log_segment::LogSegment;
+use crate::path::{LogPathFileType, ParsedLogPath};
 use crate::scan::ScanBuilder;
-use crate::schema::SchemaRef;
+use crate::schema::{ColumnName, SchemaRef};
 use crate::table_configuration::TableConfiguration;
-use crate::table_features::ColumnMappingMode;
+use crate::table_features::{parse_clustering_columns, ColumnMappingMode, CLUSTERING_DOMAIN_NAME};
 use crate::table_properties::TableProperties;
 use crate::transaction::Transaction;
-use crate::utils::{calculate_transaction_expiration_timestamp, try_parse_uri};
-use crate::{DeltaResult, Engine, Error, Version};
+use crate::utils::{
+    calculate_transaction_expiration_timestamp, deserialize_state_version, try_parse_uri,
+    VersionedState,
+};
+use crate::vacuum::VacuumPlanner;
+use crate::{DeltaResult, Engine, Error, FileMeta, FileSize, Version};
 use delta_kernel_derive::internal_api;
 
+use itertools::Itertools as _;
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use tracing::debug;
 use url::Url;
 
+mod builder;
+
+pub use builder::SnapshotBuilder;
+
 // TODO expose methods for accessing the files of a table (with file pruning).
 /// In-memory representation of a specific snapshot of a Delta table. While a `DeltaTable` exists
 /// throughout time, `Snapshot`s represent a view of a table at a specific point in time; they
 /// have a defined schema (which may change over time for any given table), specific version, and
 /// frozen log segment.
+///
+/// A `Snapshot` can be serialized and later restored without listing or replaying the log, e.g.
+/// to share one snapshot between many short-lived processes. The serialized form is versioned, and
+/// restoring a snapshot fails if its version is not supported by this version of kernel.
 #[derive(PartialEq, Eq)]
 pub struct Snapshot {
     log_segment: LogSegment,
@@ -90,16 +109,36 @@ impl Snapshot {
         engine: &dyn Engine,
         version: Option<Version>,
     ) -> DeltaResult<Self> {
-        let storage = engine.storage_handler();
-        let log_root = table_root.join("_delta_log/")?;
-
-        let checkpoint_hint = LastCheckpointHint::try_read(storage.as_ref(), &log_root)?;
+        let builder = Self::builder(table_root);
+        match version {
+            Some(version) => builder.at_version(version),
+            None => builder,
+        }
+        .build(engine)
+    }
 
-        let log_segment =
-            LogSegment::for_snapshot(storage.as_ref(), log_root, checkpoint_hint, version)?;
+    /// Create a [`SnapshotBuilder`] for the table at `table_root` (where the `_delta_log` folder is
+    /// located). Unlike [`Snapshot::try_new`], the builder can also create snapshots of
+    /// catalog-managed tables from the log tail provided by their catalog.
+    pub fn builder(table_root: Url) -> SnapshotBuilder {
+        SnapshotBuilder::new(table_root)
+    }
 
-        // try_new_from_log_segment will ensure the protocol is supported
-        Self::try_new_from_log_segment(table_root, log_segment, engine)
+    /// Create a new [`Snapshot`] instance for the given version, performing all IO through the
+    /// async handlers of the given [`AsyncEngine`]. See [`Snapshot::try_new`].
+    #[cfg(feature = "async-engine")]
+    pub async fn try_new_async(
+        table_root: Url,
+        engine: &dyn AsyncEngine,
+        version: Option<Version>,
+    ) -> DeltaResult<Self> {
+        let builder = Self::builder(table_root);
+        match version {
+            Some(version) => builder.at_version(version),
+            None => builder,
+        }
+        .build_async(engine)
+        .await
     }
 
     /// Create a new [`Snapshot`] instance from an existing [`Snapshot`]. This is useful when you
@@ -270,6 +309,15 @@ impl Snapshot {
         engine: &dyn Engine,
     ) -> DeltaResult<Self> {
         let (metadata, protocol) = log_segment.read_metadata(engine)?;
+        Self::try_new_from_metadata(location, log_segment, metadata, protocol)
+    }
+
+    fn try_new_from_metadata(
+        location: Url,
+        log_segment: LogSegment,
+        metadata: Metadata,
+        protocol: Protocol,
+    ) -> DeltaResult<Self> {
         let table_configuration =
             TableConfiguration::try_new(metadata, protocol, location, log_segment.end_version)?;
         Ok(Self {
@@ -286,6 +334,36 @@ impl Snapshot {
         CheckpointWriter::try_new(self)
     }
 
+    /// Creates a [`VacuumPlanner`] for deleting the data files that are no longer referenced by
+    /// this snapshot.
+    ///
+    /// See the [`crate::vacuum`] module documentation for more details.
+    pub fn vacuum(self: Arc<Self>) -> DeltaResult<VacuumPlanner> {
+        VacuumPlanner::try_new(self)
+    }
+
+    /// Creates a [`LogCleanupPlanner`] for deleting the expired files of this table's log.
+    ///
+    /// See the [`crate::log_cleanup`] module documentation for more details.
+    pub fn log_cleanup(self: Arc<Self>) -> DeltaResult<LogCleanupPlanner> {
+        LogCleanupPlanner::try_new(self)
+    }
+
+    /// Read the history of the table as of this snapshot: the [`HistoryEntry`] of each commit up
+    /// to this snapshot's version, newest first. If `limit` is given, at most `limit` of the newest
+    /// commits are read.
+    ///
+    /// Only the commit files of the returned entries are listed and read. Commits which have been
+    /// cleaned up (see [`Snapshot::log_cleanup`]) are not part of the history, i.e. the history
+    /// ends at the oldest commit still present in the log.
+    pub fn history(
+        &self,
+        engine: &dyn Engine,
+        limit: Option<usize>,
+    ) -> DeltaResult<Vec<HistoryEntry>> {
+        read_history(self, engine, limit)
+    }
+
     /// Log segment this snapshot uses
     #[internal_api]
     pub(crate) fn log_segment(&self) -> &LogSegment {
@@ -391,6 +469,141 @@ impl Snapshot {
 
         domain_metadata_configuration(self.log_segment(), domain, engine)
     }
+
+    /// Fetch the clustering columns of this snapshot, as logical column names. This returns None if
+    /// the table is not clustered, i.e. it does not support the `clustering` writer feature or has
+    /// no `delta.clustering` domain metadata.
+    ///
+    /// Note that this method performs log replay (fetches and processes metadata from storage).
+    pub fn get_clustering_columns(
+        &self,
+        engine: &dyn Engine,
+    ) -> DeltaResult<Option<Vec<ColumnName>>> {
+        if !self.table_configuration.is_clustering_supported() {
+            return Ok(None);
+        }
+        domain_metadata_configuration(self.log_segment(), CLUSTERING_DOMAIN_NAME, engine)?
+            .map(|configuration| parse_clustering_columns(&configuration, &self.schema()))
+            .transpose()
+    }
+}
+
+/// The version of the serialized [`Snapshot`] format written by this version of kernel.
+const SNAPSHOT_STATE_VERSION: u32 = 1;
+
+/// The serialized form of a [`Snapshot`]: the files of its log segment (relative to the log root)
+/// and its protocol and metadata. Restoring a snapshot from it needs no IO, and the restored
+/// snapshot can be refreshed with [`Snapshot::try_new_from`] like any other.
+///
+/// NOTE: The `_last_checkpoint` hint is deliberately not part of the state. A snapshot does not
+/// keep the hint once its log segment is listed: the checkpoint the hint pointed to is already
+/// among the log files, so a restored snapshot is equivalent to the original.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SnapshotState {
+    #[serde(deserialize_with = "deserialize_state_version::<SnapshotState, _>")]
+    version: u32,
+    table_root: Url,
+    table_version: Version,
+    log_files: Vec<LogFileState>,
+    protocol: Protocol,
+    metadata: Metadata,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LogFileState {
+    /// The path of the file relative to the log root, e.g. `_staged_commits/<version>.<uuid>.json`
+    path: String,
+    size: FileSize,
+    last_modified: i64,
+}
+
+impl VersionedState for SnapshotState {
+    const NAME: &'static str = "snapshot";
+    const VERSION: u32 = SNAPSHOT_STATE_VERSION;
+}
+
+impl From<&Snapshot> for SnapshotState {
+    fn from(snapshot: &Snapshot) -> Self {
+        let log_segment = &snapshot.log_segment;
+        let mut log_files: Vec<_> = log_segment
+            .checkpoint_parts
+            .iter()
+            .chain(&log_segment.ascending_compaction_files)
+            .chain(&log_segment.ascending_commit_files)
+            .chain(&log_segment.latest_crc_file)
+            .collect();
+        // Restoring regroups the files by version, so they must be ordered by version
+        log_files.sort_by_key(|file| file.version);
+        let log_root = &log_segment.log_root;
+        let log_files = log_files
+            .into_iter()
+            .map(|file| LogFileState {
+                // log files always live within the log root
+                path: log_root
+                    .make_relative(&file.location.location)
+                    .unwrap_or_else(|| file.filename.clone()),
+                size: file.location.size,
+                last_modified: file.location.last_modified,
+            })
+            .collect();
+        Self {
+            version: SNAPSHOT_STATE_VERSION,
+            table_root: snapshot.table_root().clone(),
+            table_version: snapshot.version(),
+            log_files,
+            protocol: snapshot.protocol().clone(),
+            metadata: snapshot.metadata().clone(),
+        }
+    }
+}
+
+impl TryFrom<SnapshotState> for Snapshot {
+    type Error = Error;
+
+    fn try_from(state: SnapshotState) -> DeltaResult<Self> {
+        let log_root = state.table_root.join("_delta_log/")?;
+        let log_files = state.log_files.into_iter().map(|file| {
+            let location = log_root.join(&file.path)?;
+            let file_meta = FileMeta {
+                location: location.clone(),
+                last_modified: file.last_modified,
+                size: file.size,
+            };
+            ParsedLogPath::try_from(file_meta)?.ok_or_else(|| Error::invalid_log_path(&location))
+        });
+        // staged commits were part of the log tail of the serialized snapshot
+        let (log_tail, log_files): (Vec<_>, Vec<_>) = log_files.process_results(|files| {
+            files.partition(|file| file.file_type == LogPathFileType::StagedCommit)
+        })?;
+        let end_version = Some(state.table_version);
+        let listed_files =
+            ListedLogFiles::from_log_files(log_files.into_iter().map(Ok), end_version)?
+                .with_log_tail(log_tail, end_version);
+        let log_segment = LogSegment::try_new(listed_files, log_root, Some(state.table_version))?;
+        // NOTE: This validates the protocol again, in case the snapshot was serialized by a
+        // version of kernel that supports more table features than this one.
+        Self::try_new_from_metadata(
+            state.table_root,
+            log_segment,
+            state.metadata,
+            state.protocol,
+        )
+    }
+}
+
+impl Serialize for Snapshot {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SnapshotState::from(self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Snapshot {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let state = SnapshotState::deserialize(deserializer)?;
+        Snapshot::try_from(state).map_err(D::Error::custom)
+    }
 }
 
 #[cfg(test)]
@@ -776,6 +989,84 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_snapshot_serde_roundtrip() {
+        let path = std::fs::canonicalize(PathBuf::from(
+            "./tests/data/with_checkpoint_no_last_checkpoint/",
+        ))
+        .unwrap();
+        let url = url::Url::from_directory_path(path).unwrap();
+        let engine = SyncEngine::new();
+
+        let latest = Snapshot::try_new(url.clone(), &engine, None).unwrap();
+        for version in 0..=3 {
+            let snapshot = Snapshot::try_new(url.clone(), &engine, Some(version)).unwrap();
+            let json = serde_json::to_string(&snapshot).unwrap();
+            let restored: Snapshot = serde_json::from_str(&json).unwrap();
+            assert_eq!(restored, snapshot);
+
+            // a restored snapshot can be incrementally refreshed
+            let refreshed = Snapshot::try_new_from(Arc::new(restored), &engine, None).unwrap();
+            assert_eq!(*refreshed, latest);
+        }
+    }
+
+    #[test]
+    fn test_snapshot_serde_with_last_checkpoint() {
+        // the `_last_checkpoint` hint is not serialized, but the checkpoint it points to is
+        let path =
+            std::fs::canonicalize(PathBuf::from("./tests/data/app-txn-checkpoint/")).unwrap();
+        let url = url::Url::from_directory_path(path).unwrap();
+        let engine = SyncEngine::new();
+
+        let snapshot = Snapshot::try_new(url, &engine, None).unwrap();
+        assert_eq!(snapshot.log_segment().checkpoint_version, Some(1));
+        let json = serde_json::to_string(&snapshot).unwrap();
+        let restored: Snapshot = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, snapshot);
+        assert_eq!(restored.log_segment().checkpoint_version, Some(1));
+    }
+
+    #[test]
+    fn test_snapshot_serde_log_files() {
+        let path = std::fs::canonicalize(PathBuf::from(
+            "./tests/data/with_checkpoint_no_last_checkpoint/",
+        ))
+        .unwrap();
+        let url = url::Url::from_directory_path(path).unwrap();
+        let engine = SyncEngine::new();
+
+        let snapshot = Snapshot::try_new(url, &engine, None).unwrap();
+        let state = serde_json::to_value(&snapshot).unwrap();
+        assert_eq!(state["version"], SNAPSHOT_STATE_VERSION);
+        assert_eq!(state["tableVersion"], 3);
+        let log_files: Vec<_> = state["logFiles"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|file| file["path"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            log_files,
+            [
+                "00000000000000000002.checkpoint.parquet",
+                "00000000000000000003.json"
+            ]
+        );
+
+        let mut unsupported = state.clone();
+        unsupported["version"] = (SNAPSHOT_STATE_VERSION + 1).into();
+        let err = serde_json::from_value::<Snapshot>(unsupported).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Unsupported snapshot state version 2, expected 1"));
+
+        // the restored log segment is validated
+        let mut missing_commit = state;
+        missing_commit["tableVersion"] = 4.into();
+        serde_json::from_value::<Snapshot>(missing_commit).unwrap_err();
+    }
+
     #[test]
     fn test_read_table_with_missing_last_checkpoint() {
         // this table doesn't have a _last_checkpoint file
@@ -0,0 +1,359 @@
+//! Builder for creating [`Snapshot`] instances.
+
+use url::Url;
+
+use super::Snapshot;
+#[cfg(feature = "async-engine")]
+use crate::async_engine::AsyncEngine;
+use crate::last_checkpoint_hint::LastCheckpointHint;
+use crate::log_segment::LogSegment;
+#[cfg(feature = "catalog-managed")]
+use crate::path::LogPath;
+use crate::path::ParsedLogPath;
+#[cfg(feature = "catalog-managed")]
+use crate::utils::require;
+#[cfg(feature = "catalog-managed")]
+use crate::Error;
+use crate::{DeltaResult, Engine, Version};
+
+/// Builder for a [`Snapshot`] of a table. Create one with [`Snapshot::builder`].
+///
+/// # Example
+///
+/// ```no_run
+/// # use delta_kernel::{Engine, Error, Snapshot};
+/// # use url::Url;
+/// # let engine: &dyn Engine = todo!();
+/// let table_root = Url::parse("s3://bucket/table/")?;
+/// // the latest version of the table
+/// let snapshot = Snapshot::builder(table_root.clone()).build(engine)?;
+/// // version 5 of the table
+/// let snapshot = Snapshot::builder(table_root).at_version(5).build(engine)?;
+/// # Ok::<_, Error>(())
+/// ```
+#[derive(Debug)]
+pub struct SnapshotBuilder {
+    table_root: Url,
+    version: Option<Version>,
+    #[cfg(feature = "catalog-managed")]
+    log_tail: Vec<LogPath>,
+    #[cfg(feature = "catalog-managed")]
+    max_catalog_version: Option<Version>,
+}
+
+impl SnapshotBuilder {
+    pub(crate) fn new(table_root: Url) -> Self {
+        Self {
+            table_root,
+            version: None,
+            #[cfg(feature = "catalog-managed")]
+            log_tail: vec![],
+            #[cfg(feature = "catalog-managed")]
+            max_catalog_version: None,
+        }
+    }
+
+    /// Build the snapshot at the given version of the table, instead of the latest one.
+    pub fn at_version(mut self, version: Version) -> Self {
+        self.version = Some(version);
+        self
+    }
+
+    /// Provide the log tail of a catalog-managed table, i.e. the latest commits ratified by the
+    /// catalog, in ascending and contiguous version order. These may be staged commits (in
+    /// `_delta_log/_staged_commits/`) which have not been published to the log yet. The log tail
+    /// takes precedence over the published commits of the same versions.
+    #[cfg(feature = "catalog-managed")]
+    pub fn with_log_tail(mut self, log_tail: Vec<LogPath>) -> Self {
+        self.log_tail = log_tail;
+        self
+    }
+
+    /// Provide the latest table version ratified by the catalog of a catalog-managed table. The
+    /// snapshot is built at this version (unless an older version was requested with
+    /// [`SnapshotBuilder::at_version`]), and never reads commits after it, even if they were
+    /// published.
+    #[cfg(feature = "catalog-managed")]
+    pub fn with_max_catalog_version(mut self, max_catalog_version: Version) -> Self {
+        self.max_catalog_version = Some(max_catalog_version);
+        self
+    }
+
+    /// Create the [`Snapshot`], listing the table's log with the `engine`'s storage handler.
+    pub fn build(self, engine: &dyn Engine) -> DeltaResult<Snapshot> {
+        let (table_root, version, log_tail) = self.into_parts()?;
+        let storage = engine.storage_handler();
+        let log_root = table_root.join("_delta_log/")?;
+
+        let checkpoint_hint = LastCheckpointHint::try_read(storage.as_ref(), &log_root)?;
+
+        let log_segment = LogSegment::for_snapshot(
+            storage.as_ref(),
+            log_root,
+            checkpoint_hint,
+            version,
+            log_tail,
+        )?;
+
+        // try_new_from_log_segment will ensure the protocol is supported
+        Snapshot::try_new_from_log_segment(table_root, log_segment, engine)
+    }
+
+    /// Create the [`Snapshot`], performing all IO through the async handlers of the given
+    /// [`AsyncEngine`]. See [`SnapshotBuilder::build`].
+    #[cfg(feature = "async-engine")]
+    pub async fn build_async(self, engine: &dyn AsyncEngine) -> DeltaResult<Snapshot> {
+        let (table_root, version, log_tail) = self.into_parts()?;
+        let storage = engine.async_storage_handler();
+        let log_root = table_root.join("_delta_log/")?;
+
+        let checkpoint_hint =
+            LastCheckpointHint::try_read_async(storage.as_ref(), &log_root).await?;
+
+        let log_segment = LogSegment::for_snapshot_async(
+            storage.as_ref(),
+            log_root,
+            checkpoint_hint,
+            version,
+            log_tail,
+        )
+        .await?;
+
+        let (metadata, protocol) = log_segment.read_metadata_async(engine).await?;
+        Snapshot::try_new_from_metadata(table_root, log_segment, metadata, protocol)
+    }
+
+    // The table root, the version of the snapshot to build (if known before listing the log) and
+    // the log tail to merge into the listed log files.
+    fn into_parts(self) -> DeltaResult<(Url, Option<Version>, Vec<ParsedLogPath>)> {
+        let version = self.end_version()?;
+        #[cfg(feature = "catalog-managed")]
+        let log_tail = self.log_tail.into_iter().map(|path| path.0).collect();
+        #[cfg(not(feature = "catalog-managed"))]
+        let log_tail = vec![];
+        Ok((self.table_root, version, log_tail))
+    }
+
+    // The version of the snapshot to build, if known before listing the log.
+    #[cfg(feature = "catalog-managed")]
+    fn end_version(&self) -> DeltaResult<Option<Version>> {
+        let Some(max_catalog_version) = self.max_catalog_version else {
+            return Ok(self.version);
+        };
+        if let Some(last) = self.log_tail.last() {
+            require!(
+                last.version() == max_catalog_version,
+                Error::generic(format!(
+                    "The log tail ends at version {}, but the max catalog version is {max_catalog_version}",
+                    last.version()
+                ))
+            );
+        }
+        match self.version {
+            Some(version) => {
+                require!(
+                    version <= max_catalog_version,
+                    Error::generic(format!(
+                        "Requested snapshot version {version} is newer than the max catalog version {max_catalog_version}"
+                    ))
+                );
+                Ok(Some(version))
+            }
+            None => Ok(Some(max_catalog_version)),
+        }
+    }
+
+    #[cfg(not(feature = "catalog-managed"))]
+    fn end_version(&self) -> DeltaResult<Option<Version>> {
+        Ok(self.version)
+    }
+}
+
+#[cfg(all(test, feature = "catalog-managed"))]
+mod tests {
+    use std::sync::Arc;
+
+    use object_store::memory::InMemory;
+    use object_store::path::Path;
+    use object_store::ObjectStore as _;
+    use serde_json::json;
+    use test_utils::add_commit;
+
+    use super::*;
+    use crate::engine::default::executor::tokio::TokioBackgroundExecutor;
+    use crate::engine::default::DefaultEngine;
+    use crate::FileMeta;
+
+    fn table_root() -> Url {
+        Url::parse("memory:///").unwrap()
+    }
+
+    fn metadata_commit(partition_column: &str) -> String {
+        let schema = json!({
+            "type": "struct",
+            "fields": [
+                {"name": "id", "type": "integer", "nullable": true, "metadata": {}},
+                {"name": partition_column, "type": "string", "nullable": true, "metadata": {}}
+            ]
+        });
+        json!({
+            "metaData": {
+                "id": "test_id",
+                "format": {"provider": "parquet", "options": {}},
+                "schemaString": schema.to_string(),
+                "partitionColumns": [partition_column],
+                "configuration": {},
+                "createdTime": 1677811175819u64
+            }
+        })
+        .to_string()
+    }
+
+    // Sets up a table with published commits 0 and 1, and staged commits 1 and 2 whose metadata
+    // partitions by `staged1` and `staged2`.
+    async fn setup() -> (
+        Arc<InMemory>,
+        DefaultEngine<TokioBackgroundExecutor>,
+        Vec<LogPath>,
+    ) {
+        let store = Arc::new(InMemory::new());
+        let protocol = json!({"protocol": {"minReaderVersion": 1, "minWriterVersion": 2}});
+        let commit0 = [protocol.to_string(), metadata_commit("published0")].join("\n");
+        add_commit(store.as_ref(), 0, commit0).await.unwrap();
+        add_commit(store.as_ref(), 1, metadata_commit("published1"))
+            .await
+            .unwrap();
+
+        let mut log_tail = vec![];
+        for version in [1u64, 2] {
+            let path = format!(
+                "_delta_log/_staged_commits/{version:020}.{}.json",
+                uuid::Uuid::new_v4()
+            );
+            let data = metadata_commit(&format!("staged{version}"));
+            store
+                .put(&Path::from(path.as_str()), data.into())
+                .await
+                .unwrap();
+            let location = table_root().join(&path).unwrap();
+            let file_meta = FileMeta::new(location, 0, 0);
+            log_tail.push(LogPath::try_new(file_meta).unwrap());
+        }
+        let engine = DefaultEngine::new(store.clone(), Arc::new(TokioBackgroundExecutor::new()));
+        (store, engine, log_tail)
+    }
+
+    fn partition_column(snapshot: &Snapshot) -> &str {
+        &snapshot.metadata().partition_columns[0]
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_with_log_tail() {
+        let (_store, engine, log_tail) = setup().await;
+
+        // without the log tail, only the published commits are visible
+        let snapshot = Snapshot::builder(table_root()).build(&engine).unwrap();
+        assert_eq!(snapshot.version(), 1);
+        assert_eq!(partition_column(&snapshot), "published1");
+
+        // the log tail takes precedence over published commits
+        let snapshot = Snapshot::builder(table_root())
+            .with_log_tail(log_tail.clone())
+            .with_max_catalog_version(2)
+            .build(&engine)
+            .unwrap();
+        assert_eq!(snapshot.version(), 2);
+        assert_eq!(partition_column(&snapshot), "staged2");
+        let commits = &snapshot.log_segment().ascending_commit_files;
+        assert_eq!(commits.len(), 3);
+        assert_eq!(commits[1].location, log_tail[0].0.location);
+
+        // time travel into the log tail
+        let snapshot = Snapshot::builder(table_root())
+            .at_version(1)
+            .with_log_tail(log_tail.clone())
+            .with_max_catalog_version(2)
+            .build(&engine)
+            .unwrap();
+        assert_eq!(snapshot.version(), 1);
+        assert_eq!(partition_column(&snapshot), "staged1");
+
+        // time travel before the log tail
+        let snapshot = Snapshot::builder(table_root())
+            .at_version(0)
+            .with_log_tail(log_tail)
+            .build(&engine)
+            .unwrap();
+        assert_eq!(partition_column(&snapshot), "published0");
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_with_log_tail_serde() {
+        let (_store, engine, log_tail) = setup().await;
+        let snapshot = Snapshot::builder(table_root())
+            .with_log_tail(log_tail.clone())
+            .with_max_catalog_version(2)
+            .build(&engine)
+            .unwrap();
+
+        // staged commits are serialized relative to the log root
+        let state = serde_json::to_value(&snapshot).unwrap();
+        let path = state["logFiles"][1]["path"].as_str().unwrap();
+        assert!(path.starts_with("_staged_commits/00000000000000000001."));
+
+        let restored: Snapshot = serde_json::from_value(state).unwrap();
+        assert_eq!(restored, snapshot);
+        let commits = &restored.log_segment().ascending_commit_files;
+        assert_eq!(commits[1].location, log_tail[0].0.location);
+        assert_eq!(commits[2].location, log_tail[1].0.location);
+    }
+
+    #[cfg(feature = "async-engine")]
+    #[tokio::test]
+    async fn test_snapshot_with_log_tail_async() {
+        let (_store, engine, log_tail) = setup().await;
+        let snapshot = Snapshot::builder(table_root())
+            .with_log_tail(log_tail.clone())
+            .with_max_catalog_version(2)
+            .build_async(&engine)
+            .await
+            .unwrap();
+        assert_eq!(snapshot.version(), 2);
+        assert_eq!(partition_column(&snapshot), "staged2");
+        let commits = &snapshot.log_segment().ascending_commit_files;
+        assert_eq!(commits[1].location, log_tail[0].0.location);
+    }
+
+    #[tokio::test]
+    async fn test_max_catalog_version() {
+        let (_store, engine, log_tail) = setup().await;
+
+        // published commits after the max catalog version are ignored
+        let snapshot = Snapshot::builder(table_root())
+            .with_max_catalog_version(0)
+            .build(&engine)
+            .unwrap();
+        assert_eq!(snapshot.version(), 0);
+
+        // the log tail must end at the max catalog version
+        let result = Snapshot::builder(table_root())
+            .with_log_tail(log_tail[..1].to_vec())
+            .with_max_catalog_version(2)
+            .build(&engine);
+        assert!(result.is_err());
+
+        // the requested version must be ratified
+        let result = Snapshot::builder(table_root())
+            .at_version(3)
+            .with_log_tail(log_tail.clone())
+            .with_max_catalog_version(2)
+            .build(&engine);
+        assert!(result.is_err());
+
+        // the log tail must be contiguous
+        let result = Snapshot::builder(table_root())
+            .with_log_tail(vec![log_tail[1].clone(), log_tail[0].clone()])
+            .build(&engine);
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,776 @@
+//! A streaming source over the committed versions of a table, for incremental processing.
+//!
+//! A [`StreamingSource`] yields the files added by each commit of a table as a sequence of
+//! [`StreamBatch`]es. Every batch carries the [`StreamOffset`]s where it starts and ends, so that a
+//! stream can be resumed from the end offset of the last batch it processed.
+//!
+//! # Example
+//! ```rust
+//! # use test_utils::DefaultEngineExtension;
+//! # use delta_kernel::engine::default::DefaultEngine;
+//! # use delta_kernel::streaming::{StartingVersion, StreamingOptions, StreamingSource};
+//! # use delta_kernel::Error;
+//! # let path = "./tests/data/basic_partitioned";
+//! # let engine = DefaultEngine::new_local();
+//! let url = delta_kernel::try_parse_uri(path)?;
+//! let options = StreamingOptions {
+//!     max_files_per_batch: Some(2),
+//!     ..Default::default()
+//! };
+//! let source = StreamingSource::try_new(
+//!     url,
+//!     engine.as_ref(),
+//!     StartingVersion::Version(0),
+//!     options,
+//! )?;
+//!
+//! // process all currently available batches
+//! let mut offset = source.start_offset();
+//! for batch in source.batches(engine.as_ref(), offset)? {
+//!     let batch = batch?;
+//!     // read `batch.files`, e.g. with `source.scan_state().execute(...)`
+//!     offset = batch.end_offset;
+//! }
+//! // ... and later, poll for new batches starting at `offset`
+//! # Ok::<(), Error>(())
+//! ```
+use std::slice;
+use std::sync::LazyLock;
+
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::actions::visitors::{AddVisitor, CommitInfoVisitor, RemoveVisitor};
+use crate::actions::{
+    get_log_schema, Add, Metadata, Protocol, ADD_NAME, COMMIT_INFO_NAME, METADATA_NAME,
+    PROTOCOL_NAME, REMOVE_NAME,
+};
+use crate::log_segment::LogSegment;
+use crate::path::ParsedLogPath;
+use crate::scan::state::{DvInfo, ScanFile, ScanState};
+use crate::scan::Scan;
+use crate::schema::compare::SchemaComparison as _;
+use crate::schema::SchemaRef;
+use crate::snapshot::Snapshot;
+use crate::utils::require;
+use crate::{DeltaResult, Engine, Error, RowVisitor as _, Version};
+
+// safety: we define get_log_schema() and _know_ it contains these actions
+#[allow(clippy::unwrap_used)]
+static STREAMING_READ_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
+    get_log_schema()
+        .project(&[
+            ADD_NAME,
+            REMOVE_NAME,
+            METADATA_NAME,
+            PROTOCOL_NAME,
+            COMMIT_INFO_NAME,
+        ])
+        .unwrap()
+});
+
+/// The version a [`StreamingSource`] starts streaming at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StartingVersion {
+    /// Only stream commits made after the latest version of the table.
+    Latest,
+    /// Stream all commits starting at (and including) the given version.
+    Version(Version),
+}
+
+/// Options controlling the batches yielded by a [`StreamingSource`].
+#[derive(Debug, Clone, Default)]
+pub struct StreamingOptions {
+    /// The maximum number of files in a batch. Batches always contain at least one file (if the
+    /// commit added any).
+    pub max_files_per_batch: Option<usize>,
+    /// The maximum total size (in bytes) of the files in a batch. Batches always contain at least
+    /// one file (if the commit added any), even if it is larger than this limit.
+    pub max_bytes_per_batch: Option<u64>,
+    /// Skip commits which only remove data (e.g. deleting whole partitions), instead of failing.
+    pub ignore_deletes: bool,
+    /// Skip removed files of commits which rewrite data (e.g. updates and merges), instead of
+    /// failing. The files added by such commits are yielded, so the rewritten data which did not
+    /// change is processed again.
+    pub ignore_changes: bool,
+}
+
+/// A position in the stream of files added to a table: the `index`-th file (with
+/// `dataChange = true`) added by the commit with the given `version`. Offsets are ordered, and
+/// can be serialized to persist the progress of a stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct StreamOffset {
+    /// The table version of the commit.
+    pub version: Version,
+    /// The index of the file among the files added by the commit.
+    pub index: usize,
+}
+
+impl StreamOffset {
+    /// The offset of the first file of the commit with the given `version`.
+    pub fn new(version: Version) -> Self {
+        Self { version, index: 0 }
+    }
+}
+
+/// Information about the commit a [`StreamBatch`] was read from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StreamCommit {
+    /// The table version of the commit.
+    pub version: Version,
+    /// The timestamp of the commit, in milliseconds since the epoch. This is the in-commit
+    /// timestamp if in-commit timestamps are enabled at the version of the commit (as of the
+    /// stream's snapshot), and the modification time of the commit file otherwise.
+    pub timestamp: i64,
+    /// The operation of the commit (e.g. `WRITE`), if recorded.
+    pub operation: Option<String>,
+    /// The engine which made the commit, if recorded.
+    pub engine_info: Option<String>,
+}
+
+/// A batch of files added to the table by a single commit.
+#[derive(Debug, Clone)]
+pub struct StreamBatch {
+    /// The commit the files were added by.
+    pub commit: StreamCommit,
+    /// The added files. These can be read with the [`ScanState`] of the [`StreamingSource`].
+    pub files: Vec<ScanFile>,
+    /// The offset of the first file of this batch.
+    pub start_offset: StreamOffset,
+    /// The offset to resume the stream from after this batch has been processed.
+    pub end_offset: StreamOffset,
+}
+
+/// A source of the files added to a table by each commit, for streaming (incremental) reads. See
+/// the [module documentation](self) for an example.
+///
+/// The source only yields files added with `dataChange = true`, i.e. files rewritten without
+/// changing the data (e.g. by compaction) are skipped. Commits which change existing data can only
+/// be processed with [`StreamingOptions::ignore_deletes`] or [`StreamingOptions::ignore_changes`].
+/// Additive schema changes (new nullable columns) are allowed, but the stream keeps reading with
+/// its original schema. Other changes to the schema or partitioning of the table require
+/// restarting the stream at the version of the change. In both cases, reading the commit fails with
+/// [`Error::NonAdditiveChange`].
+#[derive(Debug)]
+pub struct StreamingSource {
+    scan: Scan,
+    start_offset: StreamOffset,
+    options: StreamingOptions,
+}
+
+impl StreamingSource {
+    /// Create a new [`StreamingSource`] for the table at `table_root`, starting at
+    /// `starting_version`.
+    pub fn try_new(
+        table_root: Url,
+        engine: &dyn Engine,
+        starting_version: StartingVersion,
+        options: StreamingOptions,
+    ) -> DeltaResult<Self> {
+        let latest_snapshot = Snapshot::try_new(table_root, engine, None)?;
+        let start_version = match starting_version {
+            StartingVersion::Latest => latest_snapshot.version() + 1,
+            StartingVersion::Version(version) => version,
+        };
+        Self::try_new_from_latest(
+            latest_snapshot,
+            engine,
+            StreamOffset::new(start_version),
+            options,
+        )
+    }
+
+    /// Create a new [`StreamingSource`] for the table at `table_root`, resuming a stream at
+    /// `offset` (usually the end offset of the last batch the stream processed).
+    pub fn try_new_from_offset(
+        table_root: Url,
+        engine: &dyn Engine,
+        offset: StreamOffset,
+        options: StreamingOptions,
+    ) -> DeltaResult<Self> {
+        let latest_snapshot = Snapshot::try_new(table_root, engine, None)?;
+        Self::try_new_from_latest(latest_snapshot, engine, offset, options)
+    }
+
+    fn try_new_from_latest(
+        latest_snapshot: Snapshot,
+        engine: &dyn Engine,
+        start_offset: StreamOffset,
+        options: StreamingOptions,
+    ) -> DeltaResult<Self> {
+        let latest_version = latest_snapshot.version();
+        require!(
+            start_offset.version <= latest_version + 1,
+            Error::generic(format!(
+                "Cannot start streaming at version {}: the latest table version is {latest_version}",
+                start_offset.version
+            ))
+        );
+        // The stream reads data with the schema of the table at the start version.
+        let snapshot = if start_offset.version < latest_version {
+            let table_root = latest_snapshot.table_root().clone();
+            Snapshot::try_new(table_root, engine, Some(start_offset.version))?
+        } else {
+            latest_snapshot
+        };
+        let scan = snapshot.into_scan_builder().build()?;
+        Ok(Self {
+            scan,
+            start_offset,
+            options,
+        })
+    }
+
+    /// The offset this source was created at.
+    pub fn start_offset(&self) -> StreamOffset {
+        self.start_offset
+    }
+
+    /// The logical schema of the data read by the stream.
+    pub fn schema(&self) -> &SchemaRef {
+        self.scan.logical_schema()
+    }
+
+    /// The [`ScanState`] to read the files of [`StreamBatch`]es with.
+    pub fn scan_state(&self) -> ScanState {
+        self.scan.scan_state()
+    }
+
+    /// Get an iterator of the [`StreamBatch`]es of all commits currently in the table, starting at
+    /// `offset`. Each commit yields at least one batch (without any files if the commit didn't
+    /// add data), and more if [`StreamingOptions`] limit the size of batches. The iterator is
+    /// empty if no commit was made at or after `offset`.
+    ///
+    /// Fails with [`Error::NonAdditiveChange`] upon reaching a commit the stream cannot process.
+    pub fn batches<'a>(
+        &'a self,
+        engine: &'a dyn Engine,
+        offset: StreamOffset,
+    ) -> DeltaResult<impl Iterator<Item = DeltaResult<StreamBatch>> + 'a> {
+        let storage = engine.storage_handler();
+        let table_root = self.scan.table_root();
+        // Only commits after the version of our snapshot may not exist (yet).
+        if offset.version > self.scan.snapshot().version() {
+            let commit = ParsedLogPath::new_commit(table_root, offset.version)?;
+            match storage.head(&commit.location) {
+                Ok(_) => (),
+                Err(Error::FileNotFound(_)) => return Ok(None.into_iter().flatten()),
+                Err(err) => return Err(err),
+            }
+        }
+        let log_segment = LogSegment::for_table_changes(
+            storage.as_ref(),
+            table_root.join("_delta_log/")?,
+            offset.version,
+            None,
+        )?;
+        let batches = log_segment
+            .ascending_commit_files
+            .into_iter()
+            .map(move |commit_file| {
+                let start_index = match commit_file.version == offset.version {
+                    true => offset.index,
+                    false => 0,
+                };
+                self.read_commit(engine, commit_file, start_index)
+            })
+            .flatten_ok();
+        Ok(Some(batches).into_iter().flatten())
+    }
+
+    // Read the batches of a single commit, starting at its `start_index`-th file.
+    fn read_commit(
+        &self,
+        engine: &dyn Engine,
+        commit_file: ParsedLogPath,
+        start_index: usize,
+    ) -> DeltaResult<Vec<StreamBatch>> {
+        let version = commit_file.version;
+        let actions = engine.json_handler().read_json_files(
+            slice::from_ref(&commit_file.location),
+            STREAMING_READ_SCHEMA.clone(),
+            None,
+        )?;
+        let mut add_visitor = AddVisitor::default();
+        let mut remove_visitor = RemoveVisitor::default();
+        let mut commit_info_visitor = CommitInfoVisitor::default();
+        for actions in actions {
+            let actions = actions?;
+            add_visitor.visit_rows_of(actions.as_ref())?;
+            remove_visitor.visit_rows_of(actions.as_ref())?;
+            commit_info_visitor.visit_rows_of(actions.as_ref())?;
+            if let Some(protocol) = Protocol::try_new_from_data(actions.as_ref())? {
+                protocol.ensure_read_supported()?;
+            }
+            if let Some(metadata) = Metadata::try_new_from_data(actions.as_ref())? {
+                self.check_metadata(version, &metadata)?;
+            }
+        }
+
+        let adds: Vec<_> = add_visitor
+            .adds
+            .into_iter()
+            .filter(|add| add.data_change)
+            .collect();
+        if remove_visitor
+            .removes
+            .iter()
+            .any(|remove| remove.data_change)
+        {
+            self.check_removes_ignored(version, !adds.is_empty())?;
+        }
+
+        let commit_info = commit_info_visitor.commit_info;
+        let ict_enablement_version = self
+            .scan
+            .snapshot()
+            .table_configuration()
+            .in_commit_timestamp_enablement_version();
+        let commit = StreamCommit {
+            version,
+            timestamp: commit_info
+                .as_ref()
+                .and_then(|commit_info| commit_info.in_commit_timestamp)
+                .filter(|_| ict_enablement_version.is_some_and(|v| v <= version))
+                .unwrap_or(commit_file.location.last_modified),
+            operation: commit_info
+                .as_ref()
+                .and_then(|commit_info| commit_info.operation.clone()),
+            engine_info: commit_info.and_then(|commit_info| commit_info.engine_info),
+        };
+        self.split_into_batches(commit, adds, start_index)
+    }
+
+    // Split the files added by a commit into batches, according to the rate limits.
+    fn split_into_batches(
+        &self,
+        commit: StreamCommit,
+        adds: Vec<Add>,
+        start_index: usize,
+    ) -> DeltaResult<Vec<StreamBatch>> {
+        let version = commit.version;
+        let mut batches = vec![];
+        let mut index = start_index.min(adds.len());
+        loop {
+            let start = index;
+            let mut bytes = 0;
+            while index < adds.len() {
+                let size = u64::try_from(adds[index].size).map_err(|_| {
+                    Error::generic(format!(
+                        "Invalid size {} of file {} added in version {version}",
+                        adds[index].size, adds[index].path
+                    ))
+                })?;
+                let files_exceeded = self
+                    .options
+                    .max_files_per_batch
+                    .is_some_and(|max_files| index - start >= max_files);
+                let bytes_exceeded = self
+                    .options
+                    .max_bytes_per_batch
+                    .is_some_and(|max_bytes| bytes + size > max_bytes);
+                // always include at least one file per batch
+                if index > start && (files_exceeded || bytes_exceeded) {
+                    break;
+                }
+                bytes += size;
+                index += 1;
+            }
+            let files = adds[start..index]
+                .iter()
+                .map(|add| self.scan_file(add))
+                .try_collect()?;
+            let end_offset = match index < adds.len() {
+                true => StreamOffset { version, index },
+                false => StreamOffset::new(version + 1),
+            };
+            batches.push(StreamBatch {
+                commit: commit.clone(),
+                files,
+                start_offset: StreamOffset {
+                    version,
+                    index: start,
+                },
+                end_offset,
+            });
+            if index >= adds.len() {
+                return Ok(batches);
+            }
+        }
+    }
+
+    fn scan_file(&self, add: &Add) -> DeltaResult<ScanFile> {
+        Ok(ScanFile {
+            path: add.path.clone(),
+            size: add.size,
+            dv_info: DvInfo {
+                deletion_vector: add.deletion_vector.clone(),
+            },
+            partition_values: add.partition_values.clone(),
+            transform: self.scan.file_transform(&add.partition_values)?,
+        })
+    }
+
+    // The stream reads all files with the schema (and partitioning) of its snapshot. Files written
+    // after an additive schema change (new nullable columns) can still be read with that schema,
+    // which just doesn't read the new columns. Any other change (e.g. dropping, renaming or
+    // retyping a column, or relaxing its nullability) aborts the stream.
+    fn check_metadata(&self, version: Version, metadata: &Metadata) -> DeltaResult<()> {
+        let snapshot = self.scan.snapshot();
+        let stream_schema = snapshot.schema();
+        let schema = metadata.parse_schema()?;
+        let is_additive = stream_schema.can_read_as(&schema).is_ok()
+            && stream_schema
+                .fields()
+                .all(|field| schema.field(field.name()) == Some(field));
+        require!(
+            is_additive,
+            Error::non_additive_change(
+                version,
+                "the schema of the table changed in a non-additive way, restart the stream at this version"
+            )
+        );
+        require!(
+            metadata.partition_columns == snapshot.metadata().partition_columns,
+            Error::non_additive_change(
+                version,
+                "the partitioning of the table changed, restart the stream at this version"
+            )
+        );
+        Ok(())
+    }
+
+    fn check_removes_ignored(&self, version: Version, has_adds: bool) -> DeltaResult<()> {
+        if has_adds {
+            require!(
+                self.options.ignore_changes,
+                Error::non_additive_change(
+                    version,
+                    "existing data was rewritten (e.g. by an update), set ignore_changes to process the rewritten files"
+                )
+            );
+        } else {
+            require!(
+                self.options.ignore_deletes || self.options.ignore_changes,
+                Error::non_additive_change(
+                    version,
+                    "data was deleted, set ignore_deletes to skip deletes"
+                )
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::Arc;
+
+    use object_store::memory::InMemory;
+    use serde_json::json;
+    use test_utils::add_commit;
+
+    use crate::engine::default::executor::tokio::TokioBackgroundExecutor;
+    use crate::engine::default::DefaultEngine;
+
+    fn add(path: &str, size: i64, data_change: bool) -> serde_json::Value {
+        json!({
+            "add": {
+                "path": path,
+                "partitionValues": {"part": "1"},
+                "size": size,
+                "modificationTime": 0,
+                "dataChange": data_change
+            }
+        })
+    }
+
+    fn remove(path: &str) -> serde_json::Value {
+        json!({"remove": {"path": path, "deletionTimestamp": 0, "dataChange": true}})
+    }
+
+    fn commit_info(operation: &str) -> serde_json::Value {
+        json!({"commitInfo": {"timestamp": 0, "operation": operation, "engineInfo": "test"}})
+    }
+
+    fn metadata(schema_string: &str) -> serde_json::Value {
+        json!({
+            "metaData": {
+                "id": "test_id",
+                "format": {"provider": "parquet", "options": {}},
+                "schemaString": schema_string,
+                "partitionColumns": ["part"],
+                "configuration": {},
+                "createdTime": 1677811175819u64
+            }
+        })
+    }
+
+    const SCHEMA: &str = r#"{"type":"struct","fields":[{"name":"id","type":"integer","nullable":true,"metadata":{}},{"name":"part","type":"integer","nullable":true,"metadata":{}}]}"#;
+
+    // A table whose version 0 creates the table, and whose later versions are the given commits.
+    async fn setup_table(
+        commits: &[Vec<serde_json::Value>],
+    ) -> (Url, DefaultEngine<TokioBackgroundExecutor>) {
+        let store = Arc::new(InMemory::new());
+        let commit0 = [
+            commit_info("CREATE TABLE"),
+            json!({"protocol": {"minReaderVersion": 1, "minWriterVersion": 2}}),
+            metadata(SCHEMA),
+        ];
+        let commits = std::iter::once(commit0.to_vec()).chain(commits.iter().cloned());
+        for (version, commit) in commits.enumerate() {
+            let commit = commit.iter().map(|a| a.to_string()).join("\n");
+            add_commit(store.as_ref(), version as u64, commit)
+                .await
+                .unwrap();
+        }
+        let engine = DefaultEngine::new(store, Arc::new(TokioBackgroundExecutor::new()));
+        (Url::parse("memory:///").unwrap(), engine)
+    }
+
+    fn collect_batches(
+        source: &StreamingSource,
+        engine: &dyn Engine,
+        offset: StreamOffset,
+    ) -> DeltaResult<Vec<StreamBatch>> {
+        source.batches(engine, offset)?.try_collect()
+    }
+
+    fn file_paths(batch: &StreamBatch) -> Vec<&str> {
+        batch.files.iter().map(|f| f.path.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_stream_batches() {
+        let (table_root, engine) = setup_table(&[
+            vec![commit_info("WRITE"), add("a", 10, true), add("b", 10, true)],
+            // compaction doesn't change data
+            vec![
+                commit_info("OPTIMIZE"),
+                add("ab", 20, false),
+                json!({"remove": {"path": "a", "dataChange": false}}),
+                json!({"remove": {"path": "b", "dataChange": false}}),
+            ],
+            vec![commit_info("WRITE"), add("c", 10, true)],
+        ])
+        .await;
+        let source = StreamingSource::try_new(
+            table_root,
+            &engine,
+            StartingVersion::Version(1),
+            StreamingOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(source.start_offset(), StreamOffset::new(1));
+
+        let batches = collect_batches(&source, &engine, source.start_offset()).unwrap();
+        assert_eq!(batches.len(), 3);
+        assert_eq!(file_paths(&batches[0]), ["a", "b"]);
+        assert_eq!(batches[0].commit.operation.as_deref(), Some("WRITE"));
+        assert_eq!(batches[0].commit.engine_info.as_deref(), Some("test"));
+        assert_eq!(batches[0].end_offset, StreamOffset::new(2));
+        assert!(batches[1].files.is_empty());
+        assert_eq!(batches[1].end_offset, StreamOffset::new(3));
+        assert_eq!(file_paths(&batches[2]), ["c"]);
+        assert_eq!(batches[2].end_offset, StreamOffset::new(4));
+
+        // partition values are filled in by the transform
+        let transform = batches[0].files[0].transform.as_ref().unwrap();
+        assert!(transform.to_string().contains('1'));
+
+        // caught up with the table
+        let batches = collect_batches(&source, &engine, StreamOffset::new(4)).unwrap();
+        assert!(batches.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_stream_rate_limits() {
+        let (table_root, engine) = setup_table(&[vec![
+            commit_info("WRITE"),
+            add("a", 10, true),
+            add("b", 10, true),
+            add("c", 30, true),
+            add("d", 10, true),
+        ]])
+        .await;
+        let options = StreamingOptions {
+            max_files_per_batch: Some(3),
+            max_bytes_per_batch: Some(25),
+            ..Default::default()
+        };
+        let source =
+            StreamingSource::try_new(table_root, &engine, StartingVersion::Version(1), options)
+                .unwrap();
+        let batches = collect_batches(&source, &engine, source.start_offset()).unwrap();
+        let paths: Vec<_> = batches.iter().map(file_paths).collect();
+        assert_eq!(paths, [vec!["a", "b"], vec!["c"], vec!["d"]]);
+        let offsets: Vec<_> = batches
+            .iter()
+            .map(|batch| (batch.start_offset, batch.end_offset))
+            .collect();
+        let offset = |version, index| StreamOffset { version, index };
+        assert_eq!(
+            offsets,
+            [
+                (offset(1, 0), offset(1, 2)),
+                (offset(1, 2), offset(1, 3)),
+                (offset(1, 3), offset(2, 0))
+            ]
+        );
+
+        // resume in the middle of a commit
+        let batches = collect_batches(&source, &engine, offset(1, 3)).unwrap();
+        assert_eq!(batches.len(), 1);
+        assert_eq!(file_paths(&batches[0]), ["d"]);
+    }
+
+    #[tokio::test]
+    async fn test_stream_starting_latest() {
+        let (table_root, engine) =
+            setup_table(&[vec![commit_info("WRITE"), add("a", 10, true)]]).await;
+        let source = StreamingSource::try_new(
+            table_root,
+            &engine,
+            StartingVersion::Latest,
+            StreamingOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(source.start_offset(), StreamOffset::new(2));
+        let batches = collect_batches(&source, &engine, source.start_offset()).unwrap();
+        assert!(batches.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_stream_non_additive_changes() {
+        let (table_root, engine) = setup_table(&[
+            vec![commit_info("WRITE"), add("a", 10, true), add("b", 10, true)],
+            vec![commit_info("DELETE"), remove("a")],
+            vec![commit_info("UPDATE"), remove("b"), add("b2", 10, true)],
+        ])
+        .await;
+        let source = |ignore_deletes, ignore_changes| {
+            let options = StreamingOptions {
+                ignore_deletes,
+                ignore_changes,
+                ..Default::default()
+            };
+            StreamingSource::try_new(
+                table_root.clone(),
+                &engine,
+                StartingVersion::Version(1),
+                options,
+            )
+            .unwrap()
+        };
+
+        let result = collect_batches(&source(false, false), &engine, StreamOffset::new(1));
+        assert!(matches!(result, Err(Error::NonAdditiveChange(2, _))));
+
+        let result = collect_batches(&source(true, false), &engine, StreamOffset::new(1));
+        assert!(matches!(result, Err(Error::NonAdditiveChange(3, _))));
+
+        let batches = collect_batches(&source(false, true), &engine, StreamOffset::new(1)).unwrap();
+        let paths: Vec<_> = batches.iter().map(file_paths).collect();
+        assert_eq!(paths, [vec!["a", "b"], vec![], vec!["b2"]]);
+    }
+
+    #[tokio::test]
+    async fn test_stream_additive_schema_change() {
+        let new_column = r#",{"name":"extra","type":"string","nullable":true,"metadata":{}}]}"#;
+        let new_schema = SCHEMA.replace("]}", new_column);
+        let (table_root, engine) = setup_table(&[
+            vec![commit_info("WRITE"), add("a", 10, true)],
+            vec![commit_info("ADD COLUMNS"), metadata(&new_schema)],
+            vec![commit_info("WRITE"), add("b", 10, true)],
+        ])
+        .await;
+        let source = StreamingSource::try_new(
+            table_root,
+            &engine,
+            StartingVersion::Version(1),
+            StreamingOptions::default(),
+        )
+        .unwrap();
+        let batches = collect_batches(&source, &engine, source.start_offset()).unwrap();
+        let paths: Vec<_> = batches.iter().map(file_paths).collect();
+        assert_eq!(paths, [vec!["a"], vec![], vec!["b"]]);
+        // the stream keeps reading with its original schema
+        assert!(source.schema().field("extra").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_stream_commit_timestamp() {
+        // in-commit timestamps are not enabled, so the commit's is ignored
+        let commit_info = json!({"commitInfo": {"inCommitTimestamp": 1234, "operation": "WRITE"}});
+        let (table_root, engine) = setup_table(&[vec![commit_info, add("a", 10, true)]]).await;
+        let source = StreamingSource::try_new(
+            table_root.clone(),
+            &engine,
+            StartingVersion::Version(1),
+            StreamingOptions::default(),
+        )
+        .unwrap();
+        let batches = collect_batches(&source, &engine, source.start_offset()).unwrap();
+        let commit = table_root
+            .join("_delta_log/00000000000000000001.json")
+            .unwrap();
+        let commit = engine.storage_handler().head(&commit).unwrap();
+        assert_eq!(batches[0].commit.timestamp, commit.last_modified);
+    }
+
+    #[tokio::test]
+    async fn test_stream_negative_file_size() {
+        let (table_root, engine) =
+            setup_table(&[vec![commit_info("WRITE"), add("a", -1, true)]]).await;
+        let source = StreamingSource::try_new(
+            table_root,
+            &engine,
+            StartingVersion::Version(1),
+            StreamingOptions::default(),
+        )
+        .unwrap();
+        let result = collect_batches(&source, &engine, source.start_offset());
+        assert!(matches!(result, Err(e) if e.to_string().contains("Invalid size -1 of file a")));
+    }
+
+    #[tokio::test]
+    async fn test_stream_schema_change() {
+        let new_schema = SCHEMA.replace(r#""name":"id""#, r#""name":"new_id""#);
+        let (table_root, engine) = setup_table(&[
+            vec![commit_info("WRITE"), add("a", 10, true)],
+            vec![commit_info("CHANGE COLUMN"), metadata(&new_schema)],
+            vec![commit_info("WRITE"), add("b", 10, true)],
+        ])
+        .await;
+        let source = StreamingSource::try_new(
+            table_root.clone(),
+            &engine,
+            StartingVersion::Version(1),
+            StreamingOptions::default(),
+        )
+        .unwrap();
+        let mut batches = source.batches(&engine, source.start_offset()).unwrap();
+        assert_eq!(file_paths(&batches.next().unwrap().unwrap()), ["a"]);
+        assert!(matches!(
+            batches.next().unwrap(),
+            Err(Error::NonAdditiveChange(2, _))
+        ));
+
+        // restarting the stream at the version of the change picks up the new schema
+        let source = StreamingSource::try_new_from_offset(
+            table_root,
+            &engine,
+            StreamOffset::new(2),
+            StreamingOptions::default(),
+        )
+        .unwrap();
+        assert!(source.schema().field("new_id").is_some());
+        let batches = collect_batches(&source, &engine, source.start_offset()).unwrap();
+        let paths: Vec<_> = batches.iter().map(file_paths).collect();
+        assert_eq!(paths, [vec![], vec!["b"]]);
+    }
+}
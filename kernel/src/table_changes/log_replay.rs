@@ -25,22 +25,23 @@ use crate::table_properties::TableProperties;
 use crate::utils::require;
 use crate::{DeltaResult, Engine, EngineData, Error, PredicateRef, RowVisitor};
 
+use delta_kernel_derive::internal_api;
 use itertools::Itertools;
 
 #[cfg(test)]
 mod tests;
 
 /// Scan metadata for a Change Data Feed query. This holds metadata that's needed to read data rows.
+#[internal_api]
 pub(crate) struct TableChangesScanMetadata {
-    /// Engine data with the schema defined in [`scan_row_schema`]
-    ///
-    /// Note: The schema of the engine data will be updated in the future to include columns
-    /// used by Change Data Feed.
-    pub(crate) scan_metadata: Box<dyn EngineData>,
+    /// Engine data with one row per `add`, `remove` or `cdc` action of the commit (only the path,
+    /// deletion vector and partition values of the action are kept), along with the `timestamp`
+    /// and `commit_version` of the commit.
+    pub scan_metadata: Box<dyn EngineData>,
     /// The selection vector used to filter the `scan_metadata`.
-    pub(crate) selection_vector: Vec<bool>,
+    pub selection_vector: Vec<bool>,
     /// A map from a remove action's path to its deletion vector
-    pub(crate) remove_dvs: Arc<HashMap<String, DvInfo>>,
+    pub remove_dvs: Arc<HashMap<String, DvInfo>>,
 }
 
 /// Given an iterator of [`ParsedLogPath`] returns an iterator of [`TableChangesScanMetadata`].
@@ -2,6 +2,7 @@
 
 use std::sync::Arc;
 
+use delta_kernel_derive::internal_api;
 use itertools::Itertools;
 use tracing::debug;
 use url::Url;
@@ -11,7 +12,11 @@ use crate::scan::{ColumnType, PhysicalPredicate, ScanResult};
 use crate::schema::{SchemaRef, StructType};
 use crate::{DeltaResult, Engine, FileMeta, PredicateRef};
 
-use super::log_replay::{table_changes_action_iter, TableChangesScanMetadata};
+use super::log_replay::table_changes_action_iter;
+#[cfg(feature = "internal-api")]
+pub use super::log_replay::TableChangesScanMetadata;
+#[cfg(not(feature = "internal-api"))]
+use super::log_replay::TableChangesScanMetadata;
 use super::physical_to_logical::{physical_to_logical_expr, scan_file_physical_schema};
 use super::resolve_dvs::{resolve_scan_file_dv, ResolvedCdfScanFile};
 use super::scan_file::scan_metadata_to_scan_file;
@@ -185,10 +190,11 @@ impl TableChangesScan {
     /// deletion vectors present in the commit. The engine data in each scan metadata is guaranteed
     /// to belong to the same commit. Several [`TableChangesScanMetadata`] may belong to the same
     /// commit.
-    fn scan_metadata(
+    #[internal_api]
+    pub(crate) fn scan_metadata(
         &self,
         engine: Arc<dyn Engine>,
-    ) -> DeltaResult<impl Iterator<Item = DeltaResult<TableChangesScanMetadata>>> {
+    ) -> DeltaResult<impl Iterator<Item = DeltaResult<TableChangesScanMetadata>> + use<>> {
         let commits = self
             .table_changes
             .log_segment
@@ -239,27 +245,30 @@ impl TableChangesScan {
     pub fn execute(
         &self,
         engine: Arc<dyn Engine>,
-    ) -> DeltaResult<impl Iterator<Item = DeltaResult<ScanResult>> + use<'_>> {
+    ) -> DeltaResult<impl Iterator<Item = DeltaResult<ScanResult>> + use<>> {
         let scan_metadata = self.scan_metadata(engine.clone())?;
         let scan_files = scan_metadata_to_scan_file(scan_metadata);
 
         let table_root = self.table_changes.table_root().clone();
         let all_fields = self.all_fields.clone();
         let physical_predicate = self.physical_predicate();
+        let logical_schema = self.logical_schema().clone();
+        let physical_schema = self.physical_schema().clone();
         let dv_engine_ref = engine.clone();
+        let dv_table_root = table_root.clone();
 
         let result = scan_files
             .map(move |scan_file| {
-                resolve_scan_file_dv(dv_engine_ref.as_ref(), &table_root, scan_file?)
+                resolve_scan_file_dv(dv_engine_ref.as_ref(), &dv_table_root, scan_file?)
             }) // Iterator-Result-Iterator
             .flatten_ok() // Iterator-Result
             .map(move |resolved_scan_file| -> DeltaResult<_> {
                 read_scan_file(
                     engine.as_ref(),
                     resolved_scan_file?,
-                    self.table_root(),
-                    self.logical_schema(),
-                    self.physical_schema(),
+                    &table_root,
+                    &logical_schema,
+                    &physical_schema,
                     &all_fields,
                     physical_predicate.clone(),
                 )
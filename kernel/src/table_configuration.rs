@@ -16,8 +16,9 @@ use crate::actions::{ensure_supported_features, Metadata, Protocol};
 use crate::schema::variant_utils::validate_variant_type_feature_support;
 use crate::schema::{InvariantChecker, SchemaRef};
 use crate::table_features::{
-    column_mapping_mode, validate_schema_column_mapping, validate_timestamp_ntz_feature_support,
-    ColumnMappingMode, ReaderFeature, WriterFeature,
+    column_mapping_mode, parse_column_invariants, parse_generated_columns, parse_identity_columns,
+    validate_schema_column_mapping, validate_timestamp_ntz_feature_support, ColumnInvariant,
+    ColumnMappingMode, GeneratedColumn, IdentityColumn, ReaderFeature, WriterFeature,
 };
 use crate::table_properties::TableProperties;
 use crate::{DeltaResult, Error, Version};
@@ -169,19 +170,51 @@ impl TableConfiguration {
     pub(crate) fn ensure_write_supported(&self) -> DeltaResult<()> {
         self.protocol.ensure_write_supported()?;
 
-        // for now we don't allow invariants so although we support writer version 2 and the
-        // ColumnInvariant TableFeature we _must_ check here that they are not actually in use
-        if self.is_invariants_supported()
-            && InvariantChecker::has_invariants(self.schema().as_ref())
-        {
-            return Err(Error::unsupported(
-                "Column invariants are not yet supported",
-            ));
-        }
+        // writers must enforce any invariants in use, so we must be able to parse all of them
+        self.column_invariants()?;
+        // writers must generate values for identity and generated columns, so they must be
+        // well-formed (and, for generated columns, parseable by kernel)
+        self.identity_columns()?;
+        self.generated_columns()?;
 
         Ok(())
     }
 
+    /// The [`ColumnInvariant`]s that writers must enforce on this table. Invariants are only
+    /// enforced when the table supports the invariants feature (see
+    /// [`Self::is_invariants_supported`]), so this is empty otherwise. Returns an error if any
+    /// invariant in use cannot be parsed by kernel.
+    #[internal_api]
+    pub(crate) fn column_invariants(&self) -> DeltaResult<Vec<ColumnInvariant>> {
+        if !self.is_invariants_supported() || !InvariantChecker::has_invariants(&self.schema) {
+            return Ok(vec![]);
+        }
+        parse_column_invariants(&self.schema)
+    }
+
+    /// The [`GeneratedColumn`]s of this table, whose values writers must compute. Generated columns
+    /// only exist when the table supports the generated columns feature (see
+    /// [`Self::is_generated_columns_supported`]), so this is empty otherwise. Returns an error if
+    /// any generation expression cannot be parsed by kernel.
+    #[internal_api]
+    pub(crate) fn generated_columns(&self) -> DeltaResult<Vec<GeneratedColumn>> {
+        if !self.is_generated_columns_supported() {
+            return Ok(vec![]);
+        }
+        parse_generated_columns(&self.schema)
+    }
+
+    /// The [`IdentityColumn`]s of this table, for which writers must generate values. Identity
+    /// columns only exist when the table supports the identity columns feature (see
+    /// [`Self::is_identity_columns_supported`]), so this is empty otherwise.
+    #[internal_api]
+    pub(crate) fn identity_columns(&self) -> DeltaResult<Vec<IdentityColumn>> {
+        if !self.is_identity_columns_supported() {
+            return Ok(vec![]);
+        }
+        parse_identity_columns(&self.schema)
+    }
+
     /// Returns `true` if kernel supports reading Change Data Feed on this table.
     /// See the documentation of [`TableChanges`] for more details.
     ///
@@ -257,7 +290,6 @@ impl TableConfiguration {
         }
     }
 
-    #[allow(unused)]
     pub(crate) fn is_append_only_enabled(&self) -> bool {
         self.is_append_only_supported() && self.table_properties.append_only.unwrap_or(false)
     }
@@ -271,6 +303,49 @@ impl TableConfiguration {
         }
     }
 
+    /// Returns `true` if the table supports the generated columns table feature. To support this
+    /// feature, the table must have a writer version between 4 and 6 (inclusive), or writer version
+    /// 7 with the [`WriterFeature::GeneratedColumns`] writer feature.
+    pub(crate) fn is_generated_columns_supported(&self) -> bool {
+        let protocol = &self.protocol;
+        match protocol.min_writer_version() {
+            7 => protocol.has_writer_feature(&WriterFeature::GeneratedColumns),
+            version => (4..=6).contains(&version),
+        }
+    }
+
+    /// Returns `true` if the table supports the identity columns table feature. To support this
+    /// feature, the table must have writer version 6, or writer version 7 with the
+    /// [`WriterFeature::IdentityColumns`] writer feature.
+    pub(crate) fn is_identity_columns_supported(&self) -> bool {
+        let protocol = &self.protocol;
+        match protocol.min_writer_version() {
+            7 => protocol.has_writer_feature(&WriterFeature::IdentityColumns),
+            version => version == 6,
+        }
+    }
+
+    /// Returns `true` if the table supports the domain metadata table feature. To support this
+    /// feature, the table must have writer version 7 with the [`WriterFeature::DomainMetadata`]
+    /// writer feature.
+    pub(crate) fn is_domain_metadata_supported(&self) -> bool {
+        self.protocol.min_writer_version() == 7
+            && self
+                .protocol
+                .has_writer_feature(&WriterFeature::DomainMetadata)
+    }
+
+    /// Returns `true` if the table supports the clustered table feature. To support this feature,
+    /// the table must have writer version 7 with the [`WriterFeature::ClusteredTable`] writer
+    /// feature. Clustering columns are stored in domain metadata, so the table must also support
+    /// the [`WriterFeature::DomainMetadata`] writer feature.
+    pub(crate) fn is_clustering_supported(&self) -> bool {
+        self.is_domain_metadata_supported()
+            && self
+                .protocol
+                .has_writer_feature(&WriterFeature::ClusteredTable)
+    }
+
     /// Returns `true` if V2 checkpoint is supported on this table. To support V2 checkpoint,
     /// a table must support reader version 3, writer version 7, and the v2Checkpoint feature in
     /// both the protocol's readerFeatures and writerFeatures.
@@ -310,6 +385,16 @@ impl TableConfiguration {
                 .unwrap_or(false)
     }
 
+    /// If in-commit timestamps is enabled, returns the first version with in-commit timestamps,
+    /// i.e. the enablement version, or 0 if they were enabled when the table was created.
+    pub(crate) fn in_commit_timestamp_enablement_version(&self) -> Option<Version> {
+        self.is_in_commit_timestamps_enabled().then(|| {
+            self.table_properties()
+                .in_commit_timestamp_enablement_version
+                .unwrap_or(0)
+        })
+    }
+
     /// If in-commit timestamps is enabled, returns a tuple of the in-commit timestamp enablement
     /// version and timestamp.
     ///
@@ -672,4 +757,147 @@ mod test {
             "Should succeed when VARIANT is used with required features"
         );
     }
+
+    #[test]
+    fn test_column_invariants() {
+        let schema_string = r#"{"type":"struct","fields":[{"name":"value","type":"integer","nullable":true,"metadata":{"delta.invariants":"{\"expression\":{\"expression\":\"value > 3\"}}"}}]}"#.to_string();
+        let metadata = Metadata {
+            schema_string,
+            ..Default::default()
+        };
+        let table_root = Url::try_from("file:///").unwrap();
+
+        // writer version 2 supports invariants, so they must be enforced
+        let protocol = Protocol::try_new(1, 2, None::<Vec<String>>, None::<Vec<String>>).unwrap();
+        let table_config =
+            TableConfiguration::try_new(metadata.clone(), protocol, table_root.clone(), 0).unwrap();
+        assert!(table_config.ensure_write_supported().is_ok());
+        let invariants = table_config.column_invariants().unwrap();
+        assert_eq!(invariants.len(), 1);
+        assert_eq!(invariants[0].sql(), "value > 3");
+
+        // writer version 7 without the invariants feature ignores them
+        let protocol = Protocol::try_new(
+            3,
+            7,
+            Some::<Vec<String>>(vec![]),
+            Some::<Vec<String>>(vec![]),
+        )
+        .unwrap();
+        let table_config =
+            TableConfiguration::try_new(metadata, protocol, table_root.clone(), 0).unwrap();
+        assert!(table_config.column_invariants().unwrap().is_empty());
+
+        // invariants kernel cannot parse block writes
+        let schema_string = r#"{"type":"struct","fields":[{"name":"value","type":"integer","nullable":true,"metadata":{"delta.invariants":"{\"expression\":{\"expression\":\"my_udf(value)\"}}"}}]}"#.to_string();
+        let metadata = Metadata {
+            schema_string,
+            ..Default::default()
+        };
+        let protocol = Protocol::try_new(1, 2, None::<Vec<String>>, None::<Vec<String>>).unwrap();
+        let table_config = TableConfiguration::try_new(metadata, protocol, table_root, 0).unwrap();
+        assert_result_error_with_message(
+            table_config.ensure_write_supported(),
+            "Unsupported: Cannot parse SQL 'my_udf(value)'",
+        );
+    }
+
+    #[test]
+    fn test_identity_columns() {
+        let schema_string = r#"{"type":"struct","fields":[{"name":"id","type":"long","nullable":true,"metadata":{"delta.identity.start":1,"delta.identity.step":2,"delta.identity.highWaterMark":7}},{"name":"value","type":"string","nullable":true,"metadata":{}}]}"#.to_string();
+        let metadata = Metadata {
+            schema_string,
+            ..Default::default()
+        };
+        let table_root = Url::try_from("file:///").unwrap();
+
+        let protocol = Protocol::try_new(
+            3,
+            7,
+            Some::<Vec<String>>(vec![]),
+            Some(vec![WriterFeature::IdentityColumns]),
+        )
+        .unwrap();
+        let table_config =
+            TableConfiguration::try_new(metadata.clone(), protocol, table_root.clone(), 0).unwrap();
+        assert!(table_config.is_identity_columns_supported());
+        assert!(table_config.ensure_write_supported().is_ok());
+        let identity_columns = table_config.identity_columns().unwrap();
+        assert_eq!(identity_columns.len(), 1);
+        assert_eq!(identity_columns[0].name(), "id");
+        assert_eq!(identity_columns[0].high_water_mark(), Some(7));
+
+        // without the feature, identity metadata is ignored
+        let protocol = Protocol::try_new(1, 2, None::<Vec<String>>, None::<Vec<String>>).unwrap();
+        let table_config = TableConfiguration::try_new(metadata, protocol, table_root, 0).unwrap();
+        assert!(!table_config.is_identity_columns_supported());
+        assert!(table_config.identity_columns().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_generated_columns() {
+        let schema_string = r#"{"type":"struct","fields":[{"name":"ts","type":"timestamp","nullable":true,"metadata":{}},{"name":"day","type":"date","nullable":true,"metadata":{"delta.generationExpression":"CAST(ts AS DATE)"}}]}"#.to_string();
+        let metadata = Metadata {
+            schema_string,
+            ..Default::default()
+        };
+        let table_root = Url::try_from("file:///").unwrap();
+
+        let protocol = Protocol::try_new(
+            3,
+            7,
+            Some::<Vec<String>>(vec![]),
+            Some(vec![WriterFeature::GeneratedColumns]),
+        )
+        .unwrap();
+        let table_config =
+            TableConfiguration::try_new(metadata.clone(), protocol, table_root.clone(), 0).unwrap();
+        assert!(table_config.is_generated_columns_supported());
+        assert!(table_config.ensure_write_supported().is_ok());
+        let generated_columns = table_config.generated_columns().unwrap();
+        assert_eq!(generated_columns.len(), 1);
+        assert_eq!(generated_columns[0].name(), "day");
+
+        // without the feature, generation expressions are ignored
+        let protocol = Protocol::try_new(1, 2, None::<Vec<String>>, None::<Vec<String>>).unwrap();
+        let table_config = TableConfiguration::try_new(metadata, protocol, table_root, 0).unwrap();
+        assert!(!table_config.is_generated_columns_supported());
+        assert!(table_config.generated_columns().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_clustering_supported() {
+        let metadata = Metadata {
+            schema_string: r#"{"type":"struct","fields":[{"name":"id","type":"long","nullable":true,"metadata":{}}]}"#.to_string(),
+            ..Default::default()
+        };
+        let table_root = Url::try_from("file:///").unwrap();
+        let protocol = Protocol::try_new(
+            3,
+            7,
+            Some::<Vec<String>>(vec![]),
+            Some(vec![
+                WriterFeature::ClusteredTable,
+                WriterFeature::DomainMetadata,
+            ]),
+        )
+        .unwrap();
+        let table_config =
+            TableConfiguration::try_new(metadata.clone(), protocol, table_root.clone(), 0).unwrap();
+        assert!(table_config.is_domain_metadata_supported());
+        assert!(table_config.is_clustering_supported());
+        assert!(table_config.ensure_write_supported().is_ok());
+
+        // clustering requires domain metadata
+        let protocol = Protocol::try_new(
+            3,
+            7,
+            Some::<Vec<String>>(vec![]),
+            Some(vec![WriterFeature::ClusteredTable]),
+        )
+        .unwrap();
+        let table_config = TableConfiguration::try_new(metadata, protocol, table_root, 0).unwrap();
+        assert!(!table_config.is_domain_metadata_supported());
+        assert!(!table_config.is_clustering_supported());
+    }
 }
@@ -0,0 +1,151 @@
+//! Support for liquid clustering (the `clustering` writer feature).
+//!
+//! The clustering columns of a clustered table are stored in the `delta.clustering` domain metadata
+//! as a JSON object of the form `{"clusteringColumns": [["a"], ["b", "c"]]}`, where each column is
+//! given as the path of _physical_ field names leading to it. Kernel translates these to and from
+//! logical [`ColumnName`]s.
+//!
+//! See: <https://github.com/delta-io/delta/blob/master/PROTOCOL.md#clustered-table>
+
+use serde::{Deserialize, Serialize};
+
+use crate::schema::{ColumnName, DataType, StructField, StructType};
+use crate::{DeltaResult, Error};
+
+/// The name of the domain that stores the clustering columns of a table.
+pub(crate) const CLUSTERING_DOMAIN_NAME: &str = "delta.clustering";
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ClusteringDomainMetadata {
+    clustering_columns: Vec<Vec<String>>,
+}
+
+/// Parses the configuration of the `delta.clustering` domain into the logical names of the
+/// clustering columns.
+pub(crate) fn parse_clustering_columns(
+    configuration: &str,
+    schema: &StructType,
+) -> DeltaResult<Vec<ColumnName>> {
+    let metadata: ClusteringDomainMetadata = serde_json::from_str(configuration)?;
+    metadata
+        .clustering_columns
+        .iter()
+        .map(|physical_path| {
+            let logical_path = resolve_path(schema, physical_path, StructField::physical_name)
+                .ok_or_else(|| {
+                    Error::generic(format!(
+                        "Clustering column {} not found in schema",
+                        ColumnName::new(physical_path)
+                    ))
+                })?;
+            Ok(ColumnName::new(logical_path.iter().map(|f| f.name())))
+        })
+        .collect()
+}
+
+/// Produces the configuration of the `delta.clustering` domain for the given (logical) clustering
+/// columns. Every clustering column must be a (possibly nested) primitive column of `schema`.
+pub(crate) fn clustering_domain_configuration(
+    columns: &[ColumnName],
+    schema: &StructType,
+) -> DeltaResult<String> {
+    let clustering_columns = columns
+        .iter()
+        .map(|column| {
+            let path = resolve_path(schema, column.path(), |f| f.name())
+                .ok_or_else(|| Error::generic(format!("Clustering column {column} not found")))?;
+            let field = path
+                .last()
+                .ok_or_else(|| Error::generic("Empty clustering column"))?;
+            if !matches!(field.data_type(), DataType::Primitive(_)) {
+                return Err(Error::generic(format!(
+                    "Clustering column {column} must have a primitive type, found {}",
+                    field.data_type()
+                )));
+            }
+            Ok(path.iter().map(|f| f.physical_name().to_string()).collect())
+        })
+        .collect::<DeltaResult<_>>()?;
+    Ok(serde_json::to_string(&ClusteringDomainMetadata {
+        clustering_columns,
+    })?)
+}
+
+// Follows `path` through nested structs of `schema`, matching each path element against the name
+// of a field as given by `field_name`, and returns the fields along the way.
+fn resolve_path<'a>(
+    schema: &'a StructType,
+    path: &[String],
+    field_name: impl Fn(&'a StructField) -> &'a str,
+) -> Option<Vec<&'a StructField>> {
+    let mut fields = Vec::with_capacity(path.len());
+    let mut current = schema;
+    for (i, name) in path.iter().enumerate() {
+        let field = current.fields().find(|f| field_name(f) == name)?;
+        fields.push(field);
+        if i + 1 < path.len() {
+            let DataType::Struct(inner) = field.data_type() else {
+                return None;
+            };
+            current = inner;
+        }
+    }
+    (!fields.is_empty()).then_some(fields)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::{ColumnMetadataKey, MetadataValue};
+
+    fn physical(name: &str, data_type: impl Into<DataType>, physical_name: &str) -> StructField {
+        StructField::nullable(name, data_type).with_metadata([(
+            ColumnMetadataKey::ColumnMappingPhysicalName.as_ref(),
+            MetadataValue::String(physical_name.to_string()),
+        )])
+    }
+
+    fn test_schema() -> StructType {
+        StructType::new([
+            physical("id", DataType::LONG, "col-1"),
+            physical(
+                "s",
+                StructType::new([physical("x", DataType::INTEGER, "col-3")]),
+                "col-2",
+            ),
+        ])
+    }
+
+    #[test]
+    fn test_clustering_columns_roundtrip() {
+        let schema = test_schema();
+        let columns = vec![ColumnName::new(["id"]), ColumnName::new(["s", "x"])];
+        let configuration = clustering_domain_configuration(&columns, &schema).unwrap();
+        assert_eq!(
+            configuration,
+            r#"{"clusteringColumns":[["col-1"],["col-2","col-3"]]}"#
+        );
+        assert_eq!(
+            parse_clustering_columns(&configuration, &schema).unwrap(),
+            columns
+        );
+
+        let configuration = clustering_domain_configuration(&[], &schema).unwrap();
+        assert!(parse_clustering_columns(&configuration, &schema)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_invalid_clustering_columns() {
+        let schema = test_schema();
+        // not a primitive column
+        assert!(clustering_domain_configuration(&[ColumnName::new(["s"])], &schema).is_err());
+        // unknown column
+        assert!(clustering_domain_configuration(&[ColumnName::new(["nope"])], &schema).is_err());
+        // clustering columns refer to physical names
+        let configuration = r#"{"clusteringColumns":[["id"]]}"#;
+        assert!(parse_clustering_columns(configuration, &schema).is_err());
+    }
+}
@@ -0,0 +1,439 @@
+//! Enabling and dropping table features, i.e. upgrading and downgrading a table's protocol.
+
+use std::fmt::{Display, Formatter};
+
+use super::{ReaderFeature, WriterFeature};
+use crate::actions::Protocol;
+use crate::utils::require;
+use crate::{DeltaResult, Error};
+
+/// A table feature that can be enabled with [`Transaction::enable_feature`] and (for some of
+/// them) dropped with [`Transaction::drop_feature`].
+///
+/// [`Transaction::enable_feature`]: crate::transaction::Transaction::enable_feature
+/// [`Transaction::drop_feature`]: crate::transaction::Transaction::drop_feature
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TableFeature {
+    /// Append-only tables. Enabling it sets `delta.appendOnly = true`. Cannot be dropped.
+    AppendOnly,
+    /// Clustered tables. Enabling it also enables [`TableFeature::DomainMetadata`], where
+    /// clustering columns are stored. Cannot be dropped.
+    Clustering,
+    /// Deletion vectors. Enabling it sets `delta.enableDeletionVectors = true`. Cannot be dropped
+    /// (reader-writer feature).
+    DeletionVectors,
+    /// Domain metadata. Can only be dropped once the table has no domain metadata.
+    DomainMetadata,
+    /// Timestamps without timezone. Cannot be dropped (reader-writer feature).
+    TimestampWithoutTimezone,
+    /// Protocol checks during VACUUM. Cannot be dropped (reader-writer feature).
+    VacuumProtocolCheck,
+}
+
+impl TableFeature {
+    pub(crate) fn writer_feature(&self) -> WriterFeature {
+        match self {
+            TableFeature::AppendOnly => WriterFeature::AppendOnly,
+            TableFeature::Clustering => WriterFeature::ClusteredTable,
+            TableFeature::DeletionVectors => WriterFeature::DeletionVectors,
+            TableFeature::DomainMetadata => WriterFeature::DomainMetadata,
+            TableFeature::TimestampWithoutTimezone => WriterFeature::TimestampWithoutTimezone,
+            TableFeature::VacuumProtocolCheck => WriterFeature::VacuumProtocolCheck,
+        }
+    }
+
+    /// The reader feature of a reader-writer feature, or `None` for writer-only features.
+    pub(crate) fn reader_feature(&self) -> Option<ReaderFeature> {
+        match self {
+            TableFeature::AppendOnly | TableFeature::Clustering | TableFeature::DomainMetadata => {
+                None
+            }
+            TableFeature::DeletionVectors => Some(ReaderFeature::DeletionVectors),
+            TableFeature::TimestampWithoutTimezone => Some(ReaderFeature::TimestampWithoutTimezone),
+            TableFeature::VacuumProtocolCheck => Some(ReaderFeature::VacuumProtocolCheck),
+        }
+    }
+
+    /// The legacy writer version which implies this feature, if it predates table features.
+    fn legacy_writer_version(&self) -> Option<i32> {
+        match self {
+            TableFeature::AppendOnly => Some(2),
+            _ => None,
+        }
+    }
+
+    /// The features which must be enabled along with this feature.
+    pub(crate) fn required_features(&self) -> &'static [TableFeature] {
+        match self {
+            TableFeature::Clustering => &[TableFeature::DomainMetadata],
+            _ => &[],
+        }
+    }
+
+    /// The table property (and its value) that enables this feature, if any.
+    pub(crate) fn enabling_property(&self) -> Option<(&'static str, &'static str)> {
+        match self {
+            TableFeature::AppendOnly => Some(("delta.appendOnly", "true")),
+            TableFeature::DeletionVectors => Some(("delta.enableDeletionVectors", "true")),
+            _ => None,
+        }
+    }
+}
+
+impl Display for TableFeature {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.writer_feature())
+    }
+}
+
+// The reader features implied by a legacy (1 or 2) reader version.
+fn legacy_reader_features(min_reader_version: i32) -> Vec<ReaderFeature> {
+    match min_reader_version {
+        2 => vec![ReaderFeature::ColumnMapping],
+        _ => vec![],
+    }
+}
+
+// The writer features implied by a legacy (1 to 6) writer version.
+fn legacy_writer_features(min_writer_version: i32) -> Vec<WriterFeature> {
+    [
+        (2, WriterFeature::AppendOnly),
+        (2, WriterFeature::Invariants),
+        (3, WriterFeature::CheckConstraints),
+        (4, WriterFeature::ChangeDataFeed),
+        (4, WriterFeature::GeneratedColumns),
+        (5, WriterFeature::ColumnMapping),
+        (6, WriterFeature::IdentityColumns),
+    ]
+    .into_iter()
+    .filter(|(version, _)| *version <= min_writer_version)
+    .map(|(_, feature)| feature)
+    .collect()
+}
+
+/// Upgrade `protocol` to support `feature`. Features which predate table features only bump the
+/// writer version of a legacy protocol; all others upgrade the protocol to table features (writer
+/// version 7, and reader version 3 for reader-writer features), listing the features implied by
+/// its legacy versions explicitly.
+pub(crate) fn protocol_with_feature(
+    protocol: &Protocol,
+    feature: TableFeature,
+) -> DeltaResult<Protocol> {
+    let min_reader_version = protocol.min_reader_version();
+    let min_writer_version = protocol.min_writer_version();
+    if min_writer_version < 7 {
+        if let Some(legacy_version) = feature.legacy_writer_version() {
+            return Protocol::try_new(
+                min_reader_version,
+                min_writer_version.max(legacy_version),
+                protocol.reader_features(),
+                None::<Vec<WriterFeature>>,
+            );
+        }
+    }
+
+    let mut writer_features = match protocol.writer_features() {
+        Some(features) if min_writer_version == 7 => features.to_vec(),
+        _ => legacy_writer_features(min_writer_version),
+    };
+    let writer_feature = feature.writer_feature();
+    if !writer_features.contains(&writer_feature) {
+        writer_features.push(writer_feature);
+    }
+
+    let (min_reader_version, reader_features) = match feature.reader_feature() {
+        Some(reader_feature) => {
+            let mut reader_features = match protocol.reader_features() {
+                Some(features) if min_reader_version == 3 => features.to_vec(),
+                _ => legacy_reader_features(min_reader_version),
+            };
+            if !reader_features.contains(&reader_feature) {
+                reader_features.push(reader_feature);
+            }
+            (3, Some(reader_features))
+        }
+        None => (
+            min_reader_version,
+            protocol.reader_features().map(<[_]>::to_vec),
+        ),
+    };
+    Protocol::try_new(
+        min_reader_version,
+        7,
+        reader_features,
+        Some(writer_features),
+    )
+}
+
+/// Downgrade `protocol` to no longer support `feature`, which must be explicitly listed in it.
+/// If the remaining features are exactly those implied by a legacy reader (or writer) version,
+/// the protocol is downgraded to that version.
+pub(crate) fn protocol_without_feature(
+    protocol: &Protocol,
+    feature: TableFeature,
+) -> DeltaResult<Protocol> {
+    let writer_feature = feature.writer_feature();
+    require!(
+        protocol.min_writer_version() == 7 && protocol.has_writer_feature(&writer_feature),
+        Error::generic(format!(
+            "Cannot drop table feature {feature}: it is not listed in the table protocol"
+        ))
+    );
+    let writer_features: Vec<_> = protocol
+        .writer_features()
+        .unwrap_or_default()
+        .iter()
+        .filter(|f| **f != writer_feature)
+        .cloned()
+        .collect();
+    let reader_features = protocol.reader_features().map(|features| {
+        features
+            .iter()
+            .filter(|f| Some(*f) != feature.reader_feature().as_ref())
+            .cloned()
+            .collect::<Vec<_>>()
+    });
+
+    // NOTE: Reader version 3 requires writer version 7, so only a legacy reader protocol allows
+    // a legacy writer protocol.
+    let legacy_reader_version = match &reader_features {
+        Some(features) => (1..=2).find(|v| legacy_reader_features(*v) == *features),
+        None => Some(protocol.min_reader_version()),
+    };
+    let Some(min_reader_version) = legacy_reader_version else {
+        return Protocol::try_new(3, 7, reader_features, Some(writer_features));
+    };
+    let is_legacy_writer = |v: &i32| {
+        let features = legacy_writer_features(*v);
+        features.len() == writer_features.len()
+            && features.iter().all(|f| writer_features.contains(f))
+    };
+    match (1..=6).find(is_legacy_writer) {
+        Some(min_writer_version) => Protocol::try_new(
+            min_reader_version,
+            min_writer_version,
+            None::<Vec<ReaderFeature>>,
+            None::<Vec<WriterFeature>>,
+        ),
+        None => Protocol::try_new(
+            min_reader_version,
+            7,
+            None::<Vec<ReaderFeature>>,
+            Some(writer_features),
+        ),
+    }
+}
+
+/// Merge two protocols into the weakest protocol supporting the features of both, e.g. to restore
+/// an older table version without downgrading the table's protocol. Legacy versions are kept if
+/// both protocols are legacy; otherwise, the features implied by a legacy version are listed
+/// explicitly.
+pub(crate) fn merged_protocol(a: &Protocol, b: &Protocol) -> DeltaResult<Protocol> {
+    fn union<T: Clone + PartialEq>(mut features: Vec<T>, other: Vec<T>) -> Vec<T> {
+        for feature in other {
+            if !features.contains(&feature) {
+                features.push(feature);
+            }
+        }
+        features
+    }
+    let reader_features = |protocol: &Protocol| match protocol.reader_features() {
+        Some(features) if protocol.min_reader_version() == 3 => features.to_vec(),
+        _ => legacy_reader_features(protocol.min_reader_version()),
+    };
+    let writer_features = |protocol: &Protocol| match protocol.writer_features() {
+        Some(features) if protocol.min_writer_version() == 7 => features.to_vec(),
+        _ => legacy_writer_features(protocol.min_writer_version()),
+    };
+
+    let min_reader_version = a.min_reader_version().max(b.min_reader_version());
+    let min_writer_version = a.min_writer_version().max(b.min_writer_version());
+    let reader_features =
+        (min_reader_version == 3).then(|| union(reader_features(a), reader_features(b)));
+    let writer_features =
+        (min_writer_version == 7).then(|| union(writer_features(a), writer_features(b)));
+    Protocol::try_new(
+        min_reader_version,
+        min_writer_version,
+        reader_features,
+        writer_features,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn protocol(
+        min_reader_version: i32,
+        min_writer_version: i32,
+        reader_features: Option<&[&str]>,
+        writer_features: Option<&[&str]>,
+    ) -> Protocol {
+        Protocol::try_new(
+            min_reader_version,
+            min_writer_version,
+            reader_features,
+            writer_features,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_enable_legacy_feature() {
+        // append-only only needs a legacy writer version bump
+        let enabled = protocol_with_feature(&protocol(1, 1, None, None), TableFeature::AppendOnly);
+        assert_eq!(enabled.unwrap(), protocol(1, 2, None, None));
+        let enabled = protocol_with_feature(&protocol(1, 2, None, None), TableFeature::AppendOnly);
+        assert_eq!(enabled.unwrap(), protocol(1, 2, None, None));
+
+        // ... unless the table already uses table features
+        let enabled = protocol_with_feature(
+            &protocol(1, 7, None, Some(&["domainMetadata"])),
+            TableFeature::AppendOnly,
+        );
+        assert_eq!(
+            enabled.unwrap(),
+            protocol(1, 7, None, Some(&["domainMetadata", "appendOnly"]))
+        );
+    }
+
+    #[test]
+    fn test_enable_writer_feature() {
+        // upgrading a legacy protocol lists the features implied by its writer version
+        let enabled =
+            protocol_with_feature(&protocol(1, 2, None, None), TableFeature::DomainMetadata);
+        assert_eq!(
+            enabled.unwrap(),
+            protocol(
+                1,
+                7,
+                None,
+                Some(&["appendOnly", "invariants", "domainMetadata"])
+            )
+        );
+
+        // enabling a feature twice is a no-op
+        let table_features = protocol(3, 7, Some(&[]), Some(&["domainMetadata"]));
+        let enabled = protocol_with_feature(&table_features, TableFeature::DomainMetadata);
+        assert_eq!(enabled.unwrap(), table_features);
+    }
+
+    #[test]
+    fn test_enable_reader_writer_feature() {
+        let enabled =
+            protocol_with_feature(&protocol(1, 1, None, None), TableFeature::DeletionVectors);
+        assert_eq!(
+            enabled.unwrap(),
+            protocol(3, 7, Some(&["deletionVectors"]), Some(&["deletionVectors"]))
+        );
+
+        let enabled =
+            protocol_with_feature(&protocol(2, 5, None, None), TableFeature::DeletionVectors);
+        assert_eq!(
+            enabled.unwrap(),
+            protocol(
+                3,
+                7,
+                Some(&["columnMapping", "deletionVectors"]),
+                Some(&[
+                    "appendOnly",
+                    "invariants",
+                    "checkConstraints",
+                    "changeDataFeed",
+                    "generatedColumns",
+                    "columnMapping",
+                    "deletionVectors"
+                ])
+            )
+        );
+    }
+
+    #[test]
+    fn test_drop_feature() {
+        // dropping the last reader feature downgrades the reader version
+        let table_features = protocol(
+            3,
+            7,
+            Some(&["deletionVectors"]),
+            Some(&["deletionVectors", "domainMetadata"]),
+        );
+        let dropped = protocol_without_feature(&table_features, TableFeature::DeletionVectors);
+        assert_eq!(
+            dropped.unwrap(),
+            protocol(1, 7, None, Some(&["domainMetadata"]))
+        );
+
+        // remaining features that match a legacy writer version downgrade the writer version
+        let table_features = protocol(
+            3,
+            7,
+            Some(&["timestampNtz"]),
+            Some(&["appendOnly", "timestampNtz", "invariants"]),
+        );
+        let dropped =
+            protocol_without_feature(&table_features, TableFeature::TimestampWithoutTimezone);
+        assert_eq!(dropped.unwrap(), protocol(1, 2, None, None));
+
+        // other reader features are kept
+        let table_features = protocol(
+            3,
+            7,
+            Some(&["deletionVectors", "vacuumProtocolCheck"]),
+            Some(&["deletionVectors", "vacuumProtocolCheck"]),
+        );
+        let dropped = protocol_without_feature(&table_features, TableFeature::VacuumProtocolCheck);
+        assert_eq!(
+            dropped.unwrap(),
+            protocol(3, 7, Some(&["deletionVectors"]), Some(&["deletionVectors"]))
+        );
+
+        // only explicitly listed features can be dropped
+        let dropped =
+            protocol_without_feature(&protocol(1, 2, None, None), TableFeature::AppendOnly);
+        assert!(dropped
+            .unwrap_err()
+            .to_string()
+            .contains("Cannot drop table feature appendOnly"));
+    }
+
+    #[test]
+    fn test_merged_protocol() {
+        // legacy protocols stay legacy
+        let merged = merged_protocol(&protocol(1, 2, None, None), &protocol(2, 5, None, None));
+        assert_eq!(merged.unwrap(), protocol(2, 5, None, None));
+
+        // legacy features are listed explicitly when merged with table features
+        let merged = merged_protocol(
+            &protocol(2, 5, None, None),
+            &protocol(3, 7, Some(&["deletionVectors"]), Some(&["deletionVectors"])),
+        );
+        assert_eq!(
+            merged.unwrap(),
+            protocol(
+                3,
+                7,
+                Some(&["columnMapping", "deletionVectors"]),
+                Some(&[
+                    "appendOnly",
+                    "invariants",
+                    "checkConstraints",
+                    "changeDataFeed",
+                    "generatedColumns",
+                    "columnMapping",
+                    "deletionVectors"
+                ])
+            )
+        );
+
+        // writer features are merged without upgrading the reader version
+        let merged = merged_protocol(
+            &protocol(1, 7, None, Some(&["domainMetadata"])),
+            &protocol(1, 7, None, Some(&["appendOnly", "domainMetadata"])),
+        );
+        assert_eq!(
+            merged.unwrap(),
+            protocol(1, 7, None, Some(&["domainMetadata", "appendOnly"]))
+        );
+    }
+}
@@ -0,0 +1,153 @@
+//! Support for generated columns (the `generatedColumns` writer feature).
+//!
+//! A generated column's value is always computed from other columns of the same row, using the SQL
+//! expression stored in its `delta.generationExpression` metadata. Kernel parses the expression
+//! into an [`Expression`] over the table's logical schema, so that writers can compute the column
+//! instead of trusting caller-supplied values.
+//!
+//! See: <https://github.com/delta-io/delta/blob/master/PROTOCOL.md#generated-columns>
+
+use crate::expressions::sql::{expression_type, parse_expression};
+use crate::expressions::{ColumnName, Expression, SqlFunction};
+use crate::schema::{ColumnMetadataKey, MetadataValue, Schema, StructField};
+use crate::{DeltaResult, Error};
+
+/// A generated column: a (top-level) column whose values are computed from other columns.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeneratedColumn {
+    name: String,
+    sql: String,
+    expression: Expression,
+}
+
+impl GeneratedColumn {
+    /// The name of the generated column.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The SQL text of the generation expression, as stored in the table schema.
+    pub fn sql(&self) -> &str {
+        &self.sql
+    }
+
+    /// The parsed generation expression, expressed over the table's logical schema. The expression
+    /// always produces values of the column's type.
+    pub fn expression(&self) -> &Expression {
+        &self.expression
+    }
+}
+
+/// Collects and parses the generated columns of `schema`. Per the protocol, only top-level columns
+/// can be generated. Generation expressions that kernel cannot parse are reported as unsupported.
+pub(crate) fn parse_generated_columns(schema: &Schema) -> DeltaResult<Vec<GeneratedColumn>> {
+    let generated_columns = schema
+        .fields()
+        .filter_map(|field| {
+            let value = field.get_config_value(&ColumnMetadataKey::GenerationExpression)?;
+            Some(parse_generated_column(schema, field, value))
+        })
+        .collect::<DeltaResult<Vec<_>>>()?;
+
+    // a generated column is computed from the values the caller supplied for the other columns, so
+    // it cannot depend on another generated column
+    for generated_column in &generated_columns {
+        let references = generated_column.expression.references();
+        if let Some(other) = generated_columns
+            .iter()
+            .find(|other| references.contains(&ColumnName::new([other.name()])))
+        {
+            return Err(Error::unsupported(format!(
+                "Generated column {} references generated column {}",
+                generated_column.name, other.name
+            )));
+        }
+    }
+    Ok(generated_columns)
+}
+
+fn parse_generated_column(
+    schema: &Schema,
+    field: &StructField,
+    value: &MetadataValue,
+) -> DeltaResult<GeneratedColumn> {
+    let MetadataValue::String(sql) = value else {
+        return Err(Error::generic(format!(
+            "Invalid generation expression {value} on column {}",
+            field.name()
+        )));
+    };
+    let expression = parse_expression(sql, Some(schema))?;
+    // make sure the expression produces the column's type, e.g. `YEAR(ts)` for a LONG column
+    let expression = match expression_type(schema, &expression) {
+        Some(data_type) if data_type == *field.data_type() => expression,
+        _ => Expression::opaque(SqlFunction::Cast(field.data_type().clone()), [expression]),
+    };
+    Ok(GeneratedColumn {
+        name: field.name().clone(),
+        sql: sql.clone(),
+        expression,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expressions::{column_expr, DateTimeField};
+    use crate::schema::{DataType, StructType};
+
+    fn generated(name: &str, data_type: DataType, sql: &str) -> StructField {
+        StructField::nullable(name, data_type)
+            .with_metadata([(ColumnMetadataKey::GenerationExpression.as_ref(), sql)])
+    }
+
+    #[test]
+    fn test_parse_generated_columns() {
+        let schema = StructType::new([
+            StructField::nullable("ts", DataType::TIMESTAMP),
+            StructField::nullable("x", DataType::LONG),
+            generated("year", DataType::INTEGER, "YEAR(ts)"),
+            generated("year_long", DataType::LONG, "year(TS)"),
+            generated("x_plus_one", DataType::LONG, "x + 1"),
+        ]);
+        let generated_columns = parse_generated_columns(&schema).unwrap();
+        assert_eq!(generated_columns.len(), 3);
+
+        let year = Expression::opaque(
+            SqlFunction::Extract(DateTimeField::Year),
+            [column_expr!("ts")],
+        );
+        assert_eq!(generated_columns[0].name(), "year");
+        assert_eq!(generated_columns[0].sql(), "YEAR(ts)");
+        assert_eq!(generated_columns[0].expression(), &year);
+
+        // the INTEGER result is cast to the LONG column type
+        assert_eq!(
+            generated_columns[1].expression(),
+            &Expression::opaque(SqlFunction::Cast(DataType::LONG), [year])
+        );
+
+        assert_eq!(
+            generated_columns[2].expression(),
+            &(column_expr!("x") + Expression::literal(1i64))
+        );
+    }
+
+    #[test]
+    fn test_unsupported_generated_columns() {
+        let schema = StructType::new([
+            StructField::nullable("x", DataType::STRING),
+            generated("y", DataType::STRING, "upper(x)"),
+        ]);
+        let err = parse_generated_columns(&schema).unwrap_err();
+        assert!(matches!(err, Error::Unsupported(_)), "{err}");
+
+        let schema = StructType::new([
+            StructField::nullable("x", DataType::LONG),
+            generated("y", DataType::LONG, "x * 2"),
+            generated("z", DataType::LONG, "y + 1"),
+        ]);
+        let err = parse_generated_columns(&schema).unwrap_err();
+        assert!(matches!(err, Error::Unsupported(_)), "{err}");
+    }
+}
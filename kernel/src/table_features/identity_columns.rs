@@ -0,0 +1,333 @@
+//! Support for identity columns (the `identityColumns` writer feature).
+//!
+//! An identity column is a `LONG` column whose values are generated by the writer. The column's
+//! metadata records the `start` value, the (non-zero) `step` between generated values, and the
+//! `highWaterMark`, i.e. the last value handed out so far. Writers reserve ranges of values past the
+//! high-water mark and commit the new high-water mark as part of the table schema.
+//!
+//! See: <https://github.com/delta-io/delta/blob/master/PROTOCOL.md#identity-columns>
+
+use crate::schema::{ColumnMetadataKey, DataType, MetadataValue, Schema, StructField};
+use crate::utils::require;
+use crate::{DeltaResult, Error};
+
+/// The identity configuration of a (top-level) column, as read from its field metadata.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdentityColumn {
+    name: String,
+    start: i64,
+    step: i64,
+    high_water_mark: Option<i64>,
+    allow_explicit_insert: bool,
+    // the values reserved by the current transaction, see `IdentityColumn::is_reserved`
+    reserved: Vec<IdentityRange>,
+}
+
+impl IdentityColumn {
+    /// Parses the identity configuration of `field`, returning `None` if the field is not an
+    /// identity column.
+    pub(crate) fn try_from_field(field: &StructField) -> DeltaResult<Option<Self>> {
+        let get_long = |key: &ColumnMetadataKey| match field.get_config_value(key) {
+            None => Ok(None),
+            Some(MetadataValue::Number(n)) => Ok(Some(*n)),
+            Some(other) => Err(Error::generic(format!(
+                "Invalid {} '{other}' for identity column {}",
+                key.as_ref(),
+                field.name()
+            ))),
+        };
+        let start = get_long(&ColumnMetadataKey::IdentityStart)?;
+        let step = get_long(&ColumnMetadataKey::IdentityStep)?;
+        let (start, step) = match (start, step) {
+            (None, None) => return Ok(None),
+            (Some(start), Some(step)) => (start, step),
+            _ => {
+                return Err(Error::generic(format!(
+                    "Identity column {} must specify both start and step",
+                    field.name()
+                )))
+            }
+        };
+        require!(
+            step != 0,
+            Error::generic(format!(
+                "Identity column {} must have a non-zero step",
+                field.name()
+            ))
+        );
+        require!(
+            *field.data_type() == DataType::LONG,
+            Error::generic(format!(
+                "Identity column {} must have type LONG, found {}",
+                field.name(),
+                field.data_type()
+            ))
+        );
+        let allow_explicit_insert =
+            match field.get_config_value(&ColumnMetadataKey::IdentityAllowExplicitInsert) {
+                None => false,
+                Some(MetadataValue::Boolean(allow)) => *allow,
+                Some(other) => {
+                    return Err(Error::generic(format!(
+                        "Invalid {} '{other}' for identity column {}",
+                        ColumnMetadataKey::IdentityAllowExplicitInsert.as_ref(),
+                        field.name()
+                    )))
+                }
+            };
+        Ok(Some(Self {
+            name: field.name().clone(),
+            start,
+            step,
+            high_water_mark: get_long(&ColumnMetadataKey::IdentityHighWaterMark)?,
+            allow_explicit_insert,
+            reserved: vec![],
+        }))
+    }
+
+    /// The name of the identity column.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The first value generated for this column.
+    pub fn start(&self) -> i64 {
+        self.start
+    }
+
+    /// The (non-zero) difference between consecutive generated values.
+    pub fn step(&self) -> i64 {
+        self.step
+    }
+
+    /// The last value generated for this column so far, if any.
+    pub fn high_water_mark(&self) -> Option<i64> {
+        self.high_water_mark
+    }
+
+    /// Whether writers may insert caller-provided values into this column. If `false`, every value
+    /// of the column must be generated.
+    pub fn allow_explicit_insert(&self) -> bool {
+        self.allow_explicit_insert
+    }
+
+    /// Whether `value` was reserved for this column by the transaction this column belongs to (see
+    /// [`Transaction::reserve_identity_values`]), i.e. whether it is a generated value.
+    ///
+    /// [`Transaction::reserve_identity_values`]: crate::transaction::Transaction::reserve_identity_values
+    pub fn is_reserved(&self, value: i64) -> bool {
+        self.reserved.iter().any(|range| range.contains(value))
+    }
+
+    /// Reserves the next `count` values of this column and advances the high-water mark past them.
+    /// Values are always of the form `start + k * step` and lie strictly beyond the current
+    /// high-water mark (in the direction of `step`).
+    pub(crate) fn reserve(&mut self, count: u64) -> DeltaResult<IdentityRange> {
+        let overflow = || {
+            Error::generic(format!(
+                "Identity column {} cannot generate {count} more values without overflowing",
+                self.name
+            ))
+        };
+        let first = match self.high_water_mark {
+            // the high-water mark is usually a generated value, but may not be aligned with the
+            // step (e.g. after explicit inserts), so find the first aligned value beyond it
+            Some(hwm)
+                if (i128::from(hwm) - i128::from(self.start)).signum()
+                    * i128::from(self.step.signum())
+                    >= 0 =>
+            {
+                let steps_taken =
+                    (i128::from(hwm) - i128::from(self.start)) / i128::from(self.step);
+                i128::from(self.start) + (steps_taken + 1) * i128::from(self.step)
+            }
+            _ => i128::from(self.start),
+        };
+        let range = IdentityRange {
+            first: i64::try_from(first).map_err(|_| overflow())?,
+            step: self.step,
+            count,
+        };
+        if let Some(last) = range.last() {
+            self.high_water_mark = Some(last.ok_or_else(overflow)?);
+            self.reserved.push(range);
+        }
+        Ok(range)
+    }
+
+    /// Returns `field` with its high-water mark metadata set to this column's high-water mark.
+    pub(crate) fn update_field(&self, field: &StructField) -> StructField {
+        match self.high_water_mark {
+            Some(hwm) => field.clone().add_metadata([(
+                ColumnMetadataKey::IdentityHighWaterMark.as_ref(),
+                MetadataValue::Number(hwm),
+            )]),
+            None => field.clone(),
+        }
+    }
+}
+
+/// A contiguous range of identity values reserved for a single identity column: the `len()`
+/// values `first()`, `first() + step()`, `first() + 2 * step()`, etc. Engines must use exactly
+/// these values (each at most once) when writing the column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IdentityRange {
+    first: i64,
+    step: i64,
+    count: u64,
+}
+
+impl IdentityRange {
+    /// The first value of the range.
+    pub fn first(&self) -> i64 {
+        self.first
+    }
+
+    /// The difference between consecutive values of the range.
+    pub fn step(&self) -> i64 {
+        self.step
+    }
+
+    /// The number of values in the range.
+    pub fn len(&self) -> u64 {
+        self.count
+    }
+
+    /// Returns `true` if the range contains no values.
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// The last value of the range, or `None` if the range is empty. The inner option is `None`
+    /// if the last value does not fit in an `i64`.
+    fn last(&self) -> Option<Option<i64>> {
+        let offset = i128::from(self.count.checked_sub(1)?) * i128::from(self.step);
+        Some(i64::try_from(i128::from(self.first) + offset).ok())
+    }
+
+    /// Returns `true` if `value` is one of the values of this range.
+    pub fn contains(&self, value: i64) -> bool {
+        let offset = i128::from(value) - i128::from(self.first);
+        let step = i128::from(self.step);
+        offset % step == 0 && (0..i128::from(self.count)).contains(&(offset / step))
+    }
+
+    /// Iterates over the values of this range.
+    pub fn values(&self) -> impl Iterator<Item = i64> {
+        let IdentityRange { first, step, count } = *self;
+        (0..count).map(move |i| first + i as i64 * step)
+    }
+}
+
+/// Collects the identity columns of `schema`. Per the protocol, only top-level columns can be
+/// identity columns.
+pub(crate) fn parse_identity_columns(schema: &Schema) -> DeltaResult<Vec<IdentityColumn>> {
+    schema
+        .fields()
+        .filter_map(|field| IdentityColumn::try_from_field(field).transpose())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::StructType;
+
+    fn identity_field(name: &str, start: i64, step: i64, hwm: Option<i64>) -> StructField {
+        let field = StructField::nullable(name, DataType::LONG).with_metadata([
+            (ColumnMetadataKey::IdentityStart.as_ref(), start),
+            (ColumnMetadataKey::IdentityStep.as_ref(), step),
+        ]);
+        match hwm {
+            Some(hwm) => {
+                field.add_metadata([(ColumnMetadataKey::IdentityHighWaterMark.as_ref(), hwm)])
+            }
+            None => field,
+        }
+    }
+
+    #[test]
+    fn test_parse_identity_columns() {
+        let schema = StructType::new([
+            identity_field("id", 1, 1, Some(10)),
+            StructField::nullable("value", DataType::STRING),
+            identity_field("down", -1, -2, None).add_metadata([(
+                ColumnMetadataKey::IdentityAllowExplicitInsert.as_ref(),
+                true,
+            )]),
+        ]);
+        let columns = parse_identity_columns(&schema).unwrap();
+        assert_eq!(columns.len(), 2);
+        assert_eq!(columns[0].name(), "id");
+        assert_eq!(columns[0].high_water_mark(), Some(10));
+        assert!(!columns[0].allow_explicit_insert());
+        assert_eq!(columns[1].name(), "down");
+        assert_eq!((columns[1].start(), columns[1].step()), (-1, -2));
+        assert_eq!(columns[1].high_water_mark(), None);
+        assert!(columns[1].allow_explicit_insert());
+    }
+
+    #[test]
+    fn test_invalid_identity_columns() {
+        let zero_step = identity_field("id", 1, 0, None);
+        assert!(IdentityColumn::try_from_field(&zero_step).is_err());
+
+        let not_long = StructField::nullable("id", DataType::INTEGER).with_metadata([
+            (ColumnMetadataKey::IdentityStart.as_ref(), 1),
+            (ColumnMetadataKey::IdentityStep.as_ref(), 1),
+        ]);
+        assert!(IdentityColumn::try_from_field(&not_long).is_err());
+
+        let missing_step = StructField::nullable("id", DataType::LONG)
+            .with_metadata([(ColumnMetadataKey::IdentityStart.as_ref(), 1)]);
+        assert!(IdentityColumn::try_from_field(&missing_step).is_err());
+    }
+
+    #[test]
+    fn test_reserve() {
+        let field = identity_field("id", 5, 3, None);
+        let mut column = IdentityColumn::try_from_field(&field).unwrap().unwrap();
+
+        assert!(!column.is_reserved(5));
+        let range = column.reserve(3).unwrap();
+        assert_eq!(range.values().collect::<Vec<_>>(), vec![5, 8, 11]);
+        assert_eq!(column.high_water_mark(), Some(11));
+        assert!(range.values().all(|value| column.is_reserved(value)));
+        assert!(![2, 6, 14].iter().any(|value| column.is_reserved(*value)));
+
+        // empty reservations do not move the high-water mark
+        assert!(column.reserve(0).unwrap().is_empty());
+        assert_eq!(column.high_water_mark(), Some(11));
+
+        let range = column.reserve(2).unwrap();
+        assert_eq!(range.values().collect::<Vec<_>>(), vec![14, 17]);
+
+        let updated = column.update_field(&field);
+        let reparsed = IdentityColumn::try_from_field(&updated).unwrap().unwrap();
+        assert_eq!(reparsed.high_water_mark(), Some(17));
+    }
+
+    #[test]
+    fn test_reserve_unaligned_and_negative() {
+        // a high-water mark that is not aligned with the step (e.g. after an explicit insert)
+        let field = identity_field("id", 1, 10, Some(25));
+        let mut column = IdentityColumn::try_from_field(&field).unwrap().unwrap();
+        assert_eq!(column.reserve(1).unwrap().first(), 31);
+
+        // a high-water mark "before" the start is ignored
+        let field = identity_field("id", 100, 1, Some(50));
+        let mut column = IdentityColumn::try_from_field(&field).unwrap().unwrap();
+        assert_eq!(column.reserve(1).unwrap().first(), 100);
+
+        let field = identity_field("id", -1, -2, Some(-5));
+        let mut column = IdentityColumn::try_from_field(&field).unwrap().unwrap();
+        let range = column.reserve(2).unwrap();
+        assert_eq!(range.values().collect::<Vec<_>>(), vec![-7, -9]);
+        assert!(range.contains(-9) && !range.contains(-11) && !range.contains(-5));
+        assert_eq!(column.high_water_mark(), Some(-9));
+
+        let field = identity_field("id", i64::MAX - 1, 1, None);
+        let mut column = IdentityColumn::try_from_field(&field).unwrap().unwrap();
+        assert!(column.reserve(3).is_err());
+    }
+}
@@ -0,0 +1,186 @@
+//! Support for column invariants (the `invariants` writer feature).
+//!
+//! Invariants are stored in the `delta.invariants` metadata of a [`StructField`] as a JSON string
+//! of the form `{"expression": {"expression": "<sql>"}}`, where `<sql>` is a boolean SQL
+//! expression that every row written to the table must satisfy. Kernel parses the SQL into a
+//! [`Predicate`] that engines evaluate against the (logical) data they write.
+//!
+//! See: <https://github.com/delta-io/delta/blob/master/PROTOCOL.md#column-invariants>
+
+use serde::Deserialize;
+
+use crate::expressions::sql::parse_predicate;
+use crate::expressions::ColumnName;
+use crate::schema::{
+    ColumnMetadataKey, DataType, InvariantChecker, MetadataValue, Schema, StructField,
+};
+use crate::{DeltaResult, Error, Predicate};
+
+/// A column invariant: a predicate that every row written to the table must satisfy. A row
+/// violates the invariant unless the predicate evaluates to `true` (i.e. `false` _and_ `NULL` are
+/// both violations).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnInvariant {
+    column: ColumnName,
+    sql: String,
+    predicate: Predicate,
+}
+
+impl ColumnInvariant {
+    /// The (logical) column whose metadata declared this invariant.
+    pub fn column(&self) -> &ColumnName {
+        &self.column
+    }
+
+    /// The SQL text of the invariant, as stored in the table schema.
+    pub fn sql(&self) -> &str {
+        &self.sql
+    }
+
+    /// The parsed invariant, expressed over the table's logical schema.
+    pub fn predicate(&self) -> &Predicate {
+        &self.predicate
+    }
+}
+
+#[derive(Deserialize)]
+struct InvariantJson {
+    expression: InvariantExpressionJson,
+}
+
+#[derive(Deserialize)]
+struct InvariantExpressionJson {
+    expression: String,
+}
+
+/// Collects and parses all column invariants declared in `schema` (including nested struct
+/// fields). Invariants inside arrays or maps cannot be expressed as row-level predicates and are
+/// reported as unsupported, as are invariants whose SQL kernel cannot parse.
+pub(crate) fn parse_column_invariants(schema: &Schema) -> DeltaResult<Vec<ColumnInvariant>> {
+    let mut invariants = vec![];
+    for field in schema.fields() {
+        collect_invariants(schema, field, &mut vec![], &mut invariants)?;
+    }
+    Ok(invariants)
+}
+
+fn collect_invariants(
+    schema: &Schema,
+    field: &StructField,
+    path: &mut Vec<String>,
+    invariants: &mut Vec<ColumnInvariant>,
+) -> DeltaResult<()> {
+    path.push(field.name().clone());
+    if let Some(value) = field.get_config_value(&ColumnMetadataKey::Invariants) {
+        let column = ColumnName::new(path.iter());
+        let sql = invariant_sql(value).map_err(|err| {
+            Error::generic(format!("Invalid invariant on column {column}: {err}"))
+        })?;
+        let predicate = parse_predicate(&sql, Some(schema))?;
+        invariants.push(ColumnInvariant {
+            column,
+            sql,
+            predicate,
+        });
+    }
+    match field.data_type() {
+        DataType::Struct(inner) => {
+            for child in inner.fields() {
+                collect_invariants(schema, child, path, invariants)?;
+            }
+        }
+        DataType::Array(_) | DataType::Map(_) => {
+            // only the array/map contents matter here; the field's own invariant is handled above
+            let contents = StructField::nullable(field.name(), field.data_type().clone());
+            if InvariantChecker::has_invariants(&Schema::new([contents])) {
+                return Err(Error::unsupported(format!(
+                    "Column invariants nested inside array or map column {} are not supported",
+                    ColumnName::new(path.iter())
+                )));
+            }
+        }
+        _ => {}
+    }
+    path.pop();
+    Ok(())
+}
+
+fn invariant_sql(value: &MetadataValue) -> DeltaResult<String> {
+    let parsed: InvariantJson = match value {
+        MetadataValue::String(json) => serde_json::from_str(json)?,
+        MetadataValue::Other(json) => serde_json::from_value(json.clone())?,
+        other => return Err(Error::generic(format!("unexpected value {other}"))),
+    };
+    Ok(parsed.expression.expression)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expressions::{column_expr, Expression};
+    use crate::schema::{ArrayType, StructType};
+
+    fn invariant(sql: &str) -> (String, String) {
+        (
+            ColumnMetadataKey::Invariants.as_ref().to_string(),
+            format!(r#"{{"expression":{{"expression":"{sql}"}}}}"#),
+        )
+    }
+
+    #[test]
+    fn test_parse_column_invariants() {
+        let schema = StructType::new([
+            StructField::nullable("id", DataType::LONG).with_metadata([invariant("id > 0")]),
+            StructField::nullable("name", DataType::STRING),
+            StructField::nullable(
+                "s",
+                StructType::new([StructField::nullable("x", DataType::INTEGER)
+                    .with_metadata([invariant("s.x < 10 OR s.x IS NULL")])]),
+            ),
+        ]);
+        let invariants = parse_column_invariants(&schema).unwrap();
+        assert_eq!(invariants.len(), 2);
+
+        assert_eq!(invariants[0].column(), &ColumnName::new(["id"]));
+        assert_eq!(invariants[0].sql(), "id > 0");
+        assert_eq!(
+            invariants[0].predicate(),
+            &column_expr!("id").gt(Expression::literal(0i64))
+        );
+
+        assert_eq!(invariants[1].column(), &ColumnName::new(["s", "x"]));
+        assert_eq!(
+            invariants[1].predicate(),
+            &Predicate::or(
+                column_expr!("s.x").lt(Expression::literal(10)),
+                column_expr!("s.x").is_null(),
+            )
+        );
+    }
+
+    #[test]
+    fn test_unsupported_invariants() {
+        // unparseable SQL
+        let schema = StructType::new([
+            StructField::nullable("id", DataType::LONG).with_metadata([invariant("myudf(id)")])
+        ]);
+        let err = parse_column_invariants(&schema).unwrap_err();
+        assert!(matches!(err, Error::Unsupported(_)), "{err}");
+
+        // invariant inside an array
+        let element = StructType::new([
+            StructField::nullable("x", DataType::INTEGER).with_metadata([invariant("x > 0")])
+        ]);
+        let schema = StructType::new([StructField::nullable(
+            "arr",
+            ArrayType::new(element.into(), true),
+        )]);
+        let err = parse_column_invariants(&schema).unwrap_err();
+        assert!(matches!(err, Error::Unsupported(_)), "{err}");
+
+        // malformed invariant json
+        let schema = StructType::new([StructField::nullable("id", DataType::LONG)
+            .with_metadata([(ColumnMetadataKey::Invariants.as_ref(), "{}")])]);
+        assert!(parse_column_invariants(&schema).is_err());
+    }
+}
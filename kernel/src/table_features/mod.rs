@@ -8,10 +8,26 @@ use crate::schema::derive_macro_utils::ToDataType;
 use crate::schema::DataType;
 use delta_kernel_derive::internal_api;
 
+pub(crate) use clustering::{
+    clustering_domain_configuration, parse_clustering_columns, CLUSTERING_DOMAIN_NAME,
+};
 pub(crate) use column_mapping::column_mapping_mode;
 pub use column_mapping::{validate_schema_column_mapping, ColumnMappingMode};
+pub use enablement::TableFeature;
+pub(crate) use enablement::{merged_protocol, protocol_with_feature, protocol_without_feature};
+pub(crate) use generated_columns::parse_generated_columns;
+pub use generated_columns::GeneratedColumn;
+pub(crate) use identity_columns::parse_identity_columns;
+pub use identity_columns::{IdentityColumn, IdentityRange};
+pub(crate) use invariants::parse_column_invariants;
+pub use invariants::ColumnInvariant;
 pub(crate) use timestamp_ntz::validate_timestamp_ntz_feature_support;
+mod clustering;
 mod column_mapping;
+mod enablement;
+mod generated_columns;
+mod identity_columns;
+mod invariants;
 mod timestamp_ntz;
 
 /// Reader features communicate capabilities that must be implemented in order to correctly read a
@@ -222,15 +238,20 @@ pub(crate) static SUPPORTED_READER_FEATURES: LazyLock<Vec<ReaderFeature>> = Lazy
     ]
 });
 
-// note: we 'support' Invariants, but only insofar as we check that they are not present.
-// we support writing to tables that have Invariants enabled but not used. similarly, we only
-// support DeletionVectors in that we never write them (no DML).
+// note: we only support DeletionVectors in that we never write them (no DML). Invariants are
+// surfaced to engines via the `WriteContext` (and enforced by the default engine).
+// VacuumProtocolCheck is supported since VACUUM checks the protocol like any other write.
 pub(crate) static SUPPORTED_WRITER_FEATURES: LazyLock<Vec<WriterFeature>> = LazyLock::new(|| {
     vec![
         WriterFeature::AppendOnly,
+        WriterFeature::ClusteredTable,
         WriterFeature::DeletionVectors,
+        WriterFeature::DomainMetadata,
+        WriterFeature::GeneratedColumns,
+        WriterFeature::IdentityColumns,
         WriterFeature::Invariants,
         WriterFeature::TimestampWithoutTimezone,
+        WriterFeature::VacuumProtocolCheck,
         WriterFeature::VariantType,
         WriterFeature::VariantTypePreview,
         WriterFeature::VariantShreddingPreview,
@@ -0,0 +1,340 @@
+//! Committers decide how the actions of a [`Transaction`] become a new version of the table. By
+//! default, a transaction is committed by writing its commit file to the `_delta_log` directory
+//! (see [`FileSystemCommitter`]). Tables whose commits are coordinated by a third party, e.g. a
+//! catalog, are committed with a custom [`Committer`] instead, such as a [`StagedCommitter`].
+//!
+//! [`Transaction`]: super::Transaction
+
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use tracing::warn;
+use url::Url;
+
+use crate::path::ParsedLogPath;
+use crate::{DeltaResult, Engine, EngineData, Error, FileMeta, Version};
+
+/// The actions of a commit, as handed to a [`Committer`]. The first action is the `commitInfo`.
+pub type CommitActions<'a> = Box<dyn Iterator<Item = DeltaResult<Box<dyn EngineData>>> + Send + 'a>;
+
+/// Information about a commit, as handed to a [`Committer`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitMetadata {
+    table_root: Url,
+    version: Version,
+    commit_timestamp: i64,
+}
+
+impl CommitMetadata {
+    pub(crate) fn new(table_root: Url, version: Version, commit_timestamp: i64) -> Self {
+        Self {
+            table_root,
+            version,
+            commit_timestamp,
+        }
+    }
+
+    /// The root of the table being committed to.
+    pub fn table_root(&self) -> &Url {
+        &self.table_root
+    }
+
+    /// The version this commit attempts to create.
+    pub fn version(&self) -> Version {
+        self.version
+    }
+
+    /// The timestamp of the commit, in milliseconds since the epoch.
+    pub fn commit_timestamp(&self) -> i64 {
+        self.commit_timestamp
+    }
+}
+
+/// The outcome of a [`Committer::commit`] that did not fail.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommitResponse {
+    /// The commit was accepted and is now the committed version of the table.
+    Committed,
+    /// Another commit already created the committed version of the table.
+    Conflict,
+}
+
+/// Commits the actions of a transaction as a new version of the table. Set a committer for a
+/// transaction with [`Transaction::with_committer`].
+///
+/// A committer must make the commit atomic: either the table gains the new version with exactly the
+/// given actions, or it returns [`CommitResponse::Conflict`] if the version already exists (or an
+/// error, if the outcome is unknown).
+///
+/// [`Transaction::with_committer`]: super::Transaction::with_committer
+pub trait Committer: Debug + Send + Sync {
+    /// Commit `actions` as version `commit_metadata.version()` of the table.
+    fn commit(
+        &self,
+        engine: &dyn Engine,
+        actions: CommitActions<'_>,
+        commit_metadata: CommitMetadata,
+    ) -> DeltaResult<CommitResponse>;
+}
+
+/// The default [`Committer`], which writes `_delta_log/<version>.json` without overwriting, and
+/// treats an existing commit file as a conflict. This requires the engine's JSON handler to write
+/// files atomically, see [`JsonHandler::write_json_file`].
+///
+/// [`JsonHandler::write_json_file`]: crate::JsonHandler::write_json_file
+#[derive(Debug, Default)]
+pub struct FileSystemCommitter;
+
+impl FileSystemCommitter {
+    /// Create a new [`FileSystemCommitter`].
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Committer for FileSystemCommitter {
+    fn commit(
+        &self,
+        engine: &dyn Engine,
+        actions: CommitActions<'_>,
+        commit_metadata: CommitMetadata,
+    ) -> DeltaResult<CommitResponse> {
+        let commit_path =
+            ParsedLogPath::new_commit(commit_metadata.table_root(), commit_metadata.version())?;
+        match engine
+            .json_handler()
+            .write_json_file(&commit_path.location, actions, false)
+        {
+            Ok(()) => Ok(CommitResponse::Committed),
+            Err(Error::FileAlreadyExists(_)) => Ok(CommitResponse::Conflict),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// The catalog side of a [`StagedCommitter`], which ratifies staged commits.
+pub trait CatalogCommitHook: Debug + Send + Sync {
+    /// Ratify the commit staged at `staged_commit` as version `commit_metadata.version()` of the
+    /// table. Returns [`CommitResponse::Conflict`] if the catalog already ratified another commit
+    /// of that version.
+    fn ratify(
+        &self,
+        engine: &dyn Engine,
+        staged_commit: &FileMeta,
+        commit_metadata: &CommitMetadata,
+    ) -> DeltaResult<CommitResponse>;
+}
+
+/// A [`Committer`] for catalog-managed tables: the commit is written to a staged commit file
+/// `_delta_log/_staged_commits/<version>.<uuid>.json`, which the catalog then ratifies through a
+/// [`CatalogCommitHook`]. Publishing ratified commits to `_delta_log/<version>.json` is left to the
+/// catalog (or the engine).
+///
+/// If the catalog reports a conflict, the staged commit file is deleted again.
+#[derive(Debug)]
+pub struct StagedCommitter {
+    hook: Arc<dyn CatalogCommitHook>,
+}
+
+impl StagedCommitter {
+    /// Create a [`StagedCommitter`] ratifying commits through the given catalog `hook`.
+    pub fn new(hook: Arc<dyn CatalogCommitHook>) -> Self {
+        Self { hook }
+    }
+}
+
+impl Committer for StagedCommitter {
+    fn commit(
+        &self,
+        engine: &dyn Engine,
+        actions: CommitActions<'_>,
+        commit_metadata: CommitMetadata,
+    ) -> DeltaResult<CommitResponse> {
+        let staged_path = ParsedLogPath::new_staged_commit(
+            commit_metadata.table_root(),
+            commit_metadata.version(),
+        )?;
+        engine
+            .json_handler()
+            .write_json_file(&staged_path.location, actions, false)?;
+        let storage = engine.storage_handler();
+        let staged_commit = storage.head(&staged_path.location)?;
+        let response = self.hook.ratify(engine, &staged_commit, &commit_metadata)?;
+        if response == CommitResponse::Conflict {
+            // the staged commit will never be ratified, so it is safe to clean it up
+            if let Err(e) = storage.delete(&staged_commit.location) {
+                warn!(
+                    "Failed to delete staged commit {}: {e}",
+                    staged_commit.location
+                );
+            }
+        }
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use object_store::memory::InMemory;
+    use object_store::path::Path;
+    use object_store::ObjectStore as _;
+    use serde_json::json;
+    use test_utils::add_commit;
+
+    use super::*;
+    use crate::engine::default::executor::tokio::TokioBackgroundExecutor;
+    use crate::engine::default::DefaultEngine;
+    use crate::snapshot::Snapshot;
+    use crate::transaction::CommitResult;
+
+    async fn setup() -> (Arc<InMemory>, DefaultEngine<TokioBackgroundExecutor>, Url) {
+        let store = Arc::new(InMemory::new());
+        let schema = json!({
+            "type": "struct",
+            "fields": [{"name": "id", "type": "integer", "nullable": true, "metadata": {}}]
+        });
+        let actions = [
+            json!({"protocol": {"minReaderVersion": 1, "minWriterVersion": 1}}),
+            json!({
+                "metaData": {
+                    "id": "test_id",
+                    "format": {"provider": "parquet", "options": {}},
+                    "schemaString": schema.to_string(),
+                    "partitionColumns": [],
+                    "configuration": {},
+                    "createdTime": 1677811175819u64
+                }
+            }),
+        ];
+        add_commit(store.as_ref(), 0, actions.map(|a| a.to_string()).join("\n"))
+            .await
+            .unwrap();
+        let engine = DefaultEngine::new(store.clone(), Arc::new(TokioBackgroundExecutor::new()));
+        (store, engine, Url::parse("memory:///").unwrap())
+    }
+
+    async fn staged_commits(store: &InMemory) -> Vec<Path> {
+        let prefix = Path::from("_delta_log/_staged_commits");
+        let files = store.list_with_delimiter(Some(&prefix)).await.unwrap();
+        files
+            .objects
+            .into_iter()
+            .map(|meta| meta.location)
+            .collect()
+    }
+
+    // A catalog hook which records the staged commits it ratifies, and reports conflicts for
+    // versions that were already ratified.
+    #[derive(Debug, Default)]
+    struct TestCatalog {
+        ratified: Mutex<Vec<(Version, FileMeta)>>,
+    }
+
+    impl CatalogCommitHook for TestCatalog {
+        fn ratify(
+            &self,
+            _engine: &dyn Engine,
+            staged_commit: &FileMeta,
+            commit_metadata: &CommitMetadata,
+        ) -> DeltaResult<CommitResponse> {
+            let version = commit_metadata.version();
+            let mut ratified = self.ratified.lock().unwrap();
+            if ratified.iter().any(|(v, _)| *v == version) {
+                return Ok(CommitResponse::Conflict);
+            }
+            ratified.push((version, staged_commit.clone()));
+            Ok(CommitResponse::Committed)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_file_system_committer() {
+        let (store, engine, table_root) = setup().await;
+        let snapshot = Arc::new(Snapshot::try_new(table_root.clone(), &engine, None).unwrap());
+
+        let txn = snapshot.clone().transaction().unwrap();
+        let result = txn.commit(&engine).unwrap();
+        assert!(matches!(result, CommitResult::Committed { version: 1, .. }));
+        let commit = Path::from("_delta_log/00000000000000000001.json");
+        assert!(store.head(&commit).await.is_ok());
+
+        // version 1 was already committed
+        let txn = snapshot
+            .transaction()
+            .unwrap()
+            .with_committer(Box::new(FileSystemCommitter::new()));
+        let result = txn.commit(&engine).unwrap();
+        assert!(matches!(result, CommitResult::Conflict(_, 1)));
+    }
+
+    #[tokio::test]
+    async fn test_staged_committer() {
+        let (store, engine, table_root) = setup().await;
+        let catalog = Arc::new(TestCatalog::default());
+        let snapshot = Arc::new(Snapshot::try_new(table_root.clone(), &engine, None).unwrap());
+
+        let txn = snapshot
+            .clone()
+            .transaction()
+            .unwrap()
+            .with_committer(Box::new(StagedCommitter::new(catalog.clone())));
+        let result = txn.commit(&engine).unwrap();
+        assert!(matches!(result, CommitResult::Committed { version: 1, .. }));
+
+        // the commit was staged and ratified, but not published
+        let staged = staged_commits(&store).await;
+        assert_eq!(staged.len(), 1);
+        let ratified = catalog.ratified.lock().unwrap().clone();
+        assert_eq!(ratified.len(), 1);
+        assert_eq!(ratified[0].0, 1);
+        assert!(ratified[0].1.location.path().ends_with(staged[0].as_ref()));
+        assert!(ratified[0].1.size > 0);
+        let commit = Path::from("_delta_log/00000000000000000001.json");
+        assert!(store.head(&commit).await.is_err());
+
+        // a conflicting commit is reported and its staged commit cleaned up
+        let txn = snapshot
+            .transaction()
+            .unwrap()
+            .with_committer(Box::new(StagedCommitter::new(catalog.clone())));
+        let result = txn.commit(&engine).unwrap();
+        assert!(matches!(result, CommitResult::Conflict(_, 1)));
+        assert_eq!(staged_commits(&store).await, staged);
+    }
+
+    #[cfg(feature = "catalog-managed")]
+    #[tokio::test]
+    async fn test_read_staged_commit() {
+        use crate::path::LogPath;
+
+        let (_store, engine, table_root) = setup().await;
+        let catalog = Arc::new(TestCatalog::default());
+        let snapshot = Arc::new(Snapshot::try_new(table_root.clone(), &engine, None).unwrap());
+        let txn = snapshot
+            .transaction()
+            .unwrap()
+            .with_committer(Box::new(StagedCommitter::new(catalog.clone())))
+            .with_operation("TEST".to_string());
+        txn.commit(&engine).unwrap();
+
+        // the ratified commit is visible through the catalog's log tail
+        let log_tail = catalog
+            .ratified
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(_, file_meta)| LogPath::try_new(file_meta.clone()))
+            .collect::<DeltaResult<_>>()
+            .unwrap();
+        let snapshot = Snapshot::builder(table_root)
+            .with_log_tail(log_tail)
+            .with_max_catalog_version(1)
+            .build(&engine)
+            .unwrap();
+        assert_eq!(snapshot.version(), 1);
+        let history = snapshot.history(&engine, Some(1)).unwrap();
+        assert_eq!(history[0].operation.as_deref(), Some("TEST"));
+    }
+}
@@ -1,18 +1,40 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::iter;
 use std::sync::{Arc, LazyLock};
 use std::time::{SystemTime, UNIX_EPOCH};
 
-use crate::actions::{get_log_add_schema, get_log_commit_info_schema, get_log_txn_schema};
-use crate::actions::{CommitInfo, SetTransaction};
+use crate::actions::domain_metadata::all_domain_names;
+use crate::actions::{
+    get_log_add_schema, get_log_commit_info_schema, get_log_domain_metadata_schema,
+    get_log_metadata_schema, get_log_protocol_schema, get_log_remove_schema, get_log_txn_schema,
+};
+use crate::actions::{CommitInfo, DomainMetadata, Metadata, Protocol, SetTransaction};
+use crate::engine_data::{GetData, RowVisitor, TypedGetData as _};
 use crate::error::Error;
-use crate::path::ParsedLogPath;
-use crate::schema::{MapType, SchemaRef, StructField, StructType};
+use crate::expressions::{column_name, ColumnName};
+use crate::schema::{ColumnNamesAndTypes, MapType, SchemaRef, StructField, StructType};
 use crate::snapshot::Snapshot;
+use crate::table_features::{
+    clustering_domain_configuration, protocol_with_feature, protocol_without_feature,
+    ColumnInvariant, GeneratedColumn, IdentityColumn, IdentityRange, TableFeature, WriterFeature,
+    CLUSTERING_DOMAIN_NAME,
+};
+use crate::table_properties::TableProperties;
+use crate::utils::require;
 use crate::{DataType, DeltaResult, Engine, EngineData, Expression, IntoEngineData, Version};
 
 use url::Url;
 
+mod committer;
+mod restore;
+
+pub use committer::{
+    CatalogCommitHook, CommitActions, CommitMetadata, CommitResponse, Committer,
+    FileSystemCommitter, StagedCommitter,
+};
+use restore::RestoredChanges;
+pub use restore::{RestoreMetrics, RestoreTarget};
+
 pub(crate) static ADD_FILES_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
     Arc::new(StructType::new(vec![
         StructField::not_null("path", DataType::STRING),
@@ -36,6 +58,29 @@ pub fn add_files_schema() -> &'static SchemaRef {
     &ADD_FILES_SCHEMA
 }
 
+pub(crate) static REMOVE_FILES_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
+    Arc::new(StructType::new(vec![
+        StructField::not_null("path", DataType::STRING),
+        StructField::not_null(
+            "partitionValues",
+            MapType::new(DataType::STRING, DataType::STRING, true),
+        ),
+        StructField::not_null("size", DataType::LONG),
+        StructField::not_null("dataChange", DataType::BOOLEAN),
+    ]))
+});
+
+/// This function specifies the schema for the remove_files metadata. Concretely, it is the
+/// expected schema for engine data passed to [`remove_files`].
+///
+/// Each row represents a file to be removed from the table. The files are logically removed at the
+/// commit timestamp of the transaction.
+///
+/// [`remove_files`]: crate::transaction::Transaction::remove_files
+pub fn remove_files_schema() -> &'static SchemaRef {
+    &REMOVE_FILES_SCHEMA
+}
+
 /// A transaction represents an in-progress write to a table. After creating a transaction, changes
 /// to the table may be staged via the transaction methods before calling `commit` to commit the
 /// changes to the table.
@@ -55,6 +100,7 @@ pub struct Transaction {
     operation: Option<String>,
     engine_info: Option<String>,
     add_files_metadata: Vec<Box<dyn EngineData>>,
+    remove_files_metadata: Vec<Box<dyn EngineData>>,
     // NB: hashmap would require either duplicating the appid or splitting SetTransaction
     // key/payload. HashSet requires Borrow<&str> with matching Eq, Ord, and Hash. Plus,
     // HashSet::insert drops the to-be-inserted value without returning the existing one, which
@@ -64,6 +110,24 @@ pub struct Transaction {
     // commit-wide timestamp (in milliseconds since epoch) - used in ICT, `txn` action, etc. to
     // keep all timestamps within the same commit consistent.
     commit_timestamp: i64,
+    // column invariants of the read snapshot, parsed once and handed out via the WriteContext
+    invariants: Vec<ColumnInvariant>,
+    // generated columns of the read snapshot, computed by the logical-to-physical transform
+    generated_columns: Vec<GeneratedColumn>,
+    // identity columns of the read snapshot, whose high-water marks advance as values are reserved
+    identity_columns: Vec<IdentityColumn>,
+    // whether any identity value was reserved, i.e. whether we must commit updated metadata
+    identity_values_reserved: bool,
+    // new (logical) clustering columns to record in the `delta.clustering` domain, if any
+    clustering_columns: Option<Vec<ColumnName>>,
+    // new protocol of the table, if features were enabled or dropped by this transaction
+    protocol_update: Option<Protocol>,
+    // new table properties, if enabling or dropping features changed them
+    configuration_update: Option<HashMap<String, String>>,
+    // changes staged by restoring an earlier table version, see `Transaction::restore`
+    restored_changes: Option<RestoredChanges>,
+    // commits the actions of this transaction, see `Transaction::with_committer`
+    committer: Box<dyn Committer>,
 }
 
 impl std::fmt::Debug for Transaction {
@@ -90,6 +154,9 @@ impl Transaction {
         read_snapshot
             .table_configuration()
             .ensure_write_supported()?;
+        let invariants = read_snapshot.table_configuration().column_invariants()?;
+        let generated_columns = read_snapshot.table_configuration().generated_columns()?;
+        let identity_columns = read_snapshot.table_configuration().identity_columns()?;
 
         // TODO: unify all these into a (safer) `fn current_time_ms()`
         let commit_timestamp = SystemTime::now()
@@ -103,13 +170,34 @@ impl Transaction {
             operation: None,
             engine_info: None,
             add_files_metadata: vec![],
+            remove_files_metadata: vec![],
             set_transactions: vec![],
             commit_timestamp,
+            invariants,
+            generated_columns,
+            identity_columns,
+            identity_values_reserved: false,
+            clustering_columns: None,
+            protocol_update: None,
+            configuration_update: None,
+            restored_changes: None,
+            committer: Box::new(FileSystemCommitter::new()),
         })
     }
 
     /// Consume the transaction and commit it to the table. The result is a [CommitResult] which
-    /// will include the failed transaction in case of a conflict so the user can retry.
+    /// will include the failed transaction in case of a conflict so the user can retry. The commit
+    /// goes through the transaction's [`Committer`] (see [`Transaction::with_committer`]).
+    ///
+    /// Besides the `commitInfo`, file and `txn` actions, the commit contains an action for each
+    /// part of the table state the transaction changed: a `protocol` action if table features
+    /// were enabled or dropped, a `metaData` action if table properties changed, identity values
+    /// were reserved or the table was restored, and a `domainMetadata` action for the
+    /// `delta.clustering` domain if clustering columns were set. Restoring the table (see
+    /// [`Transaction::restore`]) must be the only change of the transaction.
+    ///
+    /// Committing to an append-only table fails with [`Error::AppendOnlyViolation`] if the
+    /// transaction removes files with `dataChange = true` or its metadata disables append-only.
     pub fn commit(self, engine: &dyn Engine) -> DeltaResult<CommitResult> {
         // step 0: if there are txn(app_id, version) actions being committed, ensure that every
         // `app_id` is unique and create a row of `EngineData` for it.
@@ -142,21 +230,64 @@ impl Transaction {
         let commit_info_schema = get_log_commit_info_schema().clone();
 
         let commit_info_action = commit_info.into_engine_data(commit_info_schema, engine);
+        self.validate_restore_only()?;
         let add_actions = generate_adds(engine, self.add_files_metadata.iter().map(|a| a.as_ref()));
+        let restored_add_actions = generate_restored_adds(
+            engine,
+            self.restored_changes
+                .iter()
+                .flat_map(|c| &c.adds)
+                .map(|a| a.as_ref()),
+        );
+        let remove_actions = generate_removes(
+            engine,
+            self.all_remove_files_metadata(),
+            self.commit_timestamp,
+        );
+
+        let restored_protocol = self
+            .restored_changes
+            .as_ref()
+            .and_then(|c| c.protocol.clone());
+        let protocol_action = self
+            .protocol_update
+            .clone()
+            .or(restored_protocol)
+            .map(|protocol| protocol.into_engine_data(get_log_protocol_schema().clone(), engine));
+
+        let metadata_update = self.generate_metadata_update()?;
+        self.validate_append_only(metadata_update.as_ref())?;
+        let metadata_action = metadata_update
+            .map(|metadata| metadata.into_engine_data(get_log_metadata_schema().clone(), engine));
+
+        let clustering_action = self.generate_clustering_domain_metadata()?.map(|domain| {
+            domain.into_engine_data(get_log_domain_metadata_schema().clone(), engine)
+        });
 
         let actions = iter::once(commit_info_action)
+            .chain(protocol_action)
+            .chain(metadata_action)
+            .chain(clustering_action)
             .chain(add_actions)
+            .chain(restored_add_actions)
+            .chain(remove_actions)
             .chain(set_transaction_actions);
 
-        // step two: set new commit version (current_version + 1) and path to write
+        // step two: set new commit version (current_version + 1)
         let commit_version = self.read_snapshot.version() + 1;
-        let commit_path =
-            ParsedLogPath::new_commit(self.read_snapshot.table_root(), commit_version)?;
+        let commit_metadata = CommitMetadata::new(
+            self.read_snapshot.table_root().clone(),
+            commit_version,
+            self.commit_timestamp,
+        );
 
-        // step three: commit the actions as a json file in the log
-        let json_handler = engine.json_handler();
-        match json_handler.write_json_file(&commit_path.location, Box::new(actions), false) {
-            Ok(()) => Ok(CommitResult::Committed {
+        // step three: commit the actions through the committer (by default, as a json file in the
+        // log)
+        match self
+            .committer
+            .commit(engine, Box::new(actions), commit_metadata)?
+        {
+            CommitResponse::Committed => Ok(CommitResult::Committed {
                 version: commit_version,
                 post_commit_stats: PostCommitStats {
                     commits_since_checkpoint: self
@@ -171,11 +302,19 @@ impl Transaction {
                         + 1,
                 },
             }),
-            Err(Error::FileAlreadyExists(_)) => Ok(CommitResult::Conflict(self, commit_version)),
-            Err(e) => Err(e),
+            CommitResponse::Conflict => Ok(CommitResult::Conflict(self, commit_version)),
         }
     }
 
+    /// Set the [`Committer`] that commits this transaction. Defaults to the
+    /// [`FileSystemCommitter`], which writes the commit file directly to the `_delta_log`; tables
+    /// whose commits are coordinated by a catalog need a committer going through that catalog,
+    /// e.g. a [`StagedCommitter`].
+    pub fn with_committer(mut self, committer: Box<dyn Committer>) -> Self {
+        self.committer = committer;
+        self
+    }
+
     /// Set the operation that this transaction is performing. This string will be persisted in the
     /// commit and visible to anyone who describes the table history.
     pub fn with_operation(mut self, operation: String) -> Self {
@@ -200,22 +339,290 @@ impl Transaction {
         self
     }
 
+    /// Set the clustering columns of a clustered table, given as logical column names. Each column
+    /// must be a (possibly nested) column of the table with a primitive type. Committing the
+    /// transaction replaces the table's clustering columns; an empty list leaves the table
+    /// clustered but without clustering columns.
+    ///
+    /// If this is not called, the transaction preserves the table's existing clustering columns.
+    /// The commit fails if the table does not support the `clustering` writer feature.
+    pub fn with_clustering_columns(mut self, columns: Vec<ColumnName>) -> Self {
+        self.clustering_columns = Some(columns);
+        self
+    }
+
+    /// The identity columns of the table, for which the engine must generate values when writing.
+    /// Identity columns that do not [allow explicit inserts] must only contain values reserved via
+    /// [`Transaction::reserve_identity_values`] (see [`WriteContext::identity_columns`]).
+    ///
+    /// [allow explicit inserts]: IdentityColumn::allow_explicit_insert
+    pub fn identity_columns(&self) -> &[IdentityColumn] {
+        &self.identity_columns
+    }
+
+    /// Reserve `count` values for the identity column named `column`. The engine must use the
+    /// returned values (each at most once) for the rows it writes in this transaction. Committing
+    /// the transaction records the new high-water mark of the column in the table metadata.
+    ///
+    /// Reservations are not coordinated with other writers: two transactions reading the same
+    /// snapshot may reserve the same values. Because both transactions then attempt to commit the
+    /// same version, only one of them succeeds and the other gets a [`CommitResult::Conflict`]. A
+    /// conflicted transaction must be rebuilt from a fresh snapshot (reserving new values and
+    /// rewriting its data), since its reserved values may already be in use.
+    pub fn reserve_identity_values(
+        &mut self,
+        column: &str,
+        count: u64,
+    ) -> DeltaResult<IdentityRange> {
+        let identity_column = self
+            .identity_columns
+            .iter_mut()
+            .find(|c| c.name() == column)
+            .ok_or_else(|| Error::generic(format!("{column} is not an identity column")))?;
+        let range = identity_column.reserve(count)?;
+        self.identity_values_reserved |= !range.is_empty();
+        Ok(range)
+    }
+
+    /// Enable a table feature, upgrading the table's protocol as needed: the minimum reader and
+    /// writer versions are bumped and the feature is added to the `readerFeatures` and/or
+    /// `writerFeatures` of the protocol. Features required by `feature` are enabled as well, and
+    /// the table property enabling the feature (e.g. `delta.enableDeletionVectors`) is set.
+    ///
+    /// Enabling a feature the table already supports is a no-op for the protocol. Fails if the
+    /// upgraded protocol is not supported by kernel.
+    pub fn enable_feature(&mut self, feature: TableFeature) -> DeltaResult<()> {
+        let mut protocol = self.protocol().clone();
+        for feature in feature.required_features().iter().chain([&feature]) {
+            protocol = protocol_with_feature(&protocol, *feature)?;
+        }
+        protocol.ensure_read_supported()?;
+        protocol.ensure_write_supported()?;
+        if let Some((key, value)) = feature.enabling_property() {
+            self.configuration()
+                .insert(key.to_string(), value.to_string());
+        }
+        if protocol != *self.read_snapshot.table_configuration().protocol() {
+            self.protocol_update = Some(protocol);
+        }
+        Ok(())
+    }
+
+    /// Drop a writer-only table feature, downgrading the table's protocol: the feature is removed
+    /// from the `writerFeatures` of the protocol, and the minimum writer version is lowered if the
+    /// remaining features allow it. The table property enabling the feature is removed.
+    ///
+    /// Only features explicitly listed in the protocol can be dropped, and only once no traces of
+    /// the feature remain in the table. Currently this means [`TableFeature::DomainMetadata`],
+    /// which can be dropped once the table is neither clustered nor has domain metadata.
+    ///
+    /// Fails with [`Error::Unsupported`] for reader-writer features (e.g.
+    /// [`TableFeature::DeletionVectors`]). The Delta protocol only allows removing those after
+    /// the table history containing their traces has been truncated and the table has been
+    /// protected by the `checkpointProtection` feature, which kernel does not implement.
+    /// [`TableFeature::AppendOnly`] and [`TableFeature::Clustering`] cannot be dropped either.
+    pub fn drop_feature(&mut self, engine: &dyn Engine, feature: TableFeature) -> DeltaResult<()> {
+        if feature.reader_feature().is_some() {
+            return Err(Error::unsupported(format!(
+                "Dropping reader-writer table feature {feature} is not supported"
+            )));
+        }
+        let protocol = protocol_without_feature(self.protocol(), feature)?;
+        self.ensure_no_feature_traces(engine, feature, &protocol)?;
+        protocol.ensure_read_supported()?;
+        protocol.ensure_write_supported()?;
+        if let Some((key, _)) = feature.enabling_property() {
+            self.configuration().remove(key);
+        }
+        self.protocol_update = Some(protocol);
+        Ok(())
+    }
+
+    // The protocol this transaction will commit with.
+    fn protocol(&self) -> &Protocol {
+        self.protocol_update
+            .as_ref()
+            .unwrap_or_else(|| self.read_snapshot.table_configuration().protocol())
+    }
+
+    // The metadata this transaction starts from, i.e. the restored or else the current metadata.
+    fn metadata(&self) -> &Metadata {
+        self.restored_metadata()
+            .unwrap_or_else(|| self.read_snapshot.metadata())
+    }
+
+    // The metadata of the table version restored by this transaction, if it differs from the
+    // current one.
+    fn restored_metadata(&self) -> Option<&Metadata> {
+        self.restored_changes.as_ref()?.metadata.as_ref()
+    }
+
+    // The files removed by this transaction, including those removed by restoring the table.
+    fn all_remove_files_metadata(&self) -> impl Iterator<Item = &dyn EngineData> + Send {
+        let restored_removes = self.restored_changes.iter().flat_map(|c| &c.removes);
+        self.remove_files_metadata
+            .iter()
+            .chain(restored_removes)
+            .map(|r| r.as_ref())
+    }
+
+    // The table properties this transaction will commit with, for updating.
+    fn configuration(&mut self) -> &mut HashMap<String, String> {
+        let configuration = self.metadata().configuration.clone();
+        self.configuration_update.get_or_insert(configuration)
+    }
+
+    // Validate that `feature` can be dropped from the table, i.e. that `protocol` (the protocol
+    // without the feature) can be committed without leaving the table in an invalid state.
+    fn ensure_no_feature_traces(
+        &self,
+        engine: &dyn Engine,
+        feature: TableFeature,
+        protocol: &Protocol,
+    ) -> DeltaResult<()> {
+        match feature {
+            TableFeature::AppendOnly
+            | TableFeature::Clustering
+            | TableFeature::DeletionVectors
+            | TableFeature::TimestampWithoutTimezone
+            | TableFeature::VacuumProtocolCheck => Err(Error::unsupported(format!(
+                "Dropping table feature {feature} is not supported"
+            ))),
+            TableFeature::DomainMetadata => {
+                require!(
+                    !protocol.has_writer_feature(&WriterFeature::ClusteredTable)
+                        && self.clustering_columns.is_none(),
+                    Error::generic(format!(
+                        "Cannot drop table feature {feature}: the table is clustered"
+                    ))
+                );
+                let domains = all_domain_names(self.read_snapshot.log_segment(), engine)?;
+                require!(
+                    domains.is_empty(),
+                    Error::generic(format!(
+                        "Cannot drop table feature {feature}: the table has domain metadata for domains {domains:?}"
+                    ))
+                );
+                Ok(())
+            }
+        }
+    }
+
+    // An append-only table only accepts new data: files may only be removed without changing the
+    // data (e.g. compaction), and append-only cannot be silently disabled by a metadata update.
+    fn validate_append_only(&self, metadata_update: Option<&Metadata>) -> DeltaResult<()> {
+        if !self
+            .read_snapshot
+            .table_configuration()
+            .is_append_only_enabled()
+        {
+            return Ok(());
+        }
+        let mut visitor = DataChangeVisitor::default();
+        for remove_files_batch in self.all_remove_files_metadata() {
+            visitor.visit_rows_of(remove_files_batch)?;
+        }
+        require!(
+            !visitor.data_change,
+            Error::append_only_violation(
+                "Cannot remove files with dataChange = true from an append-only table"
+            )
+        );
+        if let Some(metadata) = metadata_update {
+            let properties = TableProperties::from(metadata.configuration.iter());
+            require!(
+                properties.append_only == Some(true),
+                Error::append_only_violation(
+                    "Cannot disable delta.appendOnly as part of a metadata update"
+                )
+            );
+        }
+        Ok(())
+    }
+
+    // Produces the `delta.clustering` domain metadata recording the new clustering columns, if they
+    // were changed by this transaction.
+    fn generate_clustering_domain_metadata(&self) -> DeltaResult<Option<DomainMetadata>> {
+        let Some(columns) = &self.clustering_columns else {
+            return Ok(None);
+        };
+        let table_configuration = self.read_snapshot.table_configuration();
+        let clustering_enabled = self
+            .protocol_update
+            .as_ref()
+            .is_some_and(|protocol| protocol.has_writer_feature(&WriterFeature::ClusteredTable));
+        require!(
+            table_configuration.is_clustering_supported() || clustering_enabled,
+            Error::unsupported("Cannot set clustering columns on a table without clustering")
+        );
+        let configuration =
+            clustering_domain_configuration(columns, &table_configuration.schema())?;
+        Ok(Some(DomainMetadata::new(
+            CLUSTERING_DOMAIN_NAME.to_string(),
+            configuration,
+        )))
+    }
+
+    // Generate the metadata to commit, if this transaction changed it. At the moment, the only
+    // supported metadata changes are advancing the high-water marks of identity columns, updating
+    // table properties when enabling or dropping table features, and restoring older metadata.
+    fn generate_metadata_update(&self) -> DeltaResult<Option<Metadata>> {
+        if !self.identity_values_reserved
+            && self.configuration_update.is_none()
+            && self.restored_metadata().is_none()
+        {
+            return Ok(None);
+        }
+        let metadata = self.metadata();
+        let configuration = self
+            .configuration_update
+            .clone()
+            .unwrap_or_else(|| metadata.configuration.clone());
+        let schema = match self.restored_metadata() {
+            Some(metadata) => Arc::new(metadata.parse_schema()?),
+            None => self.read_snapshot.schema(),
+        };
+        let fields = schema.fields().map(|field| {
+            match self
+                .identity_columns
+                .iter()
+                .find(|c| c.name() == field.name())
+            {
+                Some(identity_column) => identity_column.update_field(field),
+                None => field.clone(),
+            }
+        });
+        let schema = StructType::new(fields);
+        Ok(Some(Metadata {
+            schema_string: serde_json::to_string(&schema)?,
+            configuration,
+            ..metadata.clone()
+        }))
+    }
+
     // Generate the logical-to-physical transform expression which must be evaluated on every data
     // chunk before writing. At the moment, this is a transaction-wide expression.
     fn generate_logical_to_physical(&self) -> Expression {
-        // for now, we just pass through all the columns except partition columns.
+        // for now, we just pass through all the columns except partition columns, and compute
+        // generated columns from the other columns.
         // note this is _incorrect_ if table config deems we need partition columns.
         let partition_columns = &self.read_snapshot.metadata().partition_columns;
         let schema = self.read_snapshot.schema();
         let fields = schema
             .fields()
             .filter(|f| !partition_columns.contains(f.name()))
-            .map(|f| Expression::column([f.name()]));
+            .map(
+                |f| match self.generated_columns.iter().find(|c| c.name() == f.name()) {
+                    Some(generated_column) => generated_column.expression().clone(),
+                    None => Expression::column([f.name()]),
+                },
+            );
         Expression::struct_from(fields)
     }
 
     /// Get the write context for this transaction. At the moment, this is constant for the whole
-    /// transaction.
+    /// transaction, except that it only knows the identity values reserved before it was created:
+    /// engines must reserve identity values before getting the write context.
     // Note: after we introduce metadata updates (modify table schema, etc.), we need to make sure
     // that engines cannot call this method after a metadata change, since the write context could
     // have invalid metadata.
@@ -223,7 +630,14 @@ impl Transaction {
         let target_dir = self.read_snapshot.table_root();
         let snapshot_schema = self.read_snapshot.schema();
         let logical_to_physical = self.generate_logical_to_physical();
-        WriteContext::new(target_dir.clone(), snapshot_schema, logical_to_physical)
+        WriteContext::new(
+            target_dir.clone(),
+            snapshot_schema,
+            logical_to_physical,
+            self.invariants.clone(),
+            self.generated_columns.clone(),
+            self.identity_columns.clone(),
+        )
     }
 
     /// Add files to include in this transaction. This API generally enables the engine to
@@ -234,6 +648,16 @@ impl Transaction {
     pub fn add_files(&mut self, add_metadata: Box<dyn EngineData>) {
         self.add_files_metadata.push(add_metadata);
     }
+
+    /// Remove files from the table in this transaction, e.g. files whose data was rewritten into
+    /// newly added files. Note that this API can be called multiple times to remove multiple
+    /// batches.
+    ///
+    /// The expected schema for `remove_metadata` is given by [`remove_files_schema`]. Append-only
+    /// tables only allow removing files with `dataChange = false`; this is checked on commit.
+    pub fn remove_files(&mut self, remove_metadata: Box<dyn EngineData>) {
+        self.remove_files_metadata.push(remove_metadata);
+    }
 }
 
 // convert add_files_metadata into add actions using an expression to transform the data in a single
@@ -261,6 +685,79 @@ fn generate_adds<'a>(
     })
 }
 
+// convert the add actions of restored files (following the log add schema) into owned add actions
+fn generate_restored_adds<'a>(
+    engine: &dyn Engine,
+    restored_adds: impl Iterator<Item = &'a dyn EngineData> + Send + 'a,
+) -> impl Iterator<Item = DeltaResult<Box<dyn EngineData>>> + Send + 'a {
+    let log_schema = get_log_add_schema();
+    let adds_evaluator = engine.evaluation_handler().new_expression_evaluator(
+        log_schema.clone(),
+        Expression::struct_from([Expression::column(["add"])]),
+        log_schema.clone().into(),
+    );
+    restored_adds.map(move |restored_adds_batch| adds_evaluator.evaluate(restored_adds_batch))
+}
+
+// convert remove_files_metadata into remove actions, deleted at `deletion_timestamp`, using an
+// expression to transform the data in a single pass
+fn generate_removes<'a>(
+    engine: &dyn Engine,
+    remove_files_metadata: impl Iterator<Item = &'a dyn EngineData> + Send + 'a,
+    deletion_timestamp: i64,
+) -> impl Iterator<Item = DeltaResult<Box<dyn EngineData>>> + Send + 'a {
+    let evaluation_handler = engine.evaluation_handler();
+    let remove_files_schema = remove_files_schema();
+    let log_schema = get_log_remove_schema();
+
+    remove_files_metadata.map(move |remove_files_batch| {
+        // NB: the fields must follow the order of the remove action schema
+        let removes_expr = Expression::struct_from([Expression::struct_from([
+            Expression::column(["path"]),
+            Expression::literal(deletion_timestamp),
+            Expression::column(["dataChange"]),
+            Expression::literal(true), // extendedFileMetadata
+            Expression::column(["partitionValues"]),
+            Expression::column(["size"]),
+        ])]);
+        let removes_evaluator = evaluation_handler.new_expression_evaluator(
+            remove_files_schema.clone(),
+            removes_expr,
+            log_schema.clone().into(),
+        );
+        removes_evaluator.evaluate(remove_files_batch)
+    })
+}
+
+// Checks whether any row of remove_files metadata has dataChange = true
+#[derive(Default)]
+struct DataChangeVisitor {
+    data_change: bool,
+}
+
+impl RowVisitor for DataChangeVisitor {
+    fn selected_column_names_and_types(&self) -> (&'static [ColumnName], &'static [DataType]) {
+        static NAMES_AND_TYPES: LazyLock<ColumnNamesAndTypes> =
+            LazyLock::new(|| (vec![column_name!("dataChange")], vec![DataType::BOOLEAN]).into());
+        NAMES_AND_TYPES.as_ref()
+    }
+
+    fn visit<'a>(&mut self, row_count: usize, getters: &[&'a dyn GetData<'a>]) -> DeltaResult<()> {
+        require!(
+            getters.len() == 1,
+            Error::InternalError(format!(
+                "Wrong number of DataChangeVisitor getters: {}",
+                getters.len()
+            ))
+        );
+        for i in 0..row_count {
+            let data_change: bool = getters[0].get(i, "remove_files.dataChange")?;
+            self.data_change |= data_change;
+        }
+        Ok(())
+    }
+}
+
 /// WriteContext is data derived from a [`Transaction`] that can be provided to writers in order to
 /// write table data.
 ///
@@ -269,14 +766,27 @@ pub struct WriteContext {
     target_dir: Url,
     schema: SchemaRef,
     logical_to_physical: Expression,
+    invariants: Vec<ColumnInvariant>,
+    generated_columns: Vec<GeneratedColumn>,
+    identity_columns: Vec<IdentityColumn>,
 }
 
 impl WriteContext {
-    fn new(target_dir: Url, schema: SchemaRef, logical_to_physical: Expression) -> Self {
+    fn new(
+        target_dir: Url,
+        schema: SchemaRef,
+        logical_to_physical: Expression,
+        invariants: Vec<ColumnInvariant>,
+        generated_columns: Vec<GeneratedColumn>,
+        identity_columns: Vec<IdentityColumn>,
+    ) -> Self {
         WriteContext {
             target_dir,
             schema,
             logical_to_physical,
+            invariants,
+            generated_columns,
+            identity_columns,
         }
     }
 
@@ -291,6 +801,36 @@ impl WriteContext {
     pub fn logical_to_physical(&self) -> &Expression {
         &self.logical_to_physical
     }
+
+    /// The column invariants that all data written to the table must satisfy. Engines must
+    /// evaluate each invariant's predicate against the logical data (i.e. before applying
+    /// [`Self::logical_to_physical`]) and refuse to write any batch in which a row does not
+    /// evaluate to `true`.
+    pub fn invariants(&self) -> &[ColumnInvariant] {
+        &self.invariants
+    }
+
+    /// The generated columns of the table. [`Self::logical_to_physical`] already computes their
+    /// values from the other columns, so engines may omit them from the logical data they write.
+    /// Engines that do supply values for them must refuse to write any batch in which a value
+    /// differs from the result of the column's [`GeneratedColumn::expression`] (NULL only matches
+    /// NULL), since the transform would otherwise silently replace it. The exception is generated
+    /// partition columns:
+    /// engines must compute their partition values themselves, e.g. by evaluating
+    /// [`GeneratedColumn::expression`].
+    pub fn generated_columns(&self) -> &[GeneratedColumn] {
+        &self.generated_columns
+    }
+
+    /// The identity columns of the table, along with the values the transaction reserved for them.
+    /// Engines must refuse to write any batch in which an identity column that does not [allow
+    /// explicit inserts] contains a value that is not [reserved] (including NULL).
+    ///
+    /// [allow explicit inserts]: IdentityColumn::allow_explicit_insert
+    /// [reserved]: IdentityColumn::is_reserved
+    pub fn identity_columns(&self) -> &[IdentityColumn] {
+        &self.identity_columns
+    }
 }
 
 /// Kernel exposes information about the state of the table that engines might want to use to
@@ -307,6 +847,9 @@ pub struct PostCommitStats {
 }
 
 /// Result of committing a transaction.
+// NB: the transaction is returned by value on conflict so the caller can retry it without an extra
+// allocation on the (common) successful path.
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug)]
 pub enum CommitResult {
     /// The transaction was successfully committed.
@@ -346,4 +889,19 @@ mod tests {
         ]);
         assert_eq!(*schema, expected.into());
     }
+
+    #[test]
+    fn test_remove_files_schema() {
+        let schema = remove_files_schema();
+        let expected = StructType::new(vec![
+            StructField::not_null("path", DataType::STRING),
+            StructField::not_null(
+                "partitionValues",
+                MapType::new(DataType::STRING, DataType::STRING, true),
+            ),
+            StructField::not_null("size", DataType::LONG),
+            StructField::not_null("dataChange", DataType::BOOLEAN),
+        ]);
+        assert_eq!(*schema, expected.into());
+    }
 }
@@ -0,0 +1,288 @@
+//! RESTORE of a table to an earlier version. See [`Transaction::restore`].
+
+use std::collections::{BTreeMap, HashSet};
+use std::sync::Arc;
+
+use super::{remove_files_schema, Transaction};
+use crate::actions::visitors::AddVisitor;
+use crate::actions::{
+    get_log_add_schema, get_log_schema, Add, Metadata, Protocol, ADD_NAME, SIDECAR_NAME,
+};
+use crate::expressions::{MapData, Scalar};
+use crate::history::history_entries;
+use crate::scan::state::ScanFile;
+use crate::schema::{DataType, MapType};
+use crate::snapshot::Snapshot;
+use crate::table_features::merged_protocol;
+use crate::utils::require;
+use crate::{
+    DeltaResult, Engine, EngineData, Error, EvaluationHandlerExtension as _, IntoEngineData as _,
+    RowVisitor as _, Version,
+};
+
+/// The table version to restore with [`Transaction::restore`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RestoreTarget {
+    /// Restore the given table version.
+    Version(Version),
+    /// Restore the latest table version committed at or before the given timestamp (in
+    /// milliseconds since the epoch). Commit timestamps are in-commit timestamps where available,
+    /// see [`HistoryEntry::timestamp`].
+    ///
+    /// [`HistoryEntry::timestamp`]: crate::history::HistoryEntry::timestamp
+    Timestamp(i64),
+}
+
+/// The changes staged by [`Transaction::restore`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RestoreMetrics {
+    /// The restored table version.
+    pub version: Version,
+    /// The number of files added back to the table.
+    pub num_restored_files: usize,
+    /// The number of files removed from the table.
+    pub num_removed_files: usize,
+    /// The total size in bytes of the files added back to the table.
+    pub restored_files_size: i64,
+    /// The total size in bytes of the files removed from the table.
+    pub removed_files_size: i64,
+}
+
+/// The changes staged by [`Transaction::restore`]. They are kept apart from the other changes of
+/// the transaction, so that committing can check that restoring is the only change.
+pub(crate) struct RestoredChanges {
+    /// The `add` actions of the restored files, as of the restored version.
+    pub(crate) adds: Vec<Box<dyn EngineData>>,
+    /// The removed files, following [`remove_files_schema`].
+    pub(crate) removes: Vec<Box<dyn EngineData>>,
+    /// The protocol supporting the features of both the current and the restored version, if it
+    /// differs from the current one.
+    pub(crate) protocol: Option<Protocol>,
+    /// The metadata of the restored version, if it differs from the current one.
+    pub(crate) metadata: Option<Metadata>,
+}
+
+impl Transaction {
+    /// Restore the table to an earlier version: files that were part of the table at the target
+    /// version but are not anymore are added back, and files that were added since are removed.
+    /// Restored files are added back with their original `add` actions (including statistics, tags
+    /// and deletion vectors). The table's metadata (schema, partition columns and table
+    /// properties) is restored as well, while the protocol is never downgraded: the transaction
+    /// commits the protocol supporting the features of both the current and the restored version.
+    /// Identity columns keep their current high-water marks, so that restored tables never reuse
+    /// identity values. Domain metadata (including clustering columns) is not restored: the table
+    /// keeps its current domains.
+    ///
+    /// Restoring must be the only change of the transaction, which is checked when committing.
+    /// The commit is recorded with the `RESTORE` operation, unless another operation was set.
+    ///
+    /// Fails with [`Error::MissingRestoredFile`] if a file to add back no longer exists, e.g.
+    /// because the table was vacuumed since the restored version; this is checked with the
+    /// engine's [`StorageHandler`]. Removing files with deletion vectors is not supported.
+    ///
+    /// Note that this method performs log replay of both the current and the restored version.
+    ///
+    /// [`StorageHandler`]: crate::StorageHandler
+    pub fn restore(
+        &mut self,
+        engine: &dyn Engine,
+        target: RestoreTarget,
+    ) -> DeltaResult<RestoreMetrics> {
+        require!(
+            self.restored_changes.is_none(),
+            Error::generic("A transaction can only restore the table once")
+        );
+        let version = self.restored_version(engine, target)?;
+        let table_root = self.read_snapshot.table_root();
+        let target_snapshot = Arc::new(Snapshot::try_new(
+            table_root.clone(),
+            engine,
+            Some(version),
+        )?);
+
+        // files are identified by their path and deletion vector
+        let mut current_files = live_files(self.read_snapshot.clone(), engine)?;
+        let (mut restored_files, mut removed_files) = (vec![], vec![]);
+        for (path, file) in live_files(target_snapshot.clone(), engine)? {
+            match current_files.remove(&path) {
+                Some(current) if current.dv_info == file.dv_info => {}
+                Some(current) => {
+                    removed_files.push(current);
+                    restored_files.push(file);
+                }
+                None => restored_files.push(file),
+            }
+        }
+        removed_files.extend(current_files.into_values());
+        require!(
+            removed_files.iter().all(|file| !file.dv_info.has_vector()),
+            Error::unsupported("Restoring a table requires removing files with deletion vectors, which is not supported")
+        );
+
+        let storage = engine.storage_handler();
+        for file in &restored_files {
+            if let Err(Error::FileNotFound(_)) = storage.head(&file.file_meta(table_root)?.location)
+            {
+                return Err(Error::missing_restored_file(version, &file.path));
+            }
+        }
+        let restored_adds = restored_add_actions(&target_snapshot, engine, &restored_files)?
+            .into_iter()
+            .map(|add| {
+                // restoring a file changes the data of the table
+                let add = Add {
+                    data_change: true,
+                    ..add
+                };
+                add.into_engine_data(get_log_add_schema().clone(), engine)
+            })
+            .collect::<DeltaResult<_>>()?;
+
+        let evaluation_handler = engine.evaluation_handler();
+        let partition_values_type = MapType::new(DataType::STRING, DataType::STRING, true);
+        let mut removes: Vec<Box<dyn EngineData>> = vec![];
+        for file in &removed_files {
+            let partition_values =
+                MapData::try_new(partition_values_type.clone(), file.partition_values.clone())?;
+            removes.push(evaluation_handler.create_one(
+                remove_files_schema().clone(),
+                &[
+                    file.path.clone().into(),
+                    Scalar::Map(partition_values),
+                    file.size.into(),
+                    true.into(),
+                ],
+            )?);
+        }
+
+        let current_configuration = self.read_snapshot.table_configuration();
+        let target_configuration = target_snapshot.table_configuration();
+        let protocol = merged_protocol(
+            current_configuration.protocol(),
+            target_configuration.protocol(),
+        )?;
+        protocol.ensure_read_supported()?;
+        protocol.ensure_write_supported()?;
+        let metadata = target_configuration.metadata();
+        self.restored_changes = Some(RestoredChanges {
+            adds: restored_adds,
+            removes,
+            protocol: (protocol != *current_configuration.protocol()).then_some(protocol),
+            metadata: (metadata != current_configuration.metadata()).then(|| metadata.clone()),
+        });
+        self.operation.get_or_insert_with(|| "RESTORE".to_string());
+
+        Ok(RestoreMetrics {
+            version,
+            num_restored_files: restored_files.len(),
+            num_removed_files: removed_files.len(),
+            restored_files_size: restored_files.iter().map(|file| file.size).sum(),
+            removed_files_size: removed_files.iter().map(|file| file.size).sum(),
+        })
+    }
+
+    // Validate that restoring is the only change of this transaction.
+    pub(super) fn validate_restore_only(&self) -> DeltaResult<()> {
+        if self.restored_changes.is_none() {
+            return Ok(());
+        }
+        require!(
+            self.add_files_metadata.is_empty()
+                && self.remove_files_metadata.is_empty()
+                && self.protocol_update.is_none()
+                && self.configuration_update.is_none()
+                && self.clustering_columns.is_none()
+                && !self.identity_values_reserved,
+            Error::generic("Restoring a table must be the only change of a transaction")
+        );
+        Ok(())
+    }
+
+    // Resolve the table version to restore, which must not be newer than the read snapshot.
+    fn restored_version(&self, engine: &dyn Engine, target: RestoreTarget) -> DeltaResult<Version> {
+        let current_version = self.read_snapshot.version();
+        match target {
+            RestoreTarget::Version(version) => {
+                require!(
+                    version <= current_version,
+                    Error::generic(format!(
+                        "Cannot restore table to version {version}: the latest version is {current_version}"
+                    ))
+                );
+                Ok(version)
+            }
+            RestoreTarget::Timestamp(timestamp) => {
+                // the history is read newest first, so this stops at the latest commit at or
+                // before the timestamp without reading any older commits
+                for entry in history_entries(&self.read_snapshot, engine, None)? {
+                    let entry = entry?;
+                    if entry.timestamp <= timestamp {
+                        return Ok(entry.version);
+                    }
+                }
+                Err(Error::generic(format!(
+                    "Cannot restore table to timestamp {timestamp}: no table version was committed at or before it"
+                )))
+            }
+        }
+    }
+}
+
+// The live files of `snapshot`, by path.
+fn live_files(
+    snapshot: Arc<Snapshot>,
+    engine: &dyn Engine,
+) -> DeltaResult<BTreeMap<String, ScanFile>> {
+    let scan = snapshot.scan_builder().build()?;
+    let mut files = BTreeMap::new();
+    for scan_metadata in scan.scan_metadata(engine)? {
+        for file in scan_metadata?.scan_files()? {
+            files.insert(file.path.clone(), file);
+        }
+    }
+    Ok(files)
+}
+
+// The `add` actions of `files` (live files of `snapshot`), found by replaying the log of `snapshot`.
+// The log is replayed newest first, so the first `add` action of a live file is the one adding it.
+fn restored_add_actions(
+    snapshot: &Snapshot,
+    engine: &dyn Engine,
+    files: &[ScanFile],
+) -> DeltaResult<Vec<Add>> {
+    let mut missing: HashSet<_> = files
+        .iter()
+        .map(|file| {
+            let dv_id = file
+                .dv_info
+                .deletion_vector
+                .as_ref()
+                .map(|dv| dv.unique_id());
+            (file.path.clone(), dv_id)
+        })
+        .collect();
+    let mut adds = Vec::with_capacity(files.len());
+    let schema = get_log_schema().project(&[ADD_NAME, SIDECAR_NAME])?;
+    let batches = snapshot
+        .log_segment()
+        .read_actions(engine, schema.clone(), schema, None)?;
+    for batch in batches {
+        if missing.is_empty() {
+            break;
+        }
+        let mut visitor = AddVisitor::default();
+        visitor.visit_rows_of(batch?.actions.as_ref())?;
+        for add in visitor.adds {
+            if missing.remove(&(add.path.clone(), add.dv_unique_id())) {
+                adds.push(add);
+            }
+        }
+    }
+    require!(
+        missing.is_empty(),
+        Error::internal_error(format!(
+            "Could not find the add actions of restored files {missing:?}"
+        ))
+    );
+    Ok(adds)
+}
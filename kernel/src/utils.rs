@@ -2,12 +2,13 @@
 use std::borrow::Cow;
 use std::ops::Deref;
 use std::path::PathBuf;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use crate::table_properties::TableProperties;
 use crate::{DeltaResult, Error};
 use delta_kernel_derive::internal_api;
 
+use serde::{de::Error as _, Deserialize, Deserializer};
 use url::Url;
 
 /// convenient way to return an error if a condition isn't true
@@ -116,6 +117,42 @@ pub(crate) fn calculate_transaction_expiration_timestamp(
         .transpose()
 }
 
+/// Calculates the timestamp (in milliseconds since epoch) before which files are considered expired,
+/// given the current time `now` and the `retention` duration. The result may be negative if the
+/// retention is longer than the time since the epoch.
+pub(crate) fn retention_cutoff_timestamp(now: Duration, retention: Duration) -> DeltaResult<i64> {
+    let now_ms = i64::try_from(now.as_millis())
+        .map_err(|_| Error::generic("Current timestamp exceeds i64 millisecond range"))?;
+    let retention_ms = i64::try_from(retention.as_millis())
+        .map_err(|_| Error::generic("Retention duration exceeds i64 millisecond range"))?;
+    Ok(now_ms - retention_ms)
+}
+
+/// A serialized kernel state (e.g. of a scan or snapshot) whose format is versioned, so that
+/// states written by an incompatible version of kernel are rejected instead of misinterpreted.
+pub(crate) trait VersionedState {
+    /// Human-readable name of the state, used in error messages.
+    const NAME: &'static str;
+    /// The version of the format written by this version of kernel.
+    const VERSION: u32;
+}
+
+/// Deserializes the format version of a [`VersionedState`], failing if it is not the version this
+/// version of kernel writes. Use as `#[serde(deserialize_with = "deserialize_state_version::<T, _>")]`.
+pub(crate) fn deserialize_state_version<'de, T: VersionedState, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<u32, D::Error> {
+    let version = u32::deserialize(deserializer)?;
+    if version != T::VERSION {
+        return Err(D::Error::custom(format!(
+            "Unsupported {} state version {version}, expected {}",
+            T::NAME,
+            T::VERSION
+        )));
+    }
+    Ok(version)
+}
+
 // Extension trait for Cow<'_, T>
 pub(crate) trait CowExt<T: ToOwned + ?Sized> {
     /// The owned type that corresopnds to Self
@@ -0,0 +1,627 @@
+//! This module implements the API for planning VACUUM operations, which delete data files that are
+//! no longer referenced by a table.
+//!
+//! The entry point for this API is [`Snapshot::vacuum`].
+//!
+//! A file is eligible for deletion when it is not referenced by the snapshot (i.e. it is neither
+//! an active file nor a tombstone that is still within the retention period, nor the deletion
+//! vector of one of those), and it was last modified before the retention period. Files in the
+//! `_delta_log` and `_change_data` directories, as well as hidden files and directories (those
+//! whose name starts with `.` or `_`, excluding partition directories) are never deleted.
+//!
+//! ## Usage
+//!
+//! ```no_run
+//! # use std::sync::Arc;
+//! # use std::time::Duration;
+//! # use delta_kernel::{Engine, Error, Snapshot};
+//! let engine: &dyn Engine = todo!(); /* create engine instance */
+//! let snapshot = Arc::new(Snapshot::try_from_uri("./tests/data/basic_partitioned", engine, None)?);
+//!
+//! // Plan the vacuum, keeping unreferenced files that were modified in the last 10 days
+//! let plan = snapshot
+//!     .vacuum()?
+//!     .with_retention(Duration::from_secs(10 * 24 * 60 * 60))
+//!     .plan(engine)?;
+//!
+//! // Delete the files
+//! let result = plan.execute(engine)?;
+//! println!("Deleted {} files", result.num_files);
+//! # Ok::<_, Error>(())
+//! ```
+//!
+//! ## Warning
+//! VACUUM deletes files that readers of older versions of the table may still need. Such readers
+//! (and writers whose transactions started before the retention period) may fail if the retention
+//! is shorter than the time they take. For that reason, [`VacuumPlanner::plan`] refuses retentions
+//! below the table's `delta.deletedFileRetentionDuration`, unless that safety check is disabled.
+use std::collections::HashSet;
+use std::sync::{Arc, LazyLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use tracing::debug;
+use url::Url;
+
+use crate::actions::deletion_vector::DeletionVectorDescriptor;
+use crate::actions::{get_log_schema, ADD_NAME, REMOVE_NAME, SIDECAR_NAME};
+use crate::checkpoint::DEFAULT_RETENTION_SECS;
+use crate::engine_data::{GetData, RowVisitor, TypedGetData as _};
+use crate::log_replay::{ActionsBatch, FileActionDeduplicator};
+use crate::schema::{column_name, ColumnName, ColumnNamesAndTypes, DataType};
+use crate::snapshot::Snapshot;
+use crate::utils::{require, retention_cutoff_timestamp};
+use crate::{DeltaResult, Engine, Error, FileMeta};
+
+/// Plans a VACUUM of a table, i.e. the deletion of data files that are no longer referenced by a
+/// [`Snapshot`] of the table. Create one with [`Snapshot::vacuum`].
+#[derive(Debug)]
+pub struct VacuumPlanner {
+    snapshot: Arc<Snapshot>,
+    retention: Option<Duration>,
+    retention_check: bool,
+    dry_run: bool,
+}
+
+impl VacuumPlanner {
+    pub(crate) fn try_new(snapshot: Arc<Snapshot>) -> DeltaResult<Self> {
+        // VACUUM deletes files, so it is subject to the same protocol checks as any other write.
+        // This is also what the `vacuumProtocolCheck` table feature requires.
+        snapshot.table_configuration().ensure_write_supported()?;
+        Ok(Self {
+            snapshot,
+            retention: None,
+            retention_check: true,
+            dry_run: false,
+        })
+    }
+
+    /// Set the retention period: files that were last modified (or, for tombstones, removed) within
+    /// this period are never deleted. Defaults to the table's `delta.deletedFileRetentionDuration`
+    /// (7 days if not set).
+    pub fn with_retention(mut self, retention: Duration) -> Self {
+        self.retention = Some(retention);
+        self
+    }
+
+    /// Enable or disable the safety check that refuses retention periods shorter than the table's
+    /// `delta.deletedFileRetentionDuration`. The check is enabled by default.
+    pub fn with_retention_check(mut self, enabled: bool) -> Self {
+        self.retention_check = enabled;
+        self
+    }
+
+    /// In dry-run mode, [`VacuumPlan::execute`] reports the files that would be deleted without
+    /// deleting them. Defaults to `false`.
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Compute the files to delete.
+    ///
+    /// The table directory is listed with the engine's [`StorageHandler`], which must return all
+    /// the files below the table root (as object stores do), not just the top-level entries.
+    ///
+    /// Note that this method performs log replay (fetches and processes metadata from storage) and
+    /// lists the whole table directory.
+    ///
+    /// [`StorageHandler`]: crate::StorageHandler
+    pub fn plan(&self, engine: &dyn Engine) -> DeltaResult<VacuumPlan> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| Error::generic(format!("Failed to calculate system time: {e}")))?;
+        self.plan_with_time(engine, now)
+    }
+
+    // Factored out of `plan` to allow testing with an injectable time.
+    fn plan_with_time(&self, engine: &dyn Engine, now: Duration) -> DeltaResult<VacuumPlan> {
+        let minimum_retention = self
+            .snapshot
+            .table_properties()
+            .deleted_file_retention_duration
+            .unwrap_or_else(|| Duration::from_secs(DEFAULT_RETENTION_SECS));
+        let retention = self.retention.unwrap_or(minimum_retention);
+        require!(
+            !self.retention_check || retention >= minimum_retention,
+            Error::generic(format!(
+                "Refusing to vacuum with a retention of {retention:?}, which is shorter than the \
+                 table's deleted file retention of {minimum_retention:?}. Readers and writers of \
+                 older versions of the table may fail. Disable the retention check to proceed."
+            ))
+        );
+        let cutoff_timestamp = retention_cutoff_timestamp(now, retention)?;
+
+        let table_root = self.snapshot.table_root();
+        let referenced = self.referenced_files(engine, cutoff_timestamp)?;
+        let mut files = vec![];
+        for file in engine.storage_handler().list_from(table_root)? {
+            let file = file?;
+            let Some(relative_path) = file.location.as_str().strip_prefix(table_root.as_str())
+            else {
+                continue;
+            };
+            if is_hidden(relative_path)
+                || referenced.contains(&file.location)
+                || file.last_modified >= cutoff_timestamp
+            {
+                continue;
+            }
+            debug!("Vacuum will delete {}", file.location);
+            files.push(file);
+        }
+        Ok(VacuumPlan {
+            files,
+            dry_run: self.dry_run,
+        })
+    }
+
+    // Replays the log to find the data files and deletion vectors referenced by the snapshot, i.e.
+    // by active files or by tombstones removed after `cutoff_timestamp`.
+    fn referenced_files(
+        &self,
+        engine: &dyn Engine,
+        cutoff_timestamp: i64,
+    ) -> DeltaResult<HashSet<Url>> {
+        let schema = get_log_schema().project(&[ADD_NAME, REMOVE_NAME, SIDECAR_NAME])?;
+        let actions =
+            self.snapshot
+                .log_segment()
+                .read_actions(engine, schema.clone(), schema, None)?;
+
+        let mut seen_file_keys = HashSet::new();
+        let mut referenced = HashSet::new();
+        for actions in actions {
+            let ActionsBatch {
+                actions,
+                is_log_batch,
+            } = actions?;
+            let mut visitor = ReferencedFilesVisitor {
+                deduplicator: FileActionDeduplicator::new(
+                    &mut seen_file_keys,
+                    is_log_batch,
+                    ReferencedFilesVisitor::ADD_PATH_INDEX,
+                    ReferencedFilesVisitor::REMOVE_PATH_INDEX,
+                    ReferencedFilesVisitor::ADD_DV_START_INDEX,
+                    ReferencedFilesVisitor::REMOVE_DV_START_INDEX,
+                ),
+                table_root: self.snapshot.table_root(),
+                cutoff_timestamp,
+                referenced: &mut referenced,
+            };
+            visitor.visit_rows_of(actions.as_ref())?;
+        }
+        Ok(referenced)
+    }
+}
+
+/// The files to delete in a VACUUM operation, as computed by [`VacuumPlanner::plan`].
+#[derive(Debug)]
+pub struct VacuumPlan {
+    files: Vec<FileMeta>,
+    dry_run: bool,
+}
+
+impl VacuumPlan {
+    /// The files to delete.
+    pub fn files(&self) -> &[FileMeta] {
+        &self.files
+    }
+
+    /// Whether this is a dry run, i.e. [`VacuumPlan::execute`] will not delete any file.
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// Delete the files of this plan with the engine's [`StorageHandler`] (unless this is a dry
+    /// run), and report what was (or, for a dry run, would have been) deleted.
+    ///
+    /// [`StorageHandler`]: crate::StorageHandler
+    pub fn execute(self, engine: &dyn Engine) -> DeltaResult<VacuumResult> {
+        if !self.dry_run {
+            let storage = engine.storage_handler();
+            self.files
+                .iter()
+                .try_for_each(|file| storage.delete(&file.location))?;
+        }
+        Ok(VacuumResult {
+            num_files: self.files.len() as u64,
+            size_in_bytes: self.files.iter().map(|file| file.size).sum(),
+            dry_run: self.dry_run,
+        })
+    }
+}
+
+/// The outcome of executing a [`VacuumPlan`].
+#[derive(Debug)]
+pub struct VacuumResult {
+    /// The number of files deleted (or that would have been deleted, for a dry run).
+    pub num_files: u64,
+    /// The total size of the files deleted (or that would have been deleted, for a dry run).
+    pub size_in_bytes: u64,
+    /// Whether this was a dry run, in which case no file was actually deleted.
+    pub dry_run: bool,
+}
+
+// Returns true if the file at `relative_path` (relative to the table root) is inside the
+// `_delta_log` or `_change_data` directories, or is (inside) a hidden file or directory. Names
+// starting with `_` that contain `=` are partition directories, which are not hidden.
+fn is_hidden(relative_path: &str) -> bool {
+    relative_path.split('/').any(|name| {
+        name == "_delta_log"
+            || name == "_change_data"
+            || name.starts_with('.')
+            || (name.starts_with('_') && !name.contains('='))
+    })
+}
+
+/// Collects the data files and deletion vectors referenced by the newest action of each file, if
+/// that action is an add or a remove newer than the cutoff timestamp.
+struct ReferencedFilesVisitor<'a> {
+    deduplicator: FileActionDeduplicator<'a>,
+    table_root: &'a Url,
+    cutoff_timestamp: i64,
+    referenced: &'a mut HashSet<Url>,
+}
+
+impl ReferencedFilesVisitor<'_> {
+    // These index positions correspond to the order of columns defined in
+    // `selected_column_names_and_types()`
+    const ADD_PATH_INDEX: usize = 0;
+    const ADD_DV_START_INDEX: usize = 1;
+    const REMOVE_PATH_INDEX: usize = 4;
+    const REMOVE_DELETION_TIMESTAMP_INDEX: usize = 5;
+    const REMOVE_DV_START_INDEX: usize = 6;
+
+    fn visit_file_action<'a>(
+        &mut self,
+        i: usize,
+        getters: &[&'a dyn GetData<'a>],
+    ) -> DeltaResult<()> {
+        let Some((file_key, is_add)) = self.deduplicator.extract_file_action(i, getters, false)?
+        else {
+            return Ok(());
+        };
+        let path = file_key.path.clone();
+        if self.deduplicator.check_and_record_seen(file_key) {
+            return Ok(());
+        }
+
+        let dv_start_index = if is_add {
+            Self::ADD_DV_START_INDEX
+        } else {
+            // As for checkpoints, tombstones without a deletion timestamp are treated as expired.
+            let deletion_timestamp = getters[Self::REMOVE_DELETION_TIMESTAMP_INDEX]
+                .get_opt(i, "remove.deletionTimestamp")?
+                .unwrap_or(0i64);
+            if deletion_timestamp <= self.cutoff_timestamp {
+                return Ok(());
+            }
+            Self::REMOVE_DV_START_INDEX
+        };
+
+        self.referenced.insert(self.table_root.join(&path)?);
+        if let Some(storage_type) =
+            getters[dv_start_index].get_opt(i, "deletionVector.storageType")?
+        {
+            let dv = DeletionVectorDescriptor {
+                storage_type,
+                path_or_inline_dv: getters[dv_start_index + 1]
+                    .get(i, "deletionVector.pathOrInlineDv")?,
+                offset: getters[dv_start_index + 2].get_opt(i, "deletionVector.offset")?,
+                size_in_bytes: 0,
+                cardinality: 0,
+            };
+            if let Some(dv_path) = dv.absolute_path(self.table_root)? {
+                self.referenced.insert(dv_path);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl RowVisitor for ReferencedFilesVisitor<'_> {
+    fn selected_column_names_and_types(&self) -> (&'static [ColumnName], &'static [DataType]) {
+        static NAMES_AND_TYPES: LazyLock<ColumnNamesAndTypes> = LazyLock::new(|| {
+            const STRING: DataType = DataType::STRING;
+            const INTEGER: DataType = DataType::INTEGER;
+            const LONG: DataType = DataType::LONG;
+            let types_and_names = vec![
+                (STRING, column_name!("add.path")),
+                (STRING, column_name!("add.deletionVector.storageType")),
+                (STRING, column_name!("add.deletionVector.pathOrInlineDv")),
+                (INTEGER, column_name!("add.deletionVector.offset")),
+                (STRING, column_name!("remove.path")),
+                (LONG, column_name!("remove.deletionTimestamp")),
+                (STRING, column_name!("remove.deletionVector.storageType")),
+                (STRING, column_name!("remove.deletionVector.pathOrInlineDv")),
+                (INTEGER, column_name!("remove.deletionVector.offset")),
+            ];
+            let (types, names) = types_and_names.into_iter().unzip();
+            (names, types).into()
+        });
+        NAMES_AND_TYPES.as_ref()
+    }
+
+    fn visit<'a>(&mut self, row_count: usize, getters: &[&'a dyn GetData<'a>]) -> DeltaResult<()> {
+        require!(
+            getters.len() == 9,
+            Error::InternalError(format!(
+                "Wrong number of visitor getters: {}",
+                getters.len()
+            ))
+        );
+        for i in 0..row_count {
+            self.visit_file_action(i, getters)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use object_store::memory::InMemory;
+    use object_store::path::Path;
+    use object_store::ObjectStore;
+    use serde_json::json;
+    use test_utils::add_commit;
+
+    use itertools::Itertools as _;
+
+    use crate::arrow::array::AsArray as _;
+    use crate::arrow::datatypes::Int32Type;
+    use crate::engine::arrow_data::ArrowEngineData;
+    use crate::engine::default::executor::tokio::TokioBackgroundExecutor;
+    use crate::engine::default::DefaultEngine;
+
+    const DAY_MILLIS: i64 = 24 * 60 * 60 * 1000;
+
+    fn now() -> Duration {
+        SystemTime::now().duration_since(UNIX_EPOCH).unwrap()
+    }
+
+    // A table with files `a.parquet` (removed, but not expired), `x=1/b.parquet` (active),
+    // `c.parquet` (removed and expired) and various unreferenced files.
+    async fn setup_table() -> (Arc<Snapshot>, DefaultEngine<TokioBackgroundExecutor>) {
+        let store = Arc::new(InMemory::new());
+        let add = |path: &str| {
+            json!({
+                "add": {
+                    "path": path,
+                    "partitionValues": {},
+                    "size": 1,
+                    "modificationTime": 0,
+                    "dataChange": true
+                }
+            })
+        };
+        let remove = |path: &str, deletion_timestamp: i64| {
+            json!({
+                "remove": {
+                    "path": path,
+                    "deletionTimestamp": deletion_timestamp,
+                    "dataChange": true
+                }
+            })
+        };
+        let commit0 = [
+            json!({"protocol": {"minReaderVersion": 1, "minWriterVersion": 2}}),
+            json!({
+                "metaData": {
+                    "id": "test_id",
+                    "format": {"provider": "parquet", "options": {}},
+                    "schemaString": r#"{"type":"struct","fields":[{"name":"id","type":"integer","nullable":true,"metadata":{}}]}"#,
+                    "partitionColumns": [],
+                    "configuration": {},
+                    "createdTime": 1677811175819u64
+                }
+            }),
+            add("a.parquet"),
+            add("x=1/b.parquet"),
+            add("c.parquet"),
+        ];
+        let far_future = now().as_millis() as i64 + 2 * DAY_MILLIS;
+        let commit1 = [remove("a.parquet", far_future), remove("c.parquet", 1)];
+        add_commit(store.as_ref(), 0, commit0.map(|a| a.to_string()).join("\n"))
+            .await
+            .unwrap();
+        add_commit(store.as_ref(), 1, commit1.map(|a| a.to_string()).join("\n"))
+            .await
+            .unwrap();
+        for path in [
+            "a.parquet",
+            "x=1/b.parquet",
+            "c.parquet",
+            "d.parquet",
+            "x=2/e.parquet",
+            "_change_data/f.parquet",
+            ".hidden/g.parquet",
+            "_tmp/h.parquet",
+            ".i.parquet.crc",
+        ] {
+            store
+                .put(&Path::from(path), vec![0u8; 10].into())
+                .await
+                .unwrap();
+        }
+
+        let engine = DefaultEngine::new(store, Arc::new(TokioBackgroundExecutor::new()));
+        let table_root = Url::parse("memory:///").unwrap();
+        let snapshot = Snapshot::try_new(table_root, &engine, None).unwrap();
+        (Arc::new(snapshot), engine)
+    }
+
+    fn planned_files(plan: &VacuumPlan) -> Vec<&str> {
+        let mut files: Vec<_> = plan.files().iter().map(|f| f.location.path()).collect();
+        files.sort();
+        files
+    }
+
+    #[tokio::test]
+    async fn test_vacuum_plan() {
+        let (snapshot, engine) = setup_table().await;
+
+        // with the default retention (7 days) the (new) unreferenced files are kept
+        let plan = snapshot.clone().vacuum().unwrap().plan(&engine).unwrap();
+        assert!(plan.files().is_empty());
+
+        // a day from now, with a retention of one hour, all unreferenced files can be deleted
+        let planner = snapshot
+            .vacuum()
+            .unwrap()
+            .with_retention(Duration::from_secs(60 * 60))
+            .with_retention_check(false);
+        let plan = planner
+            .plan_with_time(&engine, now() + Duration::from_secs(24 * 60 * 60))
+            .unwrap();
+        assert_eq!(
+            planned_files(&plan),
+            vec!["/c.parquet", "/d.parquet", "/x=2/e.parquet"]
+        );
+        assert!(!plan.is_dry_run());
+
+        let result = plan.execute(&engine).unwrap();
+        assert_eq!(result.num_files, 3);
+        assert_eq!(result.size_in_bytes, 30);
+        assert!(!result.dry_run);
+
+        // the files are gone
+        let plan = planner
+            .plan_with_time(&engine, now() + Duration::from_secs(24 * 60 * 60))
+            .unwrap();
+        assert!(plan.files().is_empty());
+        let storage = engine.storage_handler();
+        let table_root = Url::parse("memory:///").unwrap();
+        assert!(storage
+            .head(&table_root.join("d.parquet").unwrap())
+            .is_err());
+        assert!(storage.head(&table_root.join("a.parquet").unwrap()).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_vacuum_dry_run() {
+        let (snapshot, engine) = setup_table().await;
+        let plan = snapshot
+            .vacuum()
+            .unwrap()
+            .with_retention(Duration::ZERO)
+            .with_retention_check(false)
+            .with_dry_run(true)
+            .plan_with_time(&engine, now() + Duration::from_secs(1))
+            .unwrap();
+        assert!(plan.is_dry_run());
+        let result = plan.execute(&engine).unwrap();
+        assert_eq!(result.num_files, 3);
+        assert!(result.dry_run);
+        let table_root = Url::parse("memory:///").unwrap();
+        let d = table_root.join("d.parquet").unwrap();
+        assert!(engine.storage_handler().head(&d).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_vacuum_retention_check() {
+        let (snapshot, engine) = setup_table().await;
+        let planner = snapshot
+            .vacuum()
+            .unwrap()
+            .with_retention(Duration::from_secs(60 * 60));
+        assert!(planner.plan(&engine).is_err());
+        assert!(planner.with_retention_check(false).plan(&engine).is_ok());
+    }
+
+    #[test]
+    fn test_vacuum_keeps_deletion_vectors() {
+        // a copy of a table whose only file has a deletion vector deleting rows 0 and 9
+        let source = std::fs::canonicalize("./tests/data/table-with-dv-small/").unwrap();
+        let table_dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(table_dir.path().join("_delta_log")).unwrap();
+        for dir in ["", "_delta_log"] {
+            for entry in std::fs::read_dir(source.join(dir)).unwrap() {
+                let path = entry.unwrap().path();
+                if path.is_file() {
+                    let target = table_dir.path().join(dir).join(path.file_name().unwrap());
+                    std::fs::copy(&path, target).unwrap();
+                }
+            }
+        }
+        // ... plus an unreferenced deletion vector and data file
+        let stale_dv = "deletion_vector_00000000-0000-0000-0000-000000000000.bin";
+        std::fs::write(table_dir.path().join(stale_dv), [0u8; 10]).unwrap();
+        std::fs::write(table_dir.path().join("stale.parquet"), [0u8; 10]).unwrap();
+
+        let url = Url::from_directory_path(table_dir.path()).unwrap();
+        let engine = Arc::new(
+            DefaultEngine::try_new(
+                &url,
+                std::collections::HashMap::<String, String>::new(),
+                Arc::new(TokioBackgroundExecutor::new()),
+            )
+            .unwrap(),
+        );
+        let snapshot = Arc::new(Snapshot::try_new(url.clone(), engine.as_ref(), None).unwrap());
+        let plan = snapshot
+            .clone()
+            .vacuum()
+            .unwrap()
+            .with_retention(Duration::ZERO)
+            .with_retention_check(false)
+            .plan_with_time(engine.as_ref(), now() + Duration::from_secs(1))
+            .unwrap();
+        // only the unreferenced files are deleted, not the deletion vector of the table's file
+        let mut deleted: Vec<_> = plan
+            .files()
+            .iter()
+            .map(|f| f.location.path_segments().unwrap().next_back().unwrap())
+            .collect();
+        deleted.sort();
+        assert_eq!(deleted, vec![stale_dv, "stale.parquet"]);
+        plan.execute(engine.as_ref()).unwrap();
+
+        // the deletion vector still deletes rows 0 and 9 ...
+        let scan = snapshot.scan_builder().build().unwrap();
+        let scan_files: Vec<_> = scan
+            .scan_metadata(engine.as_ref())
+            .unwrap()
+            .map(|scan_metadata| scan_metadata.unwrap().scan_files().unwrap())
+            .concat();
+        let [scan_file] = &scan_files[..] else {
+            panic!("expected a single file, found {scan_files:?}");
+        };
+        let row_indexes = scan_file
+            .dv_info
+            .get_row_indexes(engine.as_ref(), &url)
+            .unwrap();
+        assert_eq!(row_indexes, Some(vec![0, 9]));
+
+        // ... so exactly the rows with values 1 to 8 survive
+        let mut values = vec![];
+        for result in scan.execute(engine).unwrap() {
+            let result = result.unwrap();
+            let mask = result.full_mask();
+            let data = ArrowEngineData::try_from_engine_data(result.raw_data.unwrap()).unwrap();
+            let column = data.record_batch().column(0).as_primitive::<Int32Type>();
+            values.extend(
+                column
+                    .values()
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, _)| mask.as_ref().is_none_or(|mask| mask[*i]))
+                    .map(|(_, value)| *value),
+            );
+        }
+        assert_eq!(values, (1..=8).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_is_hidden() {
+        assert!(is_hidden("_delta_log/00000000000000000000.json"));
+        assert!(is_hidden("_change_data/cdc-00000.parquet"));
+        assert!(is_hidden(".hidden/part-00000.parquet"));
+        assert!(is_hidden("x=1/.part-00000.parquet.crc"));
+        assert!(is_hidden("_tmp/part-00000.parquet"));
+        assert!(!is_hidden("part-00000.parquet"));
+        assert!(!is_hidden("x=1/part-00000.parquet"));
+        assert!(!is_hidden("_x=1/part-00000.parquet"));
+    }
+}
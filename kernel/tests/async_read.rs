@@ -0,0 +1,126 @@
+//! Reads tables through the async engine APIs and checks they match the synchronous ones.
+use std::path::Path;
+use std::sync::Arc;
+
+use delta_kernel::arrow::array::RecordBatch;
+use delta_kernel::arrow::compute::filter_record_batch;
+use delta_kernel::arrow::util::pretty::pretty_format_batches;
+use delta_kernel::async_engine::AsyncEngine;
+use delta_kernel::engine::default::executor::tokio::TokioMultiThreadExecutor;
+use delta_kernel::engine::default::DefaultEngine;
+use delta_kernel::{DeltaResult, Snapshot};
+use futures::{StreamExt as _, TryStreamExt as _};
+use object_store::local::LocalFileSystem;
+use test_utils::{read_scan, to_arrow};
+use url::Url;
+
+mod common;
+use test_utils::load_test_data;
+
+// The async APIs run all IO on the caller's runtime, so the executor is only needed to satisfy
+// the sync `Engine` half of the default engine (which this test never calls into for IO).
+fn engine() -> Arc<DefaultEngine<TokioMultiThreadExecutor>> {
+    let executor = TokioMultiThreadExecutor::new(tokio::runtime::Handle::current());
+    Arc::new(DefaultEngine::new(
+        Arc::new(LocalFileSystem::new()),
+        Arc::new(executor),
+    ))
+}
+
+async fn read_table_async(
+    table_root: Url,
+    engine: Arc<dyn AsyncEngine>,
+) -> DeltaResult<Vec<RecordBatch>> {
+    let snapshot = Snapshot::try_new_async(table_root, engine.as_ref(), None).await?;
+    let scan = snapshot.into_scan_builder().build()?;
+    let batches = scan
+        .execute_async(engine)?
+        .map(|scan_result| -> DeltaResult<_> {
+            let scan_result = scan_result?;
+            let mask = scan_result.full_mask();
+            let record_batch = to_arrow(scan_result.raw_data?)?;
+            match mask {
+                Some(mask) => Ok(filter_record_batch(&record_batch, &mask.into())?),
+                None => Ok(record_batch),
+            }
+        })
+        .try_collect()
+        .await;
+    batches
+}
+
+fn sorted_lines(batches: &[RecordBatch]) -> Vec<String> {
+    let formatted = pretty_format_batches(batches).unwrap().to_string();
+    let mut lines: Vec<_> = formatted.lines().map(str::to_string).collect();
+    lines.sort_unstable();
+    lines
+}
+
+async fn assert_async_matches_sync(table_path: &Path) {
+    let table_root = Url::from_directory_path(std::fs::canonicalize(table_path).unwrap()).unwrap();
+    let engine = engine();
+
+    // run the async read on another task to ensure the futures and streams are `Send`
+    let async_batches = tokio::spawn(read_table_async(table_root.clone(), engine.clone()))
+        .await
+        .unwrap()
+        .unwrap();
+
+    let sync_batches = tokio::task::spawn_blocking(move || {
+        let snapshot = Snapshot::try_new(table_root, engine.as_ref(), None)?;
+        let scan = snapshot.into_scan_builder().build()?;
+        read_scan(&scan, engine)
+    })
+    .await
+    .unwrap()
+    .unwrap();
+
+    assert!(!sync_batches.is_empty());
+    assert_eq!(sorted_lines(&async_batches), sorted_lines(&sync_batches));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn async_read_with_deletion_vectors() {
+    assert_async_matches_sync(Path::new("./tests/data/table-with-dv-small/")).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn async_read_partitioned() {
+    assert_async_matches_sync(Path::new("./tests/data/basic_partitioned/")).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn async_read_with_checkpoint() {
+    assert_async_matches_sync(Path::new("./tests/data/app-txn-checkpoint/")).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn async_read_with_sidecars() {
+    let test_name = "v2-checkpoints-parquet-with-sidecars";
+    let test_dir = load_test_data("tests/data", test_name).unwrap();
+    assert_async_matches_sync(&test_dir.path().join(test_name)).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn async_time_travel() {
+    let table_path = std::fs::canonicalize("./tests/data/basic_partitioned/").unwrap();
+    let table_root = Url::from_directory_path(table_path).unwrap();
+    let engine = engine();
+
+    let snapshot = Snapshot::try_new_async(table_root, engine.as_ref(), Some(0))
+        .await
+        .unwrap();
+    assert_eq!(snapshot.version(), 0);
+
+    let scan = snapshot.into_scan_builder().build().unwrap();
+    let num_files: usize = scan
+        .scan_metadata_async(engine.as_ref())
+        .unwrap()
+        .map_ok(|scan_metadata| scan_metadata.scan_files.selection_vector.len())
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap()
+        .into_iter()
+        .sum();
+    assert!(num_files > 0);
+}
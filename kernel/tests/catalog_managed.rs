@@ -0,0 +1,179 @@
+//! Tests of catalog-managed tables, with a [`LocalCommitCoordinator`] standing in for the catalog.
+
+use std::sync::Arc;
+
+use delta_kernel::engine::default::executor::tokio::TokioBackgroundExecutor;
+use delta_kernel::engine::default::DefaultEngine;
+use delta_kernel::transaction::CommitResult;
+use delta_kernel::{DeltaResult, Snapshot, Version};
+use object_store::memory::InMemory;
+use object_store::path::Path;
+use object_store::ObjectStore as _;
+use test_utils::commit_coordinator::LocalCommitCoordinator;
+use test_utils::{add_commit, METADATA};
+use url::Url;
+
+// Creates a table with a single published commit.
+async fn setup() -> (
+    Arc<InMemory>,
+    DefaultEngine<TokioBackgroundExecutor>,
+    Url,
+    Arc<LocalCommitCoordinator>,
+) {
+    let store = Arc::new(InMemory::new());
+    add_commit(store.as_ref(), 0, METADATA.to_string())
+        .await
+        .unwrap();
+    let engine = DefaultEngine::new(store.clone(), Arc::new(TokioBackgroundExecutor::new()));
+    let table_root = Url::parse("memory:///").unwrap();
+    let coordinator = LocalCommitCoordinator::new(table_root.clone(), 0);
+    (store, engine, table_root, coordinator)
+}
+
+fn commit(
+    snapshot: Arc<Snapshot>,
+    engine: &DefaultEngine<TokioBackgroundExecutor>,
+    coordinator: &Arc<LocalCommitCoordinator>,
+    operation: &str,
+) -> DeltaResult<CommitResult> {
+    snapshot
+        .transaction()?
+        .with_committer(coordinator.committer())
+        .with_operation(operation.to_string())
+        .commit(engine)
+}
+
+fn operations(
+    snapshot: &Snapshot,
+    engine: &DefaultEngine<TokioBackgroundExecutor>,
+) -> Vec<Option<String>> {
+    let history = snapshot.history(engine, None).unwrap();
+    history.into_iter().map(|entry| entry.operation).collect()
+}
+
+fn published_version(table_root: &Url, engine: &DefaultEngine<TokioBackgroundExecutor>) -> Version {
+    Snapshot::try_new(table_root.clone(), engine, None)
+        .unwrap()
+        .version()
+}
+
+async fn staged_commits(store: &InMemory) -> usize {
+    let prefix = Path::from("_delta_log/_staged_commits");
+    let files = store.list_with_delimiter(Some(&prefix)).await.unwrap();
+    files.objects.len()
+}
+
+#[tokio::test]
+async fn test_staged_commits_and_publish() {
+    let (store, engine, table_root, coordinator) = setup().await;
+
+    // commit two versions, each on top of the latest ratified snapshot
+    for (version, operation) in [(1, "WRITE 1"), (2, "WRITE 2")] {
+        let snapshot = Arc::new(coordinator.snapshot(&engine).unwrap());
+        let result = commit(snapshot, &engine, &coordinator, operation).unwrap();
+        assert!(matches!(result, CommitResult::Committed { version: v, .. } if v == version));
+    }
+    assert_eq!(coordinator.latest_version(), 2);
+    assert_eq!(coordinator.published_version(), 0);
+    assert_eq!(staged_commits(&store).await, 2);
+
+    // the ratified commits are only visible through the coordinator's log tail
+    assert_eq!(published_version(&table_root, &engine), 0);
+    let snapshot = coordinator.snapshot(&engine).unwrap();
+    assert_eq!(snapshot.version(), 2);
+    assert_eq!(
+        operations(&snapshot, &engine),
+        [
+            Some("WRITE 2".to_string()),
+            Some("WRITE 1".to_string()),
+            Some("WRITE".to_string())
+        ]
+    );
+
+    // publish (backfill) the ratified commits one by one
+    coordinator.publish(&engine, 1).unwrap();
+    assert_eq!(coordinator.published_version(), 1);
+    assert_eq!(coordinator.log_tail().unwrap().len(), 1);
+    assert_eq!(published_version(&table_root, &engine), 1);
+    assert_eq!(coordinator.snapshot(&engine).unwrap().version(), 2);
+
+    coordinator.publish(&engine, 2).unwrap();
+    assert!(coordinator.log_tail().unwrap().is_empty());
+    let snapshot = Snapshot::try_new(table_root, &engine, None).unwrap();
+    assert_eq!(snapshot.version(), 2);
+    assert_eq!(
+        operations(&snapshot, &engine)[0].as_deref(),
+        Some("WRITE 2")
+    );
+
+    // versions which were not ratified cannot be published
+    assert!(coordinator.publish(&engine, 3).is_err());
+}
+
+#[tokio::test]
+async fn test_conflicting_commits() {
+    let (store, engine, _table_root, coordinator) = setup().await;
+    let snapshot = Arc::new(coordinator.snapshot(&engine).unwrap());
+
+    let result = commit(snapshot.clone(), &engine, &coordinator, "WRITE 1").unwrap();
+    assert!(matches!(result, CommitResult::Committed { version: 1, .. }));
+
+    // a concurrent commit of the same version is rejected, and its staged commit cleaned up
+    let result = commit(snapshot, &engine, &coordinator, "CONFLICT").unwrap();
+    assert!(matches!(result, CommitResult::Conflict(_, 1)));
+    assert_eq!(staged_commits(&store).await, 1);
+
+    // retrying on top of the latest ratified snapshot succeeds
+    let snapshot = Arc::new(coordinator.snapshot(&engine).unwrap());
+    let result = commit(snapshot, &engine, &coordinator, "RETRY").unwrap();
+    assert!(matches!(result, CommitResult::Committed { version: 2, .. }));
+    let snapshot = coordinator.snapshot(&engine).unwrap();
+    let operations = operations(&snapshot, &engine);
+    assert_eq!(operations[0].as_deref(), Some("RETRY"));
+    assert_eq!(operations[1].as_deref(), Some("WRITE 1"));
+}
+
+#[tokio::test]
+async fn test_commit_ordering() {
+    let (store, engine, table_root, coordinator) = setup().await;
+    // a commit published behind the coordinator's back
+    add_commit(store.as_ref(), 1, METADATA.to_string())
+        .await
+        .unwrap();
+
+    // the coordinator only ratifies the next version of the table
+    let snapshot = Arc::new(Snapshot::try_new(table_root, &engine, None).unwrap());
+    assert_eq!(snapshot.version(), 1);
+    assert!(commit(snapshot, &engine, &coordinator, "WRITE").is_err());
+    assert_eq!(coordinator.latest_version(), 0);
+}
+
+#[tokio::test]
+async fn test_publish_does_not_overwrite() {
+    let (store, engine, _table_root, coordinator) = setup().await;
+    let snapshot = Arc::new(coordinator.snapshot(&engine).unwrap());
+    commit(snapshot, &engine, &coordinator, "WRITE 1").unwrap();
+    let snapshot = Arc::new(coordinator.snapshot(&engine).unwrap());
+    commit(snapshot, &engine, &coordinator, "WRITE 2").unwrap();
+
+    // version 1 was already published with the same content, e.g. by an interrupted publish
+    let prefix = Path::from("_delta_log/_staged_commits");
+    let mut staged = store.list_with_delimiter(Some(&prefix)).await.unwrap();
+    staged.objects.sort_by(|a, b| a.location.cmp(&b.location));
+    let staged_v1 = store.get(&staged.objects[0].location).await.unwrap();
+    let staged_v1 = staged_v1.bytes().await.unwrap();
+    let published_v1 = Path::from("_delta_log/00000000000000000001.json");
+    store.put(&published_v1, staged_v1.into()).await.unwrap();
+    coordinator.publish(&engine, 1).unwrap();
+    assert_eq!(coordinator.published_version(), 1);
+
+    // version 2 was published with different content, which is never overwritten
+    add_commit(store.as_ref(), 2, METADATA.to_string())
+        .await
+        .unwrap();
+    assert!(coordinator.publish(&engine, 2).is_err());
+    assert_eq!(coordinator.published_version(), 1);
+    let published_v2 = Path::from("_delta_log/00000000000000000002.json");
+    let published_v2 = store.get(&published_v2).await.unwrap();
+    assert_eq!(published_v2.bytes().await.unwrap(), METADATA.as_bytes());
+}
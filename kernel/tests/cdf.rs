@@ -13,7 +13,7 @@ use delta_kernel::{DeltaResult, Error, PredicateRef, Version};
 use test_utils::DefaultEngineExtension;
 
 mod common;
-use common::load_test_data;
+use test_utils::load_test_data;
 
 use test_utils::to_arrow;
 
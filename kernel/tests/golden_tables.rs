@@ -27,7 +27,7 @@ use paste::paste;
 use url::Url;
 
 mod common;
-use common::load_test_data;
+use test_utils::load_test_data;
 
 use test_utils::to_arrow;
 
@@ -1318,7 +1318,7 @@ fn timestamp_partitioned_table() -> Result<(), Box<dyn std::error::Error>> {
         "+----+-----+---+----------------------+",
     ];
     let test_name = "timestamp-partitioned-table";
-    let test_dir = common::load_test_data("./tests/data", test_name).unwrap();
+    let test_dir = test_utils::load_test_data("./tests/data", test_name).unwrap();
     let test_path = test_dir.path().join(test_name);
     read_table_data_str(test_path.to_str().unwrap(), None, None, expected)
 }
@@ -1337,7 +1337,7 @@ fn compacted_log_files_table() -> Result<(), Box<dyn std::error::Error>> {
         "+----+--------------------+",
     ];
     let test_name = "compacted-log-files-table";
-    let test_dir = common::load_test_data("./tests/data", test_name).unwrap();
+    let test_dir = test_utils::load_test_data("./tests/data", test_name).unwrap();
     let test_path = test_dir.path().join(test_name);
     read_table_data_str(test_path.to_str().unwrap(), None, None, expected)
 }
@@ -1346,7 +1346,7 @@ fn compacted_log_files_table() -> Result<(), Box<dyn std::error::Error>> {
 fn unshredded_variant_table() -> Result<(), Box<dyn std::error::Error>> {
     let expected = include!("data/unshredded-variant.expected.in");
     let test_name = "unshredded-variant";
-    let test_dir = common::load_test_data("./tests/data", test_name).unwrap();
+    let test_dir = test_utils::load_test_data("./tests/data", test_name).unwrap();
     let test_path = test_dir.path().join(test_name);
     read_table_data_str(test_path.to_str().unwrap(), None, None, expected)
 }
@@ -4,7 +4,7 @@ use delta_kernel::engine::default::DefaultEngine;
 use delta_kernel::{DeltaResult, Snapshot};
 
 mod common;
-use common::load_test_data;
+use test_utils::load_test_data;
 
 use test_utils::DefaultEngineExtension;
 
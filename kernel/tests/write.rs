@@ -1,13 +1,17 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
+use delta_kernel::expressions::ColumnName;
 use delta_kernel::Error as KernelError;
-use delta_kernel::{DeltaResult, Engine, Snapshot, Version};
+use delta_kernel::{DeltaResult, Engine, EngineData, FileMeta, Snapshot, Version};
 
-use delta_kernel::arrow::array::{ArrayRef, BinaryArray, StructArray};
-use delta_kernel::arrow::array::{Int32Array, StringArray, TimestampMicrosecondArray};
+use delta_kernel::arrow::array::{ArrayRef, BinaryArray, BooleanArray, Date32Array, StructArray};
+use delta_kernel::arrow::array::{Int32Array, Int64Array, StringArray, TimestampMicrosecondArray};
 use delta_kernel::arrow::buffer::NullBuffer;
-use delta_kernel::arrow::datatypes::{DataType as ArrowDataType, Field};
+use delta_kernel::arrow::compute::filter_record_batch;
+use delta_kernel::arrow::datatypes::{
+    DataType as ArrowDataType, Field, Schema as ArrowSchema, TimeUnit,
+};
 use delta_kernel::arrow::error::ArrowError;
 use delta_kernel::arrow::record_batch::RecordBatch;
 
@@ -16,21 +20,28 @@ use delta_kernel::engine::arrow_data::ArrowEngineData;
 use delta_kernel::engine::default::executor::tokio::TokioBackgroundExecutor;
 use delta_kernel::engine::default::parquet::DefaultParquetHandler;
 use delta_kernel::engine::default::DefaultEngine;
+use delta_kernel::parquet::arrow::ArrowWriter;
 
-use delta_kernel::transaction::CommitResult;
+use delta_kernel::table_features::TableFeature;
+use delta_kernel::transaction::{CommitResult, RestoreTarget};
 
 use test_utils::set_json_value;
 
 use itertools::Itertools;
+use object_store::memory::InMemory;
 use object_store::path::Path;
 use object_store::ObjectStore;
 use serde_json::json;
 use serde_json::Deserializer;
 use tempfile::tempdir;
 
-use delta_kernel::schema::{DataType, SchemaRef, StructField, StructType};
+use delta_kernel::schema::{
+    ColumnMetadataKey, DataType, MetadataValue, SchemaRef, StructField, StructType,
+};
 
-use test_utils::{create_table, engine_store_setup, setup_test_tables, test_read};
+use test_utils::{
+    add_commit, create_table, engine_store_setup, read_scan, setup_test_tables, test_read,
+};
 
 mod common;
 use url::Url;
@@ -556,6 +567,413 @@ async fn test_append_invalid_schema() -> Result<(), Box<dyn std::error::Error>>
     Ok(())
 }
 
+#[tokio::test]
+async fn test_append_with_invariants() -> Result<(), Box<dyn std::error::Error>> {
+    // setup tracing
+    let _ = tracing_subscriber::fmt::try_init();
+    // a table with one int column 'number' which must be positive. writer version 2 supports (and
+    // hence requires enforcing) invariants
+    let schema = Arc::new(StructType::new(vec![StructField::nullable(
+        "number",
+        DataType::INTEGER,
+    )
+    .with_metadata([(
+        "delta.invariants",
+        r#"{"expression":{"expression":"number > 0"}}"#,
+    )])]));
+    let store = Arc::new(InMemory::new());
+    let engine = Arc::new(DefaultEngine::new(
+        store.clone(),
+        Arc::new(TokioBackgroundExecutor::new()),
+    ));
+    let table_url = Url::parse("memory:///")?;
+    let actions = [
+        json!({"protocol": {"minReaderVersion": 1, "minWriterVersion": 2}}),
+        json!({
+            "metaData": {
+                "id": "test_id",
+                "format": {"provider": "parquet", "options": {}},
+                "schemaString": serde_json::to_string(&schema)?,
+                "partitionColumns": [],
+                "configuration": {},
+                "createdTime": 1677811175819u64
+            }
+        }),
+    ];
+    add_commit(store.as_ref(), 0, actions.map(|a| a.to_string()).join("\n")).await?;
+
+    let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), engine.as_ref(), None)?);
+    let mut txn = snapshot.transaction()?;
+    let write_context = txn.get_write_context();
+    assert_eq!(write_context.invariants().len(), 1);
+    assert_eq!(write_context.invariants()[0].sql(), "number > 0");
+
+    let arrow_schema: Arc<ArrowSchema> = Arc::new(schema.as_ref().try_into_arrow()?);
+    let valid_data = ArrowEngineData::new(RecordBatch::try_new(
+        arrow_schema.clone(),
+        vec![Arc::new(Int32Array::from(vec![1, 2, 3]))],
+    )?);
+    let add_files_metadata = engine
+        .write_parquet(&valid_data, &write_context, HashMap::new(), true)
+        .await?;
+    txn.add_files(add_files_metadata);
+
+    // both negative and NULL values violate the invariant
+    let invalid_data = ArrowEngineData::new(RecordBatch::try_new(
+        arrow_schema,
+        vec![Arc::new(Int32Array::from(vec![Some(1), Some(-2), None]))],
+    )?);
+    let res = engine
+        .write_parquet(&invalid_data, &write_context, HashMap::new(), true)
+        .await;
+    assert!(
+        matches!(res, Err(KernelError::InvariantViolation(ref msg)) if msg.contains("2 of 3 rows"))
+    );
+
+    assert!(matches!(
+        txn.commit(engine.as_ref())?,
+        CommitResult::Committed { version: 1, .. }
+    ));
+    test_read(&valid_data, &table_url, engine)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_append_with_identity_columns() -> Result<(), Box<dyn std::error::Error>> {
+    // setup tracing
+    let _ = tracing_subscriber::fmt::try_init();
+    // a table with a generated 'id' column (1, 3, 5, ...) and a regular 'number' column
+    let schema = Arc::new(StructType::new(vec![
+        StructField::nullable("id", DataType::LONG).with_metadata([
+            (
+                ColumnMetadataKey::IdentityStart.as_ref(),
+                MetadataValue::Number(1),
+            ),
+            (
+                ColumnMetadataKey::IdentityStep.as_ref(),
+                MetadataValue::Number(2),
+            ),
+            (
+                ColumnMetadataKey::IdentityAllowExplicitInsert.as_ref(),
+                MetadataValue::Boolean(false),
+            ),
+        ]),
+        StructField::nullable("number", DataType::INTEGER),
+    ]));
+    let store = Arc::new(InMemory::new());
+    let engine = Arc::new(DefaultEngine::new(
+        store.clone(),
+        Arc::new(TokioBackgroundExecutor::new()),
+    ));
+    let table_url = Url::parse("memory:///")?;
+    let actions = [
+        json!({
+            "protocol": {
+                "minReaderVersion": 3,
+                "minWriterVersion": 7,
+                "readerFeatures": [],
+                "writerFeatures": ["identityColumns"]
+            }
+        }),
+        json!({
+            "metaData": {
+                "id": "test_id",
+                "format": {"provider": "parquet", "options": {}},
+                "schemaString": serde_json::to_string(&schema)?,
+                "partitionColumns": [],
+                "configuration": {},
+                "createdTime": 1677811175819u64
+            }
+        }),
+    ];
+    add_commit(store.as_ref(), 0, actions.map(|a| a.to_string()).join("\n")).await?;
+
+    let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), engine.as_ref(), None)?);
+    let mut txn = snapshot.clone().transaction()?;
+    assert_eq!(txn.identity_columns().len(), 1);
+    assert!(txn.reserve_identity_values("number", 3).is_err());
+    let range = txn.reserve_identity_values("id", 3)?;
+    let ids: Vec<i64> = range.values().collect();
+    assert_eq!(ids, vec![1, 3, 5]);
+
+    let write_context = txn.get_write_context();
+    let arrow_schema: Arc<ArrowSchema> = Arc::new(schema.as_ref().try_into_arrow()?);
+    let data_with_ids = |ids: Vec<Option<i64>>| {
+        RecordBatch::try_new(
+            arrow_schema.clone(),
+            vec![
+                Arc::new(Int64Array::from(ids)),
+                Arc::new(Int32Array::from(vec![10, 20, 30])),
+            ],
+        )
+        .map(ArrowEngineData::new)
+    };
+
+    // 'id' does not allow explicit inserts, so it may only contain the reserved values
+    for explicit_ids in [
+        vec![Some(1), Some(3), Some(7)],
+        vec![Some(1), None, Some(2)],
+    ] {
+        let res = engine
+            .write_parquet(
+                &data_with_ids(explicit_ids)?,
+                &write_context,
+                HashMap::new(),
+                true,
+            )
+            .await;
+        assert!(matches!(
+            res,
+            Err(KernelError::InvariantViolation(ref msg)) if msg.contains("identity column id")
+        ));
+    }
+
+    let data = data_with_ids(ids.into_iter().map(Some).collect())?;
+    let add_files_metadata = engine
+        .write_parquet(&data, &write_context, HashMap::new(), true)
+        .await?;
+    txn.add_files(add_files_metadata);
+
+    // a concurrent transaction on the same snapshot reserves the same values
+    let mut concurrent_txn = snapshot.transaction()?;
+    assert_eq!(concurrent_txn.reserve_identity_values("id", 1)?.first(), 1);
+
+    assert!(matches!(
+        txn.commit(engine.as_ref())?,
+        CommitResult::Committed { version: 1, .. }
+    ));
+    // ... so it must not be able to commit after the first transaction advanced the watermark
+    assert!(matches!(
+        concurrent_txn.commit(engine.as_ref())?,
+        CommitResult::Conflict(_, 1)
+    ));
+
+    // the new high-water mark is part of the table schema
+    let snapshot = Snapshot::try_new(table_url.clone(), engine.as_ref(), None)?;
+    let id_field = snapshot.schema().field("id").cloned().unwrap();
+    assert_eq!(
+        id_field.get_config_value(&ColumnMetadataKey::IdentityHighWaterMark),
+        Some(&MetadataValue::Number(5))
+    );
+    let mut txn = Arc::new(snapshot).transaction()?;
+    assert_eq!(txn.reserve_identity_values("id", 2)?.first(), 7);
+
+    test_read(&data, &table_url, engine)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_append_with_generated_columns() -> Result<(), Box<dyn std::error::Error>> {
+    // setup tracing
+    let _ = tracing_subscriber::fmt::try_init();
+    // a table with a timestamp column 'ts' and two columns generated from it
+    let generated = |name: &str, data_type: DataType, sql: &str| {
+        StructField::nullable(name, data_type)
+            .with_metadata([(ColumnMetadataKey::GenerationExpression.as_ref(), sql)])
+    };
+    let schema = Arc::new(StructType::new(vec![
+        StructField::nullable("ts", DataType::TIMESTAMP),
+        generated("day", DataType::DATE, "CAST(ts AS DATE)"),
+        generated("hour", DataType::LONG, "HOUR(ts)"),
+    ]));
+    let store = Arc::new(InMemory::new());
+    let engine = Arc::new(DefaultEngine::new(
+        store.clone(),
+        Arc::new(TokioBackgroundExecutor::new()),
+    ));
+    let table_url = Url::parse("memory:///")?;
+    let actions = [
+        json!({
+            "protocol": {
+                "minReaderVersion": 3,
+                "minWriterVersion": 7,
+                "readerFeatures": [],
+                "writerFeatures": ["generatedColumns"]
+            }
+        }),
+        json!({
+            "metaData": {
+                "id": "test_id",
+                "format": {"provider": "parquet", "options": {}},
+                "schemaString": serde_json::to_string(&schema)?,
+                "partitionColumns": [],
+                "configuration": {},
+                "createdTime": 1677811175819u64
+            }
+        }),
+    ];
+    add_commit(store.as_ref(), 0, actions.map(|a| a.to_string()).join("\n")).await?;
+
+    let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), engine.as_ref(), None)?);
+    let mut txn = snapshot.transaction()?;
+    let write_context = txn.get_write_context();
+    assert_eq!(write_context.generated_columns().len(), 2);
+
+    // 2024-05-17 13:45:30 UTC and 1969-12-31 23:00:00 UTC
+    let timestamps = vec![Some(1_715_953_530_000_000), Some(-3_600_000_000), None];
+    let ts_field = Field::new(
+        "ts",
+        ArrowDataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into())),
+        true,
+    );
+    let ts_array = Arc::new(TimestampMicrosecondArray::from(timestamps).with_timezone("UTC"));
+    // the generated columns are computed by the write, so the data doesn't need to include them
+    let data = ArrowEngineData::new(RecordBatch::try_new(
+        Arc::new(ArrowSchema::new(vec![ts_field.clone()])),
+        vec![ts_array.clone()],
+    )?);
+    let add_files_metadata = engine
+        .write_parquet(&data, &write_context, HashMap::new(), true)
+        .await?;
+    txn.add_files(add_files_metadata);
+
+    // values supplied for a generated column must match its generation expression
+    let day_field = Field::new("day", ArrowDataType::Date32, true);
+    let with_days = |days: Vec<Option<i32>>| {
+        RecordBatch::try_new(
+            Arc::new(ArrowSchema::new(vec![ts_field.clone(), day_field.clone()])),
+            vec![ts_array.clone(), Arc::new(Date32Array::from(days))],
+        )
+        .map(ArrowEngineData::new)
+    };
+    let res = engine
+        .write_parquet(
+            &with_days(vec![Some(19_860), Some(0), None])?,
+            &write_context,
+            HashMap::new(),
+            true,
+        )
+        .await;
+    assert!(matches!(
+        res,
+        Err(KernelError::InvariantViolation(ref msg)) if msg.contains("1 of 3 rows do not match")
+    ));
+    let res = engine
+        .write_parquet(
+            &with_days(vec![Some(19_860), Some(-1), None])?,
+            &write_context,
+            HashMap::new(),
+            true,
+        )
+        .await;
+    assert!(res.is_ok());
+
+    assert!(matches!(
+        txn.commit(engine.as_ref())?,
+        CommitResult::Committed { version: 1, .. }
+    ));
+
+    let expected = ArrowEngineData::new(RecordBatch::try_new(
+        Arc::new(schema.as_ref().try_into_arrow()?),
+        vec![
+            ts_array,
+            Arc::new(Date32Array::from(vec![Some(19_860), Some(-1), None])),
+            Arc::new(Int64Array::from(vec![Some(13), Some(23), None])),
+        ],
+    )?);
+    test_read(&expected, &table_url, engine)?;
+    Ok(())
+}
+
+// turn the add_files metadata returned by `write_parquet` into remove_files metadata
+fn removes_from_adds(
+    adds: &RecordBatch,
+    data_change: bool,
+) -> Result<Box<dyn EngineData>, Box<dyn std::error::Error>> {
+    let num_rows = adds.num_rows();
+    let fields = adds.schema().fields()[..3]
+        .iter()
+        .cloned()
+        .chain([Arc::new(Field::new(
+            "dataChange",
+            ArrowDataType::Boolean,
+            false,
+        ))])
+        .collect::<Vec<_>>();
+    let columns = adds.columns()[..3]
+        .iter()
+        .cloned()
+        .chain([Arc::new(BooleanArray::from(vec![data_change; num_rows])) as ArrayRef])
+        .collect();
+    let removes = RecordBatch::try_new(Arc::new(ArrowSchema::new(fields)), columns)?;
+    Ok(Box::new(ArrowEngineData::new(removes)))
+}
+
+#[tokio::test]
+async fn test_remove_files_append_only() -> Result<(), Box<dyn std::error::Error>> {
+    // setup tracing
+    let _ = tracing_subscriber::fmt::try_init();
+    let schema = Arc::new(StructType::new(vec![StructField::nullable(
+        "number",
+        DataType::INTEGER,
+    )]));
+    let store = Arc::new(InMemory::new());
+    let engine = Arc::new(DefaultEngine::new(
+        store.clone(),
+        Arc::new(TokioBackgroundExecutor::new()),
+    ));
+    let table_url = Url::parse("memory:///")?;
+    let actions = [
+        json!({"protocol": {"minReaderVersion": 1, "minWriterVersion": 2}}),
+        json!({
+            "metaData": {
+                "id": "test_id",
+                "format": {"provider": "parquet", "options": {}},
+                "schemaString": serde_json::to_string(&schema)?,
+                "partitionColumns": [],
+                "configuration": {"delta.appendOnly": "true"},
+                "createdTime": 1677811175819u64
+            }
+        }),
+    ];
+    add_commit(store.as_ref(), 0, actions.map(|a| a.to_string()).join("\n")).await?;
+
+    // append a file
+    let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), engine.as_ref(), None)?);
+    let mut txn = snapshot.transaction()?;
+    let data = ArrowEngineData::new(RecordBatch::try_new(
+        Arc::new(schema.as_ref().try_into_arrow()?),
+        vec![Arc::new(Int32Array::from(vec![1, 2, 3]))],
+    )?);
+    let add_files_metadata = engine
+        .write_parquet(&data, &txn.get_write_context(), HashMap::new(), true)
+        .await?;
+    let adds = ArrowEngineData::try_from_engine_data(add_files_metadata)?;
+    let removes = removes_from_adds(adds.record_batch(), true)?;
+    let rearranged = removes_from_adds(adds.record_batch(), false)?;
+    txn.add_files(adds);
+    txn.commit(engine.as_ref())?;
+
+    // deleting the file's data is not allowed
+    let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), engine.as_ref(), None)?);
+    let mut txn = snapshot.clone().transaction()?;
+    txn.remove_files(removes);
+    assert!(matches!(
+        txn.commit(engine.as_ref()),
+        Err(KernelError::AppendOnlyViolation(_))
+    ));
+
+    // ... but removing it without changing the data (e.g. as part of compaction) is
+    let mut txn = snapshot.transaction()?;
+    txn.remove_files(rearranged);
+    assert!(matches!(
+        txn.commit(engine.as_ref())?,
+        CommitResult::Committed { version: 2, .. }
+    ));
+
+    let commit2 = store
+        .get(&Path::from("_delta_log/00000000000000000002.json"))
+        .await?;
+    let parsed_commits: Vec<_> = Deserializer::from_slice(&commit2.bytes().await?)
+        .into_iter::<serde_json::Value>()
+        .try_collect()?;
+    let remove = parsed_commits[1].get("remove").unwrap();
+    assert_eq!(remove["dataChange"], json!(false));
+    assert_eq!(remove["extendedFileMetadata"], json!(true));
+    assert!(remove["deletionTimestamp"].is_i64());
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_write_txn_actions() -> Result<(), Box<dyn std::error::Error>> {
     // setup tracing
@@ -1144,3 +1562,616 @@ async fn test_shredded_variant_read_rejection() -> Result<(), Box<dyn std::error
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_clustering_columns() -> Result<(), Box<dyn std::error::Error>> {
+    // setup tracing
+    let _ = tracing_subscriber::fmt::try_init();
+    // a table clustered by 'id'
+    let schema = Arc::new(StructType::new(vec![
+        StructField::nullable("id", DataType::LONG),
+        StructField::nullable("name", DataType::STRING),
+    ]));
+    let store = Arc::new(InMemory::new());
+    let engine = Arc::new(DefaultEngine::new(
+        store.clone(),
+        Arc::new(TokioBackgroundExecutor::new()),
+    ));
+    let table_url = Url::parse("memory:///")?;
+    let actions = [
+        json!({
+            "protocol": {
+                "minReaderVersion": 3,
+                "minWriterVersion": 7,
+                "readerFeatures": [],
+                "writerFeatures": ["clustering", "domainMetadata"]
+            }
+        }),
+        json!({
+            "metaData": {
+                "id": "test_id",
+                "format": {"provider": "parquet", "options": {}},
+                "schemaString": serde_json::to_string(&schema)?,
+                "partitionColumns": [],
+                "configuration": {},
+                "createdTime": 1677811175819u64
+            }
+        }),
+        json!({
+            "domainMetadata": {
+                "domain": "delta.clustering",
+                "configuration": r#"{"clusteringColumns":[["id"]]}"#,
+                "removed": false
+            }
+        }),
+    ];
+    add_commit(store.as_ref(), 0, actions.map(|a| a.to_string()).join("\n")).await?;
+
+    let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), engine.as_ref(), None)?);
+    assert_eq!(
+        snapshot.get_clustering_columns(engine.as_ref())?,
+        Some(vec![ColumnName::new(["id"])])
+    );
+
+    // change the clustering columns
+    let txn = snapshot
+        .clone()
+        .transaction()?
+        .with_clustering_columns(vec![ColumnName::new(["name"]), ColumnName::new(["id"])]);
+    assert!(matches!(
+        txn.commit(engine.as_ref())?,
+        CommitResult::Committed { version: 1, .. }
+    ));
+    let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), engine.as_ref(), None)?);
+    let expected = Some(vec![ColumnName::new(["name"]), ColumnName::new(["id"])]);
+    assert_eq!(snapshot.get_clustering_columns(engine.as_ref())?, expected);
+
+    // a transaction that doesn't set clustering columns preserves them
+    let txn = snapshot.clone().transaction()?;
+    assert!(matches!(
+        txn.commit(engine.as_ref())?,
+        CommitResult::Committed { version: 2, .. }
+    ));
+    let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), engine.as_ref(), None)?);
+    assert_eq!(snapshot.get_clustering_columns(engine.as_ref())?, expected);
+
+    // clustering columns must exist in the schema
+    let txn = snapshot
+        .transaction()?
+        .with_clustering_columns(vec![ColumnName::new(["nope"])]);
+    assert!(txn.commit(engine.as_ref()).is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_domain_metadata_preserved() -> Result<(), Box<dyn std::error::Error>> {
+    // setup tracing
+    let _ = tracing_subscriber::fmt::try_init();
+    let schema = Arc::new(StructType::new(vec![
+        StructField::nullable("id", DataType::LONG),
+        StructField::nullable("name", DataType::STRING),
+    ]));
+    let store = Arc::new(InMemory::new());
+    let engine = Arc::new(DefaultEngine::new(
+        store.clone(),
+        Arc::new(TokioBackgroundExecutor::new()),
+    ));
+    let table_url = Url::parse("memory:///")?;
+    let domain_metadata = |domain: &str, configuration: &str, removed: bool| {
+        json!({
+            "domainMetadata": {
+                "domain": domain,
+                "configuration": configuration,
+                "removed": removed
+            }
+        })
+    };
+    let actions = [
+        json!({
+            "protocol": {
+                "minReaderVersion": 3,
+                "minWriterVersion": 7,
+                "readerFeatures": [],
+                "writerFeatures": ["clustering", "domainMetadata"]
+            }
+        }),
+        json!({
+            "metaData": {
+                "id": "test_id",
+                "format": {"provider": "parquet", "options": {}},
+                "schemaString": serde_json::to_string(&schema)?,
+                "partitionColumns": [],
+                "configuration": {},
+                "createdTime": 1677811175819u64
+            }
+        }),
+        domain_metadata(
+            "delta.clustering",
+            r#"{"clusteringColumns":[["id"]]}"#,
+            false,
+        ),
+        domain_metadata("app.kept", "v0", false),
+        domain_metadata("app.removed", "v0", false),
+    ];
+    add_commit(store.as_ref(), 0, actions.map(|a| a.to_string()).join("\n")).await?;
+
+    // version 1 changes the clustering columns, version 2 updates one domain and removes another
+    let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), engine.as_ref(), None)?);
+    let txn = snapshot
+        .transaction()?
+        .with_clustering_columns(vec![ColumnName::new(["name"])]);
+    txn.commit(engine.as_ref())?;
+    let actions = [
+        domain_metadata("app.kept", "v2", false),
+        domain_metadata("app.removed", "v0", true),
+    ];
+    add_commit(store.as_ref(), 2, actions.map(|a| a.to_string()).join("\n")).await?;
+
+    let check_domains = |snapshot: &Snapshot| -> DeltaResult<()> {
+        assert_eq!(
+            snapshot.get_clustering_columns(engine.as_ref())?,
+            Some(vec![ColumnName::new(["name"])])
+        );
+        assert_eq!(
+            snapshot.get_domain_metadata("app.kept", engine.as_ref())?,
+            Some("v2".to_string())
+        );
+        assert_eq!(
+            snapshot.get_domain_metadata("app.removed", engine.as_ref())?,
+            None
+        );
+        Ok(())
+    };
+
+    // RESTORE leaves the domain metadata of the table untouched
+    let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), engine.as_ref(), None)?);
+    let mut txn = snapshot.transaction()?;
+    txn.restore(engine.as_ref(), RestoreTarget::Version(0))?;
+    assert!(matches!(
+        txn.commit(engine.as_ref())?,
+        CommitResult::Committed { version: 3, .. }
+    ));
+    let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), engine.as_ref(), None)?);
+    check_domains(&snapshot)?;
+
+    // a checkpoint keeps the latest domain metadata of each domain that was not removed
+    let writer = snapshot.checkpoint()?;
+    let checkpoint_path = writer.checkpoint_path()?;
+    let mut data = writer.checkpoint_data(engine.as_ref())?;
+    let mut batches = vec![];
+    for filtered_data in data.by_ref() {
+        let filtered_data = filtered_data?;
+        let batch = ArrowEngineData::try_from_engine_data(filtered_data.data)?;
+        let selection_vector = BooleanArray::from(filtered_data.selection_vector);
+        batches.push(filter_record_batch(
+            batch.record_batch(),
+            &selection_vector,
+        )?);
+    }
+    let mut buffer = vec![];
+    let mut parquet_writer = ArrowWriter::try_new(&mut buffer, batches[0].schema(), None)?;
+    for batch in &batches {
+        parquet_writer.write(batch)?;
+    }
+    parquet_writer.close()?;
+    let size = buffer.len() as u64;
+    let path = Path::from_url_path(checkpoint_path.path())?;
+    store.put(&path, buffer.into()).await?;
+    let file_meta = FileMeta::new(checkpoint_path, 0, size);
+    writer.finalize(engine.as_ref(), &file_meta, data)?;
+
+    // ... so the table has the same domain metadata when read from the checkpoint alone
+    for version in 0..=3 {
+        let commit = format!("_delta_log/{version:020}.json");
+        store.delete(&Path::from(commit)).await?;
+    }
+    let snapshot = Snapshot::try_new(table_url.clone(), engine.as_ref(), None)?;
+    assert_eq!(snapshot.version(), 3);
+    check_domains(&snapshot)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_clustering_columns_unsupported() -> Result<(), Box<dyn std::error::Error>> {
+    // setup tracing
+    let _ = tracing_subscriber::fmt::try_init();
+    let schema = Arc::new(StructType::new(vec![StructField::nullable(
+        "number",
+        DataType::INTEGER,
+    )]));
+    for (table_url, engine, _store, _table_name) in
+        setup_test_tables(schema, &[], None, "test_table").await?
+    {
+        let snapshot = Arc::new(Snapshot::try_new(table_url, &engine, None)?);
+        assert_eq!(snapshot.get_clustering_columns(&engine)?, None);
+        let txn = snapshot
+            .transaction()?
+            .with_clustering_columns(vec![ColumnName::new(["number"])]);
+        assert!(matches!(
+            txn.commit(&engine),
+            Err(KernelError::Unsupported(_))
+        ));
+    }
+    Ok(())
+}
+
+// Read the `protocol` action of the given commit, if it has one.
+async fn read_commit_protocol(
+    store: &dyn ObjectStore,
+    table_name: &str,
+    version: u64,
+) -> Result<Option<serde_json::Value>, Box<dyn std::error::Error>> {
+    let commit = store
+        .get(&Path::from(format!(
+            "/{table_name}/_delta_log/{version:020}.json"
+        )))
+        .await?;
+    let protocol = Deserializer::from_slice(&commit.bytes().await?)
+        .into_iter::<serde_json::Value>()
+        .map_ok(|action| action.get("protocol").cloned())
+        .flatten_ok()
+        .next()
+        .transpose()?;
+    Ok(protocol)
+}
+
+async fn read_commit_adds(
+    store: &dyn ObjectStore,
+    table_name: &str,
+    version: u64,
+) -> Result<Vec<serde_json::Value>, Box<dyn std::error::Error>> {
+    let commit = store
+        .get(&Path::from(format!(
+            "/{table_name}/_delta_log/{version:020}.json"
+        )))
+        .await?;
+    let mut adds: Vec<_> = Deserializer::from_slice(&commit.bytes().await?)
+        .into_iter::<serde_json::Value>()
+        .map_ok(|action| action.get("add").cloned())
+        .flatten_ok()
+        .try_collect()?;
+    adds.sort_by_key(|add| add["path"].to_string());
+    Ok(adds)
+}
+
+#[tokio::test]
+async fn test_enable_and_drop_table_features() -> Result<(), Box<dyn std::error::Error>> {
+    // setup tracing
+    let _ = tracing_subscriber::fmt::try_init();
+    let schema = Arc::new(StructType::new(vec![StructField::nullable(
+        "number",
+        DataType::INTEGER,
+    )]));
+    for (table_url, engine, store, table_name) in
+        setup_test_tables(schema, &[], None, "test_table").await?
+    {
+        let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), &engine, None)?);
+        let mut txn = snapshot.transaction()?;
+        txn.enable_feature(TableFeature::DeletionVectors)?;
+        txn.enable_feature(TableFeature::VacuumProtocolCheck)?;
+        txn.enable_feature(TableFeature::DomainMetadata)?;
+        assert!(matches!(
+            txn.commit(&engine)?,
+            CommitResult::Committed { version: 1, .. }
+        ));
+
+        let protocol = read_commit_protocol(store.as_ref(), table_name, 1).await?;
+        assert_eq!(
+            protocol,
+            Some(json!({
+                "minReaderVersion": 3,
+                "minWriterVersion": 7,
+                "readerFeatures": ["deletionVectors", "vacuumProtocolCheck"],
+                "writerFeatures": ["deletionVectors", "vacuumProtocolCheck", "domainMetadata"]
+            }))
+        );
+        let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), &engine, None)?);
+        assert_eq!(
+            snapshot.table_properties().enable_deletion_vectors,
+            Some(true)
+        );
+
+        // reader-writer features cannot be dropped without truncating the table history
+        let mut txn = snapshot.transaction()?;
+        for feature in [
+            TableFeature::DeletionVectors,
+            TableFeature::VacuumProtocolCheck,
+        ] {
+            let result = txn.drop_feature(&engine, feature);
+            assert!(matches!(result, Err(KernelError::Unsupported(_))));
+        }
+
+        // the table has no domain metadata, so the writer-only feature can be dropped again
+        txn.drop_feature(&engine, TableFeature::DomainMetadata)?;
+        assert!(matches!(
+            txn.commit(&engine)?,
+            CommitResult::Committed { version: 2, .. }
+        ));
+
+        let protocol = read_commit_protocol(store.as_ref(), table_name, 2).await?;
+        assert_eq!(
+            protocol,
+            Some(json!({
+                "minReaderVersion": 3,
+                "minWriterVersion": 7,
+                "readerFeatures": ["deletionVectors", "vacuumProtocolCheck"],
+                "writerFeatures": ["deletionVectors", "vacuumProtocolCheck"]
+            }))
+        );
+        let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), &engine, None)?);
+        assert_eq!(
+            snapshot.table_properties().enable_deletion_vectors,
+            Some(true)
+        );
+
+        // features which are not listed in the protocol cannot be dropped
+        let mut txn = snapshot.transaction()?;
+        assert!(txn
+            .drop_feature(&engine, TableFeature::DomainMetadata)
+            .is_err());
+    }
+    Ok(())
+}
+
+#[test]
+fn test_drop_deletion_vectors_unsupported() -> Result<(), Box<dyn std::error::Error>> {
+    let path = std::fs::canonicalize("./tests/data/table-with-dv-small/")?;
+    let table_url = Url::from_directory_path(path).unwrap();
+    let engine = DefaultEngine::try_new(
+        &table_url,
+        std::iter::empty::<(&str, &str)>(),
+        Arc::new(TokioBackgroundExecutor::new()),
+    )?;
+    let snapshot = Arc::new(Snapshot::try_new(table_url, &engine, None)?);
+    let mut txn = snapshot.transaction()?;
+    let result = txn.drop_feature(&engine, TableFeature::DeletionVectors);
+    assert!(matches!(result, Err(KernelError::Unsupported(_))));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_enable_clustering() -> Result<(), Box<dyn std::error::Error>> {
+    // setup tracing
+    let _ = tracing_subscriber::fmt::try_init();
+    let schema = Arc::new(StructType::new(vec![StructField::nullable(
+        "number",
+        DataType::INTEGER,
+    )]));
+    for (table_url, engine, store, table_name) in
+        setup_test_tables(schema, &[], None, "test_table").await?
+    {
+        // enabling clustering also enables domain metadata, and allows setting clustering columns
+        let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), &engine, None)?);
+        let mut txn = snapshot
+            .transaction()?
+            .with_clustering_columns(vec![ColumnName::new(["number"])]);
+        txn.enable_feature(TableFeature::Clustering)?;
+        assert!(matches!(
+            txn.commit(&engine)?,
+            CommitResult::Committed { version: 1, .. }
+        ));
+        let protocol = read_commit_protocol(store.as_ref(), table_name, 1).await?;
+        let writer_features = protocol.unwrap()["writerFeatures"].clone();
+        assert_eq!(writer_features, json!(["domainMetadata", "clustering"]));
+
+        let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), &engine, None)?);
+        assert_eq!(
+            snapshot.get_clustering_columns(&engine)?,
+            Some(vec![ColumnName::new(["number"])])
+        );
+
+        // domain metadata cannot be dropped from a clustered table, nor can clustering be dropped
+        let mut txn = snapshot.transaction()?;
+        let result = txn.drop_feature(&engine, TableFeature::DomainMetadata);
+        assert!(matches!(result, Err(e) if e.to_string().contains("the table is clustered")));
+        let result = txn.drop_feature(&engine, TableFeature::Clustering);
+        assert!(matches!(result, Err(KernelError::Unsupported(_))));
+    }
+    Ok(())
+}
+
+// Read the `number` column of the table, sorted since files are not read in any particular order.
+fn read_numbers(
+    table_url: &Url,
+    engine: Arc<DefaultEngine<TokioBackgroundExecutor>>,
+) -> Result<Vec<i32>, Box<dyn std::error::Error>> {
+    let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), engine.as_ref(), None)?);
+    let scan = snapshot.scan_builder().build()?;
+    let mut numbers = vec![];
+    for batch in read_scan(&scan, engine)? {
+        let column = batch.column(0).as_any().downcast_ref::<Int32Array>();
+        numbers.extend(column.unwrap().values());
+    }
+    numbers.sort();
+    Ok(numbers)
+}
+
+#[tokio::test]
+async fn test_restore() -> Result<(), Box<dyn std::error::Error>> {
+    // setup tracing
+    let _ = tracing_subscriber::fmt::try_init();
+    let schema = Arc::new(StructType::new(vec![StructField::nullable(
+        "number",
+        DataType::INTEGER,
+    )]));
+    for (table_url, engine, store, table_name) in
+        setup_test_tables(schema.clone(), &[], None, "test_table").await?
+    {
+        let engine = Arc::new(engine);
+        write_data_and_check_result_and_stats(table_url.clone(), schema.clone(), engine.clone(), 1)
+            .await?;
+        write_data_and_check_result_and_stats(table_url.clone(), schema.clone(), engine.clone(), 2)
+            .await?;
+        let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), engine.as_ref(), None)?);
+        let protocol = read_commit_protocol(store.as_ref(), table_name, 0).await?;
+        let mut txn = snapshot.transaction()?;
+        txn.enable_feature(TableFeature::DeletionVectors)?;
+        txn.commit(engine.as_ref())?;
+        let upgraded_protocol = read_commit_protocol(store.as_ref(), table_name, 3).await?;
+        assert_ne!(protocol, upgraded_protocol);
+
+        // restore version 1 (by timestamp): the files added by version 2 are removed, and the
+        // table properties are restored without downgrading the protocol
+        let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), engine.as_ref(), None)?);
+        let history = snapshot.history(engine.as_ref(), None)?;
+        let timestamp = history
+            .iter()
+            .find(|entry| entry.version == 1)
+            .unwrap()
+            .timestamp;
+        let mut txn = snapshot.transaction()?;
+        let metrics = txn.restore(engine.as_ref(), RestoreTarget::Timestamp(timestamp))?;
+        assert_eq!(metrics.version, 1);
+        assert_eq!(metrics.num_restored_files, 0);
+        assert_eq!(metrics.num_removed_files, 2);
+        assert!(matches!(
+            txn.commit(engine.as_ref())?,
+            CommitResult::Committed { version: 4, .. }
+        ));
+        let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), engine.as_ref(), None)?);
+        assert_eq!(snapshot.table_properties().enable_deletion_vectors, None);
+        assert_eq!(
+            snapshot.history(engine.as_ref(), Some(1))?[0]
+                .operation
+                .as_deref(),
+            Some("RESTORE")
+        );
+        assert_eq!(
+            read_commit_protocol(store.as_ref(), table_name, 4).await?,
+            None
+        );
+        assert_eq!(
+            read_numbers(&table_url, engine.clone())?,
+            [1, 2, 3, 4, 5, 6]
+        );
+
+        // restore version 2: the removed files are added back
+        let mut txn = snapshot.transaction()?;
+        let metrics = txn.restore(engine.as_ref(), RestoreTarget::Version(2))?;
+        assert_eq!(metrics.num_restored_files, 2);
+        assert_eq!(metrics.num_removed_files, 0);
+        assert!(metrics.restored_files_size > 0);
+        txn.commit(engine.as_ref())?;
+        assert_eq!(
+            read_numbers(&table_url, engine.clone())?,
+            [1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6]
+        );
+
+        // the original add actions are restored
+        let added = read_commit_adds(store.as_ref(), table_name, 2).await?;
+        let restored = read_commit_adds(store.as_ref(), table_name, 5).await?;
+        assert_eq!(restored.len(), 2);
+        for (added, restored) in added.iter().zip(&restored) {
+            for field in ["path", "partitionValues", "size", "modificationTime"] {
+                assert_eq!(added[field], restored[field], "{field}");
+            }
+            assert_eq!(restored["dataChange"], true);
+        }
+
+        // restoring must be the only change of the transaction, which is checked on commit
+        let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), engine.as_ref(), None)?);
+        let mut txn = snapshot.transaction()?;
+        txn.restore(engine.as_ref(), RestoreTarget::Version(1))?;
+        txn.enable_feature(TableFeature::DomainMetadata)?;
+        assert!(matches!(
+            txn.commit(engine.as_ref()),
+            Err(KernelError::Generic(msg)) if msg.contains("only change")
+        ));
+
+        // restoring fails cleanly if a file to add back has been deleted (e.g. vacuumed)
+        let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), engine.as_ref(), None)?);
+        let mut txn = snapshot.transaction()?;
+        txn.restore(engine.as_ref(), RestoreTarget::Version(1))?;
+        txn.commit(engine.as_ref())?;
+        let commit2 = store
+            .get(&Path::from(format!(
+                "/{table_name}/_delta_log/00000000000000000002.json"
+            )))
+            .await?;
+        let added_paths: Vec<String> = Deserializer::from_slice(&commit2.bytes().await?)
+            .into_iter::<serde_json::Value>()
+            .map_ok(|action| action["add"]["path"].as_str().map(ToString::to_string))
+            .flatten_ok()
+            .try_collect()?;
+        assert_eq!(added_paths.len(), 2);
+        let deleted_path = Url::parse(&added_paths[0])?;
+        store
+            .delete(&Path::from_url_path(deleted_path.path())?)
+            .await?;
+        let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), engine.as_ref(), None)?);
+        let mut txn = snapshot.transaction()?;
+        assert!(matches!(
+            txn.restore(engine.as_ref(), RestoreTarget::Version(2)),
+            Err(KernelError::MissingRestoredFile(2, _))
+        ));
+
+        // versions after the read snapshot cannot be restored
+        assert!(txn
+            .restore(engine.as_ref(), RestoreTarget::Version(7))
+            .is_err());
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_restore_keeps_add_actions() -> Result<(), Box<dyn std::error::Error>> {
+    // setup tracing
+    let _ = tracing_subscriber::fmt::try_init();
+    let schema = Arc::new(StructType::new(vec![StructField::nullable(
+        "number",
+        DataType::INTEGER,
+    )]));
+    for (table_url, engine, store, table_name) in
+        setup_test_tables(schema.clone(), &[], None, "test_table").await?
+    {
+        let engine = Arc::new(engine);
+        write_data_and_check_result_and_stats(table_url.clone(), schema.clone(), engine.clone(), 1)
+            .await?;
+
+        // re-add the files of version 1 with statistics and tags, without changing the data
+        let adds = read_commit_adds(store.as_ref(), table_name, 1).await?;
+        let stats = r#"{"numRecords":3,"minValues":{"number":1},"maxValues":{"number":3}}"#;
+        let actions = adds.iter().map(|add| {
+            let mut add = add.clone();
+            add["dataChange"] = false.into();
+            add["modificationTime"] = 1234.into();
+            add["stats"] = stats.into();
+            add["tags"] = json!({"tag": "value"});
+            json!({ "add": add }).to_string()
+        });
+        store
+            .put(
+                &Path::from(format!("/{table_name}/_delta_log/{:020}.json", 2)),
+                actions.collect_vec().join("\n").into(),
+            )
+            .await?;
+
+        // remove all files, then add them back: the restored add actions are the ones of
+        // version 2, including their statistics and tags
+        for (version, restored_version) in [(3, 0), (4, 2)] {
+            let snapshot = Arc::new(Snapshot::try_new(table_url.clone(), engine.as_ref(), None)?);
+            let mut txn = snapshot.transaction()?;
+            txn.restore(engine.as_ref(), RestoreTarget::Version(restored_version))?;
+            assert!(matches!(
+                txn.commit(engine.as_ref())?,
+                CommitResult::Committed { version: v, .. } if v == version
+            ));
+        }
+        let restored = read_commit_adds(store.as_ref(), table_name, 4).await?;
+        assert_eq!(restored.len(), adds.len());
+        for (add, restored) in adds.iter().zip(&restored) {
+            assert_eq!(restored["path"], add["path"]);
+            assert_eq!(restored["size"], add["size"]);
+            assert_eq!(restored["dataChange"], true);
+            assert_eq!(restored["modificationTime"], 1234);
+            assert_eq!(restored["stats"], stats);
+            assert_eq!(restored["tags"], json!({"tag": "value"}));
+        }
+        assert_eq!(
+            read_numbers(&table_url, engine.clone())?,
+            [1, 2, 3, 4, 5, 6]
+        );
+    }
+    Ok(())
+}
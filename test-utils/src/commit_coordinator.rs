@@ -0,0 +1,154 @@
+//! An in-process commit coordinator, standing in for a catalog in tests of catalog-managed tables.
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+use bytes::Bytes;
+use delta_kernel::transaction::{
+    CatalogCommitHook, CommitMetadata, CommitResponse, StagedCommitter,
+};
+use delta_kernel::{DeltaResult, Engine, Error, FileMeta, LogPath, Snapshot, Version};
+use url::Url;
+
+/// A commit coordinator for a single catalog-managed table, keeping its state in memory. Commits
+/// are staged in `_delta_log/_staged_commits/` by a [`StagedCommitter`] (see
+/// [`LocalCommitCoordinator::committer`]) and ratified by the coordinator, which only accepts the
+/// next version of the table. Ratified commits are tracked until they are published to
+/// `_delta_log/<version>.json` with [`LocalCommitCoordinator::publish`].
+#[derive(Debug)]
+pub struct LocalCommitCoordinator {
+    table_root: Url,
+    state: Mutex<State>,
+}
+
+#[derive(Debug)]
+struct State {
+    // the latest ratified version of the table
+    latest_version: Version,
+    // the ratified commits which were not published yet, by version
+    unpublished: BTreeMap<Version, FileMeta>,
+}
+
+impl LocalCommitCoordinator {
+    /// Create a coordinator for the table at `table_root`, whose commits up to `latest_version`
+    /// are already published.
+    pub fn new(table_root: Url, latest_version: Version) -> Arc<Self> {
+        Arc::new(Self {
+            table_root,
+            state: Mutex::new(State {
+                latest_version,
+                unpublished: BTreeMap::new(),
+            }),
+        })
+    }
+
+    /// A committer staging commits and ratifying them with this coordinator.
+    pub fn committer(self: &Arc<Self>) -> Box<StagedCommitter> {
+        Box::new(StagedCommitter::new(self.clone()))
+    }
+
+    /// The latest ratified version of the table.
+    pub fn latest_version(&self) -> Version {
+        self.state.lock().unwrap().latest_version
+    }
+
+    /// The latest version published to `_delta_log`.
+    pub fn published_version(&self) -> Version {
+        let state = self.state.lock().unwrap();
+        match state.unpublished.keys().next() {
+            Some(version) => version - 1,
+            None => state.latest_version,
+        }
+    }
+
+    /// The ratified commits which were not published yet, in ascending version order.
+    pub fn log_tail(&self) -> DeltaResult<Vec<LogPath>> {
+        let state = self.state.lock().unwrap();
+        state
+            .unpublished
+            .values()
+            .map(|file_meta| LogPath::try_new(file_meta.clone()))
+            .collect()
+    }
+
+    /// Load the latest ratified snapshot of the table, as a catalog client would: with the
+    /// unpublished commits as log tail.
+    pub fn snapshot(&self, engine: &dyn Engine) -> DeltaResult<Snapshot> {
+        Snapshot::builder(self.table_root.clone())
+            .with_log_tail(self.log_tail()?)
+            .with_max_catalog_version(self.latest_version())
+            .build(engine)
+    }
+
+    /// Publish the ratified commits up to `version` to `_delta_log`, after which the coordinator
+    /// stops tracking them. A published commit is never overwritten: finding the commit already
+    /// published with the same content is fine, but different content is an error.
+    pub fn publish(&self, engine: &dyn Engine, version: Version) -> DeltaResult<()> {
+        let mut state = self.state.lock().unwrap();
+        if version > state.latest_version {
+            return Err(Error::generic(format!(
+                "Cannot publish version {version}: the latest ratified version is {}",
+                state.latest_version
+            )));
+        }
+        let storage = engine.storage_handler();
+        // publish in version order, so that the published log never has gaps
+        while let Some(entry) = state.unpublished.first_entry() {
+            if *entry.key() > version {
+                break;
+            }
+            let published = self
+                .table_root
+                .join(&format!("_delta_log/{:020}.json", entry.key()))?;
+            let staged = entry.get().location.clone();
+            let data = read_file(engine, &staged)?;
+            match storage.put(&published, data.clone(), false) {
+                Ok(()) => {}
+                // a previous (possibly interrupted) publish already wrote this commit
+                Err(Error::FileAlreadyExists(_)) if read_file(engine, &published)? == data => {}
+                Err(Error::FileAlreadyExists(_)) => {
+                    let version = entry.key();
+                    return Err(Error::generic(format!(
+                        "Cannot publish version {version}: {published} already exists with different content"
+                    )));
+                }
+                Err(err) => return Err(err),
+            }
+            entry.remove();
+        }
+        Ok(())
+    }
+}
+
+fn read_file(engine: &dyn Engine, location: &Url) -> DeltaResult<Bytes> {
+    let mut files = engine
+        .storage_handler()
+        .read_files(vec![(location.clone(), None)])?;
+    files
+        .next()
+        .unwrap_or_else(|| Err(Error::file_not_found(location.as_str())))
+}
+
+impl CatalogCommitHook for LocalCommitCoordinator {
+    fn ratify(
+        &self,
+        _engine: &dyn Engine,
+        staged_commit: &FileMeta,
+        commit_metadata: &CommitMetadata,
+    ) -> DeltaResult<CommitResponse> {
+        let mut state = self.state.lock().unwrap();
+        let version = commit_metadata.version();
+        if version <= state.latest_version {
+            return Ok(CommitResponse::Conflict);
+        }
+        if version != state.latest_version + 1 {
+            return Err(Error::generic(format!(
+                "Cannot ratify version {version}: the next version of the table is {}",
+                state.latest_version + 1
+            )));
+        }
+        state.unpublished.insert(version, staged_commit.clone());
+        state.latest_version = version;
+        Ok(CommitResponse::Committed)
+    }
+}
@@ -26,6 +26,9 @@ use object_store::{path::Path, ObjectStore};
 use serde_json::{json, to_vec};
 use url::Url;
 
+#[cfg(feature = "catalog-managed")]
+pub mod commit_coordinator;
+
 /// A common useful initial metadata and protocol. Also includes a single commitInfo
 pub const METADATA: &str = r#"{"commitInfo":{"timestamp":1587968586154,"operation":"WRITE","operationParameters":{"mode":"ErrorIfExists","partitionBy":"[]"},"isBlindAppend":true}}
 {"protocol":{"minReaderVersion":1,"minWriterVersion":2}}
@@ -133,6 +136,20 @@ pub fn generate_simple_batch() -> Result<RecordBatch, ArrowError> {
     ])
 }
 
+/// unpack the test data from {test_parent_dir}/{test_name}.tar.zst into a temp dir, and return the dir it was
+/// unpacked into
+pub fn load_test_data(
+    test_parent_dir: &str,
+    test_name: &str,
+) -> Result<tempfile::TempDir, Box<dyn std::error::Error>> {
+    let path = format!("{test_parent_dir}/{test_name}.tar.zst");
+    let tar = zstd::Decoder::new(std::fs::File::open(path)?)?;
+    let mut archive = tar::Archive::new(tar);
+    let temp_dir = tempfile::tempdir()?;
+    archive.unpack(temp_dir.path())?;
+    Ok(temp_dir)
+}
+
 /// get an ObjectStore path for a delta file, based on the version
 pub fn delta_path_for_version(version: u64, suffix: &str) -> Path {
     let path = format!("_delta_log/{version:020}.{suffix}");
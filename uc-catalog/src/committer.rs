@@ -0,0 +1,80 @@
+use std::sync::Arc;
+
+use delta_kernel::transaction::{CatalogCommitHook, CommitMetadata, CommitResponse};
+use delta_kernel::{DeltaResult, Engine, Error as KernelError, FileMeta};
+use tokio::runtime::Handle;
+use tracing::debug;
+use uc_client::models::commits::{Commit, CommitRequest};
+use uc_client::UCClient;
+
+/// A [`CatalogCommitHook`] which ratifies staged commits by registering them with Unity Catalog.
+/// Use it with a [`StagedCommitter`], see [`UCCatalog::committer`].
+///
+/// The hook blocks on the UC commit request within the tokio runtime it was created in, which must
+/// be a multi-threaded runtime.
+///
+/// [`StagedCommitter`]: delta_kernel::transaction::StagedCommitter
+/// [`UCCatalog::committer`]: crate::UCCatalog::committer
+#[derive(Debug)]
+pub struct UCCommitHook {
+    client: Arc<UCClient>,
+    table_id: String,
+    table_uri: String,
+    runtime: Handle,
+}
+
+impl UCCommitHook {
+    /// Create a hook committing to the UC table with the given id and storage location. Must be
+    /// called within a multi-threaded tokio runtime.
+    pub fn new(
+        client: Arc<UCClient>,
+        table_id: impl Into<String>,
+        table_uri: impl Into<String>,
+    ) -> Self {
+        Self {
+            client,
+            table_id: table_id.into(),
+            table_uri: table_uri.into(),
+            runtime: Handle::current(),
+        }
+    }
+}
+
+impl CatalogCommitHook for UCCommitHook {
+    fn ratify(
+        &self,
+        _engine: &dyn Engine,
+        staged_commit: &FileMeta,
+        commit_metadata: &CommitMetadata,
+    ) -> DeltaResult<CommitResponse> {
+        let file_name = staged_commit
+            .location
+            .path_segments()
+            .and_then(|mut segments| segments.next_back())
+            .ok_or_else(|| {
+                KernelError::generic(format!("Invalid staged commit {}", staged_commit.location))
+            })?;
+        let commit = Commit::new(
+            to_i64(commit_metadata.version())?,
+            commit_metadata.commit_timestamp(),
+            file_name,
+            to_i64(staged_commit.size)?,
+            staged_commit.last_modified,
+        );
+        debug!("Committing {file_name} to table {}", self.table_id);
+        let request = CommitRequest::new(&self.table_id, &self.table_uri, commit);
+        let result =
+            tokio::task::block_in_place(|| self.runtime.block_on(self.client.commit(request)));
+        match result {
+            Ok(_) => Ok(CommitResponse::Committed),
+            Err(uc_client::Error::CommitConflict(_)) => Ok(CommitResponse::Conflict),
+            // any other failure (e.g. `UnknownOutcome`) must not be reported as a conflict, since
+            // the staged commit of a conflict is deleted although UC may have ratified it
+            Err(e) => Err(KernelError::generic_err(e)),
+        }
+    }
+}
+
+fn to_i64(value: u64) -> DeltaResult<i64> {
+    i64::try_from(value).map_err(KernelError::generic_err)
+}
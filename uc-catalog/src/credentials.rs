@@ -0,0 +1,84 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use delta_kernel::engine::default::credentials::{CredentialProvider, StorageCredentials};
+use delta_kernel::{DeltaResult, Error as KernelError};
+use futures::future::BoxFuture;
+use tracing::debug;
+use uc_client::models::credentials::{Operation, TemporaryTableCredentials};
+use uc_client::UCClient;
+
+/// A [`CredentialProvider`] vending the temporary credentials of a UC table, so that a
+/// [`DefaultEngine`] can refresh them before they expire. See [`UCCatalog::engine`].
+///
+/// [`DefaultEngine`]: delta_kernel::engine::default::DefaultEngine
+/// [`UCCatalog::engine`]: crate::UCCatalog::engine
+#[derive(Debug)]
+pub struct UCCredentialProvider {
+    client: Arc<UCClient>,
+    table_id: String,
+    operation: Operation,
+    // credentials already fetched from UC, vended instead of fetching new ones on the first call
+    initial_credentials: Mutex<Option<TemporaryTableCredentials>>,
+}
+
+impl UCCredentialProvider {
+    pub fn new(client: Arc<UCClient>, table_id: impl Into<String>, operation: Operation) -> Self {
+        Self {
+            client,
+            table_id: table_id.into(),
+            operation,
+            initial_credentials: Mutex::new(None),
+        }
+    }
+
+    /// Vend the given `credentials` (e.g. those of a [`ResolvedTable`]) first, and only fetch new
+    /// credentials from UC once they need to be refreshed.
+    ///
+    /// [`ResolvedTable`]: crate::ResolvedTable
+    pub fn with_initial_credentials(self, credentials: TemporaryTableCredentials) -> Self {
+        Self {
+            initial_credentials: Mutex::new(Some(credentials)),
+            ..self
+        }
+    }
+}
+
+impl CredentialProvider for UCCredentialProvider {
+    fn get_credentials(&self) -> BoxFuture<'_, DeltaResult<StorageCredentials>> {
+        Box::pin(async move {
+            let initial_credentials = self.initial_credentials.lock().unwrap().take();
+            let credentials = match initial_credentials {
+                Some(credentials) => credentials,
+                None => {
+                    debug!(
+                        "Fetching {} credentials for table {}",
+                        self.operation, self.table_id
+                    );
+                    self.client
+                        .get_credentials(&self.table_id, self.operation.clone())
+                        .await
+                        .map_err(KernelError::generic_err)?
+                }
+            };
+            Ok(StorageCredentials {
+                options: storage_options(&credentials),
+                expires_at: Some(credentials.expiration_time),
+            })
+        })
+    }
+}
+
+/// The object store options to access a table's storage with its temporary `credentials`.
+pub(crate) fn storage_options(credentials: &TemporaryTableCredentials) -> HashMap<String, String> {
+    let mut options = HashMap::new();
+    if let Some(aws) = &credentials.aws_temp_credentials {
+        options.insert("aws_access_key_id".into(), aws.access_key_id.clone());
+        options.insert(
+            "aws_secret_access_key".into(),
+            aws.secret_access_key.clone(),
+        );
+        options.insert("aws_session_token".into(), aws.session_token.clone());
+    }
+    options
+}
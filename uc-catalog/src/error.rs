@@ -0,0 +1,21 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Delta kernel error: {0}")]
+    Kernel(#[from] delta_kernel::Error),
+
+    #[error("Unity Catalog error: {0}")]
+    Client(#[from] uc_client::Error),
+
+    #[error("URL parse error: {0}")]
+    UrlParse(#[from] url::ParseError),
+
+    #[error("Unsupported table {0}: {1}")]
+    UnsupportedTable(String, String),
+
+    #[error("Invalid commit from Unity Catalog: {0}")]
+    InvalidCommit(String),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
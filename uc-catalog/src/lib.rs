@@ -0,0 +1,211 @@
+//! Unity Catalog integration for Delta Kernel
+//!
+//! This crate connects the [`uc_client`] to `delta_kernel`: it resolves Unity Catalog (UC) tables
+//! to their storage location and temporary credentials, builds a [`DefaultEngine`] with those
+//! credentials, loads snapshots of catalog-managed tables using the commits tracked by UC as the
+//! log tail, and commits to catalog-managed tables through UC.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use std::sync::Arc;
+//!
+//! use uc_catalog::UCCatalog;
+//! use uc_client::{models::credentials::Operation, UCClient};
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let client = UCClient::builder("uc.awesome.org", "your-token").build()?;
+//!     let catalog = UCCatalog::new(Arc::new(client));
+//!
+//!     let table = catalog.resolve_table("catalog.schema.table", Operation::ReadWrite).await?;
+//!     let engine = catalog.engine(&table)?;
+//!     let snapshot = Arc::new(catalog.load_snapshot(&table, &engine, None).await?);
+//!
+//!     let txn = snapshot
+//!         .transaction()?
+//!         .with_committer(Box::new(catalog.committer(&table)));
+//!     txn.commit(&engine)?;
+//!
+//!     Ok(())
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use delta_kernel::engine::default::executor::tokio::TokioBackgroundExecutor;
+use delta_kernel::engine::default::DefaultEngine;
+use delta_kernel::transaction::StagedCommitter;
+use delta_kernel::{Engine, FileMeta, LogPath, Snapshot, Version};
+use tracing::instrument;
+use uc_client::models::commits::{Commit, CommitsRequest};
+use uc_client::models::credentials::{Operation, TemporaryTableCredentials};
+use uc_client::models::tables::TablesResponse;
+use uc_client::UCClient;
+use url::Url;
+
+mod committer;
+mod credentials;
+mod error;
+
+#[cfg(test)]
+mod tests;
+
+pub use committer::UCCommitHook;
+pub use credentials::UCCredentialProvider;
+pub use error::{Error, Result};
+
+/// A table resolved through Unity Catalog, see [`UCCatalog::resolve_table`].
+#[derive(Debug, Clone)]
+pub struct ResolvedTable {
+    /// The table's metadata in UC.
+    pub table: TablesResponse,
+    /// The root of the table in storage, with a trailing slash.
+    pub table_root: Url,
+    /// Temporary credentials to access the table's storage.
+    pub credentials: TemporaryTableCredentials,
+    /// The operation the credentials were requested for.
+    pub operation: Operation,
+}
+
+impl ResolvedTable {
+    /// The UC id of the table.
+    pub fn table_id(&self) -> &str {
+        &self.table.table_id
+    }
+
+    /// The object store options to access the table's storage with its temporary credentials.
+    pub fn storage_options(&self) -> HashMap<String, String> {
+        credentials::storage_options(&self.credentials)
+    }
+}
+
+/// Access to the Delta tables of a Unity Catalog.
+#[derive(Debug, Clone)]
+pub struct UCCatalog {
+    client: Arc<UCClient>,
+}
+
+impl UCCatalog {
+    pub fn new(client: Arc<UCClient>) -> Self {
+        Self { client }
+    }
+
+    /// Resolve the table `table_name` (`catalog.schema.table`) to its storage location, and get
+    /// temporary credentials for the given `operation` on it.
+    #[instrument(skip(self))]
+    pub async fn resolve_table(
+        &self,
+        table_name: &str,
+        operation: Operation,
+    ) -> Result<ResolvedTable> {
+        let table = self.client.get_table(table_name).await?;
+        if !table.is_delta_table() {
+            return Err(Error::UnsupportedTable(
+                table_name.to_string(),
+                format!(
+                    "unsupported data source format {}",
+                    table.data_source_format
+                ),
+            ));
+        }
+        let mut table_root = Url::parse(&table.storage_location)?;
+        if !table_root.path().ends_with('/') {
+            table_root.set_path(&format!("{}/", table_root.path()));
+        }
+        let credentials = self
+            .client
+            .get_credentials(&table.table_id, operation.clone())
+            .await?;
+        Ok(ResolvedTable {
+            table,
+            table_root,
+            credentials,
+            operation,
+        })
+    }
+
+    /// Create a [`DefaultEngine`] accessing the table's storage with the temporary credentials the
+    /// table was resolved with. The credentials are refreshed through UC before they expire, see
+    /// [`UCCredentialProvider`].
+    pub fn engine(&self, table: &ResolvedTable) -> Result<DefaultEngine<TokioBackgroundExecutor>> {
+        let provider = UCCredentialProvider::new(
+            self.client.clone(),
+            table.table_id(),
+            table.operation.clone(),
+        )
+        .with_initial_credentials(table.credentials.clone());
+        Ok(DefaultEngine::try_new_with_credentials(
+            &table.table_root,
+            HashMap::<String, String>::new(),
+            Arc::new(provider),
+            Arc::new(TokioBackgroundExecutor::new()),
+        )?)
+    }
+
+    /// Load a [`Snapshot`] of a catalog-managed table, at the given version or the latest version
+    /// ratified by UC. The commits tracked by UC, which may not be published to the `_delta_log`
+    /// yet, are used as the snapshot's log tail.
+    #[instrument(skip(self, engine))]
+    pub async fn load_snapshot(
+        &self,
+        table: &ResolvedTable,
+        engine: &dyn Engine,
+        version: Option<Version>,
+    ) -> Result<Snapshot> {
+        let (commits, latest_table_version) = self.get_all_commits(table).await?;
+        let staged_commits_dir = table.table_root.join("_delta_log/_staged_commits/")?;
+        let log_tail = commits
+            .iter()
+            .map(|commit| {
+                let location = staged_commits_dir.join(&commit.file_name)?;
+                let size = u64::try_from(commit.file_size)
+                    .map_err(|_| Error::InvalidCommit(format!("file size {}", commit.file_size)))?;
+                let file_meta = FileMeta::new(location, commit.file_modification_timestamp, size);
+                Ok(LogPath::try_new(file_meta)?)
+            })
+            .collect::<Result<_>>()?;
+
+        let mut builder = Snapshot::builder(table.table_root.clone()).with_log_tail(log_tail);
+        // a negative latest version means UC does not track any commits of the table yet
+        if let Ok(max_catalog_version) = Version::try_from(latest_table_version) {
+            builder = builder.with_max_catalog_version(max_catalog_version);
+        }
+        if let Some(version) = version {
+            builder = builder.at_version(version);
+        }
+        Ok(builder.build(engine)?)
+    }
+
+    /// Create a [`StagedCommitter`] committing to the table through UC. Must be called within a
+    /// multi-threaded tokio runtime, see [`UCCommitHook`].
+    pub fn committer(&self, table: &ResolvedTable) -> StagedCommitter {
+        let hook = UCCommitHook::new(
+            self.client.clone(),
+            table.table_id(),
+            &table.table.storage_location,
+        );
+        StagedCommitter::new(Arc::new(hook))
+    }
+
+    // Get all commits UC tracks for the table, in ascending version order, along with the latest
+    // table version. UC may return the commits in several pages.
+    async fn get_all_commits(&self, table: &ResolvedTable) -> Result<(Vec<Commit>, i64)> {
+        let mut commits: Vec<Commit> = vec![];
+        loop {
+            let start_version = commits.last().map_or(0, |commit| commit.version + 1);
+            let request = CommitsRequest::new(table.table_id(), &table.table.storage_location)
+                .with_start_version(start_version);
+            let response = self.client.get_commits(request).await?;
+            let page = response.commits.unwrap_or_default();
+            let done = page
+                .last()
+                .is_none_or(|commit| commit.version >= response.latest_table_version);
+            commits.extend(page);
+            if done {
+                return Ok((commits, response.latest_table_version));
+            }
+        }
+    }
+}
@@ -0,0 +1,330 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use delta_kernel::transaction::CommitResult;
+use serde_json::{json, Value};
+use tempfile::TempDir;
+use uc_client::models::credentials::{Operation, TemporaryTableCredentials};
+use uc_client::UCClient;
+use url::Url;
+use wiremock::matchers::{body_partial_json, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use delta_kernel::engine::default::credentials::CredentialProvider as _;
+
+use crate::{Error, UCCatalog, UCCredentialProvider};
+
+const TABLE_NAME: &str = "catalog.schema.table";
+const API_PATH: &str = "/api/2.1/unity-catalog";
+
+// Creates a table with a single commit in a temp dir, and a mock UC server serving it.
+async fn setup() -> (TempDir, Url, MockServer, UCCatalog) {
+    let dir = tempfile::tempdir().unwrap();
+    let log_dir = dir.path().join("_delta_log");
+    std::fs::create_dir_all(log_dir.join("_staged_commits")).unwrap();
+    let schema = json!({
+        "type": "struct",
+        "fields": [{"name": "id", "type": "integer", "nullable": true, "metadata": {}}]
+    });
+    let actions = [
+        json!({"protocol": {"minReaderVersion": 1, "minWriterVersion": 2}}),
+        json!({
+            "metaData": {
+                "id": "test_id",
+                "format": {"provider": "parquet", "options": {}},
+                "schemaString": schema.to_string(),
+                "partitionColumns": [],
+                "configuration": {},
+                "createdTime": 1677811175819u64
+            }
+        }),
+    ];
+    let commit = actions.map(|action| action.to_string()).join("\n");
+    std::fs::write(log_dir.join("00000000000000000000.json"), commit).unwrap();
+    let table_uri = Url::from_directory_path(dir.path()).unwrap();
+
+    let server = MockServer::start().await;
+    mount_table(&server, &table_uri, "DELTA").await;
+    let client = UCClient::builder(server.uri(), "token")
+        .with_max_retries(0)
+        .build()
+        .unwrap();
+    (dir, table_uri, server, UCCatalog::new(Arc::new(client)))
+}
+
+async fn mount_table(server: &MockServer, table_uri: &Url, format: &str) {
+    // the storage location is reported without a trailing slash
+    let storage_location = table_uri.as_str().trim_end_matches('/');
+    Mock::given(method("GET"))
+        .and(path(format!("{API_PATH}/tables/{TABLE_NAME}")))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "name": "table",
+            "catalog_name": "catalog",
+            "schema_name": "schema",
+            "table_type": "MANAGED",
+            "data_source_format": format,
+            "storage_location": storage_location,
+            "owner": "user",
+            "securable_kind": "TABLE_DELTA",
+            "metastore_id": "metastore-id",
+            "table_id": "table-id",
+            "schema_id": "schema-id",
+            "catalog_id": "catalog-id"
+        })))
+        .mount(server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path(format!("{API_PATH}/temporary-table-credentials")))
+        .and(body_partial_json(json!({"table_id": "table-id"})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "expiration_time": 4102444800000i64,
+            "url": storage_location
+        })))
+        .mount(server)
+        .await;
+}
+
+async fn mount_commits(server: &MockServer, start_version: i64, commits: Value, latest: i64) {
+    Mock::given(method("GET"))
+        .and(path(format!("{API_PATH}/delta/preview/commits")))
+        .and(body_partial_json(json!({"start_version": start_version})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "commits": commits,
+            "latest_table_version": latest
+        })))
+        .mount(server)
+        .await;
+}
+
+fn staged_commit_names(table_dir: &Path) -> Vec<String> {
+    let staged_dir = table_dir.join("_delta_log/_staged_commits");
+    let mut names: Vec<_> = std::fs::read_dir(staged_dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect();
+    names.sort();
+    names
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_commit_and_load_snapshot() {
+    let (dir, table_uri, server, catalog) = setup().await;
+    mount_commits(&server, 0, json!([]), 0).await;
+
+    let table = catalog
+        .resolve_table(TABLE_NAME, Operation::ReadWrite)
+        .await
+        .unwrap();
+    assert_eq!(table.table_id(), "table-id");
+    assert_eq!(table.table_root, table_uri);
+    assert!(table.storage_options().is_empty());
+    let engine = catalog.engine(&table).unwrap();
+    let snapshot = catalog.load_snapshot(&table, &engine, None).await.unwrap();
+    assert_eq!(snapshot.version(), 0);
+
+    // commit version 1 through UC
+    Mock::given(method("POST"))
+        .and(path(format!("{API_PATH}/delta/preview/commits")))
+        .and(body_partial_json(json!({
+            "table_id": "table-id",
+            "commit_info": {"version": 1}
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({})))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let txn = Arc::new(snapshot)
+        .transaction()
+        .unwrap()
+        .with_committer(Box::new(catalog.committer(&table)));
+    let result = txn.commit(&engine).unwrap();
+    assert!(matches!(result, CommitResult::Committed { version: 1, .. }));
+    server.verify().await;
+
+    // the commit is staged, and visible once UC reports it
+    let staged = staged_commit_names(dir.path());
+    assert_eq!(staged.len(), 1);
+    let file_size = std::fs::metadata(
+        dir.path()
+            .join("_delta_log/_staged_commits")
+            .join(&staged[0]),
+    )
+    .unwrap()
+    .len();
+    server.reset().await;
+    mount_table(&server, &table_uri, "DELTA").await;
+    let commits = json!([{
+        "version": 1,
+        "timestamp": 1000,
+        "file_name": staged[0],
+        "file_size": file_size,
+        "file_modification_timestamp": 1000
+    }]);
+    mount_commits(&server, 0, commits, 1).await;
+    let snapshot = catalog.load_snapshot(&table, &engine, None).await.unwrap();
+    assert_eq!(snapshot.version(), 1);
+    let snapshot = catalog
+        .load_snapshot(&table, &engine, Some(0))
+        .await
+        .unwrap();
+    assert_eq!(snapshot.version(), 0);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_commit_conflict() {
+    let (dir, _table_uri, server, catalog) = setup().await;
+    mount_commits(&server, 0, json!([]), 0).await;
+    Mock::given(method("POST"))
+        .and(path(format!("{API_PATH}/delta/preview/commits")))
+        .respond_with(ResponseTemplate::new(409))
+        .mount(&server)
+        .await;
+
+    let table = catalog
+        .resolve_table(TABLE_NAME, Operation::ReadWrite)
+        .await
+        .unwrap();
+    let engine = catalog.engine(&table).unwrap();
+    let snapshot = catalog.load_snapshot(&table, &engine, None).await.unwrap();
+    let txn = Arc::new(snapshot)
+        .transaction()
+        .unwrap()
+        .with_committer(Box::new(catalog.committer(&table)));
+    let result = txn.commit(&engine).unwrap();
+    assert!(matches!(result, CommitResult::Conflict(_, 1)));
+    // the rejected staged commit is cleaned up
+    assert!(staged_commit_names(dir.path()).is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_commit_unknown_outcome() {
+    let (dir, _table_uri, server, catalog) = setup().await;
+    mount_commits(&server, 0, json!([]), 0).await;
+    Mock::given(method("POST"))
+        .and(path(format!("{API_PATH}/delta/preview/commits")))
+        .respond_with(ResponseTemplate::new(503))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let table = catalog
+        .resolve_table(TABLE_NAME, Operation::ReadWrite)
+        .await
+        .unwrap();
+    let engine = catalog.engine(&table).unwrap();
+    let snapshot = catalog.load_snapshot(&table, &engine, None).await.unwrap();
+    let txn = Arc::new(snapshot)
+        .transaction()
+        .unwrap()
+        .with_committer(Box::new(catalog.committer(&table)));
+    // UC may have ratified the commit, so it fails without cleaning up the staged commit
+    assert!(txn.commit(&engine).is_err());
+    assert_eq!(staged_commit_names(dir.path()).len(), 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_load_snapshot_with_paged_commits() {
+    let (dir, _table_uri, server, catalog) = setup().await;
+    let staged_dir = dir.path().join("_delta_log/_staged_commits");
+    let mut commits = vec![];
+    for version in 1..=2 {
+        let file_name = format!("{version:020}.7d17ac10-5cf3-4d7a-8b2a-4a0a1e7c3b0{version}.json");
+        let data = json!({"commitInfo": {"operation": format!("WRITE {version}")}}).to_string();
+        std::fs::write(staged_dir.join(&file_name), &data).unwrap();
+        commits.push(json!({
+            "version": version,
+            "timestamp": 1000,
+            "file_name": file_name,
+            "file_size": data.len(),
+            "file_modification_timestamp": 1000
+        }));
+    }
+    // UC returns one commit per page
+    mount_commits(&server, 0, json!([commits[0]]), 2).await;
+    mount_commits(&server, 2, json!([commits[1]]), 2).await;
+
+    let table = catalog
+        .resolve_table(TABLE_NAME, Operation::Read)
+        .await
+        .unwrap();
+    let engine = catalog.engine(&table).unwrap();
+    let snapshot = catalog.load_snapshot(&table, &engine, None).await.unwrap();
+    assert_eq!(snapshot.version(), 2);
+    let history = snapshot.history(&engine, None).unwrap();
+    let operations: Vec<_> = history.iter().map(|e| e.operation.as_deref()).collect();
+    assert_eq!(operations, [Some("WRITE 2"), Some("WRITE 1"), None]);
+}
+
+#[tokio::test]
+async fn test_resolve_unsupported_table() {
+    let dir = tempfile::tempdir().unwrap();
+    let table_uri = Url::from_directory_path(dir.path()).unwrap();
+    let server = MockServer::start().await;
+    mount_table(&server, &table_uri, "PARQUET").await;
+    let client = UCClient::builder(server.uri(), "token").build().unwrap();
+    let catalog = UCCatalog::new(Arc::new(client));
+
+    let result = catalog.resolve_table(TABLE_NAME, Operation::Read).await;
+    assert!(matches!(result, Err(Error::UnsupportedTable(name, _)) if name == TABLE_NAME));
+}
+
+#[tokio::test]
+async fn test_credential_provider() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path(format!("{API_PATH}/temporary-table-credentials")))
+        .and(body_partial_json(
+            json!({"table_id": "table-id", "operation": "READ"}),
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "aws_temp_credentials": {
+                "access_key_id": "key-id",
+                "secret_access_key": "secret",
+                "session_token": "token"
+            },
+            "expiration_time": 1000,
+            "url": "s3://bucket/table"
+        })))
+        .expect(2)
+        .mount(&server)
+        .await;
+    let client = UCClient::builder(server.uri(), "token").build().unwrap();
+    let provider = UCCredentialProvider::new(Arc::new(client), "table-id", Operation::Read);
+
+    // every call fetches fresh credentials
+    for _ in 0..2 {
+        let credentials = provider.get_credentials().await.unwrap();
+        assert_eq!(credentials.expires_at, Some(1000));
+        assert_eq!(credentials.options["aws_access_key_id"], "key-id");
+        assert_eq!(credentials.options["aws_secret_access_key"], "secret");
+        assert_eq!(credentials.options["aws_session_token"], "token");
+    }
+}
+
+#[tokio::test]
+async fn test_credential_provider_with_initial_credentials() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path(format!("{API_PATH}/temporary-table-credentials")))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "expiration_time": 2000,
+            "url": "s3://bucket/table"
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let client = UCClient::builder(server.uri(), "token").build().unwrap();
+    let initial: TemporaryTableCredentials = serde_json::from_value(json!({
+        "expiration_time": 1000,
+        "url": "s3://bucket/table"
+    }))
+    .unwrap();
+    let provider = UCCredentialProvider::new(Arc::new(client), "table-id", Operation::Read)
+        .with_initial_credentials(initial);
+
+    // the initial credentials are vended first, and only refreshing fetches new ones
+    let credentials = provider.get_credentials().await.unwrap();
+    assert_eq!(credentials.expires_at, Some(1000));
+    let credentials = provider.get_credentials().await.unwrap();
+    assert_eq!(credentials.expires_at, Some(2000));
+}
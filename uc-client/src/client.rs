@@ -7,9 +7,9 @@ use url::Url;
 
 use crate::config::{ClientConfig, ClientConfigBuilder};
 use crate::error::{Error, Result};
-use crate::models::commits::{CommitsRequest, CommitsResponse};
+use crate::models::commits::{CommitRequest, CommitResponse, CommitsRequest, CommitsResponse};
 use crate::models::credentials::{CredentialsRequest, Operation, TemporaryTableCredentials};
-use crate::models::tables::TablesResponse;
+use crate::models::tables::{CreateTableRequest, TablesResponse};
 
 #[derive(Debug, Clone)]
 pub struct UCClient {
@@ -64,6 +64,39 @@ impl UCClient {
         self.handle_response(response).await
     }
 
+    /// Register a staged commit as the next version of a catalog-managed table. Fails with
+    /// [`Error::CommitConflict`] if the version was already committed.
+    ///
+    /// The request is sent exactly once, since a retry of a commit that was applied (but whose
+    /// response was lost) would be reported as a conflict. Fails with [`Error::UnknownOutcome`]
+    /// if the commit may or may not have been applied.
+    #[instrument(skip(self))]
+    pub async fn commit(&self, request: CommitRequest) -> Result<CommitResponse> {
+        let url = self.base_url.join("delta/preview/commits")?;
+
+        let response = self
+            .execute_once(self.client.post(url.clone()).json(&request).send())
+            .await?;
+
+        match (response.status(), &request.commit_info) {
+            (StatusCode::CONFLICT, Some(commit)) => Err(Error::CommitConflict(commit.version)),
+            _ => self.handle_response(response).await,
+        }
+    }
+
+    /// Notify the catalog that all commits of a table up to `version` were published
+    /// (backfilled) to the `_delta_log`, so it may stop tracking them.
+    #[instrument(skip(self))]
+    pub async fn publish(
+        &self,
+        table_id: &str,
+        table_uri: &str,
+        version: i64,
+    ) -> Result<CommitResponse> {
+        self.commit(CommitRequest::backfill(table_id, table_uri, version))
+            .await
+    }
+
     #[instrument(skip(self))]
     pub async fn get_table(&self, table_name: &str) -> Result<TablesResponse> {
         let url = self.base_url.join(&format!("tables/{}", table_name))?;
@@ -78,6 +111,27 @@ impl UCClient {
         }
     }
 
+    /// Create a table. Fails with [`Error::TableAlreadyExists`] if a table of that name exists.
+    ///
+    /// Like [`UCClient::commit`], the request is sent exactly once and fails with
+    /// [`Error::UnknownOutcome`] if the table may or may not have been created.
+    #[instrument(skip(self))]
+    pub async fn create_table(&self, request: CreateTableRequest) -> Result<TablesResponse> {
+        let url = self.base_url.join("tables")?;
+
+        let response = self
+            .execute_once(self.client.post(url.clone()).json(&request).send())
+            .await?;
+
+        match response.status() {
+            StatusCode::CONFLICT => Err(Error::TableAlreadyExists(format!(
+                "{}.{}.{}",
+                request.catalog_name, request.schema_name, request.name
+            ))),
+            _ => self.handle_response(response).await,
+        }
+    }
+
     #[instrument(skip(self))]
     pub async fn get_credentials(
         &self,
@@ -134,6 +188,27 @@ impl UCClient {
         Err(Error::MaxRetriesExceeded)
     }
 
+    // Send a non-idempotent request without retrying it. Failures after the request may have
+    // reached the server leave its outcome unknown.
+    async fn execute_once(
+        &self,
+        request: impl Future<Output = std::result::Result<Response, reqwest::Error>>,
+    ) -> Result<Response> {
+        match request.await {
+            Ok(response) if response.status().is_server_error() => {
+                let status = response.status();
+                let body = response.text().await.unwrap_or_default();
+                Err(Error::UnknownOutcome(format!(
+                    "server error {status}: {body}"
+                )))
+            }
+            Ok(response) => Ok(response),
+            // the request was never sent
+            Err(e) if e.is_connect() || e.is_builder() => Err(Error::from(e)),
+            Err(e) => Err(Error::UnknownOutcome(e.to_string())),
+        }
+    }
+
     async fn handle_response<T>(&self, response: Response) -> Result<T>
     where
         T: serde::de::DeserializeOwned,
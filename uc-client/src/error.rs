@@ -20,6 +20,17 @@ pub enum Error {
     #[error("Table not found: {0}")]
     TableNotFound(String),
 
+    #[error("Commit conflict: version {0} was already committed")]
+    CommitConflict(i64),
+
+    #[error("Table already exists: {0}")]
+    TableAlreadyExists(String),
+
+    /// A request that must not be retried failed in a way that leaves its outcome unknown, e.g.
+    /// a timeout or server error: the catalog may or may not have applied it.
+    #[error("Unknown outcome of request: {0}")]
+    UnknownOutcome(String),
+
     #[error("Invalid configuration: {0}")]
     InvalidConfiguration(String),
 
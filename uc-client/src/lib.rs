@@ -36,8 +36,8 @@ pub mod prelude {
     pub use crate::client::UCClient;
     pub use crate::error::Result;
     pub use crate::models::{
-        commits::{Commit, CommitsRequest, CommitsResponse},
+        commits::{Commit, CommitRequest, CommitResponse, CommitsRequest, CommitsResponse},
         credentials::{Operation, TemporaryTableCredentials},
-        tables::TablesResponse,
+        tables::{CreateTableRequest, TablesResponse},
     };
 }
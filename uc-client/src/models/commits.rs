@@ -58,3 +58,69 @@ impl Commit {
         chrono::DateTime::from_timestamp_millis(self.file_modification_timestamp)
     }
 }
+
+/// Request to register a staged commit with the catalog, or to report the latest version that was
+/// backfilled (published) to the `_delta_log`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitRequest {
+    pub table_id: String,
+    pub table_uri: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit_info: Option<Commit>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latest_backfilled_version: Option<i64>,
+}
+
+impl CommitRequest {
+    /// Register `commit` as the next version of the table.
+    pub fn new(table_id: impl Into<String>, table_uri: impl Into<String>, commit: Commit) -> Self {
+        Self {
+            table_id: table_id.into(),
+            table_uri: table_uri.into(),
+            commit_info: Some(commit),
+            latest_backfilled_version: None,
+        }
+    }
+
+    /// Only report that all commits up to `version` were backfilled, without committing.
+    pub fn backfill(
+        table_id: impl Into<String>,
+        table_uri: impl Into<String>,
+        version: i64,
+    ) -> Self {
+        Self {
+            table_id: table_id.into(),
+            table_uri: table_uri.into(),
+            commit_info: None,
+            latest_backfilled_version: Some(version),
+        }
+    }
+
+    pub fn with_latest_backfilled_version(mut self, version: i64) -> Self {
+        self.latest_backfilled_version = Some(version);
+        self
+    }
+}
+
+// the commit endpoint responds with an empty object
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CommitResponse {}
+
+impl Commit {
+    pub fn new(
+        version: i64,
+        timestamp: i64,
+        file_name: impl Into<String>,
+        file_size: i64,
+        file_modification_timestamp: i64,
+    ) -> Self {
+        Self {
+            version,
+            timestamp,
+            file_name: file_name.into(),
+            file_size,
+            file_modification_timestamp,
+            is_disown_commit: None,
+        }
+    }
+}
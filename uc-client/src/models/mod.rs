@@ -2,6 +2,6 @@ pub mod commits;
 pub mod credentials;
 pub mod tables;
 
-pub use commits::{Commit, CommitsRequest, CommitsResponse};
+pub use commits::{Commit, CommitRequest, CommitResponse, CommitsRequest, CommitsResponse};
 pub use credentials::{AwsTempCredentials, TemporaryTableCredentials};
-pub use tables::TablesResponse;
+pub use tables::{ColumnInfo, CreateTableRequest, TablesResponse};
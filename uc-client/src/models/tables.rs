@@ -64,3 +64,77 @@ impl Display for TablesResponse {
         Ok(())
     }
 }
+
+/// Request to create a table.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateTableRequest {
+    pub name: String,
+    pub catalog_name: String,
+    pub schema_name: String,
+    pub table_type: String,
+    pub data_source_format: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub storage_location: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub columns: Vec<ColumnInfo>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub properties: HashMap<String, String>,
+}
+
+impl CreateTableRequest {
+    /// Create a managed Delta table named `catalog.schema.name`.
+    pub fn new(
+        catalog_name: impl Into<String>,
+        schema_name: impl Into<String>,
+        name: impl Into<String>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            catalog_name: catalog_name.into(),
+            schema_name: schema_name.into(),
+            table_type: "MANAGED".to_string(),
+            data_source_format: "DELTA".to_string(),
+            storage_location: None,
+            columns: vec![],
+            properties: HashMap::new(),
+        }
+    }
+
+    pub fn with_table_type(mut self, table_type: impl Into<String>) -> Self {
+        self.table_type = table_type.into();
+        self
+    }
+
+    pub fn with_storage_location(mut self, storage_location: impl Into<String>) -> Self {
+        self.storage_location = Some(storage_location.into());
+        self
+    }
+
+    pub fn with_columns(mut self, columns: impl IntoIterator<Item = ColumnInfo>) -> Self {
+        self.columns = columns.into_iter().collect();
+        self
+    }
+
+    pub fn with_properties(
+        mut self,
+        properties: impl IntoIterator<Item = (impl Into<String>, impl Into<String>)>,
+    ) -> Self {
+        self.properties = properties
+            .into_iter()
+            .map(|(k, v)| (k.into(), v.into()))
+            .collect();
+        self
+    }
+}
+
+/// A column of a table to create.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColumnInfo {
+    pub name: String,
+    pub type_name: String,
+    pub type_text: String,
+    /// The column type as a Delta JSON type string.
+    pub type_json: String,
+    pub position: i32,
+    pub nullable: bool,
+}
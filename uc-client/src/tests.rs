@@ -46,3 +46,186 @@ fn test_table_response_helpers() {
     assert!(table.is_managed_table());
     assert!(!table.is_external_table());
 }
+
+mod endpoints {
+    use serde_json::json;
+    use wiremock::matchers::{body_json, header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use crate::error::Error;
+    use crate::models::commits::{Commit, CommitRequest};
+    use crate::models::tables::{ColumnInfo, CreateTableRequest};
+    use crate::UCClient;
+
+    const COMMITS_PATH: &str = "/api/2.1/unity-catalog/delta/preview/commits";
+
+    fn client(server: &MockServer) -> UCClient {
+        UCClient::builder(server.uri(), "token")
+            .with_max_retries(0)
+            .build()
+            .unwrap()
+    }
+
+    fn table_json() -> serde_json::Value {
+        json!({
+            "name": "my_table",
+            "catalog_name": "catalog",
+            "schema_name": "schema",
+            "table_type": "MANAGED",
+            "data_source_format": "DELTA",
+            "storage_location": "s3://bucket/table",
+            "owner": "user",
+            "securable_kind": "TABLE_DELTA",
+            "metastore_id": "metastore-id",
+            "table_id": "table-id",
+            "schema_id": "schema-id",
+            "catalog_id": "catalog-id"
+        })
+    }
+
+    #[tokio::test]
+    async fn test_commit() {
+        let server = MockServer::start().await;
+        let commit = Commit::new(3, 1000, "00000000000000000003.uuid.json", 512, 1001);
+        let request = CommitRequest::new("table-id", "s3://bucket/table", commit)
+            .with_latest_backfilled_version(1);
+        Mock::given(method("POST"))
+            .and(path(COMMITS_PATH))
+            .and(header("authorization", "Bearer token"))
+            .and(body_json(json!({
+                "table_id": "table-id",
+                "table_uri": "s3://bucket/table",
+                "commit_info": {
+                    "version": 3,
+                    "timestamp": 1000,
+                    "file_name": "00000000000000000003.uuid.json",
+                    "file_size": 512,
+                    "file_modification_timestamp": 1001
+                },
+                "latest_backfilled_version": 1
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({})))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        client(&server).commit(request).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_commit_conflict() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path(COMMITS_PATH))
+            .respond_with(ResponseTemplate::new(409).set_body_string("version already exists"))
+            .mount(&server)
+            .await;
+
+        let commit = Commit::new(3, 1000, "00000000000000000003.uuid.json", 512, 1001);
+        let request = CommitRequest::new("table-id", "s3://bucket/table", commit);
+        let result = client(&server).commit(request).await;
+        assert!(matches!(result, Err(Error::CommitConflict(3))));
+    }
+
+    #[tokio::test]
+    async fn test_commit_not_retried() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path(COMMITS_PATH))
+            .respond_with(ResponseTemplate::new(503))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let client = UCClient::builder(server.uri(), "token")
+            .with_max_retries(3)
+            .build()
+            .unwrap();
+
+        // the commit may have been applied, so it is neither retried nor reported as a conflict
+        let commit = Commit::new(3, 1000, "00000000000000000003.uuid.json", 512, 1001);
+        let request = CommitRequest::new("table-id", "s3://bucket/table", commit);
+        let result = client.commit(request).await;
+        assert!(matches!(result, Err(Error::UnknownOutcome(_))));
+    }
+
+    #[tokio::test]
+    async fn test_publish() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path(COMMITS_PATH))
+            .and(body_json(json!({
+                "table_id": "table-id",
+                "table_uri": "s3://bucket/table",
+                "latest_backfilled_version": 5
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({})))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        client(&server)
+            .publish("table-id", "s3://bucket/table", 5)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_create_table() {
+        let server = MockServer::start().await;
+        let column = ColumnInfo {
+            name: "id".to_string(),
+            type_name: "LONG".to_string(),
+            type_text: "bigint".to_string(),
+            type_json: r#"{"name":"id","type":"long","nullable":true,"metadata":{}}"#.to_string(),
+            position: 0,
+            nullable: true,
+        };
+        let request = CreateTableRequest::new("catalog", "schema", "my_table")
+            .with_storage_location("s3://bucket/table")
+            .with_columns([column])
+            .with_properties([("delta.feature.catalogManaged", "supported")]);
+        Mock::given(method("POST"))
+            .and(path("/api/2.1/unity-catalog/tables"))
+            .and(body_json(json!({
+                "name": "my_table",
+                "catalog_name": "catalog",
+                "schema_name": "schema",
+                "table_type": "MANAGED",
+                "data_source_format": "DELTA",
+                "storage_location": "s3://bucket/table",
+                "columns": [{
+                    "name": "id",
+                    "type_name": "LONG",
+                    "type_text": "bigint",
+                    "type_json": r#"{"name":"id","type":"long","nullable":true,"metadata":{}}"#,
+                    "position": 0,
+                    "nullable": true
+                }],
+                "properties": {"delta.feature.catalogManaged": "supported"}
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(table_json()))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let table = client(&server).create_table(request).await.unwrap();
+        assert_eq!(table.full_name(), "catalog.schema.my_table");
+        assert_eq!(table.table_id, "table-id");
+    }
+
+    #[tokio::test]
+    async fn test_create_existing_table() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/2.1/unity-catalog/tables"))
+            .respond_with(ResponseTemplate::new(409))
+            .mount(&server)
+            .await;
+
+        let request = CreateTableRequest::new("catalog", "schema", "my_table");
+        let result = client(&server).create_table(request).await;
+        assert!(
+            matches!(result, Err(Error::TableAlreadyExists(name)) if name == "catalog.schema.my_table")
+        );
+    }
+}